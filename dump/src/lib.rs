@@ -265,7 +265,7 @@ pub(crate) mod test {
     pub fn create_test_settings() -> Settings<Checked> {
         let settings = Settings {
             displayed_attributes: Setting::Set(vec![S("race"), S("name")]).into(),
-            searchable_attributes: Setting::Set(vec![S("name"), S("race")]).into(),
+            searchable_attributes: Setting::Set(vec![S("name").into(), S("race").into()]).into(),
             filterable_attributes: Setting::Set(btreeset! { S("race"), S("age") }),
             sortable_attributes: Setting::Set(btreeset! { S("age") }),
             ranking_rules: Setting::NotSet,
@@ -274,6 +274,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_rules: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -287,6 +288,10 @@ pub(crate) mod test {
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
             localized_attributes: Setting::NotSet,
+            nested_attributes: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            phonetic_attributes: Setting::NotSet,
+            rules: Setting::NotSet,
             _kind: std::marker::PhantomData,
         };
         settings.check()
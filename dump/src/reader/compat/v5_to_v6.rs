@@ -316,7 +316,14 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
     fn from(settings: v5::Settings<T>) -> Self {
         v6::Settings {
             displayed_attributes: v6::Setting::from(settings.displayed_attributes).into(),
-            searchable_attributes: v6::Setting::from(settings.searchable_attributes).into(),
+            searchable_attributes: match settings.searchable_attributes {
+                v5::settings::Setting::Set(fields) => {
+                    v6::Setting::Set(fields.into_iter().map(Into::into).collect())
+                }
+                v5::settings::Setting::Reset => v6::Setting::Reset,
+                v5::settings::Setting::NotSet => v6::Setting::NotSet,
+            }
+            .into(),
             filterable_attributes: settings.filterable_attributes.into(),
             sortable_attributes: settings.sortable_attributes.into(),
             ranking_rules: {
@@ -344,6 +351,7 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             separator_tokens: v6::Setting::NotSet,
             dictionary: v6::Setting::NotSet,
             synonyms: settings.synonyms.into(),
+            synonym_rules: v6::Setting::NotSet,
             distinct_attribute: settings.distinct_attribute.into(),
             proximity_precision: v6::Setting::NotSet,
             typo_tolerance: match settings.typo_tolerance {
@@ -359,6 +367,8 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
                     },
                     disable_on_words: typo.disable_on_words.into(),
                     disable_on_attributes: typo.disable_on_attributes.into(),
+                    max_typos_per_attribute: v6::Setting::NotSet,
+                    max_typos_per_query: v6::Setting::NotSet,
                 }),
                 v5::Setting::Reset => v6::Setting::Reset,
                 v5::Setting::NotSet => v6::Setting::NotSet,
@@ -380,6 +390,10 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             },
             embedders: v6::Setting::NotSet,
             localized_attributes: v6::Setting::NotSet,
+            nested_attributes: v6::Setting::NotSet,
+            date_attributes: v6::Setting::NotSet,
+            phonetic_attributes: v6::Setting::NotSet,
+            rules: v6::Setting::NotSet,
             search_cutoff_ms: v6::Setting::NotSet,
             _kind: std::marker::PhantomData,
         }
@@ -0,0 +1,185 @@
+//! BNF grammar:
+//!
+//! ```text
+//! expression     = term (("+" | "-" WS+) term)*
+//! term           = operand (("*" | "/") operand)*
+//! operand        = ("$" value) | value
+//! ```
+//!
+//! An operand prefixed by `$` references the numbers of a field, the other operands are numbers
+//! or dates. The `-` operator must be followed by a whitespace because it is a valid character of
+//! a word, `$stock-reserved` references the field named `stock-reserved`.
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, multispace0, multispace1};
+use nom::combinator::{cut, map, peek};
+use nom::multi::fold_many0;
+use nom::sequence::{preceded, terminated, tuple};
+
+use crate::{parse_value, IResult, Span, Token};
+
+/// An arithmetic expression on the numbers of the fields of a document, its values are numbers
+/// or dates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression<'a> {
+    Value(Token<'a>),
+    Field(Token<'a>),
+    Operation { left: Box<Self>, operator: ArithmeticOperator, right: Box<Self> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// The operator of a [`FilterCondition::Comparison`](crate::FilterCondition::Comparison).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LowerThan,
+    LowerThanOrEqual,
+}
+
+impl<'a> Expression<'a> {
+    /// Returns the fields the expression is about, from left to right.
+    pub fn fields(&self) -> Vec<&Token<'a>> {
+        match self {
+            Expression::Value(_) => Vec::new(),
+            Expression::Field(field) => vec![field],
+            Expression::Operation { left, operator: _, right } => {
+                let mut fields = left.fields();
+                fields.extend(right.fields());
+                fields
+            }
+        }
+    }
+
+    /// Returns the leftmost token of the expression.
+    pub fn first_token(&self) -> &Token<'a> {
+        match self {
+            Expression::Value(token) | Expression::Field(token) => token,
+            Expression::Operation { left, .. } => left.first_token(),
+        }
+    }
+}
+
+impl ArithmeticOperator {
+    pub fn apply(self, left: f64, right: f64) -> f64 {
+        match self {
+            ArithmeticOperator::Add => left + right,
+            ArithmeticOperator::Subtract => left - right,
+            ArithmeticOperator::Multiply => left * right,
+            ArithmeticOperator::Divide => left / right,
+        }
+    }
+}
+
+impl ComparisonOperator {
+    pub(crate) fn from_symbol(symbol: &str) -> Self {
+        match symbol {
+            "=" => ComparisonOperator::Equal,
+            "!=" => ComparisonOperator::NotEqual,
+            ">" => ComparisonOperator::GreaterThan,
+            ">=" => ComparisonOperator::GreaterThanOrEqual,
+            "<" => ComparisonOperator::LowerThan,
+            "<=" => ComparisonOperator::LowerThanOrEqual,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn compare(self, left: f64, right: f64) -> bool {
+        match self {
+            ComparisonOperator::Equal => left == right,
+            ComparisonOperator::NotEqual => left != right,
+            ComparisonOperator::GreaterThan => left > right,
+            ComparisonOperator::GreaterThanOrEqual => left >= right,
+            ComparisonOperator::LowerThan => left < right,
+            ComparisonOperator::LowerThanOrEqual => left <= right,
+        }
+    }
+}
+
+/// expression     = term (("+" | "-" WS+) term)*
+pub fn parse_expression(input: Span) -> IResult<Expression> {
+    let (input, first) = parse_term(input)?;
+    let operator = alt((
+        map(tag("+"), |_| ArithmeticOperator::Add),
+        map(terminated(tag("-"), peek(multispace1)), |_| ArithmeticOperator::Subtract),
+    ));
+    fold_many0(
+        tuple((operator, cut(parse_term))),
+        move || first.clone(),
+        |left, (operator, right)| Expression::Operation {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        },
+    )(input)
+}
+
+/// term           = operand (("*" | "/") operand)*
+fn parse_term(input: Span) -> IResult<Expression> {
+    let (input, first) = parse_operand(input)?;
+    let operator = alt((
+        map(tag("*"), |_| ArithmeticOperator::Multiply),
+        map(tag("/"), |_| ArithmeticOperator::Divide),
+    ));
+    fold_many0(
+        tuple((operator, cut(parse_operand))),
+        move || first.clone(),
+        |left, (operator, right)| Expression::Operation {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        },
+    )(input)
+}
+
+/// operand        = ("$" value) | value
+fn parse_operand(input: Span) -> IResult<Expression> {
+    let field = preceded(tuple((multispace0, char('$'))), cut(parse_value));
+    alt((map(field, Expression::Field), map(parse_value, Expression::Value)))(input)
+}
+
+impl<'a> std::fmt::Display for Expression<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Value(token) => write!(f, "{token}"),
+            Expression::Field(token) => write!(f, "${token}"),
+            Expression::Operation { left, operator, right } => {
+                write!(f, "({left} {operator} {right})")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticOperator::Add => write!(f, "+"),
+            ArithmeticOperator::Subtract => write!(f, "-"),
+            ArithmeticOperator::Multiply => write!(f, "*"),
+            ArithmeticOperator::Divide => write!(f, "/"),
+        }
+    }
+}
+
+impl std::fmt::Display for ComparisonOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComparisonOperator::Equal => write!(f, "="),
+            ComparisonOperator::NotEqual => write!(f, "!="),
+            ComparisonOperator::GreaterThan => write!(f, ">"),
+            ComparisonOperator::GreaterThanOrEqual => write!(f, ">="),
+            ComparisonOperator::LowerThan => write!(f, "<"),
+            ComparisonOperator::LowerThanOrEqual => write!(f, "<="),
+        }
+    }
+}
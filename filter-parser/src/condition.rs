@@ -1,7 +1,7 @@
 //! BNF grammar:
 //!
 //! ```text
-//! condition      = value ("==" | ">" ...) value
+//! condition      = expression ("==" | ">" ...) expression
 //! to             = value value TO value
 //! ```
 
@@ -12,7 +12,8 @@ use nom::combinator::cut;
 use nom::sequence::{terminated, tuple};
 use Condition::*;
 
-use crate::{parse_value, FilterCondition, IResult, Span, Token};
+use crate::arithmetic::{parse_expression, ComparisonOperator, Expression};
+use crate::{parse_regex, parse_value, FilterCondition, IResult, Span, Token};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition<'a> {
@@ -22,18 +23,40 @@ pub enum Condition<'a> {
     NotEqual(Token<'a>),
     Null,
     Empty,
+    True,
+    False,
     Exists,
     LowerThan(Token<'a>),
     LowerThanOrEqual(Token<'a>),
     Between { from: Token<'a>, to: Token<'a> },
     Contains { keyword: Token<'a>, word: Token<'a> },
     StartsWith { keyword: Token<'a>, word: Token<'a> },
+    EndsWith { keyword: Token<'a>, word: Token<'a> },
+    Like { keyword: Token<'a>, pattern: Token<'a> },
+    Matches { keyword: Token<'a>, regex: Token<'a> },
 }
 
-/// condition      = value ("==" | ">" ...) value
+/// condition      = expression ("==" | ">" ...) expression
+///
+/// A field compared to a value is a [`FilterCondition::Condition`]. The condition is a
+/// [`FilterCondition::Comparison`] when the value references a field, like `price > $cost`, or
+/// uses arithmetic. As in every condition, a name alone on the left is a field.
 pub fn parse_condition(input: Span) -> IResult<FilterCondition> {
     let operator = alt((tag("<="), tag(">="), tag("!="), tag("<"), tag(">"), tag("=")));
-    let (input, (fid, op, value)) = tuple((parse_value, operator, cut(parse_value)))(input)?;
+    let (input, (left, op, right)) =
+        tuple((parse_expression, operator, cut(parse_expression)))(input)?;
+
+    let (fid, value) = match (left, right) {
+        (Expression::Value(fid) | Expression::Field(fid), Expression::Value(value)) => (fid, value),
+        (left, right) => {
+            let left = match left {
+                Expression::Value(field) => Expression::Field(field),
+                left => left,
+            };
+            let op = ComparisonOperator::from_symbol(op.fragment());
+            return Ok((input, FilterCondition::Comparison { left, op, right }));
+        }
+    };
 
     let condition = match *op.fragment() {
         "<=" => FilterCondition::Condition { fid, op: LowerThanOrEqual(value) },
@@ -80,6 +103,22 @@ pub fn parse_is_not_empty(input: Span) -> IResult<FilterCondition> {
     Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid: key, op: Empty }))))
 }
 
+/// true           = value "IS" WS+ "TRUE"
+pub fn parse_is_true(input: Span) -> IResult<FilterCondition> {
+    let (input, key) = parse_value(input)?;
+
+    let (input, _) = tuple((tag("IS"), multispace1, tag("TRUE")))(input)?;
+    Ok((input, FilterCondition::Condition { fid: key, op: True }))
+}
+
+/// false          = value "IS" WS+ "FALSE"
+pub fn parse_is_false(input: Span) -> IResult<FilterCondition> {
+    let (input, key) = parse_value(input)?;
+
+    let (input, _) = tuple((tag("IS"), multispace1, tag("FALSE")))(input)?;
+    Ok((input, FilterCondition::Condition { fid: key, op: False }))
+}
+
 /// exist          = value "EXISTS"
 pub fn parse_exists(input: Span) -> IResult<FilterCondition> {
     let (input, key) = terminated(parse_value, tag("EXISTS"))(input)?;
@@ -150,6 +189,89 @@ pub fn parse_not_starts_with(input: Span) -> IResult<FilterCondition> {
     ))
 }
 
+/// ends with        = value "ENDS WITH" value
+pub fn parse_ends_with(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, ends_with, value)) =
+        tuple((parse_value, tag("ENDS WITH"), cut(parse_value)))(input)?;
+    Ok((
+        input,
+        FilterCondition::Condition {
+            fid,
+            op: EndsWith { keyword: Token { span: ends_with, value: None }, word: value },
+        },
+    ))
+}
+
+/// ends with        = value "NOT" WS+ "ENDS WITH" value
+pub fn parse_not_ends_with(input: Span) -> IResult<FilterCondition> {
+    let keyword = tuple((tag("NOT"), multispace1, tag("ENDS WITH")));
+    let (input, (fid, (_not, _spaces, ends_with), value)) =
+        tuple((parse_value, keyword, cut(parse_value)))(input)?;
+
+    Ok((
+        input,
+        FilterCondition::Not(Box::new(FilterCondition::Condition {
+            fid,
+            op: EndsWith { keyword: Token { span: ends_with, value: None }, word: value },
+        })),
+    ))
+}
+
+/// like           = value "LIKE" value
+pub fn parse_like(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, like, value)) = tuple((parse_value, tag("LIKE"), cut(parse_value)))(input)?;
+    Ok((
+        input,
+        FilterCondition::Condition {
+            fid,
+            op: Like { keyword: Token { span: like, value: None }, pattern: value },
+        },
+    ))
+}
+
+/// like           = value "NOT" WS+ "LIKE" value
+pub fn parse_not_like(input: Span) -> IResult<FilterCondition> {
+    let keyword = tuple((tag("NOT"), multispace1, tag("LIKE")));
+    let (input, (fid, (_not, _spaces, like), value)) =
+        tuple((parse_value, keyword, cut(parse_value)))(input)?;
+
+    Ok((
+        input,
+        FilterCondition::Not(Box::new(FilterCondition::Condition {
+            fid,
+            op: Like { keyword: Token { span: like, value: None }, pattern: value },
+        })),
+    ))
+}
+
+/// matches        = value "MATCHES" regex
+pub fn parse_matches(input: Span) -> IResult<FilterCondition> {
+    let (input, (fid, matches, regex)) =
+        tuple((parse_value, tag("MATCHES"), cut(parse_regex)))(input)?;
+    Ok((
+        input,
+        FilterCondition::Condition {
+            fid,
+            op: Matches { keyword: Token { span: matches, value: None }, regex },
+        },
+    ))
+}
+
+/// matches        = value "NOT" WS+ "MATCHES" regex
+pub fn parse_not_matches(input: Span) -> IResult<FilterCondition> {
+    let keyword = tuple((tag("NOT"), multispace1, tag("MATCHES")));
+    let (input, (fid, (_not, _spaces, matches), regex)) =
+        tuple((parse_value, keyword, cut(parse_regex)))(input)?;
+
+    Ok((
+        input,
+        FilterCondition::Not(Box::new(FilterCondition::Condition {
+            fid,
+            op: Matches { keyword: Token { span: matches, value: None }, regex },
+        })),
+    ))
+}
+
 /// to             = value value "TO" WS+ value
 pub fn parse_to(input: Span) -> IResult<FilterCondition> {
     let (input, (key, from, _, _, to)) =
@@ -0,0 +1,137 @@
+//! Dates are filtered as their Unix timestamp in seconds, which is how the values of the
+//! date attributes are indexed. `NOW()` is only resolved when the filter is evaluated, the
+//! parsing doesn't depend on the current time.
+
+use nom::bytes::complete::{tag, take_while};
+use nom::character::complete::{multispace0, one_of};
+use nom::combinator::{consumed, map, opt};
+use nom::sequence::{pair, preceded};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+use crate::{Error, ErrorKind, IResult, Span, Token};
+
+/// A date of a filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterDate {
+    /// The Unix timestamp of an RFC 3339 or `YYYY-MM-DD` date.
+    Timestamp(f64),
+    /// `NOW()`, shifted by the number of seconds.
+    Now(f64),
+}
+
+impl FilterDate {
+    /// Returns the Unix timestamp of the date, `now` being the current one.
+    pub fn timestamp(self, now: f64) -> f64 {
+        match self {
+            FilterDate::Timestamp(timestamp) => timestamp,
+            FilterDate::Now(offset) => now + offset,
+        }
+    }
+}
+
+/// Parses an RFC 3339 date, or a `YYYY-MM-DD` date at midnight UTC, into its Unix timestamp.
+pub fn parse_date(s: &str) -> Option<f64> {
+    let datetime = match OffsetDateTime::parse(s, &Rfc3339) {
+        Ok(datetime) => datetime,
+        Err(_) => Date::parse(s, format_description!("[year]-[month]-[day]"))
+            .ok()?
+            .midnight()
+            .assume_utc(),
+    };
+    Some(datetime.unix_timestamp_nanos() as f64 / 1_000_000_000.0)
+}
+
+/// now            = "NOW()" (WS* ("+" | "-") WS* duration)?
+/// duration       = digit+ ("s" | "m" | "h" | "d" | "w")
+///
+/// The returned token is the date as written, see [`Token::as_date`].
+pub fn parse_now(input: Span) -> IResult<Token> {
+    map(consumed(parse_now_offset), |(span, _)| Token::new(span, None))(input)
+}
+
+/// Returns the number of seconds `NOW()` is shifted by.
+pub(crate) fn parse_now_offset(input: Span) -> IResult<f64> {
+    let (input, offset) = preceded(
+        tag("NOW()"),
+        opt(pair(preceded(multispace0, one_of("+-")), preceded(multispace0, parse_duration))),
+    )(input)?;
+
+    let offset = match offset {
+        Some(('+', seconds)) => seconds as f64,
+        Some((_, seconds)) => -(seconds as f64),
+        None => 0.0,
+    };
+
+    Ok((input, offset))
+}
+
+/// Returns the number of seconds of the duration, the sign having been parsed it can only fail.
+fn parse_duration(input: Span) -> IResult<u64> {
+    let (input, duration) = take_while(char::is_alphanumeric)(input)?;
+    let invalid = || nom::Err::Failure(Error::new_from_kind(duration, ErrorKind::InvalidDuration));
+
+    let duration = *duration.fragment();
+    let unit = match duration.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        Some('w') => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let count = &duration[..duration.len() - 1];
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let seconds = count.parse::<u64>().ok().and_then(|count| count.checked_mul(unit));
+
+    seconds.map(|seconds| (input, seconds)).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NomErrorExt;
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse_date("1970-01-01"), Some(0.0));
+        assert_eq!(parse_date("2024-02-29"), Some(1709164800.0));
+        assert_eq!(parse_date("2024-02-29T12:00:00Z"), Some(1709208000.0));
+        assert_eq!(parse_date("2024-02-29T12:00:00+02:00"), Some(1709200800.0));
+        assert_eq!(parse_date("2024-02-29T12:00:00.5Z"), Some(1709208000.5));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("12"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn now() {
+        let date = |input| {
+            let (_, token) = parse_now(Span::new_extra(input, input)).unwrap();
+            token.as_date().unwrap()
+        };
+
+        assert_eq!(date("NOW()"), FilterDate::Now(0.0));
+        assert_eq!(date("NOW() - 7d"), FilterDate::Now(-7.0 * 86400.0));
+        assert_eq!(date("NOW()+12h"), FilterDate::Now(12.0 * 3600.0));
+        assert_eq!(date("NOW() -   2w"), FilterDate::Now(-14.0 * 86400.0));
+        assert_eq!(date("NOW() - 7d").timestamp(1_000_000.0), 1_000_000.0 - 7.0 * 86400.0);
+
+        let (rest, token) = parse_now(Span::new_extra("NOW() AND", "NOW() AND")).unwrap();
+        assert_eq!(token.lexeme(), "NOW()");
+        assert_eq!(*rest.fragment(), " AND");
+
+        let (rest, token) = parse_now(Span::new_extra("NOW() - 30m ", "NOW() - 30m ")).unwrap();
+        assert_eq!(token.lexeme(), "NOW() - 30m");
+        assert_eq!(*rest.fragment(), " ");
+
+        assert!(parse_now(Span::new_extra("NOW", "NOW")).is_err());
+        for input in ["NOW() - 7", "NOW() - d", "NOW() + 7y", "NOW() - 99999999999999999999s"] {
+            let error = parse_now(Span::new_extra(input, input)).unwrap_err();
+            assert!(error.is_failure(), "{input}");
+        }
+    }
+}
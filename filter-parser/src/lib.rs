@@ -6,19 +6,27 @@
 //! or             = and ("OR" WS+ and)*
 //! and            = not ("AND" WS+ not)*
 //! not            = ("NOT" WS+ not) | primary
-//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | in | condition | exists | not_exists | to
+//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | in | condition | exists | not_exists | to | true | false
 //! in             = value "IN" WS* "[" value_list "]"
-//! condition      = value ("=" | "!=" | ">" | ">=" | "<" | "<=") value
+//! condition      = expression ("=" | "!=" | ">" | ">=" | "<" | "<=") expression
+//! expression     = term (("+" | "-" WS+) term)*
+//! term           = operand (("*" | "/") operand)*
+//! operand        = ("$" value) | value
 //! exists         = value "EXISTS"
 //! not_exists     = value "NOT" WS+ "EXISTS"
 //! to             = value value "TO" WS+ value
-//! value          = WS* ( word | singleQuoted | doubleQuoted) WS+
+//! true           = value "IS" WS+ "TRUE"
+//! false          = value "IS" WS+ "FALSE"
+//! value          = WS* ( word | singleQuoted | doubleQuoted | now) WS+
 //! value_list     = (value ("," value)* ","?)?
 //! singleQuoted   = "'" .* all but quotes "'"
 //! doubleQuoted   = "\"" .* all but double quotes "\""
 //! word           = (alphanumeric | _ | - | .)+
+//! now            = "NOW()" (WS* ("+" | "-") WS* digit+ ("s" | "m" | "h" | "d" | "w"))?
 //! geoRadius      = "_geoRadius(" WS* float WS* "," WS* float WS* "," float WS* ")"
 //! geoBoundingBox = "_geoBoundingBox([" WS * float WS* "," WS* float WS* "], [" WS* float WS* "," WS* float WS* "]")
+//! geoPolygon     = "_geoPolygon([" WS* geoPair WS* ("," WS* geoPair WS*)* "])"
+//! geoPair        = "[" WS* float WS* "," WS* float WS* "]"
 //! ```
 //!
 //! Other BNF grammar used to handle some specific errors:
@@ -39,18 +47,31 @@
 //! field = _geoRadius(12, 13, 14)
 //! ```
 //!
+//! - If a user write a geoPolygon with less than three distinct points, with all its points
+//!   on the same line, or with edges crossing each other we must throw an error.
+//! ```text
+//! _geoPolygon([[1, 1], [2, 2]])
+//! _geoPolygon([[0, 0], [0, 2], [2, 0], [2, 2]])
+//! ```
+//!
 
+mod arithmetic;
 mod condition;
+mod date;
 mod error;
 mod value;
 
 use std::fmt::Debug;
 
+pub use arithmetic::{ArithmeticOperator, ComparisonOperator, Expression};
 pub use condition::{parse_condition, parse_to, Condition};
 use condition::{
-    parse_contains, parse_exists, parse_is_empty, parse_is_not_empty, parse_is_not_null,
-    parse_is_null, parse_not_contains, parse_not_exists, parse_not_starts_with, parse_starts_with,
+    parse_contains, parse_ends_with, parse_exists, parse_is_empty, parse_is_false,
+    parse_is_not_empty, parse_is_not_null, parse_is_null, parse_is_true, parse_like, parse_matches,
+    parse_not_contains, parse_not_ends_with, parse_not_exists, parse_not_like, parse_not_matches,
+    parse_not_starts_with, parse_starts_with,
 };
+pub use date::{parse_date, FilterDate};
 use error::{cut_with_err, ExpectedValueKind, NomErrorExt};
 pub use error::{Error, ErrorKind};
 use nom::branch::alt;
@@ -62,8 +83,8 @@ use nom::number::complete::recognize_float;
 use nom::sequence::{delimited, preceded, terminated, tuple};
 use nom::Finish;
 use nom_locate::LocatedSpan;
-pub(crate) use value::parse_value;
 use value::word_exact;
+pub(crate) use value::{parse_regex, parse_value};
 
 pub type Span<'a> = LocatedSpan<&'a str, &'a str>;
 
@@ -121,6 +142,24 @@ impl<'a> Token<'a> {
             Err(Error::new_from_kind(self.span, ErrorKind::NonFiniteFloat))
         }
     }
+
+    /// Parses the token as a date, `NOW()` with an optional offset or an RFC 3339 or
+    /// `YYYY-MM-DD` date.
+    pub fn as_date(&self) -> Option<FilterDate> {
+        match date::parse_now_offset(self.span) {
+            Ok((rest, offset)) if rest.fragment().is_empty() => Some(FilterDate::Now(offset)),
+            _ => parse_date(self.value()).map(FilterDate::Timestamp),
+        }
+    }
+
+    /// Parses the token as a number or as the Unix timestamp of a date, `now` being the
+    /// timestamp of `NOW()`. Only the values of the comparisons and ranges can be dates.
+    pub fn parse_finite_float_or_date(&self, now: f64) -> Result<f64, Error> {
+        match (self.value().parse::<f64>(), self.as_date()) {
+            (Err(_), Some(date)) => Ok(date.timestamp(now)),
+            _ => self.parse_finite_float(),
+        }
+    }
 }
 
 impl<'a> From<Span<'a>> for Token<'a> {
@@ -145,6 +184,8 @@ pub enum FilterCondition<'a> {
     And(Vec<Self>),
     GeoLowerThan { point: [Token<'a>; 2], radius: Token<'a> },
     GeoBoundingBox { top_right_point: [Token<'a>; 2], bottom_left_point: [Token<'a>; 2] },
+    GeoPolygon { points: Vec<[Token<'a>; 2]> },
+    Comparison { left: Expression<'a>, op: ComparisonOperator, right: Expression<'a> },
 }
 
 pub enum TraversedElement<'a> {
@@ -162,10 +203,15 @@ impl<'a> FilterCondition<'a> {
                 | Condition::NotEqual(_)
                 | Condition::Null
                 | Condition::Empty
+                | Condition::True
+                | Condition::False
                 | Condition::Exists
                 | Condition::LowerThan(_)
                 | Condition::LowerThanOrEqual(_)
-                | Condition::Between { .. } => None,
+                | Condition::Between { .. }
+                | Condition::EndsWith { .. }
+                | Condition::Like { .. }
+                | Condition::Matches { .. } => None,
                 Condition::Contains { keyword, word: _ }
                 | Condition::StartsWith { keyword, word: _ } => Some(keyword),
             },
@@ -175,6 +221,42 @@ impl<'a> FilterCondition<'a> {
             }
             FilterCondition::GeoLowerThan { .. }
             | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. }
+            | FilterCondition::Comparison { .. }
+            | FilterCondition::In { .. } => None,
+        }
+    }
+
+    /// Returns the keyword of the first `ENDS WITH`, `LIKE` or `MATCHES` operator of the filter.
+    pub fn use_pattern_operator(&self) -> Option<&Token> {
+        match self {
+            FilterCondition::Condition { fid: _, op } => match op {
+                Condition::EndsWith { keyword, word: _ }
+                | Condition::Like { keyword, pattern: _ }
+                | Condition::Matches { keyword, regex: _ } => Some(keyword),
+                Condition::GreaterThan(_)
+                | Condition::GreaterThanOrEqual(_)
+                | Condition::Equal(_)
+                | Condition::NotEqual(_)
+                | Condition::Null
+                | Condition::Empty
+                | Condition::True
+                | Condition::False
+                | Condition::Exists
+                | Condition::LowerThan(_)
+                | Condition::LowerThanOrEqual(_)
+                | Condition::Between { .. }
+                | Condition::Contains { .. }
+                | Condition::StartsWith { .. } => None,
+            },
+            FilterCondition::Not(this) => this.use_pattern_operator(),
+            FilterCondition::Or(seq) | FilterCondition::And(seq) => {
+                seq.iter().find_map(|filter| filter.use_pattern_operator())
+            }
+            FilterCondition::GeoLowerThan { .. }
+            | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. }
+            | FilterCondition::Comparison { .. }
             | FilterCondition::In { .. } => None,
         }
     }
@@ -202,6 +284,7 @@ impl<'a> FilterCondition<'a> {
                 None
             }
             FilterCondition::GeoLowerThan { point: [point, _], .. } if depth == 0 => Some(point),
+            FilterCondition::Comparison { left, .. } if depth == 0 => Some(left.first_token()),
             _ => None,
         }
     }
@@ -402,6 +485,135 @@ fn parse_geo_bounding_box(input: Span) -> IResult<FilterCondition> {
     Ok((input, res))
 }
 
+/// geoPolygon      = WS* "_geoPolygon([[float WS* "," WS* float WS* "], [float WS* "," WS* float WS* "], ...])"
+/// If we parse `_geoPolygon` we MUST parse the rest of the expression.
+/// The ring can be explicitly closed by repeating the first point at the end.
+fn parse_geo_polygon(input: Span) -> IResult<FilterCondition> {
+    // we want to allow space BEFORE the _geoPolygon but not after
+    let parsed = preceded(
+        tuple((multispace0, word_exact("_geoPolygon"))),
+        // if we were able to parse `_geoPolygon` and can't parse the rest of the input we return a failure
+        cut(delimited(
+            char('('),
+            ws(delimited(
+                char('['),
+                separated_list1(
+                    tag(","),
+                    ws(delimited(
+                        char('['),
+                        separated_list1(tag(","), ws(recognize_float)),
+                        char(']'),
+                    )),
+                ),
+                char(']'),
+            )),
+            char(')'),
+        )),
+    )(input)
+    .map_err(|e| e.map(|_| Error::new_from_kind(input, ErrorKind::GeoPolygon)));
+
+    let (rest, args) = parsed?;
+
+    if args.iter().any(|point| point.len() != 2) {
+        return Err(nom::Err::Failure(Error::new_from_kind(input, ErrorKind::GeoPolygon)));
+    }
+
+    let mut points: Vec<[Token; 2]> =
+        args.into_iter().map(|point| [point[0].into(), point[1].into()]).collect();
+    // the values were recognized as floats, if they can't be parsed it's because they are
+    // out of bounds and we let the caller report the error on the right token.
+    let coords: Option<Vec<[f64; 2]>> = points
+        .iter()
+        .map(|[lat, lng]| Some([lat.value().parse().ok()?, lng.value().parse().ok()?]))
+        .collect();
+
+    if let Some(mut coords) = coords {
+        // an explicitly closed ring is the same as an implicitly closed one
+        if coords.len() > 1 && coords.first() == coords.last() {
+            coords.pop();
+            points.pop();
+        }
+        if is_degenerate_ring(&coords) {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::GeoPolygonDegenerate,
+            )));
+        }
+        if is_self_intersecting_ring(&coords) {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::GeoPolygonSelfIntersecting,
+            )));
+        }
+    } else if points.len() < 3 {
+        return Err(nom::Err::Failure(Error::new_from_kind(
+            input,
+            ErrorKind::GeoPolygonDegenerate,
+        )));
+    }
+
+    Ok((rest, FilterCondition::GeoPolygon { points }))
+}
+
+/// Returns the orientation of the `c` point relative to the `a`-`b` line:
+/// positive when counter-clockwise, negative when clockwise and zero when collinear.
+fn orientation(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// A ring is degenerate when it doesn't have three distinct points or when all its points are collinear.
+fn is_degenerate_ring(ring: &[[f64; 2]]) -> bool {
+    let mut distinct: Vec<[f64; 2]> = Vec::with_capacity(ring.len());
+    for point in ring {
+        if !distinct.contains(point) {
+            distinct.push(*point);
+        }
+    }
+    match distinct.as_slice() {
+        [a, b, rest @ ..] if !rest.is_empty() => {
+            rest.iter().all(|c| orientation(*a, *b, *c) == 0.0)
+        }
+        _ => true,
+    }
+}
+
+/// Returns `true` if two non-adjacent edges of the implicitly closed ring touch or cross each other.
+fn is_self_intersecting_ring(ring: &[[f64; 2]]) -> bool {
+    fn on_segment(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+        c[0] >= a[0].min(b[0])
+            && c[0] <= a[0].max(b[0])
+            && c[1] >= a[1].min(b[1])
+            && c[1] <= a[1].max(b[1])
+    }
+
+    fn intersects((a, b): ([f64; 2], [f64; 2]), (c, d): ([f64; 2], [f64; 2])) -> bool {
+        let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+        let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+        if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+            return true;
+        }
+        (o1 == 0.0 && on_segment(a, b, c))
+            || (o2 == 0.0 && on_segment(a, b, d))
+            || (o3 == 0.0 && on_segment(c, d, a))
+            || (o4 == 0.0 && on_segment(c, d, b))
+    }
+
+    let len = ring.len();
+    let edge = |i: usize| (ring[i], ring[(i + 1) % len]);
+    for i in 0..len {
+        // adjacent edges always share a point, we only need to check the following non-adjacent ones
+        for j in (i + 2)..len {
+            if i == 0 && j == len - 1 {
+                continue;
+            }
+            if intersects(edge(i), edge(j)) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
 /// geoPoint      = WS* "_geoPoint(float WS* "," WS* float WS* "," WS* float)
 fn parse_geo_point(input: Span) -> IResult<FilterCondition> {
     // we want to forbid space BEFORE the _geoPoint but not after
@@ -457,7 +669,7 @@ fn parse_error_reserved_keyword(input: Span) -> IResult<FilterCondition> {
     }
 }
 
-/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | condition | exists | not_exists | to
+/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | condition | exists | not_exists | to | true | false
 fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
     if depth > MAX_FILTER_DEPTH {
         return Err(nom::Err::Error(Error::new_from_kind(input, ErrorKind::DepthLimitReached)));
@@ -471,8 +683,8 @@ fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
                 Error::new_from_kind(input, ErrorKind::MissingClosingDelimiter(c.char()))
             }),
         ),
-        parse_geo_radius,
-        parse_geo_bounding_box,
+        // nom's `alt` can't hold more than 21 parsers so we group the geo ones together
+        alt((parse_geo_radius, parse_geo_bounding_box, parse_geo_polygon)),
         parse_in,
         parse_not_in,
         parse_condition,
@@ -480,13 +692,24 @@ fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
         parse_is_not_null,
         parse_is_empty,
         parse_is_not_empty,
+        parse_is_true,
+        parse_is_false,
         parse_exists,
         parse_not_exists,
         parse_to,
-        parse_contains,
-        parse_not_contains,
-        parse_starts_with,
-        parse_not_starts_with,
+        // nom's `alt` can't hold more than 21 parsers so we group the string pattern ones together
+        alt((
+            parse_contains,
+            parse_not_contains,
+            parse_starts_with,
+            parse_not_starts_with,
+            parse_ends_with,
+            parse_not_ends_with,
+            parse_like,
+            parse_not_like,
+            parse_matches,
+            parse_not_matches,
+        )),
         // the next lines are only for error handling and are written at the end to have the less possible performance impact
         parse_geo,
         parse_geo_distance,
@@ -553,6 +776,17 @@ impl<'a> std::fmt::Display for FilterCondition<'a> {
                     bottom_right_point[1]
                 )
             }
+            FilterCondition::GeoPolygon { points } => {
+                write!(f, "_geoPolygon([")?;
+                for (i, [lat, lng]) in points.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "[{lat}, {lng}]")?;
+                }
+                write!(f, "])")
+            }
+            FilterCondition::Comparison { left, op, right } => write!(f, "{left} {op} {right}"),
         }
     }
 }
@@ -565,12 +799,17 @@ impl<'a> std::fmt::Display for Condition<'a> {
             Condition::NotEqual(token) => write!(f, "!= {token}"),
             Condition::Null => write!(f, "IS NULL"),
             Condition::Empty => write!(f, "IS EMPTY"),
+            Condition::True => write!(f, "IS TRUE"),
+            Condition::False => write!(f, "IS FALSE"),
             Condition::Exists => write!(f, "EXISTS"),
             Condition::LowerThan(token) => write!(f, "< {token}"),
             Condition::LowerThanOrEqual(token) => write!(f, "<= {token}"),
             Condition::Between { from, to } => write!(f, "{from} TO {to}"),
             Condition::Contains { word, keyword: _ } => write!(f, "CONTAINS {word}"),
             Condition::StartsWith { word, keyword: _ } => write!(f, "STARTS WITH {word}"),
+            Condition::EndsWith { word, keyword: _ } => write!(f, "ENDS WITH {word}"),
+            Condition::Like { pattern, keyword: _ } => write!(f, "LIKE {pattern}"),
+            Condition::Matches { regex, keyword: _ } => write!(f, "MATCHES {regex}"),
         }
     }
 }
@@ -670,6 +909,13 @@ pub mod tests {
         insta::assert_snapshot!(p("NOT subscribers IS NOT EMPTY"), @"{subscribers} IS EMPTY");
         insta::assert_snapshot!(p("subscribers  IS   NOT   EMPTY"), @"NOT ({subscribers} IS EMPTY)");
 
+        // Test TRUE + FALSE
+        insta::assert_snapshot!(p("in_stock IS TRUE"), @"{in_stock} IS TRUE");
+        insta::assert_snapshot!(p("in_stock IS FALSE"), @"{in_stock} IS FALSE");
+        insta::assert_snapshot!(p("NOT in_stock IS TRUE"), @"NOT ({in_stock} IS TRUE)");
+        insta::assert_snapshot!(p("in_stock  IS   FALSE"), @"{in_stock} IS FALSE");
+        insta::assert_snapshot!(p("in_stock = true"), @"{in_stock} = {true}");
+
         // Test EXISTS + NOT EXITS
         insta::assert_snapshot!(p("subscribers EXISTS"), @"{subscribers} EXISTS");
         insta::assert_snapshot!(p("NOT subscribers EXISTS"), @"NOT ({subscribers} EXISTS)");
@@ -684,6 +930,37 @@ pub mod tests {
         insta::assert_snapshot!(p("NOT subscribers NOT CONTAINS 'hello'"), @"{subscribers} CONTAINS {hello}");
         insta::assert_snapshot!(p("subscribers NOT   CONTAINS 'hello'"), @"NOT ({subscribers} CONTAINS {hello})");
 
+        // Test dates
+        insta::assert_snapshot!(p("created_at > 2024-01-01"), @"{created_at} > {2024-01-01}");
+        insta::assert_snapshot!(p("created_at <= '2024-01-01T12:00:00Z'"), @"{created_at} <= {2024-01-01T12:00:00Z}");
+        let filter = Fc::parse("created_at NOW() - 7d TO NOW()").unwrap().unwrap();
+        let FilterCondition::Condition { op: Condition::Between { from, to }, .. } = &filter else {
+            panic!("unexpected filter: {filter}");
+        };
+        assert_eq!(from.lexeme(), "NOW() - 7d");
+        assert_eq!(to.lexeme(), "NOW()");
+        assert_eq!(from.as_date(), Some(FilterDate::Now(-7.0 * 24.0 * 60.0 * 60.0)));
+        assert_eq!(to.as_date(), Some(FilterDate::Now(0.0)));
+        assert_eq!(to.parse_finite_float_or_date(1704110400.0).unwrap(), 1704110400.0);
+        assert!(to.parse_finite_float().is_err());
+        let date = Token::from("2024-01-01T12:00:00Z");
+        assert_eq!(date.parse_finite_float_or_date(0.0).unwrap(), 1704110400.0);
+        assert!(date.parse_finite_float().is_err());
+        assert_eq!(Token::from("12").parse_finite_float_or_date(0.0).unwrap(), 12.0);
+
+        // Test comparisons between fields and arithmetic
+        insta::assert_snapshot!(p("discounted_price < $price"), @"${discounted_price} < ${price}");
+        insta::assert_snapshot!(p("$stock - $reserved > 0"), @"(${stock} - ${reserved}) > {0}");
+        insta::assert_snapshot!(p("$price * 1.2 + $shipping <= 100"), @"((${price} * {1.2}) + ${shipping}) <= {100}");
+        insta::assert_snapshot!(p("$price / 2 > 10"), @"(${price} / {2}) > {10}");
+        insta::assert_snapshot!(p("total = $price + $tax"), @"${total} = (${price} + ${tax})");
+        insta::assert_snapshot!(p("$'unit price' >= $ cost"), @"${unit price} >= ${cost}");
+        insta::assert_snapshot!(p("$price > 10"), @"{price} > {10}");
+        insta::assert_snapshot!(p("price > 'cheap'"), @"{price} > {cheap}");
+        insta::assert_snapshot!(p("price > cheap"), @"{price} > {cheap}");
+        insta::assert_snapshot!(p("price = discounted_price"), @"{price} = {discounted_price}");
+        insta::assert_snapshot!(p("$stock-reserved > -1"), @"{stock-reserved} > {-1}");
+
         // Test STARTS WITH + NOT STARTS WITH
         insta::assert_snapshot!(p("subscribers STARTS WITH 'hel'"), @"{subscribers} STARTS WITH {hel}");
         insta::assert_snapshot!(p("NOT subscribers STARTS WITH 'hel'"), @"NOT ({subscribers} STARTS WITH {hel})");
@@ -691,6 +968,22 @@ pub mod tests {
         insta::assert_snapshot!(p("NOT subscribers NOT STARTS WITH 'hel'"), @"{subscribers} STARTS WITH {hel}");
         insta::assert_snapshot!(p("subscribers NOT   STARTS WITH 'hel'"), @"NOT ({subscribers} STARTS WITH {hel})");
 
+        // Test ENDS WITH + NOT ENDS WITH
+        insta::assert_snapshot!(p("subscribers ENDS WITH 'llo'"), @"{subscribers} ENDS WITH {llo}");
+        insta::assert_snapshot!(p("NOT subscribers ENDS WITH 'llo'"), @"NOT ({subscribers} ENDS WITH {llo})");
+        insta::assert_snapshot!(p("subscribers NOT ENDS WITH llo"), @"NOT ({subscribers} ENDS WITH {llo})");
+
+        // Test LIKE + NOT LIKE
+        insta::assert_snapshot!(p("subscribers LIKE 'h%l_o'"), @"{subscribers} LIKE {h%l_o}");
+        insta::assert_snapshot!(p("NOT subscribers LIKE 'h%l_o'"), @"NOT ({subscribers} LIKE {h%l_o})");
+        insta::assert_snapshot!(p("subscribers NOT LIKE 'h%'"), @"NOT ({subscribers} LIKE {h%})");
+
+        // Test MATCHES + NOT MATCHES
+        insta::assert_snapshot!(p("subscribers MATCHES /h[a-z]+o/"), @"{subscribers} MATCHES {h[a-z]+o}");
+        insta::assert_snapshot!(p(r"subscribers MATCHES /a\/b\d/"), @r"{subscribers} MATCHES {a/b\d}");
+        insta::assert_snapshot!(p("NOT subscribers MATCHES /h.*/"), @"NOT ({subscribers} MATCHES {h.*})");
+        insta::assert_snapshot!(p("subscribers NOT MATCHES /h.*/ AND x = 1"), @"AND[NOT ({subscribers} MATCHES {h.*}), {x} = {1}, ]");
+
         // Test nested NOT
         insta::assert_snapshot!(p("NOT NOT NOT NOT x = 5"), @"{x} = {5}");
         insta::assert_snapshot!(p("NOT NOT (NOT NOT x = 5)"), @"{x} = {5}");
@@ -705,6 +998,12 @@ pub mod tests {
         insta::assert_snapshot!(p("NOT _geoBoundingBox([12, 13], [14, 15])"), @"NOT (_geoBoundingBox([{12}, {13}], [{14}, {15}]))");
         insta::assert_snapshot!(p("_geoBoundingBox([12,13],[14,15])"), @"_geoBoundingBox([{12}, {13}], [{14}, {15}])");
 
+        // Test geo polygon
+        insta::assert_snapshot!(p("_geoPolygon([[12, 13], [14, 15], [12, 16]])"), @"_geoPolygon([[{12}, {13}], [{14}, {15}], [{12}, {16}]])");
+        insta::assert_snapshot!(p("NOT _geoPolygon([[12, 13], [14, 15], [12, 16]])"), @"NOT (_geoPolygon([[{12}, {13}], [{14}, {15}], [{12}, {16}]]))");
+        insta::assert_snapshot!(p("_geoPolygon([[12,13],[14,15],[12,16]])"), @"_geoPolygon([[{12}, {13}], [{14}, {15}], [{12}, {16}]])");
+        insta::assert_snapshot!(p("_geoPolygon( [ [12, 13], [14, 15], [12, 16], [12, 13] ] )"), @"_geoPolygon([[{12}, {13}], [{14}, {15}], [{12}, {16}]])");
+
         // Test OR + AND
         insta::assert_snapshot!(p("channel = ponce AND 'dog race' != 'bernese mountain'"), @"AND[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
         insta::assert_snapshot!(p("channel = ponce OR 'dog race' != 'bernese mountain'"), @"OR[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
@@ -762,7 +1061,7 @@ pub mod tests {
         "###);
 
         insta::assert_snapshot!(p("'OR'"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `\'OR\'`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `\'OR\'`.
         1:5 'OR'
         "###);
 
@@ -772,12 +1071,12 @@ pub mod tests {
         "###);
 
         insta::assert_snapshot!(p("channel Ponce"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `channel Ponce`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `channel Ponce`.
         1:14 channel Ponce
         "###);
 
         insta::assert_snapshot!(p("channel = Ponce OR"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` but instead got nothing.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` but instead got nothing.
         19:19 channel = Ponce OR
         "###);
 
@@ -806,33 +1105,73 @@ pub mod tests {
         1:26 _geoBoundingBox(1.0, 1.0)
         "###);
 
+        insta::assert_snapshot!(p("_geoPolygon"), @r###"
+        The `_geoPolygon` filter expects a list of pairs of arguments: `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])`.
+        1:12 _geoPolygon
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([12, 13], [14, 15], [12, 16])"), @r###"
+        The `_geoPolygon` filter expects a list of pairs of arguments: `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])`.
+        1:42 _geoPolygon([12, 13], [14, 15], [12, 16])
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([[12, 13, 14], [14, 15], [12, 16]])"), @r###"
+        The `_geoPolygon` filter expects a list of pairs of arguments: `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])`.
+        1:48 _geoPolygon([[12, 13, 14], [14, 15], [12, 16]])
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([[12, 13], [14, 15]])"), @r###"
+        The `_geoPolygon` filter expects at least three distinct points that are not all on the same line.
+        1:34 _geoPolygon([[12, 13], [14, 15]])
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([[12, 13], [14, 15], [12, 13]])"), @r###"
+        The `_geoPolygon` filter expects at least three distinct points that are not all on the same line.
+        1:44 _geoPolygon([[12, 13], [14, 15], [12, 13]])
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([[1, 1], [2, 2], [3, 3]])"), @r###"
+        The `_geoPolygon` filter expects at least three distinct points that are not all on the same line.
+        1:38 _geoPolygon([[1, 1], [2, 2], [3, 3]])
+        "###);
+
+        insta::assert_snapshot!(p("_geoPolygon([[0, 0], [0, 2], [2, 0], [2, 2]])"), @r###"
+        The `_geoPolygon` filter expects a polygon whose edges do not intersect each other.
+        1:46 _geoPolygon([[0, 0], [0, 2], [2, 0], [2, 2]])
+        "###);
+
+        insta::assert_snapshot!(p("position <= _geoPolygon([[12, 13], [14, 15], [12, 16]])"), @r###"
+        The `_geoPolygon` filter is an operation and can't be used as a value.
+        13:56 position <= _geoPolygon([[12, 13], [14, 15], [12, 16]])
+        "###);
+
         insta::assert_snapshot!(p("_geoPoint(12, 13, 14)"), @r###"
-        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         1:22 _geoPoint(12, 13, 14)
         "###);
 
         insta::assert_snapshot!(p("position <= _geoPoint(12, 13, 14)"), @r###"
-        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         13:34 position <= _geoPoint(12, 13, 14)
         "###);
 
         insta::assert_snapshot!(p("_geoDistance(12, 13, 14)"), @r###"
-        `_geoDistance` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geoDistance` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         1:25 _geoDistance(12, 13, 14)
         "###);
 
         insta::assert_snapshot!(p("position <= _geoDistance(12, 13, 14)"), @r###"
-        `_geoDistance` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geoDistance` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         13:37 position <= _geoDistance(12, 13, 14)
         "###);
 
         insta::assert_snapshot!(p("_geo(12, 13, 14)"), @r###"
-        `_geo` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geo` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         1:17 _geo(12, 13, 14)
         "###);
 
         insta::assert_snapshot!(p("position <= _geo(12, 13, 14)"), @r###"
-        `_geo` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
+        `_geo` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)`, `_geoBoundingBox([latitude, longitude], [latitude, longitude])` or `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude], ...])` built-in rules to filter on `_geo` coordinates.
         13:29 position <= _geo(12, 13, 14)
         "###);
 
@@ -851,6 +1190,26 @@ pub mod tests {
         11:17 channel = "ponce
         "###);
 
+        insta::assert_snapshot!(p("channel MATCHES /pon.e"), @r###"
+        Expression `/pon.e` is missing the following closing delimiter: `/`.
+        17:23 channel MATCHES /pon.e
+        "###);
+
+        insta::assert_snapshot!(p("channel MATCHES ponce AND followers > 1"), @r###"
+        Was expecting a regular expression delimited by slashes, like `/pattern/`, but instead got `ponce`.
+        17:22 channel MATCHES ponce AND followers > 1
+        "###);
+
+        insta::assert_snapshot!(p("created_at > NOW() - 7x"), @r###"
+        Was expecting a duration like `7d` after `NOW() -` or `NOW() +`, but instead got `7x`. A duration is a whole number followed by one of `s`, `m`, `h`, `d` or `w`.
+        22:24 created_at > NOW() - 7x
+        "###);
+
+        insta::assert_snapshot!(p("$stock - > 0"), @r###"
+        Was expecting a value but instead got nothing.
+        10:10 $stock - > 0
+        "###);
+
         insta::assert_snapshot!(p("channel = mv OR (followers >= 1000"), @r###"
         Expression `(followers >= 1000` is missing the following closing delimiter: `)`.
         17:35 channel = mv OR (followers >= 1000
@@ -862,12 +1221,12 @@ pub mod tests {
         "###);
 
         insta::assert_snapshot!(p("colour NOT EXIST"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `colour NOT EXIST`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `colour NOT EXIST`.
         1:17 colour NOT EXIST
         "###);
 
         insta::assert_snapshot!(p("subscribers 100 TO1000"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `subscribers 100 TO1000`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `subscribers 100 TO1000`.
         1:23 subscribers 100 TO1000
         "###);
 
@@ -930,35 +1289,35 @@ pub mod tests {
         "###);
 
         insta::assert_snapshot!(p(r#"value NULL"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value NULL`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value NULL`.
         1:11 value NULL
         "###);
         insta::assert_snapshot!(p(r#"value NOT NULL"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value NOT NULL`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value NOT NULL`.
         1:15 value NOT NULL
         "###);
         insta::assert_snapshot!(p(r#"value EMPTY"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value EMPTY`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value EMPTY`.
         1:12 value EMPTY
         "###);
         insta::assert_snapshot!(p(r#"value NOT EMPTY"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value NOT EMPTY`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value NOT EMPTY`.
         1:16 value NOT EMPTY
         "###);
         insta::assert_snapshot!(p(r#"value IS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value IS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value IS`.
         1:9 value IS
         "###);
         insta::assert_snapshot!(p(r#"value IS NOT"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value IS NOT`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value IS NOT`.
         1:13 value IS NOT
         "###);
         insta::assert_snapshot!(p(r#"value IS EXISTS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value IS EXISTS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value IS EXISTS`.
         1:16 value IS EXISTS
         "###);
         insta::assert_snapshot!(p(r#"value IS NOT EXISTS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, or `_geoBoundingBox` at `value IS NOT EXISTS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `IS TRUE`, `IS FALSE`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `ENDS WITH`, `NOT ENDS WITH`, `LIKE`, `NOT LIKE`, `MATCHES`, `NOT MATCHES`, `_geoRadius`, `_geoBoundingBox`, or `_geoPolygon` at `value IS NOT EXISTS`.
         1:20 value IS NOT EXISTS
         "###);
     }
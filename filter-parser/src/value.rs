@@ -5,10 +5,11 @@ use nom::combinator::cut;
 use nom::sequence::{delimited, terminated};
 use nom::{InputIter, InputLength, InputTake, Slice};
 
+use crate::date::parse_now;
 use crate::error::{ExpectedValueKind, NomErrorExt};
 use crate::{
-    parse_geo, parse_geo_bounding_box, parse_geo_distance, parse_geo_point, parse_geo_radius,
-    Error, ErrorKind, IResult, Span, Token,
+    parse_geo, parse_geo_bounding_box, parse_geo_distance, parse_geo_point, parse_geo_polygon,
+    parse_geo_radius, Error, ErrorKind, IResult, Span, Token,
 };
 
 /// This function goes through all characters in the [Span] if it finds any escaped character (`\`).
@@ -80,7 +81,7 @@ pub fn word_exact<'a, 'b: 'a>(tag: &'b str) -> impl Fn(Span<'a>) -> IResult<'a,
     }
 }
 
-/// value          = WS* ( word | singleQuoted | doubleQuoted) WS+
+/// value          = WS* ( word | singleQuoted | doubleQuoted | now) WS+
 pub fn parse_value(input: Span) -> IResult<Token> {
     // to get better diagnostic message we are going to strip the left whitespaces from the input right now
     let (input, _) = take_while(char::is_whitespace)(input)?;
@@ -128,6 +129,24 @@ pub fn parse_value(input: Span) -> IResult<Token> {
         _ => (),
     }
 
+    match parse_geo_polygon(input) {
+        Ok(_) => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoPolygon,
+            )))
+        }
+        // if we encountered a failure it means the user badly wrote a _geoPolygon filter.
+        // But instead of showing them how to fix his syntax we are going to tell them they should not use this filter as a value.
+        Err(e) if e.is_failure() => {
+            return Err(nom::Err::Failure(Error::new_from_kind(
+                input,
+                ErrorKind::MisusedGeoPolygon,
+            )))
+        }
+        _ => (),
+    }
+
     // this parser is only used when an error is encountered and it parse the
     // largest string possible that do not contain any “language” syntax.
     // If we try to parse `name = 🦀 AND language = rust` we want to return an
@@ -141,6 +160,7 @@ pub fn parse_value(input: Span) -> IResult<Token> {
         alt((
             delimited(char('\''), cut(|input| quoted_by('\'', input)), cut(char('\''))),
             delimited(char('"'), cut(|input| quoted_by('"', input)), cut(char('"'))),
+            parse_now,
             word_not_keyword,
         )),
         multispace0,
@@ -191,6 +211,32 @@ pub fn parse_value(input: Span) -> IResult<Token> {
     }
 }
 
+/// regex          = WS* "/" .* all but unescaped slashes "/" WS*
+pub fn parse_regex(input: Span) -> IResult<Token> {
+    let (input, _) = take_while(char::is_whitespace)(input)?;
+
+    terminated(
+        delimited(char('/'), cut(|input| quoted_by('/', input)), cut(char('/'))),
+        multispace0,
+    )(input)
+    // if there is no opening slash the user did not write a regex
+    .map_err(|e: nom::Err<Error>| {
+        e.map_err(|_| {
+            let word = take_till::<_, _, Error>(char::is_whitespace)(input).unwrap().1;
+            Error::new_from_kind(word, ErrorKind::ExpectedRegex)
+        })
+    })
+    .map_err(|e| {
+        e.map_fail(|failure| {
+            if matches!(failure.kind(), ErrorKind::Char(_)) {
+                Error::new_from_kind(input, ErrorKind::MissingClosingDelimiter(failure.char()))
+            } else {
+                failure
+            }
+        })
+    })
+}
+
 fn is_value_component(c: char) -> bool {
     c.is_alphanumeric() || ['_', '-', '.'].contains(&c)
 }
@@ -213,9 +259,13 @@ fn is_keyword(s: &str) -> bool {
             | "EMPTY"
             | "CONTAINS"
             | "STARTS"
+            | "ENDS"
             | "WITH"
+            | "LIKE"
+            | "MATCHES"
             | "_geoRadius"
             | "_geoBoundingBox"
+            | "_geoPolygon"
     )
 }
 
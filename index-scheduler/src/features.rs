@@ -94,6 +94,19 @@ impl RoFeatures {
             .into())
         }
     }
+
+    pub fn check_pattern_filter(&self) -> Result<()> {
+        if self.runtime.contains_filter {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Using `ENDS WITH`, `LIKE` or `MATCHES` in a filter",
+                feature: "contains filter",
+                issue_link: "https://github.com/orgs/meilisearch/discussions/763",
+            }
+            .into())
+        }
+    }
 }
 
 impl FeatureData {
@@ -517,10 +517,35 @@ impl IndexScheduler {
             features,
         };
 
+        this.upgrade_facets()?;
         this.run();
         Ok(this)
     }
 
+    /// Extracts again the facets of the indexes that were stored with an older layout of the
+    /// facet databases, before processing any task on them.
+    fn upgrade_facets(&self) -> Result<()> {
+        let rtxn = self.env.read_txn()?;
+        for name in self.index_mapper.index_names(&rtxn)? {
+            let index = self.index_mapper.index(&rtxn, &name)?;
+            if index.facets_version(&index.read_txn()?)? == milli::index::FACETS_VERSION {
+                continue;
+            }
+
+            tracing::info!(index = %name, "Upgrading the facets of the index");
+            let mut index_wtxn = index.write_txn()?;
+            let mut builder = milli::update::Settings::new(
+                &mut index_wtxn,
+                &index,
+                self.index_mapper.indexer_config(),
+            );
+            builder.upgrade_facets();
+            builder.execute(|indexing_step| tracing::debug!(update = ?indexing_step), || false)?;
+            index_wtxn.commit()?;
+        }
+        Ok(())
+    }
+
     /// Return `Ok(())` if the index scheduler is able to access one of its database.
     pub fn health(&self) -> Result<()> {
         let rtxn = self.env.read_txn()?;
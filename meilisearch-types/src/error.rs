@@ -255,14 +255,21 @@ InvalidSearchAttributesToHighlight    , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarAttributesToRetrieve    , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarRetrieveVectors         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToRetrieve     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRankingRules             , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchRankingScoreThreshold    , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarRankingScoreThreshold   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchRetrieveVectors          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropLength               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetBuckets             , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchExtendedFacetStats       , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetHierarchies         , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetFilters             , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSemanticRatio            , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLocales                  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSynonyms                 , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchStopWords                , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarId                      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                   , InvalidRequest       , BAD_REQUEST ;
@@ -278,6 +285,7 @@ InvalidSearchOffset                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchPage                     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchQ                        , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchQuery               , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchFacetParent         , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchName                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchVector                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowMatchesPosition      , InvalidRequest       , BAD_REQUEST ;
@@ -303,8 +311,13 @@ InvalidSettingsNonSeparatorTokens     , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSeparatorTokens        , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDictionary             , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSynonyms               , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsSynonymRules           , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsTypoTolerance          , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsLocalizedAttributes    , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsNestedAttributes       , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsDateAttributes         , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsPhoneticAttributes     , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsRules                  , InvalidRequest       , BAD_REQUEST ;
 InvalidState                          , Internal             , INTERNAL_SERVER_ERROR ;
 InvalidStoreFile                      , Internal             , INTERNAL_SERVER_ERROR ;
 InvalidSwapDuplicateIndexFound        , InvalidRequest       , BAD_REQUEST ;
@@ -408,11 +421,20 @@ impl ErrorCode for milli::Error {
                     UserError::PrimaryKeyCannotBeChanged(_) => Code::IndexPrimaryKeyAlreadyExists,
                     UserError::InvalidDistinctAttribute { .. } => Code::InvalidSearchDistinct,
                     UserError::SortRankingRuleMissing => Code::InvalidSearchSort,
+                    UserError::InvalidSearchRankingRules(_) => Code::InvalidSearchRankingRules,
+                    UserError::TooManySearchSynonyms { .. }
+                    | UserError::TooManySearchSynonymsForWord { .. }
+                    | UserError::SearchSynonymTooLong { .. } => Code::InvalidSearchSynonyms,
+                    UserError::TooManySearchStopWords { .. }
+                    | UserError::SearchStopWordTooLong { .. } => Code::InvalidSearchStopWords,
                     UserError::InvalidFacetsDistribution { .. } => Code::InvalidSearchFacets,
                     UserError::InvalidSortableAttribute { .. } => Code::InvalidSearchSort,
                     UserError::InvalidSearchableAttribute { .. } => {
                         Code::InvalidSearchAttributesToSearchOn
                     }
+                    UserError::MissingSearchableAttributeWeight { .. } => {
+                        Code::InvalidSettingsSearchableAttributes
+                    }
                     UserError::InvalidFacetSearchFacetName { .. } => {
                         Code::InvalidFacetSearchFacetName
                     }
@@ -423,9 +445,12 @@ impl ErrorCode for milli::Error {
                     | UserError::InvalidVectorsEmbedderConf { .. } => Code::InvalidVectorsType,
                     UserError::TooManyVectors(_, _) => Code::TooManyVectors,
                     UserError::SortError(_) => Code::InvalidSearchSort,
-                    UserError::InvalidMinTypoWordLenSetting(_, _) => {
+                    UserError::InvalidMinTypoWordLenSetting(_, _)
+                    | UserError::InvalidMaxTyposPerAttribute { .. }
+                    | UserError::InvalidMaxTyposAttribute { .. } => {
                         Code::InvalidSettingsTypoTolerance
                     }
+                    UserError::InvalidMerchandisingRuleFilter { .. } => Code::InvalidSettingsRules,
                     UserError::InvalidEmbedder(_) => Code::InvalidEmbedder,
                     UserError::VectorEmbeddingError(_) | UserError::DocumentEmbeddingError(_) => {
                         Code::VectorEmbeddingError
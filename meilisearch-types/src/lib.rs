@@ -8,6 +8,7 @@ pub mod index_uid;
 pub mod index_uid_pattern;
 pub mod keys;
 pub mod locales;
+pub mod merchandising;
 pub mod settings;
 pub mod star_or;
 pub mod task_view;
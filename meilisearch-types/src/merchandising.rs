@@ -0,0 +1,133 @@
+use std::convert::Infallible;
+
+use deserr::{DeserializeError, Deserr, ErrorKind, ValuePointerRef};
+use milli::{
+    Filter, MerchandisingActions, MerchandisingCondition, MerchandisingRule, PinnedDocument,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::deserr::DeserrJsonError;
+use crate::error::deserr_codes::InvalidSettingsRules;
+
+fn validate_merchandising_rule<E: DeserializeError>(
+    rule: MerchandisingRuleView,
+    location: ValuePointerRef,
+) -> Result<MerchandisingRuleView, E> {
+    let filters = [
+        ("condition.filter", &rule.condition.filter),
+        ("actions.filter", &rule.actions.filter),
+        ("actions.boost", &rule.actions.boost),
+    ];
+    for (name, filter) in filters {
+        if let Some(Err(error)) = filter.as_deref().map(Filter::from_str) {
+            return Err(deserr::take_cf_content(E::error::<Infallible>(
+                None,
+                ErrorKind::Unexpected { msg: format!("`{name}` is not a valid filter: {error}") },
+                location,
+            )));
+        }
+    }
+    if let Some(pinned) = rule.actions.pin.iter().find(|pinned| pinned.position == 0) {
+        return Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: format!(
+                    "the position of the pinned document `{}` must start at 1, but found 0",
+                    pinned.id
+                ),
+            },
+            location,
+        )));
+    }
+    Ok(rule)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[deserr(rename_all = camelCase, deny_unknown_fields, validate = validate_merchandising_rule -> DeserrJsonError<InvalidSettingsRules>)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingRuleView {
+    #[deserr(default)]
+    pub condition: MerchandisingConditionView,
+    pub actions: MerchandisingActionsView,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingConditionView {
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_equals: Option<String>,
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_contains: Option<String>,
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingActionsView {
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pin: Vec<PinnedDocumentView>,
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hide: Vec<String>,
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[deserr(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedDocumentView {
+    pub id: String,
+    pub position: usize,
+}
+
+impl From<MerchandisingRule> for MerchandisingRuleView {
+    fn from(rule: MerchandisingRule) -> Self {
+        let MerchandisingRule { condition, actions } = rule;
+        let MerchandisingCondition { query_equals, query_contains, filter } = condition;
+        let MerchandisingActions { pin, hide, filter: actions_filter, boost } = actions;
+        Self {
+            condition: MerchandisingConditionView { query_equals, query_contains, filter },
+            actions: MerchandisingActionsView {
+                pin: pin
+                    .into_iter()
+                    .map(|PinnedDocument { id, position }| PinnedDocumentView { id, position })
+                    .collect(),
+                hide,
+                filter: actions_filter,
+                boost,
+            },
+        }
+    }
+}
+
+impl From<MerchandisingRuleView> for MerchandisingRule {
+    fn from(view: MerchandisingRuleView) -> Self {
+        let MerchandisingRuleView { condition, actions } = view;
+        let MerchandisingConditionView { query_equals, query_contains, filter } = condition;
+        let MerchandisingActionsView { pin, hide, filter: actions_filter, boost } = actions;
+        Self {
+            condition: MerchandisingCondition { query_equals, query_contains, filter },
+            actions: MerchandisingActions {
+                pin: pin
+                    .into_iter()
+                    .map(|PinnedDocumentView { id, position }| PinnedDocument { id, position })
+                    .collect(),
+                hide,
+                filter: actions_filter,
+                boost,
+            },
+        }
+    }
+}
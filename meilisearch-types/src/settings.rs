@@ -16,8 +16,10 @@ use serde::{Deserialize, Serialize, Serializer};
 
 use crate::deserr::DeserrJsonError;
 use crate::error::deserr_codes::*;
+use crate::error::ErrorCode;
 use crate::facet_values_sort::FacetValuesSort;
 use crate::locales::LocalizedAttributesRuleView;
+use crate::merchandising::MerchandisingRuleView;
 
 /// The maximum number of results that the engine
 /// will be able to return in one search call.
@@ -97,6 +99,12 @@ pub struct TypoSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     pub disable_on_attributes: Setting<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_typos_per_attribute: Setting<BTreeMap<String, u8>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_typos_per_query: Setting<u8>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
@@ -120,7 +128,7 @@ pub struct PaginationSettings {
     pub max_total_hits: Setting<usize>,
 }
 
-impl MergeWithError<milli::CriterionError> for DeserrJsonError<InvalidSettingsRankingRules> {
+impl<C: Default + ErrorCode> MergeWithError<milli::CriterionError> for DeserrJsonError<C> {
     fn merge(
         _self_: Option<Self>,
         other: milli::CriterionError,
@@ -151,7 +159,7 @@ pub struct Settings<T> {
 
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSearchableAttributes>)]
-    pub searchable_attributes: WildcardSetting,
+    pub searchable_attributes: SearchableAttributesSetting,
 
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsFilterableAttributes>)]
@@ -177,6 +185,10 @@ pub struct Settings<T> {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSynonyms>)]
     pub synonyms: Setting<BTreeMap<String, Vec<String>>>,
+    /// The synonym rules, whose keys can be lists of equivalent words, one-way rules or placeholders.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsSynonymRules>)]
+    pub synonym_rules: Setting<BTreeMap<String, Vec<String>>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsDistinctAttribute>)]
     pub distinct_attribute: Setting<String>,
@@ -202,6 +214,24 @@ pub struct Settings<T> {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsLocalizedAttributes>)]
     pub localized_attributes: Setting<Vec<LocalizedAttributesRuleView>>,
+    /// The arrays of objects whose objects must each match all the conditions of a filter about
+    /// them, and whose facet values are only counted in the objects matching these conditions.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsNestedAttributes>)]
+    pub nested_attributes: Setting<BTreeSet<String>>,
+    /// The attributes whose RFC 3339 dates are filtered and sorted chronologically.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsDateAttributes>)]
+    pub date_attributes: Setting<BTreeSet<String>>,
+    /// The attributes whose words can be found by the query words sounding alike.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsPhoneticAttributes>)]
+    pub phonetic_attributes: Setting<BTreeSet<String>>,
+    /// The merchandising rules pinning, hiding, filtering or boosting documents in the searches
+    /// matching their condition.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsRules>)]
+    pub rules: Setting<Vec<MerchandisingRuleView>>,
 
     #[serde(skip)]
     #[deserr(skip)]
@@ -255,6 +285,7 @@ impl Settings<Checked> {
             ranking_rules: Setting::Reset,
             stop_words: Setting::Reset,
             synonyms: Setting::Reset,
+            synonym_rules: Setting::Reset,
             non_separator_tokens: Setting::Reset,
             separator_tokens: Setting::Reset,
             dictionary: Setting::Reset,
@@ -266,6 +297,10 @@ impl Settings<Checked> {
             embedders: Setting::Reset,
             search_cutoff_ms: Setting::Reset,
             localized_attributes: Setting::Reset,
+            nested_attributes: Setting::Reset,
+            date_attributes: Setting::Reset,
+            phonetic_attributes: Setting::Reset,
+            rules: Setting::Reset,
             _kind: PhantomData,
         }
     }
@@ -282,6 +317,7 @@ impl Settings<Checked> {
             separator_tokens,
             dictionary,
             synonyms,
+            synonym_rules,
             distinct_attribute,
             proximity_precision,
             typo_tolerance,
@@ -290,6 +326,10 @@ impl Settings<Checked> {
             embedders,
             search_cutoff_ms,
             localized_attributes: localized_attributes_rules,
+            nested_attributes,
+            date_attributes,
+            phonetic_attributes,
+            rules,
             _kind,
         } = self;
 
@@ -304,6 +344,7 @@ impl Settings<Checked> {
             separator_tokens,
             dictionary,
             synonyms,
+            synonym_rules,
             distinct_attribute,
             proximity_precision,
             typo_tolerance,
@@ -312,6 +353,10 @@ impl Settings<Checked> {
             embedders,
             search_cutoff_ms,
             localized_attributes: localized_attributes_rules,
+            nested_attributes,
+            date_attributes,
+            phonetic_attributes,
+            rules,
             _kind: PhantomData,
         }
     }
@@ -332,7 +377,7 @@ impl Settings<Unchecked> {
 
         let searchable_attributes = match self.searchable_attributes.0 {
             Setting::Set(fields) => {
-                if fields.iter().any(|f| f == "*") {
+                if fields.iter().any(|f| f.name() == "*") {
                     Setting::Reset
                 } else {
                     Setting::Set(fields)
@@ -349,6 +394,7 @@ impl Settings<Unchecked> {
             ranking_rules: self.ranking_rules,
             stop_words: self.stop_words,
             synonyms: self.synonyms,
+            synonym_rules: self.synonym_rules,
             non_separator_tokens: self.non_separator_tokens,
             separator_tokens: self.separator_tokens,
             dictionary: self.dictionary,
@@ -360,12 +406,33 @@ impl Settings<Unchecked> {
             embedders: self.embedders,
             search_cutoff_ms: self.search_cutoff_ms,
             localized_attributes: self.localized_attributes,
+            nested_attributes: self.nested_attributes,
+            date_attributes: self.date_attributes,
+            phonetic_attributes: self.phonetic_attributes,
+            rules: self.rules,
             _kind: PhantomData,
         }
     }
 
     pub fn validate(self) -> Result<Self, milli::Error> {
-        self.validate_embedding_settings()
+        self.validate_searchable_attributes()?.validate_embedding_settings()
+    }
+
+    /// The searchable attributes must either all have a weight, or none of them.
+    fn validate_searchable_attributes(self) -> Result<Self, milli::Error> {
+        if let Setting::Set(attributes) = self.searchable_attributes.deref() {
+            if attributes.iter().any(|attribute| attribute.weight().is_some()) {
+                if let Some(attribute) =
+                    attributes.iter().find(|attribute| attribute.weight().is_none())
+                {
+                    return Err(milli::UserError::MissingSearchableAttributeWeight {
+                        attribute: attribute.name().to_string(),
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(self)
     }
 
     fn validate_embedding_settings(mut self) -> Result<Self, milli::Error> {
@@ -403,6 +470,7 @@ pub fn apply_settings_to_builder(
         separator_tokens,
         dictionary,
         synonyms,
+        synonym_rules,
         distinct_attribute,
         proximity_precision,
         typo_tolerance,
@@ -411,11 +479,30 @@ pub fn apply_settings_to_builder(
         embedders,
         search_cutoff_ms,
         localized_attributes: localized_attributes_rules,
+        nested_attributes,
+        date_attributes,
+        phonetic_attributes,
+        rules,
         _kind,
     } = settings;
 
     match searchable_attributes.deref() {
-        Setting::Set(ref names) => builder.set_searchable_fields(names.clone()),
+        Setting::Set(ref attributes) => {
+            if attributes.iter().any(|attribute| attribute.weight().is_some()) {
+                builder.set_weighted_searchable_fields(
+                    attributes
+                        .iter()
+                        .map(|attribute| {
+                            (attribute.name().to_string(), attribute.weight().unwrap_or_default())
+                        })
+                        .collect(),
+                )
+            } else {
+                builder.set_searchable_fields(
+                    attributes.iter().map(|attribute| attribute.name().to_string()).collect(),
+                )
+            }
+        }
         Setting::Reset => builder.reset_searchable_fields(),
         Setting::NotSet => (),
     }
@@ -482,6 +569,12 @@ pub fn apply_settings_to_builder(
         Setting::NotSet => (),
     }
 
+    match synonym_rules {
+        Setting::Set(ref rules) => builder.set_synonym_rules(rules.clone()),
+        Setting::Reset => builder.reset_synonym_rules(),
+        Setting::NotSet => (),
+    }
+
     match distinct_attribute {
         Setting::Set(ref attr) => builder.set_distinct_field(attr.clone()),
         Setting::Reset => builder.reset_distinct_field(),
@@ -544,6 +637,20 @@ pub fn apply_settings_to_builder(
                 Setting::Reset => builder.reset_exact_attributes(),
                 Setting::NotSet => (),
             }
+
+            match value.max_typos_per_attribute {
+                Setting::Set(ref max_typos) => {
+                    builder.set_max_typos_per_attribute(max_typos.clone())
+                }
+                Setting::Reset => builder.reset_max_typos_per_attribute(),
+                Setting::NotSet => (),
+            }
+
+            match value.max_typos_per_query {
+                Setting::Set(val) => builder.set_max_typos_per_query(val),
+                Setting::Reset => builder.reset_max_typos_per_query(),
+                Setting::NotSet => (),
+            }
         }
         Setting::Reset => {
             // all typo settings need to be reset here.
@@ -552,6 +659,8 @@ pub fn apply_settings_to_builder(
             builder.reset_min_word_len_two_typos();
             builder.reset_exact_words();
             builder.reset_exact_attributes();
+            builder.reset_max_typos_per_attribute();
+            builder.reset_max_typos_per_query();
         }
         Setting::NotSet => (),
     }
@@ -599,6 +708,32 @@ pub fn apply_settings_to_builder(
         Setting::Reset => builder.reset_search_cutoff(),
         Setting::NotSet => (),
     }
+
+    match nested_attributes {
+        Setting::Set(fields) => builder.set_nested_fields(fields.clone()),
+        Setting::Reset => builder.reset_nested_fields(),
+        Setting::NotSet => (),
+    }
+
+    match date_attributes {
+        Setting::Set(fields) => builder.set_date_fields(fields.clone()),
+        Setting::Reset => builder.reset_date_fields(),
+        Setting::NotSet => (),
+    }
+
+    match phonetic_attributes {
+        Setting::Set(fields) => builder.set_phonetic_fields(fields.clone()),
+        Setting::Reset => builder.reset_phonetic_fields(),
+        Setting::NotSet => (),
+    }
+
+    match rules {
+        Setting::Set(rules) => {
+            builder.set_merchandising_rules(rules.iter().cloned().map(Into::into).collect())
+        }
+        Setting::Reset => builder.reset_merchandising_rules(),
+        Setting::NotSet => (),
+    }
 }
 
 pub enum SecretPolicy {
@@ -614,9 +749,19 @@ pub fn settings(
     let displayed_attributes =
         index.displayed_fields(rtxn)?.map(|fields| fields.into_iter().map(String::from).collect());
 
-    let searchable_attributes = index
-        .user_defined_searchable_fields(rtxn)?
-        .map(|fields| fields.into_iter().map(String::from).collect());
+    let searchable_weights = index.user_defined_searchable_fields_weights(rtxn)?;
+    let searchable_attributes = index.user_defined_searchable_fields(rtxn)?.map(|fields| {
+        fields
+            .into_iter()
+            .map(|field| match searchable_weights.as_ref().and_then(|weights| weights.get(field)) {
+                Some(&weight) => SearchableAttribute::Weighted(WeightedSearchableAttribute {
+                    attribute: field.to_string(),
+                    weight,
+                }),
+                None => SearchableAttribute::Name(field.to_string()),
+            })
+            .collect()
+    });
 
     let filterable_attributes = index.filterable_fields(rtxn)?.into_iter().collect();
 
@@ -642,6 +787,10 @@ pub fn settings(
 
     let synonyms = index.user_defined_synonyms(rtxn)?;
 
+    let synonym_rules = index.user_defined_synonym_rules(rtxn)?;
+    let synonym_rules =
+        if synonym_rules.is_empty() { Setting::NotSet } else { Setting::Set(synonym_rules) };
+
     let min_typo_word_len = MinWordSizeTyposSetting {
         one_typo: Setting::Set(index.min_word_len_one_typo(rtxn)?),
         two_typos: Setting::Set(index.min_word_len_two_typos(rtxn)?),
@@ -654,11 +803,22 @@ pub fn settings(
 
     let disabled_attributes = index.exact_attributes(rtxn)?.into_iter().map(String::from).collect();
 
+    let max_typos_per_attribute = index.max_typos_per_attribute(rtxn)?;
+
     let typo_tolerance = TypoSettings {
         enabled: Setting::Set(index.authorize_typos(rtxn)?),
         min_word_size_for_typos: Setting::Set(min_typo_word_len),
         disable_on_words: Setting::Set(disabled_words),
         disable_on_attributes: Setting::Set(disabled_attributes),
+        max_typos_per_attribute: if max_typos_per_attribute.is_empty() {
+            Setting::NotSet
+        } else {
+            Setting::Set(max_typos_per_attribute)
+        },
+        max_typos_per_query: match index.max_typos_per_query(rtxn)? {
+            Some(max_typos) => Setting::Set(max_typos),
+            None => Setting::NotSet,
+        },
     };
 
     let faceting = FacetingSettings {
@@ -697,6 +857,31 @@ pub fn settings(
 
     let localized_attributes_rules = index.localized_attributes_rules(rtxn)?;
 
+    let nested_attributes = index.nested_fields(rtxn)?;
+    let nested_attributes = if nested_attributes.is_empty() {
+        Setting::NotSet
+    } else {
+        Setting::Set(nested_attributes)
+    };
+
+    let date_attributes = index.date_fields(rtxn)?;
+    let date_attributes =
+        if date_attributes.is_empty() { Setting::NotSet } else { Setting::Set(date_attributes) };
+
+    let phonetic_attributes = index.phonetic_fields(rtxn)?;
+    let phonetic_attributes = if phonetic_attributes.is_empty() {
+        Setting::NotSet
+    } else {
+        Setting::Set(phonetic_attributes)
+    };
+
+    let rules = index.merchandising_rules(rtxn)?;
+    let rules = if rules.is_empty() {
+        Setting::NotSet
+    } else {
+        Setting::Set(rules.into_iter().map(Into::into).collect())
+    };
+
     let mut settings = Settings {
         displayed_attributes: match displayed_attributes {
             Some(attrs) => Setting::Set(attrs),
@@ -721,6 +906,7 @@ pub fn settings(
         },
         proximity_precision: Setting::Set(proximity_precision.unwrap_or_default()),
         synonyms: Setting::Set(synonyms),
+        synonym_rules,
         typo_tolerance: Setting::Set(typo_tolerance),
         faceting: Setting::Set(faceting),
         pagination: Setting::Set(pagination),
@@ -733,6 +919,10 @@ pub fn settings(
             Some(rules) => Setting::Set(rules.into_iter().map(|r| r.into()).collect()),
             None => Setting::Reset,
         },
+        nested_attributes,
+        date_attributes,
+        phonetic_attributes,
+        rules,
         _kind: PhantomData,
     };
 
@@ -749,6 +939,9 @@ pub enum RankingRuleView {
     /// Sorted by decreasing number of matched query terms.
     /// Query words at the front of an attribute is considered better than if it was at the back.
     Words,
+    /// Sorted by decreasing rarity of the matched query terms, a document matching the terms
+    /// appearing in the fewest documents is considered better. Replaces the `words` rule.
+    TermRarity,
     /// Sorted by increasing number of typos.
     Typo,
     /// Sorted by increasing distance between matched query terms.
@@ -765,6 +958,14 @@ pub enum RankingRuleView {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the decreasing value of the expression specified,
+    /// computed from the number values of the fields of the documents.
+    Score(String),
+    /// Sorted by the decreasing closeness of the value of a field to an origin,
+    /// the arguments of the decay function are specified.
+    Decay(String),
+    /// The documents matching the filter specified are ranked before the others.
+    Boost(String),
 }
 impl Serialize for RankingRuleView {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -814,6 +1015,7 @@ impl From<Criterion> for RankingRuleView {
     fn from(value: Criterion) -> Self {
         match value {
             Criterion::Words => RankingRuleView::Words,
+            Criterion::TermRarity => RankingRuleView::TermRarity,
             Criterion::Typo => RankingRuleView::Typo,
             Criterion::Proximity => RankingRuleView::Proximity,
             Criterion::Attribute => RankingRuleView::Attribute,
@@ -821,6 +1023,9 @@ impl From<Criterion> for RankingRuleView {
             Criterion::Exactness => RankingRuleView::Exactness,
             Criterion::Asc(x) => RankingRuleView::Asc(x),
             Criterion::Desc(x) => RankingRuleView::Desc(x),
+            Criterion::Score(x) => RankingRuleView::Score(x),
+            Criterion::Decay(x) => RankingRuleView::Decay(x),
+            Criterion::Boost(x) => RankingRuleView::Boost(x),
         }
     }
 }
@@ -828,6 +1033,7 @@ impl From<RankingRuleView> for Criterion {
     fn from(value: RankingRuleView) -> Self {
         match value {
             RankingRuleView::Words => Criterion::Words,
+            RankingRuleView::TermRarity => Criterion::TermRarity,
             RankingRuleView::Typo => Criterion::Typo,
             RankingRuleView::Proximity => Criterion::Proximity,
             RankingRuleView::Attribute => Criterion::Attribute,
@@ -835,6 +1041,9 @@ impl From<RankingRuleView> for Criterion {
             RankingRuleView::Exactness => Criterion::Exactness,
             RankingRuleView::Asc(x) => Criterion::Asc(x),
             RankingRuleView::Desc(x) => Criterion::Desc(x),
+            RankingRuleView::Score(x) => Criterion::Score(x),
+            RankingRuleView::Decay(x) => Criterion::Decay(x),
+            RankingRuleView::Boost(x) => Criterion::Boost(x),
         }
     }
 }
@@ -900,6 +1109,110 @@ impl std::ops::Deref for WildcardSetting {
     }
 }
 
+/// An entry of the `searchableAttributes` setting, either the name of an attribute
+/// or an attribute with its weight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SearchableAttribute {
+    Name(String),
+    Weighted(WeightedSearchableAttribute),
+}
+
+/// The higher the weight of an attribute, the more important it is.
+/// Attributes with the same weight are equally important.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct WeightedSearchableAttribute {
+    pub attribute: String,
+    pub weight: milli::Weight,
+}
+
+impl SearchableAttribute {
+    pub fn name(&self) -> &str {
+        match self {
+            SearchableAttribute::Name(name) => name,
+            SearchableAttribute::Weighted(weighted) => &weighted.attribute,
+        }
+    }
+
+    pub fn weight(&self) -> Option<milli::Weight> {
+        match self {
+            SearchableAttribute::Name(_) => None,
+            SearchableAttribute::Weighted(weighted) => Some(weighted.weight),
+        }
+    }
+}
+
+impl From<String> for SearchableAttribute {
+    fn from(name: String) -> Self {
+        SearchableAttribute::Name(name)
+    }
+}
+
+impl<E: DeserializeError> Deserr<E> for SearchableAttribute {
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        match value {
+            deserr::Value::String(name) => Ok(SearchableAttribute::Name(name)),
+            deserr::Value::Map(_) => Ok(SearchableAttribute::Weighted(
+                WeightedSearchableAttribute::deserialize_from_value(value, location)?,
+            )),
+            value => Err(deserr::take_cf_content(E::error(
+                None,
+                ErrorKind::IncorrectValueKind {
+                    actual: value,
+                    accepted: &[deserr::ValueKind::String, deserr::ValueKind::Map],
+                },
+                location,
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct SearchableAttributesSetting(Setting<Vec<SearchableAttribute>>);
+
+impl From<Setting<Vec<SearchableAttribute>>> for SearchableAttributesSetting {
+    fn from(setting: Setting<Vec<SearchableAttribute>>) -> Self {
+        Self(setting)
+    }
+}
+
+impl Serialize for SearchableAttributesSetting {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let wildcard = vec![SearchableAttribute::Name("*".to_string())];
+        match &self.0 {
+            Setting::Set(value) => Some(value),
+            Setting::Reset => Some(&wildcard),
+            Setting::NotSet => None,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<E: deserr::DeserializeError> Deserr<E> for SearchableAttributesSetting {
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        Ok(Self(Setting::deserialize_from_value(value, location)?))
+    }
+}
+
+impl std::ops::Deref for SearchableAttributesSetting {
+    type Target = Setting<Vec<SearchableAttribute>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
@@ -909,7 +1222,7 @@ pub(crate) mod test {
         // test no changes
         let settings = Settings {
             displayed_attributes: Setting::Set(vec![String::from("hello")]).into(),
-            searchable_attributes: Setting::Set(vec![String::from("hello")]).into(),
+            searchable_attributes: Setting::Set(vec![String::from("hello").into()]).into(),
             filterable_attributes: Setting::NotSet,
             sortable_attributes: Setting::NotSet,
             ranking_rules: Setting::NotSet,
@@ -918,6 +1231,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_rules: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -926,6 +1240,10 @@ pub(crate) mod test {
             embedders: Setting::NotSet,
             localized_attributes: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            nested_attributes: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            phonetic_attributes: Setting::NotSet,
+            rules: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -937,8 +1255,11 @@ pub(crate) mod test {
         // test no changes
         let settings = Settings {
             displayed_attributes: Setting::Set(vec![String::from("*")]).into(),
-            searchable_attributes: Setting::Set(vec![String::from("hello"), String::from("*")])
-                .into(),
+            searchable_attributes: Setting::Set(vec![
+                String::from("hello").into(),
+                String::from("*").into(),
+            ])
+            .into(),
             filterable_attributes: Setting::NotSet,
             sortable_attributes: Setting::NotSet,
             ranking_rules: Setting::NotSet,
@@ -947,6 +1268,7 @@ pub(crate) mod test {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_rules: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
@@ -955,6 +1277,10 @@ pub(crate) mod test {
             embedders: Setting::NotSet,
             localized_attributes: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            nested_attributes: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            phonetic_attributes: Setting::NotSet,
+            rules: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -700,13 +700,20 @@ impl SearchAggregator {
             show_ranking_score_details,
             filter,
             sort,
+            ranking_rules: _,
             distinct,
             facets: _,
+            facet_buckets: _,
+            extended_facet_stats: _,
+            facet_hierarchies: _,
+            facet_filters: _,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
             matching_strategy,
             attributes_to_search_on,
+            synonyms: _,
+            stop_words: _,
             hybrid,
             ranking_score_threshold,
             locales,
@@ -808,6 +815,8 @@ impl SearchAggregator {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            facet_buckets: _,
+            facet_hierarchies: _,
             degraded,
             used_negative_operator,
         } = result;
@@ -1149,13 +1158,20 @@ impl MultiSearchAggregator {
                     show_matches_position: _,
                     filter: _,
                     sort: _,
+                    ranking_rules: _,
                     distinct: _,
                     facets: _,
+                    facet_buckets: _,
+                    extended_facet_stats: _,
+                    facet_hierarchies: _,
+                    facet_filters: _,
                     highlight_pre_tag: _,
                     highlight_post_tag: _,
                     crop_marker: _,
                     matching_strategy: _,
                     attributes_to_search_on: _,
+                    synonyms: _,
+                    stop_words: _,
                     hybrid: _,
                     ranking_score_threshold: _,
                     locales: _,
@@ -1309,6 +1325,7 @@ impl FacetSearchAggregator {
         let FacetSearchQuery {
             facet_query: _,
             facet_name,
+            facet_parent,
             vector,
             q,
             filter,
@@ -1327,6 +1344,7 @@ impl FacetSearchAggregator {
         ret.facet_names = Some(facet_name.clone()).into_iter().collect();
 
         ret.additional_search_parameters_provided = q.is_some()
+            || facet_parent.is_some()
             || vector.is_some()
             || filter.is_some()
             || *matching_strategy != MatchingStrategy::default()
@@ -15,9 +15,10 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_facet_search, HybridQuery, MatchingStrategy, RankingScoreThreshold,
-    SearchQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
+    add_search_rules, perform_facet_search, FacetParentQuery, HybridQuery, MatchingStrategy,
+    RankingScoreThreshold, SearchQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET,
 };
 use crate::search_queue::SearchQueue;
 
@@ -35,6 +36,8 @@ pub struct FacetSearchQuery {
     pub facet_query: Option<String>,
     #[deserr(error = DeserrJsonError<InvalidFacetSearchFacetName>, missing_field_error = DeserrJsonError::missing_facet_search_facet_name)]
     pub facet_name: String,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchFacetParent>)]
+    pub facet_parent: Option<FacetParentQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
@@ -70,6 +73,7 @@ pub async fn search(
 
     let facet_query = query.facet_query.clone();
     let facet_name = query.facet_name.clone();
+    let facet_parent = query.facet_parent.clone();
     let locales = query.locales.clone().map(|l| l.into_iter().map(Into::into).collect());
     let mut search_query = SearchQuery::from(query);
 
@@ -88,6 +92,7 @@ pub async fn search(
             search_query,
             facet_query,
             facet_name,
+            facet_parent,
             search_kind,
             index_scheduler.features(),
             locales,
@@ -113,6 +118,7 @@ impl From<FacetSearchQuery> for SearchQuery {
         let FacetSearchQuery {
             facet_query: _,
             facet_name: _,
+            facet_parent: _,
             q,
             vector,
             filter,
@@ -139,14 +145,21 @@ impl From<FacetSearchQuery> for SearchQuery {
             show_ranking_score_details: false,
             filter,
             sort: None,
+            ranking_rules: None,
             distinct: None,
             facets: None,
+            facet_buckets: None,
+            extended_facet_stats: false,
+            facet_hierarchies: None,
+            facet_filters: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
             matching_strategy,
             vector,
             attributes_to_search_on,
+            synonyms: None,
+            stop_words: None,
             hybrid,
             ranking_score_threshold,
             locales,
@@ -7,7 +7,10 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::facet_values_sort::FacetValuesSort;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::Setting;
-use meilisearch_types::settings::{settings, RankingRuleView, SecretPolicy, Settings, Unchecked};
+use meilisearch_types::milli::Filter;
+use meilisearch_types::settings::{
+    settings, RankingRuleView, SearchableAttribute, SecretPolicy, Settings, Unchecked,
+};
 use meilisearch_types::tasks::KindWithContent;
 use serde_json::json;
 use tracing::debug;
@@ -16,6 +19,7 @@ use crate::analytics::Analytics;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
+use crate::search::check_filter_features;
 use crate::Opt;
 
 #[macro_export]
@@ -277,14 +281,14 @@ make_setting_route!(
 make_setting_route!(
     "/searchable-attributes",
     put,
-    Vec<String>,
+    Vec<SearchableAttribute>,
     meilisearch_types::deserr::DeserrJsonError<
         meilisearch_types::error::deserr_codes::InvalidSettingsSearchableAttributes,
     >,
     searchable_attributes,
     "searchableAttributes",
     analytics,
-    |setting: &Option<Vec<String>>, req: &HttpRequest| {
+    |setting: &Option<Vec<SearchableAttribute>>, req: &HttpRequest| {
         use serde_json::json;
 
         analytics.publish(
@@ -292,7 +296,8 @@ make_setting_route!(
             json!({
                 "searchable_attributes": {
                     "total": setting.as_ref().map(|searchable| searchable.len()),
-                    "with_wildcard": setting.as_ref().map(|searchable| searchable.iter().any(|searchable| searchable == "*")),
+                    "with_wildcard": setting.as_ref().map(|searchable| searchable.iter().any(|searchable| searchable.name() == "*")),
+                    "with_weights": setting.as_ref().map(|searchable| searchable.iter().any(|searchable| searchable.weight().is_some())),
                 },
             }),
             Some(req),
@@ -425,6 +430,31 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/synonym-rules",
+    put,
+    std::collections::BTreeMap<String, Vec<String>>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsSynonymRules,
+    >,
+    synonym_rules,
+    "synonymRules",
+    analytics,
+    |rules: &Option<std::collections::BTreeMap<String, Vec<String>>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "SynonymRules Updated".to_string(),
+            json!({
+                "synonym_rules": {
+                    "total": rules.as_ref().map(|rules| rules.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
 make_setting_route!(
     "/distinct-attribute",
     put,
@@ -679,6 +709,108 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/nested-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsNestedAttributes,
+    >,
+    nested_attributes,
+    "nestedAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "NestedAttributes Updated".to_string(),
+            json!({
+                "nested_attributes": {
+                    "total": setting.as_ref().map(|nested| nested.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/date-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsDateAttributes,
+    >,
+    date_attributes,
+    "dateAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "DateAttributes Updated".to_string(),
+            json!({
+                "date_attributes": {
+                    "total": setting.as_ref().map(|dates| dates.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/phonetic-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsPhoneticAttributes,
+    >,
+    phonetic_attributes,
+    "phoneticAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "PhoneticAttributes Updated".to_string(),
+            json!({
+                "phonetic_attributes": {
+                    "total": setting.as_ref().map(|phonetic| phonetic.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/rules",
+    put,
+    Vec<meilisearch_types::merchandising::MerchandisingRuleView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsRules,
+    >,
+    rules,
+    "rules",
+    analytics,
+    |setting: &Option<Vec<meilisearch_types::merchandising::MerchandisingRuleView>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "Rules Updated".to_string(),
+            json!({
+                "rules": {
+                    "total": setting.as_ref().map(|rules| rules.len()),
+                    "pinned_documents": setting.as_ref().map(|rules| rules.iter().map(|rule| rule.actions.pin.len()).sum::<usize>()),
+                    "hidden_documents": setting.as_ref().map(|rules| rules.iter().map(|rule| rule.actions.hide.len()).sum::<usize>()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
 macro_rules! generate_configure {
     ($($mod:ident),*) => {
         pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -706,12 +838,17 @@ generate_configure!(
     non_separator_tokens,
     dictionary,
     synonyms,
+    synonym_rules,
     ranking_rules,
     typo_tolerance,
     pagination,
     faceting,
     embedders,
-    search_cutoff_ms
+    search_cutoff_ms,
+    nested_attributes,
+    date_attributes,
+    phonetic_attributes,
+    rules
 );
 
 pub async fn update_all(
@@ -742,7 +879,8 @@ pub async fn update_all(
             },
             "searchable_attributes": {
                 "total": new_settings.searchable_attributes.as_ref().set().map(|searchable| searchable.len()),
-                "with_wildcard": new_settings.searchable_attributes.as_ref().set().map(|searchable| searchable.iter().any(|searchable| searchable == "*")),
+                "with_wildcard": new_settings.searchable_attributes.as_ref().set().map(|searchable| searchable.iter().any(|searchable| searchable.name() == "*")),
+                "with_weights": new_settings.searchable_attributes.as_ref().set().map(|searchable| searchable.iter().any(|searchable| searchable.weight().is_some())),
             },
             "displayed_attributes": {
                 "total": new_settings.displayed_attributes.as_ref().set().map(|displayed| displayed.len()),
@@ -822,8 +960,23 @@ pub async fn update_all(
             "synonyms": {
                 "total": new_settings.synonyms.as_ref().set().map(|synonyms| synonyms.len()),
             },
+            "synonym_rules": {
+                "total": new_settings.synonym_rules.as_ref().set().map(|rules| rules.len()),
+            },
             "embedders": crate::routes::indexes::settings::embedder_analytics(new_settings.embedders.as_ref().set()),
             "search_cutoff_ms": new_settings.search_cutoff_ms.as_ref().set(),
+            "nested_attributes": {
+                "total": new_settings.nested_attributes.as_ref().set().map(|nested| nested.len()),
+            },
+            "date_attributes": {
+                "total": new_settings.date_attributes.as_ref().set().map(|dates| dates.len()),
+            },
+            "phonetic_attributes": {
+                "total": new_settings.phonetic_attributes.as_ref().set().map(|phonetic| phonetic.len()),
+            },
+            "rules": {
+                "total": new_settings.rules.as_ref().set().map(|rules| rules.len()),
+            },
             "locales": new_settings.localized_attributes.as_ref().set().map(|rules| rules.iter().flat_map(|rule| rule.locales.iter().cloned()).collect::<std::collections::BTreeSet<_>>()),
         }),
         Some(&req),
@@ -897,5 +1050,21 @@ fn validate_settings(
     if matches!(settings.embedders, Setting::Set(_)) {
         index_scheduler.features().check_vector("Passing `embedders` in settings")?
     }
+
+    let boosts =
+        settings.ranking_rules.as_ref().set().into_iter().flatten().filter_map(|rule| match rule {
+            RankingRuleView::Boost(filter) => Some(filter),
+            _ => None,
+        });
+    let rules = settings.rules.as_ref().set().into_iter().flatten().flat_map(|rule| {
+        [&rule.condition.filter, &rule.actions.filter, &rule.actions.boost].into_iter().flatten()
+    });
+    for filter in boosts.chain(rules) {
+        // the syntax of the filters is validated with the rest of the settings
+        if let Ok(Some(filter)) = Filter::from_str(filter) {
+            check_filter_features(&filter, index_scheduler.features())?;
+        }
+    }
+
     Ok(settings.validate()?)
 }
@@ -23,10 +23,12 @@ use meilisearch_types::milli::{self, DocumentId, OrderBy, TimeBudget};
 use roaring::RoaringBitmap;
 use serde::Serialize;
 
+use super::merchandising::MatchedRules;
 use super::ranking_rules::{self, RankingRules};
 use super::{
-    compute_facet_distribution_stats, prepare_search, AttributesFormat, ComputedFacets, FacetStats,
-    HitMaker, HitsInfo, RetrieveVectors, SearchHit, SearchKind, SearchQuery, SearchQueryWithIndex,
+    compute_facet_distribution_stats, prepare_search, stringify_distribution, AttributesFormat,
+    ComputedFacets, FacetStats, HitMaker, HitsInfo, RetrieveVectors, SearchHit, SearchKind,
+    SearchQuery, SearchQueryWithIndex,
 };
 use crate::error::MeilisearchHttpError;
 use crate::routes::indexes::search::search_kind;
@@ -221,11 +223,20 @@ impl<'a> WeightedScore<'a> {
                         }
                     }
                 }
+                (Some(ScoreValue::FunctionScore(left)), Some(ScoreValue::FunctionScore(right))) => {
+                    match left.partial_cmp(right) {
+                        Some(Ordering::Equal) => continue,
+                        Some(order) => return order,
+                        None => return self.compare_weighted_global_scores(other),
+                    }
+                }
                 // not comparable details, use global
                 (Some(ScoreValue::Score(_)), Some(_))
                 | (Some(_), Some(ScoreValue::Score(_)))
                 | (Some(ScoreValue::GeoSort(_)), Some(ScoreValue::Sort(_)))
-                | (Some(ScoreValue::Sort(_)), Some(ScoreValue::GeoSort(_))) => {
+                | (Some(ScoreValue::Sort(_)), Some(ScoreValue::GeoSort(_)))
+                | (Some(ScoreValue::FunctionScore(_)), Some(_))
+                | (Some(_), Some(ScoreValue::FunctionScore(_))) => {
                     let left_count = left_it.count();
                     let right_count = right_it.count();
                     // compare how many remaining groups of rules each side has.
@@ -420,6 +431,7 @@ impl FederatedFacets {
             let order_by = facet_order.get(facet).map(|(_, order)| *order).unwrap_or_default();
 
             match order_by {
+                // the numbers first, then the booleans and the strings, like an index does
                 OrderBy::Lexicographic => {
                     values.sort_unstable_by(|left, _, right, _| left.cmp(right))
                 }
@@ -437,7 +449,12 @@ impl FederatedFacets {
             };
         }
 
-        Some(ComputedFacets { distribution, stats })
+        Some(ComputedFacets {
+            distribution,
+            stats,
+            buckets: Default::default(),
+            hierarchies: Default::default(),
+        })
     }
 }
 
@@ -474,6 +491,14 @@ pub fn perform_federated_search(
             .into());
         }
 
+        if federated_query.has_facet_buckets().is_some() {
+            return Err(MeilisearchHttpError::FacetBucketsInFederatedQuery(query_index).into());
+        }
+
+        if federated_query.has_facet_hierarchies().is_some() {
+            return Err(MeilisearchHttpError::FacetHierarchiesInFederatedQuery(query_index).into());
+        }
+
         let (index_uid, query, federation_options) = federated_query.into_index_query_federation();
 
         queries_by_index.entry(index_uid.into_inner()).or_default().push(QueryByIndex {
@@ -556,19 +581,15 @@ pub fn perform_federated_search(
         // 2.1. Compute all candidates for each query in the index
         let mut results_by_query = Vec::with_capacity(queries.len());
 
-        for QueryByIndex { query, federation_options, query_index } in queries {
+        for QueryByIndex { mut query, federation_options, query_index } in queries {
             // use an immediately invoked lambda to capture the result without returning from the function
 
             let res: Result<(), ResponseError> = (|| {
                 let search_kind = search_kind(&query, index_scheduler, &index, features)?;
+                let rules = MatchedRules::apply(&index, &rtxn, &mut query, features)?;
 
-                let canonicalization_kind = match (&search_kind, &query.q) {
-                    (SearchKind::SemanticOnly { .. }, _) => {
-                        ranking_rules::CanonicalizationKind::Vector
-                    }
-                    (_, Some(q)) if !q.is_empty() => ranking_rules::CanonicalizationKind::Keyword,
-                    _ => ranking_rules::CanonicalizationKind::Placeholder,
-                };
+                let canonicalization_kind =
+                    ranking_rules::CanonicalizationKind::new(&search_kind, query.q.as_deref());
 
                 let sort = if let Some(sort) = &query.sort {
                     let sorts: Vec<_> =
@@ -586,8 +607,16 @@ pub fn perform_federated_search(
                     None
                 };
 
+                // the ranking rules of the query override the ones of the index
+                let criteria = match &query.ranking_rules {
+                    Some(ranking_rules) => {
+                        ranking_rules.iter().cloned().map(milli::Criterion::from).collect()
+                    }
+                    None => criteria.clone(),
+                };
+
                 let ranking_rules = ranking_rules::RankingRules::new(
-                    criteria.clone(),
+                    criteria,
                     sort,
                     query.matching_strategy.into(),
                     canonicalization_kind,
@@ -636,7 +665,16 @@ pub fn perform_federated_search(
                 search.offset(0);
                 search.limit(required_hit_count);
 
-                let (result, _semantic_hit_count) = super::search_from_kind(search_kind, search)?;
+                let (result, _semantic_hit_count) = rules.execute(
+                    &index,
+                    &rtxn,
+                    &query,
+                    search_kind,
+                    search,
+                    features,
+                    0,
+                    required_hit_count,
+                )?;
                 let format = AttributesFormat {
                     attributes_to_retrieve: query.attributes_to_retrieve,
                     retrieve_vectors,
@@ -656,6 +694,7 @@ pub fn perform_federated_search(
                 let milli::SearchResult {
                     matching_words,
                     candidates: query_candidates,
+                    disjunctive_candidates: _,
                     documents_ids,
                     document_scores,
                     degraded: query_degraded,
@@ -728,9 +767,13 @@ pub fn perform_federated_search(
             .map(|facets_by_index| {
                 compute_facet_distribution_stats(
                     &facets_by_index,
+                    &[],
+                    false,
+                    &[],
                     &index,
                     &rtxn,
                     candidates,
+                    None,
                     super::Route::MultiSearch,
                 )
             })
@@ -790,9 +833,13 @@ pub fn perform_federated_search(
         if let Some(facets) = facets {
             if let Err(mut error) = compute_facet_distribution_stats(
                 &facets,
+                &[],
+                false,
+                &[],
                 &index,
                 &rtxn,
                 Default::default(),
+                None,
                 super::Route::MultiSearch,
             ) {
                 error.message =
@@ -853,7 +900,9 @@ pub fn perform_federated_search(
                 let facets = facets.merge(merge_facets, facet_order);
 
                 let (facet_distribution, facet_stats) = facets
-                    .map(|ComputedFacets { distribution, stats }| (distribution, stats))
+                    .map(|ComputedFacets { distribution, stats, .. }| {
+                        (stringify_distribution(distribution), stats)
+                    })
                     .unzip();
 
                 (facet_distribution, facet_stats, FederatedFacets::default())
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use index_scheduler::RoFeatures;
+use meilisearch_auth::IndexSearchRules;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::heed::RoTxn;
+use meilisearch_types::milli::score_details::{self, ScoreDetails};
+use meilisearch_types::milli::{self, DocumentId, Index, MerchandisingActions, PinnedDocument};
+use meilisearch_types::settings::RankingRuleView;
+use roaring::RoaringBitmap;
+use serde_json::Value;
+
+use super::{add_search_rules, search_filter, search_from_kind, SearchKind, SearchQuery};
+
+/// The pinned and hidden documents of the merchandising rules of an index whose condition
+/// matches a search.
+#[derive(Debug, Default)]
+pub struct MatchedRules {
+    pins: Vec<PinnedDocument>,
+    hidden: Vec<String>,
+}
+
+impl MatchedRules {
+    /// Applies the filters and the boosts of the rules matching the query to the query itself,
+    /// and returns the documents to pin and to hide in its results.
+    ///
+    /// The filters of the rules are added to the filter of the query, and the boosts are
+    /// ranking rules placed before the ones of the query, or of the index.
+    pub fn apply(
+        index: &Index,
+        rtxn: &RoTxn,
+        query: &mut SearchQuery,
+        features: RoFeatures,
+    ) -> Result<Self, ResponseError> {
+        let rules = index.merchandising_rules(rtxn)?;
+        if rules.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut matched = Self::default();
+        let mut filters = Vec::new();
+        let mut boosts = Vec::new();
+        {
+            let facet_filters = query.facet_filters.iter().flat_map(BTreeMap::values);
+            let filter = search_filter(query.filter.as_ref(), facet_filters, features)?;
+            for rule in rules {
+                if !rule.condition.matches(query.q.as_deref(), filter.as_ref())? {
+                    continue;
+                }
+                let MerchandisingActions { pin, hide, filter, boost } = rule.actions;
+                matched.pins.extend(pin);
+                matched.hidden.extend(hide);
+                filters.extend(filter);
+                boosts.extend(boost);
+            }
+        }
+
+        for rule_filter in filters {
+            add_search_rules(
+                &mut query.filter,
+                IndexSearchRules { filter: Some(Value::String(rule_filter)) },
+            );
+        }
+        if !boosts.is_empty() {
+            let ranking_rules = match query.ranking_rules.take() {
+                Some(ranking_rules) => ranking_rules,
+                None => index.criteria(rtxn)?.into_iter().map(RankingRuleView::from).collect(),
+            };
+            query.ranking_rules =
+                Some(boosts.into_iter().map(RankingRuleView::Boost).chain(ranking_rules).collect());
+        }
+
+        Ok(matched)
+    }
+
+    /// Executes the search, removing the hidden documents from its results and inserting
+    /// the pinned ones at their positions.
+    ///
+    /// The organic results are fetched from the first one so that the pinned and hidden documents
+    /// are removed before the pagination, which keeps the pages and the number of hits consistent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn,
+        query: &SearchQuery,
+        search_kind: SearchKind,
+        mut search: milli::Search<'_>,
+        features: RoFeatures,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(milli::SearchResult, Option<u32>), ResponseError> {
+        if self.pins.is_empty() && self.hidden.is_empty() {
+            return Ok(search_from_kind(search_kind, search)?);
+        }
+
+        let external_documents_ids = index.external_documents_ids();
+        let mut hidden = RoaringBitmap::new();
+        for id in &self.hidden {
+            if let Some(docid) = external_documents_ids.get(rtxn, id)? {
+                hidden.insert(docid);
+            }
+        }
+
+        // the pinned documents must match the filter of the search, but not its query
+        let facet_filters = query.facet_filters.iter().flat_map(BTreeMap::values);
+        let filter = search_filter(query.filter.as_ref(), facet_filters, features)?;
+        let universe = milli::filtered_universe(index, rtxn, &filter)?;
+        let mut pinned = RoaringBitmap::new();
+        let mut pins: Vec<(usize, DocumentId)> = Vec::new();
+        for PinnedDocument { id, position } in &self.pins {
+            let Some(docid) = external_documents_ids.get(rtxn, id)? else { continue };
+            if universe.contains(docid) && !hidden.contains(docid) && pinned.insert(docid) {
+                pins.push((*position, docid));
+            }
+        }
+        pins.sort_by_key(|(position, _)| *position);
+        let excluded = &pinned | &hidden;
+
+        search.offset(0);
+        search.limit(offset + limit + excluded.len() as usize);
+        let (result, semantic_hit_count) = search_from_kind(search_kind, search)?;
+        let milli::SearchResult {
+            matching_words,
+            mut candidates,
+            mut disjunctive_candidates,
+            documents_ids,
+            document_scores,
+            degraded,
+            used_negative_operator,
+        } = result;
+
+        candidates -= &excluded;
+        // the pinned documents are only counted by the disjunctive facets they organically match
+        for candidates in &mut disjunctive_candidates {
+            *candidates -= &hidden;
+        }
+        let mut documents: Vec<_> = documents_ids.into_iter().zip(document_scores).collect();
+        documents.retain(|(docid, _)| !excluded.contains(*docid));
+
+        // the pinned documents beyond the organic results are moved at the end of the results
+        let organic_hits = candidates.len() as usize;
+        let mut pinned_indexes: Vec<(usize, DocumentId)> = Vec::with_capacity(pins.len());
+        for (i, (position, docid)) in pins.into_iter().enumerate() {
+            let mut index = position.saturating_sub(1);
+            if let Some((previous, _)) = pinned_indexes.last() {
+                index = index.max(previous + 1);
+            }
+            pinned_indexes.push((index.min(organic_hits + i), docid));
+        }
+        candidates |= pinned;
+
+        let pinned_hit = |(index, docid): (usize, DocumentId)| {
+            let details = score_details::Pinned { position: index + 1 };
+            (docid, vec![ScoreDetails::Pinned(details)])
+        };
+        let pinned_before = pinned_indexes.iter().filter(|(index, _)| *index < offset).count();
+        let mut pinned_in_page = pinned_indexes
+            .into_iter()
+            .filter(|(index, _)| (offset..offset + limit).contains(index))
+            .peekable();
+        let mut organic = documents.into_iter().skip(offset - pinned_before);
+        let mut hits = Vec::with_capacity(limit);
+        for index in offset..offset + limit {
+            match pinned_in_page.next_if(|(pinned_index, _)| *pinned_index == index) {
+                Some(pinned) => hits.push(pinned_hit(pinned)),
+                None => match organic.next() {
+                    Some(hit) => hits.push(hit),
+                    None => {
+                        hits.extend(pinned_in_page.map(pinned_hit));
+                        break;
+                    }
+                },
+            }
+        }
+        let (documents_ids, document_scores) = hits.into_iter().unzip();
+
+        let result = milli::SearchResult {
+            matching_words,
+            candidates,
+            disjunctive_candidates,
+            documents_ids,
+            document_scores,
+            degraded,
+            used_negative_operator,
+        };
+        Ok((result, semantic_hit_count))
+    }
+}
@@ -1,11 +1,12 @@
 use core::fmt;
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::convert::Infallible;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use deserr::Deserr;
+use deserr::{DeserializeError, Deserr, ErrorKind, ValuePointerRef};
 use either::Either;
 use index_scheduler::RoFeatures;
 use indexmap::IndexMap;
@@ -19,23 +20,30 @@ use meilisearch_types::locales::Locale;
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
 use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
 use meilisearch_types::milli::vector::Embedder;
-use meilisearch_types::milli::{FacetValueHit, OrderBy, SearchForFacetValues, TimeBudget};
-use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
+use meilisearch_types::milli::{
+    CalendarInterval, FacetBuckets, FacetHierarchy, FacetHierarchyNode, FacetNumberStats,
+    FacetRange, FacetValue, FacetValueHit, FilterAnalysis, FilterIssue, FilterNode, OrderBy,
+    SearchForFacetValues, TimeBudget,
+};
+use meilisearch_types::settings::{RankingRuleView, DEFAULT_PAGINATION_MAX_TOTAL_HITS};
 use meilisearch_types::{milli, Document};
 use milli::tokenizer::{Language, TokenizerBuilder};
 use milli::{
-    AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, LocalizedAttributesRule,
-    MatchBounds, MatcherBuilder, SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    AscDesc, Criterion, FieldId, FieldsIdsMap, Filter, FormatOptions, Index,
+    LocalizedAttributesRule, MatchBounds, MatcherBuilder, SortError, TermsMatchingStrategy,
+    DEFAULT_VALUES_PER_FACET,
 };
 use regex::Regex;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use serde_json::{json, Value};
 
+use self::merchandising::MatchedRules;
 use crate::error::MeilisearchHttpError;
 
 mod federated;
 pub use federated::{perform_federated_search, FederatedSearch, Federation, FederationOptions};
 
+mod merchandising;
 mod ranking_rules;
 
 type MatchesPosition = BTreeMap<String, Vec<MatchBounds>>;
@@ -47,6 +55,7 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_FACET_HIERARCHY_SEPARATOR: fn() -> String = || " > ".to_string();
 
 #[derive(Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -83,12 +92,22 @@ pub struct SearchQuery {
     pub show_ranking_score_details: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetFilters>)]
+    pub facet_filters: Option<BTreeMap<String, Value>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingRules>)]
+    pub ranking_rules: Option<Vec<RankingRuleView>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetBuckets>)]
+    pub facet_buckets: Option<Vec<FacetBucketsQuery>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchExtendedFacetStats>, default)]
+    pub extended_facet_stats: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHierarchies>)]
+    pub facet_hierarchies: Option<Vec<FacetHierarchyQuery>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -99,6 +118,10 @@ pub struct SearchQuery {
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSynonyms>, default)]
+    pub synonyms: Option<BTreeMap<String, Vec<String>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStopWords>, default)]
+    pub stop_words: Option<BTreeSet<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>, default)]
     pub ranking_score_threshold: Option<RankingScoreThreshold>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchLocales>, default)]
@@ -164,13 +187,20 @@ impl fmt::Debug for SearchQuery {
             show_ranking_score_details,
             filter,
             sort,
+            ranking_rules,
             distinct,
             facets,
+            facet_buckets,
+            extended_facet_stats,
+            facet_hierarchies,
+            facet_filters,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
             matching_strategy,
             attributes_to_search_on,
+            synonyms,
+            stop_words,
             ranking_score_threshold,
             locales,
         } = self;
@@ -209,18 +239,39 @@ impl fmt::Debug for SearchQuery {
         if let Some(attributes_to_search_on) = attributes_to_search_on {
             debug.field("attributes_to_search_on", &attributes_to_search_on);
         }
+        if let Some(synonyms) = synonyms {
+            debug.field("synonyms", &synonyms);
+        }
+        if let Some(stop_words) = stop_words {
+            debug.field("stop_words", &stop_words);
+        }
         if let Some(filter) = filter {
             debug.field("filter", &filter);
         }
+        if let Some(facet_filters) = facet_filters {
+            debug.field("facet_filters", &facet_filters);
+        }
         if let Some(sort) = sort {
             debug.field("sort", &sort);
         }
+        if let Some(ranking_rules) = ranking_rules {
+            debug.field("ranking_rules", &ranking_rules);
+        }
         if let Some(distinct) = distinct {
             debug.field("distinct", &distinct);
         }
         if let Some(facets) = facets {
             debug.field("facets", &facets);
         }
+        if let Some(facet_buckets) = facet_buckets {
+            debug.field("facet_buckets", &facet_buckets);
+        }
+        if *extended_facet_stats {
+            debug.field("extended_facet_stats", &extended_facet_stats);
+        }
+        if let Some(facet_hierarchies) = facet_hierarchies {
+            debug.field("facet_hierarchies", &facet_hierarchies);
+        }
         debug.field("matching_strategy", &matching_strategy);
 
         // Then everything related to the formatting
@@ -271,6 +322,152 @@ pub struct HybridQuery {
     pub embedder: String,
 }
 
+/// Count the documents per bucket of values of a numeric facet.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchFacetBuckets>, rename_all = camelCase, deny_unknown_fields, validate = validate_facet_buckets -> DeserrJsonError<InvalidSearchFacetBuckets>)]
+pub struct FacetBucketsQuery {
+    pub attribute: String,
+    #[deserr(default)]
+    pub ranges: Option<Vec<FacetRangeQuery>>,
+    #[deserr(default)]
+    pub interval: Option<f64>,
+    #[deserr(default)]
+    pub calendar_interval: Option<CalendarIntervalQuery>,
+}
+
+/// Group the timestamps of a date attribute by calendar unit, in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum CalendarIntervalQuery {
+    Day,
+    Week,
+    Month,
+}
+
+impl From<CalendarIntervalQuery> for CalendarInterval {
+    fn from(other: CalendarIntervalQuery) -> Self {
+        match other {
+            CalendarIntervalQuery::Day => Self::Day,
+            CalendarIntervalQuery::Week => Self::Week,
+            CalendarIntervalQuery::Month => Self::Month,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchFacetBuckets>, rename_all = camelCase, deny_unknown_fields)]
+pub struct FacetRangeQuery {
+    #[deserr(default)]
+    pub from: Option<f64>,
+    #[deserr(default)]
+    pub to: Option<f64>,
+}
+
+fn validate_facet_buckets<E: DeserializeError>(
+    query: FacetBucketsQuery,
+    location: ValuePointerRef,
+) -> Result<FacetBucketsQuery, E> {
+    let msg = match (&query.ranges, query.interval, query.calendar_interval) {
+        (Some(_), None, None) | (None, None, Some(_)) => return Ok(query),
+        (None, Some(interval), None) if interval.is_finite() && interval > 0.0 => return Ok(query),
+        (None, Some(interval), None) => {
+            format!("`interval` must be a strictly positive number, but found `{interval}`.")
+        }
+        (None, None, None) => {
+            String::from("One of `ranges`, `interval` or `calendarInterval` must be specified.")
+        }
+        _ => String::from("`ranges`, `interval` and `calendarInterval` cannot be used together."),
+    };
+    Err(deserr::take_cf_content(E::error::<Infallible>(
+        None,
+        ErrorKind::Unexpected { msg },
+        location,
+    )))
+}
+
+/// Count the documents per value of a tree of facet values, see [`FacetHierarchy`].
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchFacetHierarchies>, rename_all = camelCase, deny_unknown_fields, validate = validate_facet_hierarchy -> DeserrJsonError<InvalidSearchFacetHierarchies>)]
+pub struct FacetHierarchyQuery {
+    pub attributes: Vec<String>,
+    #[deserr(default = DEFAULT_FACET_HIERARCHY_SEPARATOR())]
+    pub separator: String,
+    #[deserr(default)]
+    pub selected: Option<String>,
+}
+
+fn validate_facet_hierarchy<E: DeserializeError>(
+    query: FacetHierarchyQuery,
+    location: ValuePointerRef,
+) -> Result<FacetHierarchyQuery, E> {
+    let msg = if query.attributes.is_empty() {
+        "`attributes` must contain at least one attribute."
+    } else if query.separator.is_empty() {
+        "`separator` cannot be an empty string."
+    } else {
+        return Ok(query);
+    };
+    Err(deserr::take_cf_content(E::error::<Infallible>(
+        None,
+        ErrorKind::Unexpected { msg: msg.to_string() },
+        location,
+    )))
+}
+
+/// Restrict a facet search to the descendants of a value of a hierarchy of facet values.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidFacetSearchFacetParent>, rename_all = camelCase, deny_unknown_fields, validate = validate_facet_parent -> DeserrJsonError<InvalidFacetSearchFacetParent>)]
+pub struct FacetParentQuery {
+    pub path: String,
+    #[deserr(default = DEFAULT_FACET_HIERARCHY_SEPARATOR())]
+    pub separator: String,
+}
+
+fn validate_facet_parent<E: DeserializeError>(
+    query: FacetParentQuery,
+    location: ValuePointerRef,
+) -> Result<FacetParentQuery, E> {
+    if !query.separator.is_empty() {
+        return Ok(query);
+    }
+    Err(deserr::take_cf_content(E::error::<Infallible>(
+        None,
+        ErrorKind::Unexpected { msg: "`separator` cannot be an empty string.".to_string() },
+        location,
+    )))
+}
+
+impl FacetHierarchyQuery {
+    /// The name of the hierarchy in the response, its attributes separated by commas.
+    fn name(&self) -> String {
+        self.attributes.join(",")
+    }
+
+    fn hierarchy(&self) -> FacetHierarchy {
+        let selected = match &self.selected {
+            Some(selected) => selected.split(self.separator.as_str()).map(String::from).collect(),
+            None => Vec::new(),
+        };
+        FacetHierarchy {
+            attributes: self.attributes.clone(),
+            separator: self.separator.clone(),
+            selected,
+        }
+    }
+}
+
+impl FacetBucketsQuery {
+    fn buckets(&self) -> FacetBuckets {
+        match (&self.ranges, self.interval, self.calendar_interval) {
+            (Some(ranges), _, _) => FacetBuckets::Ranges(
+                ranges.iter().map(|range| FacetRange { from: range.from, to: range.to }).collect(),
+            ),
+            (None, _, Some(unit)) => FacetBuckets::Calendar(unit.into()),
+            (None, interval, None) => FacetBuckets::Interval(interval.unwrap_or_default()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum SearchKind {
     KeywordOnly,
@@ -411,12 +608,22 @@ pub struct SearchQueryWithIndex {
     pub show_matches_position: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetFilters>)]
+    pub facet_filters: Option<BTreeMap<String, Value>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingRules>)]
+    pub ranking_rules: Option<Vec<RankingRuleView>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetBuckets>)]
+    pub facet_buckets: Option<Vec<FacetBucketsQuery>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchExtendedFacetStats>, default)]
+    pub extended_facet_stats: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHierarchies>)]
+    pub facet_hierarchies: Option<Vec<FacetHierarchyQuery>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -427,6 +634,10 @@ pub struct SearchQueryWithIndex {
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSynonyms>, default)]
+    pub synonyms: Option<BTreeMap<String, Vec<String>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStopWords>, default)]
+    pub stop_words: Option<BTreeSet<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>, default)]
     pub ranking_score_threshold: Option<RankingScoreThreshold>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchLocales>, default)]
@@ -455,6 +666,14 @@ impl SearchQueryWithIndex {
         self.facets.as_deref().filter(|v| !v.is_empty())
     }
 
+    pub fn has_facet_buckets(&self) -> Option<&[FacetBucketsQuery]> {
+        self.facet_buckets.as_deref().filter(|v| !v.is_empty())
+    }
+
+    pub fn has_facet_hierarchies(&self) -> Option<&[FacetHierarchyQuery]> {
+        self.facet_hierarchies.as_deref().filter(|v| !v.is_empty())
+    }
+
     pub fn into_index_query_federation(self) -> (IndexUid, SearchQuery, Option<FederationOptions>) {
         let SearchQueryWithIndex {
             index_uid,
@@ -475,13 +694,20 @@ impl SearchQueryWithIndex {
             show_matches_position,
             filter,
             sort,
+            ranking_rules,
             distinct,
             facets,
+            facet_buckets,
+            extended_facet_stats,
+            facet_hierarchies,
+            facet_filters,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
             matching_strategy,
             attributes_to_search_on,
+            synonyms,
+            stop_words,
             hybrid,
             ranking_score_threshold,
             locales,
@@ -505,13 +731,20 @@ impl SearchQueryWithIndex {
                 show_matches_position,
                 filter,
                 sort,
+                ranking_rules,
                 distinct,
                 facets,
+                facet_buckets,
+                extended_facet_stats,
+                facet_hierarchies,
+                facet_filters,
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
                 matching_strategy,
                 attributes_to_search_on,
+                synonyms,
+                stop_words,
                 hybrid,
                 ranking_score_threshold,
                 locales,
@@ -653,6 +886,10 @@ pub struct SearchResult {
     pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_buckets: Option<BTreeMap<String, Vec<FacetBucket>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_hierarchies: Option<BTreeMap<String, Vec<FacetHierarchyNode>>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
@@ -673,6 +910,8 @@ impl fmt::Debug for SearchResult {
             hits_info,
             facet_distribution,
             facet_stats,
+            facet_buckets,
+            facet_hierarchies,
             semantic_hit_count,
             degraded,
             used_negative_operator,
@@ -696,6 +935,12 @@ impl fmt::Debug for SearchResult {
         if let Some(facet_stats) = facet_stats {
             debug.field("facet_stats", &facet_stats);
         }
+        if let Some(facet_buckets) = facet_buckets {
+            debug.field("facet_buckets", &facet_buckets);
+        }
+        if let Some(facet_hierarchies) = facet_hierarchies {
+            debug.field("facet_hierarchies", &facet_hierarchies);
+        }
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
@@ -735,6 +980,29 @@ pub enum HitsInfo {
 pub struct FacetStats {
     pub min: f64,
     pub max: f64,
+    #[serde(flatten)]
+    pub extended: Option<ExtendedFacetStats>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedFacetStats {
+    pub sum: f64,
+    pub avg: f64,
+    pub count: u64,
+    pub distinct_count: u64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FacetBucket {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<f64>,
+    pub count: u64,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -745,6 +1013,15 @@ pub struct FacetSearchResult {
     pub processing_time_ms: u128,
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterValidationResult {
+    pub valid: bool,
+    pub errors: Vec<ResponseError>,
+    pub warnings: Vec<FilterIssue>,
+    pub ast: Option<FilterNode>,
+}
+
 /// Incorporate search rules in search query
 pub fn add_search_rules(filter: &mut Option<Value>, rules: IndexSearchRules) {
     *filter = match (filter.take(), rules.filter) {
@@ -823,6 +1100,14 @@ fn prepare_search<'t>(
         search.searchable_attributes(searchable);
     }
 
+    if let Some(ref synonyms) = query.synonyms {
+        search.synonyms(synonyms.clone());
+    }
+
+    if let Some(ref stop_words) = query.stop_words {
+        search.stop_words(stop_words.clone());
+    }
+
     let is_finite_pagination = query.is_finite_pagination();
     search.terms_matching_strategy(query.matching_strategy.into());
 
@@ -844,8 +1129,70 @@ fn prepare_search<'t>(
         },
     );
 
+    let (offset, limit) = search_window(query, max_total_hits);
+    search.offset(offset);
+    search.limit(limit);
+
+    let facet_filters = query.facet_filters.iter().flatten();
+    let facet_filters = facet_filters.filter(|(facet, _)| !is_disjunctive_facet(query, facet));
+    if let Some(filter) =
+        search_filter(query.filter.as_ref(), facet_filters.map(|(_, filter)| filter), features)?
+    {
+        search.filter(filter);
+    }
+
+    // the search returns the candidates of the disjunctive facets along with its results
+    let disjunctive_filters = disjunctive_facet_filters(query, features)?;
+    if !disjunctive_filters.is_empty() {
+        search.disjunctive_filters(
+            disjunctive_filters.into_iter().map(|(_, filter)| filter).collect(),
+        );
+    }
+
+    let sort = match &query.sort {
+        Some(sort) => match sort.iter().map(|s| AscDesc::from_str(s)).collect() {
+            Ok(sorts) => Some(sorts),
+            Err(asc_desc_error) => {
+                return Err(milli::Error::from(SortError::from(asc_desc_error)).into())
+            }
+        },
+        None => None,
+    };
+
+    // the ranking rules of the query are canonicalized like the ones of a federated search
+    if let Some(ref ranking_rules) = query.ranking_rules {
+        for rule in ranking_rules {
+            // the invalid boost filters are reported by milli when building the ranking rules
+            if let RankingRuleView::Boost(filter) = rule {
+                if let Ok(Some(filter)) = Filter::from_str(filter) {
+                    check_filter_features(&filter, features)?;
+                }
+            }
+        }
+        let ranking_rules = ranking_rules::RankingRules::new(
+            ranking_rules.iter().cloned().map(Criterion::from).collect(),
+            sort.clone(),
+            query.matching_strategy.into(),
+            ranking_rules::CanonicalizationKind::new(search_kind, query.q.as_deref()),
+        );
+        search.ranking_rules(ranking_rules.into_canonical_criteria());
+    }
+
+    if let Some(sort) = sort {
+        search.sort_criteria(sort);
+    }
+
+    if let Some(ref locales) = query.locales {
+        search.locales(locales.iter().copied().map(Into::into).collect());
+    }
+
+    Ok((search, is_finite_pagination, max_total_hits, offset))
+}
+
+/// Returns the offset and the limit of a search, bounded by the maximum number of hits.
+fn search_window(query: &SearchQuery, max_total_hits: usize) -> (usize, usize) {
     // compute the offset on the limit depending on the pagination mode.
-    let (offset, limit) = if is_finite_pagination {
+    let (offset, limit) = if query.is_finite_pagination() {
         let limit = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
         let page = query.page.unwrap_or(1);
 
@@ -859,37 +1206,50 @@ fn prepare_search<'t>(
     // we align that on the offset too.
     let offset = min(offset, max_total_hits);
     let limit = min(limit, max_total_hits.saturating_sub(offset));
+    (offset, limit)
+}
 
-    search.offset(offset);
-    search.limit(limit);
-
-    if let Some(ref filter) = query.filter {
-        if let Some(facets) = parse_filter(filter, Code::InvalidSearchFilter, features)? {
-            search.filter(facets);
-        }
+/// Returns the `filter` of a search and the given filters of its `facetFilters`, joined by `AND`.
+fn search_filter<'a>(
+    filter: Option<&'a Value>,
+    facet_filters: impl IntoIterator<Item = &'a Value>,
+    features: RoFeatures,
+) -> Result<Option<Filter<'a>>, ResponseError> {
+    let mut filters = Vec::new();
+    if let Some(filter) = filter {
+        filters.extend(parse_filter(filter, Code::InvalidSearchFilter, features)?);
     }
-
-    if let Some(ref sort) = query.sort {
-        let sort = match sort.iter().map(|s| AscDesc::from_str(s)).collect() {
-            Ok(sorts) => sorts,
-            Err(asc_desc_error) => {
-                return Err(milli::Error::from(SortError::from(asc_desc_error)).into())
-            }
-        };
-
-        search.sort_criteria(sort);
+    for filter in facet_filters {
+        filters.extend(parse_filter(filter, Code::InvalidSearchFacetFilters, features)?);
     }
+    Ok(Filter::and(filters)?)
+}
 
-    if let Some(ref locales) = query.locales {
-        search.locales(locales.iter().copied().map(Into::into).collect());
-    }
+/// Whether a facet of the `facetFilters` is counted as if its own filter was not applied,
+/// which is the case when its distribution is requested.
+fn is_disjunctive_facet(query: &SearchQuery, facet: &str) -> bool {
+    query.facets.iter().flatten().any(|name| name == "*" || name == facet)
+}
 
-    Ok((search, is_finite_pagination, max_total_hits, offset))
+/// Returns the filters of the disjunctive facets of the `facetFilters`, see [`is_disjunctive_facet`].
+fn disjunctive_facet_filters<'a>(
+    query: &'a SearchQuery,
+    features: RoFeatures,
+) -> Result<Vec<(&'a str, Filter<'a>)>, ResponseError> {
+    let mut filters = Vec::new();
+    for (facet, filter) in query.facet_filters.iter().flatten() {
+        if is_disjunctive_facet(query, facet) {
+            if let Some(filter) = parse_filter(filter, Code::InvalidSearchFacetFilters, features)? {
+                filters.push((facet.as_str(), filter));
+            }
+        }
+    }
+    Ok(filters)
 }
 
 pub fn perform_search(
     index: &Index,
-    query: SearchQuery,
+    mut query: SearchQuery,
     search_kind: SearchKind,
     retrieve_vectors: RetrieveVectors,
     features: RoFeatures,
@@ -901,20 +1261,29 @@ pub fn perform_search(
         None => TimeBudget::default(),
     };
 
+    let rules = MatchedRules::apply(index, &rtxn, &mut query, features)?;
+
+    let disjunctive_facets: Vec<_> = disjunctive_facet_filters(&query, features)?
+        .into_iter()
+        .map(|(facet, _)| facet.to_string())
+        .collect();
+
     let (search, is_finite_pagination, max_total_hits, offset) =
         prepare_search(index, &rtxn, &query, &search_kind, time_budget, features)?;
+    let (_, limit) = search_window(&query, max_total_hits);
 
     let (
         milli::SearchResult {
             documents_ids,
             matching_words,
             candidates,
+            disjunctive_candidates,
             document_scores,
             degraded,
             used_negative_operator,
         },
         semantic_hit_count,
-    ) = search_from_kind(search_kind, search)?;
+    ) = rules.execute(index, &rtxn, &query, search_kind, search, features, offset, limit)?;
 
     let SearchQuery {
         q,
@@ -932,6 +1301,9 @@ pub fn perform_search(
         show_ranking_score_details,
         sort,
         facets,
+        facet_buckets,
+        extended_facet_stats,
+        facet_hierarchies,
         highlight_pre_tag,
         highlight_post_tag,
         crop_marker,
@@ -943,7 +1315,11 @@ pub fn perform_search(
         ranking_score_threshold: _,
         matching_strategy: _,
         attributes_to_search_on: _,
-        filter: _,
+        synonyms: _,
+        stop_words: _,
+        ranking_rules: _,
+        filter,
+        facet_filters,
         distinct: _,
     } = query;
 
@@ -989,21 +1365,71 @@ pub fn perform_search(
         HitsInfo::OffsetLimit { limit, offset, estimated_total_hits: number_of_hits }
     };
 
-    let (facet_distribution, facet_stats) = facets
-        .map(move |facets| {
-            compute_facet_distribution_stats(&facets, index, &rtxn, candidates, Route::Search)
-        })
-        .transpose()?
-        .map(|ComputedFacets { distribution, stats }| (distribution, stats))
-        .unzip();
+    let (mut facet_distribution, mut facet_stats, facet_buckets, facet_hierarchies) =
+        match (facets, facet_buckets, facet_hierarchies) {
+            (None, None, None) => (None, None, None, None),
+            (facets, facet_buckets, facet_hierarchies) => {
+                // the values of the nested attributes are counted in the objects matching the filter
+                let facet_filters = facet_filters.iter().flat_map(BTreeMap::values);
+                let filter = search_filter(filter.as_ref(), facet_filters, features)?;
+                let ComputedFacets { distribution, stats, buckets, hierarchies } =
+                    compute_facet_distribution_stats(
+                        facets.as_deref().unwrap_or_default(),
+                        facet_buckets.as_deref().unwrap_or_default(),
+                        extended_facet_stats,
+                        facet_hierarchies.as_deref().unwrap_or_default(),
+                        index,
+                        &rtxn,
+                        candidates,
+                        filter,
+                        Route::Search,
+                    )?;
+                let (distribution, stats) = facets.map(|_| (distribution, stats)).unzip();
+                (
+                    distribution,
+                    stats,
+                    facet_buckets.map(|_| buckets),
+                    facet_hierarchies.map(|_| hierarchies),
+                )
+            }
+        };
+
+    // The facets of the `facetFilters` are counted as if their own filter was not applied
+    if let (Some(distribution), Some(stats)) = (&mut facet_distribution, &mut facet_stats) {
+        for (facet, candidates) in disjunctive_facets.into_iter().zip(disjunctive_candidates) {
+            if !distribution.contains_key(&facet) {
+                continue;
+            }
+            let ComputedFacets {
+                distribution: disjunctive_distribution,
+                stats: disjunctive_stats,
+                ..
+            } = compute_facet_distribution_stats(
+                &[&facet],
+                &[],
+                extended_facet_stats,
+                &[],
+                index,
+                &rtxn,
+                candidates,
+                None,
+                Route::Search,
+            )?;
+            distribution.extend(disjunctive_distribution);
+            stats.remove(&facet);
+            stats.extend(disjunctive_stats);
+        }
+    }
 
     let result = SearchResult {
         hits: documents,
         hits_info,
         query: q.unwrap_or_default(),
         processing_time_ms: before_search.elapsed().as_millis(),
-        facet_distribution,
+        facet_distribution: facet_distribution.map(stringify_distribution),
         facet_stats,
+        facet_buckets,
+        facet_hierarchies,
         degraded,
         used_negative_operator,
         semantic_hit_count,
@@ -1013,8 +1439,34 @@ pub fn perform_search(
 
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct ComputedFacets {
-    pub distribution: BTreeMap<String, IndexMap<String, u64>>,
+    #[serde(serialize_with = "serialize_distribution")]
+    pub distribution: BTreeMap<String, IndexMap<FacetValue, u64>>,
     pub stats: BTreeMap<String, FacetStats>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub buckets: BTreeMap<String, Vec<FacetBucket>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hierarchies: BTreeMap<String, Vec<FacetHierarchyNode>>,
+}
+
+/// Returns the distributions with their values as strings, the way they are returned.
+pub fn stringify_distribution(
+    distribution: BTreeMap<String, IndexMap<FacetValue, u64>>,
+) -> BTreeMap<String, IndexMap<String, u64>> {
+    distribution
+        .into_iter()
+        .map(|(facet, values)| (facet, milli::stringify_facet_values(values)))
+        .collect()
+}
+
+fn serialize_distribution<S: Serializer>(
+    distribution: &BTreeMap<String, IndexMap<FacetValue, u64>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(distribution.iter().map(|(facet, values)| {
+        let values: IndexMap<_, _> =
+            values.iter().map(|(value, count)| (value.to_string(), count)).collect();
+        (facet, values)
+    }))
 }
 
 enum Route {
@@ -1022,14 +1474,22 @@ enum Route {
     MultiSearch,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compute_facet_distribution_stats<S: AsRef<str>>(
     facets: &[S],
+    facet_buckets: &[FacetBucketsQuery],
+    extended_stats: bool,
+    facet_hierarchies: &[FacetHierarchyQuery],
     index: &Index,
     rtxn: &RoTxn,
     candidates: roaring::RoaringBitmap,
+    filter: Option<Filter>,
     route: Route,
 ) -> Result<ComputedFacets, ResponseError> {
     let mut facet_distribution = index.facets_distribution(rtxn);
+    if let Some(filter) = filter {
+        facet_distribution.filter(filter);
+    }
 
     let max_values_by_facet = index
         .max_values_per_facet(rtxn)
@@ -1051,7 +1511,7 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
     let distribution = facet_distribution
         .candidates(candidates)
         .default_order_by(sort_facet_values_by.get("*"))
-        .execute()
+        .execute_typed()
         .map_err(|error| match (error, route) {
             (
                 error @ milli::Error::UserError(milli::UserError::InvalidFacetsDistribution {
@@ -1061,9 +1521,68 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
             ) => ResponseError::from_msg(error.to_string(), Code::InvalidMultiSearchFacets),
             (error, _) => error.into(),
         })?;
-    let stats = facet_distribution.compute_stats()?;
-    let stats = stats.into_iter().map(|(k, (min, max))| (k, FacetStats { min, max })).collect();
-    Ok(ComputedFacets { distribution, stats })
+    let stats = if extended_stats {
+        let stats = facet_distribution.compute_extended_stats()?;
+        stats
+            .into_iter()
+            .map(|(k, stats)| {
+                let FacetNumberStats { min, max, sum, avg, count, distinct_count, p50, p90, p99 } =
+                    stats;
+                let extended =
+                    Some(ExtendedFacetStats { sum, avg, count, distinct_count, p50, p90, p99 });
+                (k, FacetStats { min, max, extended })
+            })
+            .collect()
+    } else {
+        let stats = facet_distribution.compute_stats()?;
+        stats
+            .into_iter()
+            .map(|(k, (min, max))| (k, FacetStats { min, max, extended: None }))
+            .collect()
+    };
+
+    let buckets = facet_distribution
+        .buckets(facet_buckets.iter().map(|query| (&query.attribute, query.buckets())))
+        .compute_buckets()
+        .map_err(|error| match error {
+            error @ milli::Error::UserError(milli::UserError::InvalidFacetsDistribution {
+                ..
+            }) => ResponseError::from_msg(error.to_string(), Code::InvalidSearchFacetBuckets),
+            error => error.into(),
+        })?;
+    let buckets = buckets
+        .into_iter()
+        .map(|(k, buckets)| {
+            let buckets = buckets
+                .into_iter()
+                .map(|(FacetRange { from, to }, count)| FacetBucket { from, to, count })
+                .collect();
+            (k, buckets)
+        })
+        .collect();
+
+    let mut names = BTreeSet::new();
+    if let Some(query) = facet_hierarchies.iter().find(|query| !names.insert(query.name())) {
+        return Err(ResponseError::from_msg(
+            format!(
+                "The hierarchy of the attributes `{}` is requested several times.",
+                query.attributes.join("`, `")
+            ),
+            Code::InvalidSearchFacetHierarchies,
+        ));
+    }
+
+    let hierarchies = facet_distribution
+        .hierarchies(facet_hierarchies.iter().map(|query| (query.name(), query.hierarchy())))
+        .compute_hierarchies()
+        .map_err(|error| match error {
+            error @ milli::Error::UserError(milli::UserError::InvalidFacetsDistribution {
+                ..
+            }) => ResponseError::from_msg(error.to_string(), Code::InvalidSearchFacetHierarchies),
+            error => error.into(),
+        })?;
+
+    Ok(ComputedFacets { distribution, stats, buckets, hierarchies })
 }
 
 pub fn search_from_kind(
@@ -1384,11 +1903,13 @@ fn make_hits<'a>(
     Ok(documents)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn perform_facet_search(
     index: &Index,
     search_query: SearchQuery,
     facet_query: Option<String>,
     facet_name: String,
+    facet_parent: Option<FacetParentQuery>,
     search_kind: SearchKind,
     features: RoFeatures,
     locales: Option<Vec<Language>>,
@@ -1424,6 +1945,9 @@ pub fn perform_facet_search(
     if let Some(facet_query) = &facet_query {
         facet_search.query(facet_query);
     }
+    if let Some(FacetParentQuery { path, separator }) = facet_parent {
+        facet_search.parent(path, separator);
+    }
     if let Some(max_facets) = index.max_values_per_facet(&rtxn)? {
         facet_search.max_values(max_facets as usize);
     }
@@ -1439,6 +1963,46 @@ pub fn perform_facet_search(
     })
 }
 
+pub fn perform_filter_validation(
+    index: &Index,
+    filter: Option<&Value>,
+    features: RoFeatures,
+) -> Result<FilterValidationResult, ResponseError> {
+    let filter = match filter {
+        Some(filter) => parse_filter(filter, Code::InvalidSearchFilter, features),
+        None => Ok(None),
+    };
+    let filter = match filter {
+        Ok(Some(filter)) => filter,
+        Ok(None) => {
+            return Ok(FilterValidationResult {
+                valid: true,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+                ast: None,
+            })
+        }
+        // the filter can't be analyzed if it can't be parsed
+        Err(error) => {
+            return Ok(FilterValidationResult {
+                valid: false,
+                errors: vec![error],
+                warnings: Vec::new(),
+                ast: None,
+            })
+        }
+    };
+
+    let rtxn = index.read_txn()?;
+    let FilterAnalysis { ast, errors, warnings } = filter.analyze(&rtxn, index)?;
+    let errors: Vec<_> = errors
+        .into_iter()
+        .map(|issue| ResponseError::from_msg(issue.message, Code::InvalidSearchFilter))
+        .collect();
+
+    Ok(FilterValidationResult { valid: errors.is_empty(), errors, warnings, ast: Some(ast) })
+}
+
 pub fn perform_similar(
     index: &Index,
     query: SimilarQuery,
@@ -1498,6 +2062,7 @@ pub fn perform_similar(
         documents_ids,
         matching_words: _,
         candidates,
+        disjunctive_candidates: _,
         document_scores,
         degraded: _,
         used_negative_operator: _,
@@ -1559,10 +2124,34 @@ fn insert_geo_distance(sorts: &[String], document: &mut Document) {
         // TODO: TAMO: milli encountered an internal error, what do we want to do?
         let base = [capture_group[1].parse().unwrap(), capture_group[2].parse().unwrap()];
         let geo_point = &document.get("_geo").unwrap_or(&json!(null));
-        if let Some((lat, lng)) =
-            extract_geo_value(&geo_point["lat"]).zip(extract_geo_value(&geo_point["lng"]))
-        {
-            let distance = milli::distance_between_two_points(&base, &[lat, lng]);
+        // when the document is located at multiple points we return the distance to the
+        // closest one, and the point itself in `_geoMatchedPoint`. Returning the point inside
+        // `_geoDistance` would have turned it into an object, and broken the clients reading
+        // it as a number, so we return it on the side instead.
+        if let Value::Array(points) = geo_point {
+            let closest = points
+                .iter()
+                .filter_map(|point| {
+                    let lat = extract_geo_value(&point["lat"])?;
+                    let lng = extract_geo_value(&point["lng"])?;
+                    Some((milli::distance_between_two_points(&base, &[lat, lng]), point))
+                })
+                .min_by(|(left, _), (right, _)| left.total_cmp(right));
+            if let Some((distance, point)) = closest {
+                let point = point.clone();
+                document.insert("_geoDistance".to_string(), json!(distance.round() as usize));
+                document.insert("_geoMatchedPoint".to_string(), point);
+            }
+            return;
+        }
+        let distance = if milli::GeoShape::is_geojson(geo_point) {
+            milli::GeoShape::from_geojson(geo_point).ok().and_then(|shape| shape.distance_to(&base))
+        } else {
+            extract_geo_value(&geo_point["lat"])
+                .zip(extract_geo_value(&geo_point["lng"]))
+                .map(|(lat, lng)| milli::distance_between_two_points(&base, &[lat, lng]))
+        };
+        if let Some(distance) = distance {
             document.insert("_geoDistance".to_string(), json!(distance.round() as usize));
         }
     }
@@ -1878,20 +2467,38 @@ pub(crate) fn parse_filter(
     })?;
 
     if let Some(ref filter) = filter {
-        // If the contains operator is used while the contains filter features is not enabled, errors out
-        if let Some((token, error)) =
-            filter.use_contains_operator().zip(features.check_contains_filter().err())
-        {
-            return Err(ResponseError::from_msg(
-                token.as_external_error(error).to_string(),
-                Code::FeatureNotEnabled,
-            ));
-        }
+        check_filter_features(filter, features)?;
     }
 
     Ok(filter)
 }
 
+/// Errors out if the filter uses an operator whose experimental feature is not enabled.
+pub(crate) fn check_filter_features(
+    filter: &Filter,
+    features: RoFeatures,
+) -> Result<(), ResponseError> {
+    // If the contains operator is used while the contains filter features is not enabled, errors out
+    if let Some((token, error)) =
+        filter.use_contains_operator().zip(features.check_contains_filter().err())
+    {
+        return Err(ResponseError::from_msg(
+            token.as_external_error(error).to_string(),
+            Code::FeatureNotEnabled,
+        ));
+    }
+    // Same for the `ENDS WITH`, `LIKE` and `MATCHES` operators, gated by the same feature
+    if let Some((token, error)) =
+        filter.use_pattern_operator().zip(features.check_pattern_filter().err())
+    {
+        return Err(ResponseError::from_msg(
+            token.as_external_error(error).to_string(),
+            Code::FeatureNotEnabled,
+        ));
+    }
+    Ok(())
+}
+
 fn parse_filter_array(arr: &[Value]) -> Result<Option<Filter>, MeilisearchHttpError> {
     let mut ands = Vec::new();
     for value in arr {
@@ -2038,4 +2645,38 @@ mod test {
         insert_geo_distance(sorters, &mut document);
         assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
     }
+
+    #[test]
+    fn test_insert_geo_distance_with_multiple_points() {
+        let value: Document = serde_json::from_str(
+            r#"{
+              "_geo": [
+                { "lat": 10, "lng": 10 },
+                { "lat": 50, "lng": 3 }
+              ]
+            }"#,
+        )
+        .unwrap();
+
+        // the distance stays a number, the closest point is returned on the side
+        let sorters = &["_geoPoint(50,3):asc".to_string()];
+        let mut document = value.clone();
+        insert_geo_distance(sorters, &mut document);
+        assert_eq!(document.get("_geoDistance"), Some(&json!(0)));
+        assert_eq!(document.get("_geoMatchedPoint"), Some(&json!({ "lat": 50, "lng": 3 })));
+
+        let value: Document = serde_json::from_str(
+            r#"{
+              "_geo": {
+                "lat": 50,
+                "lng": 3
+              }
+            }"#,
+        )
+        .unwrap();
+
+        let mut document = value.clone();
+        insert_geo_distance(sorters, &mut document);
+        assert_eq!(document.get("_geoMatchedPoint"), None);
+    }
 }
@@ -5,6 +5,8 @@ use itertools::Itertools as _;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::milli::{AscDesc, Criterion, Member, TermsMatchingStrategy};
 
+use super::SearchKind;
+
 pub struct RankingRules {
     canonical_criteria: Vec<Criterion>,
     canonical_sort: Option<Vec<AscDesc>>,
@@ -44,6 +46,7 @@ pub enum CanonicalizationAction {
 pub enum RemoveWords {
     WasPrepended,
     MatchingStrategyAll,
+    AlreadyApplied,
 }
 
 impl std::fmt::Display for RemoveWords {
@@ -51,6 +54,9 @@ impl std::fmt::Display for RemoveWords {
         let reason = match self {
             RemoveWords::WasPrepended => "it was previously prepended",
             RemoveWords::MatchingStrategyAll => "`query.matchingWords` is set to `all`",
+            RemoveWords::AlreadyApplied => {
+                "the matched words are already ranked by an earlier rule"
+            }
         };
         f.write_str(reason)
     }
@@ -62,6 +68,16 @@ pub enum CanonicalizationKind {
     Vector,
 }
 
+impl CanonicalizationKind {
+    pub fn new(search_kind: &SearchKind, q: Option<&str>) -> Self {
+        match (search_kind, q) {
+            (SearchKind::SemanticOnly { .. }, _) => CanonicalizationKind::Vector,
+            (_, Some(q)) if !q.is_empty() => CanonicalizationKind::Keyword,
+            _ => CanonicalizationKind::Placeholder,
+        }
+    }
+}
+
 pub struct CompatibilityError {
     previous: RankingRule,
     current: RankingRule,
@@ -152,9 +168,14 @@ impl CompatibilityError {
             | (RankingRuleKind::AscendingSort, RankingRuleKind::AscendingSort)
             | (RankingRuleKind::DescendingSort, RankingRuleKind::DescendingSort)
             | (RankingRuleKind::AscendingGeoSort, RankingRuleKind::AscendingGeoSort)
-            | (RankingRuleKind::DescendingGeoSort, RankingRuleKind::DescendingGeoSort) => {
+            | (RankingRuleKind::DescendingGeoSort, RankingRuleKind::DescendingGeoSort)
+            | (RankingRuleKind::FunctionScore, RankingRuleKind::FunctionScore) => {
                 "internal error, comparison should be possible"
             }
+
+            (RankingRuleKind::FunctionScore, _) | (_, RankingRuleKind::FunctionScore) => {
+                "cannot compare a function score rule with another kind of rule"
+            }
         }
     }
 }
@@ -206,6 +227,7 @@ impl RankingRules {
         for (criterion_index, criterion) in criteria.iter().enumerate() {
             match criterion.clone() {
                 Criterion::Words
+                | Criterion::TermRarity
                 | Criterion::Typo
                 | Criterion::Proximity
                 | Criterion::Attribute
@@ -247,6 +269,9 @@ impl RankingRules {
                         canonical_criteria.push(criterion.clone())
                     }
                 },
+                Criterion::Score(_) | Criterion::Decay(_) | Criterion::Boost(_) => {
+                    canonical_criteria.push(criterion.clone())
+                }
             }
         }
 
@@ -269,6 +294,7 @@ impl RankingRules {
         'criteria: for (criterion_index, criterion) in criteria.iter().enumerate() {
             match criterion.clone() {
                 Criterion::Words
+                | Criterion::TermRarity
                 | Criterion::Typo
                 | Criterion::Proximity
                 | Criterion::Attribute
@@ -327,6 +353,9 @@ impl RankingRules {
                         canonical_criteria.push(criterion.clone())
                     }
                 },
+                Criterion::Score(_) | Criterion::Decay(_) | Criterion::Boost(_) => {
+                    canonical_criteria.push(criterion.clone())
+                }
             }
         }
 
@@ -364,7 +393,14 @@ impl RankingRules {
                     }
                     if let Some(maybe_previous_index) = words {
                         if let Some(previous_index) = maybe_previous_index {
-                            canonicalization_actions.push(
+                            let action = if criteria[previous_index] == Criterion::TermRarity {
+                                CanonicalizationAction::RemovedWords {
+                                    reason: RemoveWords::AlreadyApplied,
+                                    removed_occurrence: RankingRuleSource::Criterion(
+                                        criterion_index,
+                                    ),
+                                }
+                            } else {
                                 CanonicalizationAction::RemovedDuplicate {
                                     earlier_occurrence: RankingRuleSource::Criterion(
                                         previous_index,
@@ -372,8 +408,9 @@ impl RankingRules {
                                     removed_occurrence: RankingRuleSource::Criterion(
                                         criterion_index,
                                     ),
-                                },
-                            );
+                                }
+                            };
+                            canonicalization_actions.push(action);
                             continue;
                         }
                         canonicalization_actions.push(CanonicalizationAction::RemovedWords {
@@ -384,6 +421,24 @@ impl RankingRules {
                     words = Some(Some(criterion_index));
                     canonical_criteria.push(criterion);
                 }
+                Criterion::TermRarity => {
+                    // `termRarity` replaces `words`, so it is removed when `words` already applies
+                    let reason = match (terms_matching_strategy, words) {
+                        (TermsMatchingStrategy::All, _) => Some(RemoveWords::MatchingStrategyAll),
+                        (_, Some(None)) => Some(RemoveWords::WasPrepended),
+                        (_, Some(Some(_))) => Some(RemoveWords::AlreadyApplied),
+                        (_, None) => None,
+                    };
+                    if let Some(reason) = reason {
+                        canonicalization_actions.push(CanonicalizationAction::RemovedWords {
+                            reason,
+                            removed_occurrence: RankingRuleSource::Criterion(criterion_index),
+                        });
+                        continue;
+                    }
+                    words = Some(Some(criterion_index));
+                    canonical_criteria.push(criterion);
+                }
                 Criterion::Typo => {
                     canonicalize_criterion(
                         criterion,
@@ -461,12 +516,21 @@ impl RankingRules {
                         canonical_criteria.push(criterion)
                     }
                 },
+                Criterion::Score(_) | Criterion::Decay(_) | Criterion::Boost(_) => {
+                    canonical_criteria.push(criterion)
+                }
             }
         }
 
         (canonical_criteria, canonical_sort, canonicalization_actions)
     }
 
+    /// Returns the ranking rules to give to the search, without the ones removed during the
+    /// canonicalization.
+    pub fn into_canonical_criteria(self) -> Vec<Criterion> {
+        self.canonical_criteria
+    }
+
     pub fn is_compatible_with(&self, previous: &Self) -> Result<(), CompatibilityError> {
         for (current, previous) in self.coalesce_iterator().zip(previous.coalesce_iterator()) {
             if current.kind != previous.kind {
@@ -532,7 +596,8 @@ impl RankingRules {
                 ),
                 RemovedWords { reason, removed_occurrence } => writeln!(
                     &mut notes,
-                    "    {index}. Removed rule `words` at position {} because {reason}",
+                    "    {index}. Removed rule `{}` at position {} because {reason}",
+                    removed_occurrence.rule_name(&self.source_criteria, &self.source_sort),
                     removed_occurrence.rule_position()
                 ),
                 RemovedPlaceholder { removed_occurrence } => writeln!(
@@ -650,6 +715,7 @@ enum RankingRuleKind {
     DescendingSort,
     AscendingGeoSort,
     DescendingGeoSort,
+    FunctionScore,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -720,16 +786,22 @@ impl RankingRule {
     ) -> impl Iterator<Item = Self> + 'a {
         let kind = match criterion {
             Criterion::Words
+            | Criterion::TermRarity
             | Criterion::Typo
             | Criterion::Proximity
             | Criterion::Attribute
             | Criterion::Exactness => RankingRuleKind::Relevancy,
+            // the decay score is between 0 and 1, like the relevancy rules
+            Criterion::Decay(_) => RankingRuleKind::Relevancy,
+            // the documents matching the boost filter have a better rank
+            Criterion::Boost(_) => RankingRuleKind::Relevancy,
             Criterion::Asc(s) if s == "_geo" => RankingRuleKind::AscendingGeoSort,
 
             Criterion::Asc(_) => RankingRuleKind::AscendingSort,
             Criterion::Desc(s) if s == "_geo" => RankingRuleKind::DescendingGeoSort,
 
             Criterion::Desc(_) => RankingRuleKind::DescendingSort,
+            Criterion::Score(_) => RankingRuleKind::FunctionScore,
             Criterion::Sort => {
                 return either::Right(sort.iter().flatten().enumerate().map(
                     move |(rule_index, asc_desc)| {
@@ -774,6 +846,7 @@ impl RankingRule {
             RankingRuleKind::DescendingSort => "descending sort",
             RankingRuleKind::AscendingGeoSort => "ascending geo sort",
             RankingRuleKind::DescendingGeoSort => "descending geo sort",
+            RankingRuleKind::FunctionScore => "function score",
         };
         let rules = self.fetch_from_source(canonical_criteria, canonical_sort);
 
@@ -200,3 +200,103 @@ async fn simple_facet_search_with_sort_by_count() {
     assert_eq!(hits[0], json!({ "value": "Action", "count": 3 }));
     assert_eq!(hits[1], json!({ "value": "Adventure", "count": 2 }));
 }
+
+#[actix_rt::test]
+async fn facet_search_with_facet_parent() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "category": "Books > Fiction" },
+        { "id": 1, "category": "Books > Fantasy" },
+        { "id": 2, "category": "Books > Poetry" },
+        { "id": 3, "category": "Music > Funk" },
+    ]);
+    index.update_settings_filterable_attributes(json!(["category"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "category",
+            "facetQuery": "f",
+            "facetParent": { "path": "Books" },
+        }))
+        .await;
+
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(
+        response["facetHits"],
+        json!([
+            { "value": "Books > Fantasy", "count": 1 },
+            { "value": "Books > Fiction", "count": 1 },
+        ])
+    );
+
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "category",
+            "facetParent": { "path": "Music", "separator": " > " },
+        }))
+        .await;
+
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["facetHits"], json!([{ "value": "Music > Funk", "count": 1 }]));
+
+    let (response, code) = index
+        .facet_search(json!({ "facetName": "category", "facetParent": { "separator": "/" } }))
+        .await;
+
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_facet_search_facet_parent", "{}", response);
+
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "category",
+            "facetParent": { "path": "Books", "separator": "" },
+        }))
+        .await;
+
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_facet_search_facet_parent", "{}", response);
+}
+
+#[actix_rt::test]
+async fn facet_search_booleans() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "available": true },
+        { "id": 1, "available": false },
+        { "id": 2, "available": true },
+        { "id": 3, "available": "truck" },
+    ]);
+    index.update_settings_filterable_attributes(json!(["available"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "available", "facetQuery": "Tr"})).await;
+
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(
+        response["facetHits"],
+        json!([
+            { "value": "true", "count": 2 },
+            { "value": "truck", "count": 1 },
+        ])
+    );
+
+    let (response, code) = index.facet_search(json!({"facetName": "available"})).await;
+
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(
+        response["facetHits"],
+        json!([
+            { "value": "false", "count": 1 },
+            { "value": "true", "count": 2 },
+            { "value": "truck", "count": 1 },
+        ])
+    );
+}
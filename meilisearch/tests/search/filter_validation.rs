@@ -0,0 +1,186 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "color": "red", "price": 10 },
+        { "id": 2, "color": "blue", "price": 20 },
+        { "id": 3, "color": "red" },
+    ])
+});
+
+#[actix_rt::test]
+async fn validate_filter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["color", "price"])).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .validate_filter(json!({
+            "filter": "(color = red AND price EXISTS AND price > 5) OR price = cheap"
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "valid": true,
+      "errors": [],
+      "warnings": [
+        {
+          "kind": "unusedClause",
+          "expression": "price EXISTS",
+          "message": "The clause `price EXISTS` does not change the documents matched by the `AND` it belongs to."
+        },
+        {
+          "kind": "typeMismatch",
+          "expression": "price = cheap",
+          "message": "The attribute `price` only contains numbers but `cheap` is not a number."
+        },
+        {
+          "kind": "unusedClause",
+          "expression": "price = cheap",
+          "message": "The clause `price = cheap` does not change the documents matched by the `OR` it belongs to."
+        }
+      ],
+      "ast": {
+        "operator": "OR",
+        "conditions": [
+          {
+            "operator": "AND",
+            "conditions": [
+              {
+                "operator": "=",
+                "attribute": "color",
+                "values": [
+                  "red"
+                ],
+                "expression": "color = red",
+                "estimatedCandidates": 2
+              },
+              {
+                "operator": "EXISTS",
+                "attribute": "price",
+                "expression": "price EXISTS",
+                "estimatedCandidates": 2
+              },
+              {
+                "operator": ">",
+                "attribute": "price",
+                "values": [
+                  "5"
+                ],
+                "expression": "price > 5",
+                "estimatedCandidates": 2
+              }
+            ],
+            "expression": "color = red AND price EXISTS AND price > 5",
+            "estimatedCandidates": 1
+          },
+          {
+            "operator": "=",
+            "attribute": "price",
+            "values": [
+              "cheap"
+            ],
+            "expression": "price = cheap",
+            "estimatedCandidates": 0
+          }
+        ],
+        "expression": "color = red AND price EXISTS AND price > 5 OR price = cheap",
+        "estimatedCandidates": 1
+      }
+    }
+    "###);
+
+    let (response, code) = index.validate_filter(json!({ "filter": ["color = blue"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "valid": true,
+      "errors": [],
+      "warnings": [],
+      "ast": {
+        "operator": "=",
+        "attribute": "color",
+        "values": [
+          "blue"
+        ],
+        "expression": "color = blue",
+        "estimatedCandidates": 1
+      }
+    }
+    "###);
+
+    let (response, code) = index.validate_filter(json!({})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "valid": true,
+      "errors": [],
+      "warnings": [],
+      "ast": null
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn validate_invalid_filter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["color", "price"])).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.validate_filter(json!({ "filter": "title = hello OR price > cheap" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["valid"], @"false");
+    snapshot!(response["ast"]["estimatedCandidates"], @"null");
+    snapshot!(response["ast"]["conditions"][0]["estimatedCandidates"], @"null");
+    snapshot!(json_string!(response["errors"]), @r###"
+    [
+      {
+        "message": "Attribute `title` is not filterable. Available filterable attributes are: `color`, `price`.\n1:6 title = hello OR price > cheap",
+        "code": "invalid_search_filter",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+      },
+      {
+        "message": "invalid float literal\n26:31 title = hello OR price > cheap",
+        "code": "invalid_search_filter",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+      }
+    ]
+    "###);
+
+    let (response, code) = index.validate_filter(json!({ "filter": "color =" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["valid"], @"false");
+    snapshot!(response["ast"], @"null");
+    snapshot!(response["errors"][0]["code"], @r###""invalid_search_filter""###);
+
+    let (response, code) = index.validate_filter(json!({ "filter": "color CONTAINS r" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["valid"], @"false");
+    snapshot!(response["errors"][0]["code"], @r###""feature_not_enabled""###);
+
+    let (response, code) = index.validate_filter(json!({ "filter": 42 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["valid"], @"false");
+
+    let (response, code) = index.validate_filter(json!({ "q": "hello" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""bad_request""###);
+
+    let (response, code) = server.index("unknown").validate_filter(json!({})).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""index_not_found""###);
+}
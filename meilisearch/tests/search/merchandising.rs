@@ -0,0 +1,296 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::index::Index;
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "Acme TV 55", "brand": "acme", "category": "tv", "price": 500 },
+        { "id": 2, "title": "Bolt TV 42", "brand": "bolt", "category": "tv", "price": 300 },
+        { "id": 3, "title": "Acme TV 65", "brand": "acme", "category": "tv", "price": 900 },
+        { "id": 4, "title": "Bolt TV stand", "brand": "bolt", "category": "furniture", "price": 50 },
+        { "id": 5, "title": "Cheap TV", "brand": "cheap", "category": "tv", "price": 100 },
+        { "id": 6, "title": "Soundbar", "brand": "acme", "category": "audio", "price": 200 },
+    ])
+});
+
+async fn index_with_rules<'a>(server: &'a Server) -> Index<'a> {
+    let index = server.index("test");
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["brand", "category"],
+            "rankingRules": ["words", "price:asc"],
+            "rules": [
+                {
+                    "condition": { "queryEquals": "tv" },
+                    "actions": {
+                        "pin": [{ "id": "6", "position": 1 }, { "id": "3", "position": 3 }],
+                        "hide": ["2"]
+                    }
+                },
+                {
+                    "condition": { "filter": "category = tv" },
+                    "actions": { "boost": "brand = acme" }
+                },
+                {
+                    "condition": { "queryContains": "stand" },
+                    "actions": { "filter": "category = furniture" }
+                }
+            ]
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    index
+}
+
+fn ids(response: &Value) -> Vec<u64> {
+    response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64().unwrap()).collect()
+}
+
+#[actix_rt::test]
+async fn pin_and_hide_documents() {
+    let server = Server::new().await;
+    let index = index_with_rules(&server).await;
+
+    let (response, code) = index.search_post(json!({ "q": "tv" })).await;
+    assert_eq!(code, 200, "{}", response);
+    // the soundbar is pinned even if it doesn't match the query, and the Bolt TV 42 is hidden
+    assert_eq!(ids(&response), vec![6, 4, 3, 5, 1]);
+    assert_eq!(response["estimatedTotalHits"], json!(5));
+
+    // the pages are consistent with the complete results
+    for (offset, expected) in [(0, vec![6, 4]), (2, vec![3, 5]), (4, vec![1])] {
+        let (response, code) =
+            index.search_post(json!({ "q": "TV", "offset": offset, "limit": 2 })).await;
+        assert_eq!(code, 200, "{}", response);
+        assert_eq!(ids(&response), expected, "{}", response);
+        assert_eq!(response["estimatedTotalHits"], json!(5));
+    }
+
+    let (response, code) =
+        index.search_post(json!({ "q": "tv", "page": 2, "hitsPerPage": 2 })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![3, 5]);
+    assert_eq!(response["totalHits"], json!(5));
+    assert_eq!(response["totalPages"], json!(3));
+
+    // the rule only applies to the exact query
+    let (response, code) = index.search_post(json!({ "q": "acme tv" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![1, 3, 6]);
+
+    // the pinned documents have their own score details
+    let (response, code) = index
+        .search_post(json!({
+            "q": "tv",
+            "limit": 1,
+            "showRankingScore": true,
+            "showRankingScoreDetails": true
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    snapshot!(json_string!(response["hits"][0]), @r###"
+    {
+      "id": 6,
+      "title": "Soundbar",
+      "brand": "acme",
+      "category": "audio",
+      "price": 200,
+      "_rankingScore": 1.0,
+      "_rankingScoreDetails": {
+        "pinned": {
+          "order": 0,
+          "position": 1,
+          "score": 1.0
+        }
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn rules_apply_to_federated_search() {
+    let server = Server::new().await;
+    let _index = index_with_rules(&server).await;
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [{ "indexUid": "test", "q": "tv" }] }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![6, 4, 3, 5, 1]);
+    assert_eq!(response["estimatedTotalHits"], json!(5));
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [
+            { "indexUid": "test", "filter": "category = tv" }
+        ] }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![1, 3, 5, 2]);
+}
+
+#[actix_rt::test]
+async fn boost_and_filter_documents() {
+    let server = Server::new().await;
+    let index = index_with_rules(&server).await;
+
+    // the documents of the brand are ranked first on the category page
+    let (response, code) = index.search_post(json!({ "filter": "category = tv" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![1, 3, 5, 2]);
+    assert_eq!(response["estimatedTotalHits"], json!(4));
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "category = tv", "offset": 1, "limit": 2 })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![3, 5]);
+
+    // the condition is matched on the parsed filter of the search
+    let (response, code) =
+        index.search_post(json!({ "filter": ["brand != cheap", "(category   =   tv)"] })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![1, 3, 2]);
+    let (response, code) =
+        index.search_post(json!({ "filter": "brand != cheap OR category = tv" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![4, 5, 6, 2, 1, 3]);
+
+    // the pinned documents must match the filter of the search
+    let (response, code) =
+        index.search_post(json!({ "q": "tv", "filter": ["category = tv"] })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![1, 5, 3]);
+    assert_eq!(response["estimatedTotalHits"], json!(3));
+
+    // the filter of a rule is added to the filter of the search, and to the facet distribution
+    let (response, code) =
+        index.search_post(json!({ "q": "tv stand", "facets": ["category"] })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![4]);
+    assert_eq!(response["facetDistribution"], json!({ "category": { "furniture": 1 } }));
+}
+
+#[actix_rt::test]
+async fn get_and_reset_rules() {
+    let server = Server::new().await;
+    let index = index_with_rules(&server).await;
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    snapshot!(json_string!(response["rules"]), @r###"
+    [
+      {
+        "condition": {
+          "queryEquals": "tv"
+        },
+        "actions": {
+          "pin": [
+            {
+              "id": "6",
+              "position": 1
+            },
+            {
+              "id": "3",
+              "position": 3
+            }
+          ],
+          "hide": [
+            "2"
+          ]
+        }
+      },
+      {
+        "condition": {
+          "filter": "category = tv"
+        },
+        "actions": {
+          "boost": "brand = acme"
+        }
+      },
+      {
+        "condition": {
+          "queryContains": "stand"
+        },
+        "actions": {
+          "filter": "category = furniture"
+        }
+      }
+    ]
+    "###);
+
+    let (task, _code) = index.update_settings(json!({ "rules": null })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "q": "tv" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(ids(&response), vec![4, 5, 2, 1, 3]);
+}
+
+#[actix_rt::test]
+async fn invalid_rules() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .update_settings(json!({ "rules": [
+            { "actions": { "pin": [{ "id": "6", "position": 0 }] } }
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rules[0]`: the position of the pinned document `6` must start at 1, but found 0",
+      "code": "invalid_settings_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "rules": [
+            { "condition": { "queryEquals": "tv" }, "actions": { "boost": "brand =" } }
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_settings_rules""###);
+}
+
+#[actix_rt::test]
+async fn rules_filter_on_filterable_attributes() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["category"],
+            "rules": [{ "actions": { "boost": "brand = acme" } }]
+        }))
+        .await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`rules` setting is invalid. The `actions.boost` filter of a rule is about the `brand` attribute, which is not filterable. Available filterable attributes are: `category`.",
+      "code": "invalid_settings_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_rules"
+    }
+    "###);
+
+    // the attributes of the existing rules can't stop being filterable
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["category"],
+            "rules": [{ "condition": { "filter": "category = tv" }, "actions": { "hide": ["2"] } }]
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index.update_settings(json!({ "filterableAttributes": [] })).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response["error"]["message"]), @r###""`rules` setting is invalid. The `condition.filter` filter of a rule is about the `category` attribute, which is not filterable. Available filterable attributes are: ``.""###);
+}
@@ -4,12 +4,14 @@
 mod distinct;
 mod errors;
 mod facet_search;
+mod filter_validation;
 mod formatted;
 mod geo;
 mod hybrid;
 #[cfg(not(feature = "chinese-pinyin"))]
 mod locales;
 mod matching_strategy;
+mod merchandising;
 mod multi;
 mod pagination;
 mod restrict_searchable;
@@ -366,6 +368,84 @@ async fn search_with_contains_filter() {
     assert_eq!(response["hits"].as_array().unwrap().len(), 2);
 }
 
+#[actix_rt::test]
+async fn search_with_pattern_filter() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"containsFilter": true})).await;
+    snapshot!(code, @"200 OK");
+    let index = server.index("movies");
+
+    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    let (request, _code) = index.add_documents(documents, None).await;
+    index.wait_task(request.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "filter": "title ENDS WITH room" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+
+    let (response, code) = index.search_post(json!({ "filter": "title LIKE '_la%'" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "title MATCHES /(captain|escape) .*/" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 2);
+
+    let (response, code) = index.search_post(json!({ "filter": "title NOT LIKE '%a%'" })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 0);
+}
+
+#[actix_rt::test]
+async fn search_with_field_comparison_filter() {
+    let server = Server::new().await;
+    let index = server.index("products");
+
+    let (task, _code) = index
+        .update_settings(
+            json!({"filterableAttributes": ["price", "discounted_price", "stock", "reserved"]}),
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 0, "price": 10, "discounted_price": 8, "stock": 5, "reserved": 5 },
+        { "id": 1, "price": 20, "discounted_price": 25, "stock": 3, "reserved": 1 },
+        { "id": 2, "price": 30, "discounted_price": 20, "stock": 4, "reserved": 2 },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(
+            json!({ "filter": "discounted_price < $price", "attributesToRetrieve": ["id"] }),
+        )
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"], json!([{ "id": 0 }, { "id": 2 }]));
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "$stock - $reserved > 0 AND $price * 0.5 <= 10",
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"], json!([{ "id": 1 }]));
+
+    let (response, code) = index.search_post(json!({ "filter": "price > $id" })).await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_filter", "{}", response);
+
+    // without a `$` the value is a literal
+    let (response, code) = index.search_post(json!({ "filter": "price > discounted_price" })).await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_filter", "{}", response);
+}
+
 #[actix_rt::test]
 async fn search_with_sort_on_numbers() {
     let index = shared_index_with_documents().await;
@@ -436,6 +516,141 @@ async fn search_with_multiple_sort() {
     assert_eq!(response["hits"].as_array().unwrap().len(), 5);
 }
 
+#[actix_rt::test]
+async fn search_with_ranking_rules_override() {
+    let index = shared_index_with_documents().await;
+    let (response, code) = index
+        .search_post(json!({
+            "rankingRules": ["title:asc"],
+            "attributesToRetrieve": ["title"]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "Captain Marvel"
+      },
+      {
+        "title": "Escape Room"
+      },
+      {
+        "title": "Gläss"
+      },
+      {
+        "title": "How to Train Your Dragon: The Hidden World"
+      },
+      {
+        "title": "Shazam!"
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "the",
+            "rankingRules": ["words", "title:desc"],
+            "attributesToRetrieve": ["title"]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "How to Train Your Dragon: The Hidden World"
+      }
+    ]
+    "###);
+
+    // the attributes used by the ranking rules must already be faceted by the index
+    let (response, code) =
+        index.search_post(json!({ "rankingRules": ["words", "color:asc"] })).await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid ranking rules at search time: `color:asc` ranking rule is invalid: the `color` attribute must be filterable or sortable.",
+      "code": "invalid_search_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_rules"
+    }
+    "###);
+
+    // and the attributes of the boosts must be filterable
+    let (response, code) =
+        index.search_post(json!({ "rankingRules": ["boost(color = red)", "words"] })).await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid ranking rules at search time: `boost(color = red)` ranking rule is invalid: the `color` attribute must be filterable.",
+      "code": "invalid_search_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_rules"
+    }
+    "###);
+
+    // the ranking rules of the query are canonicalized, `termRarity` is removed after `words`
+    let (response, code) = index
+        .search_post(json!({
+            "q": "the",
+            "rankingRules": ["words", "termRarity", "title:desc"],
+            "attributesToRetrieve": ["title"]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "How to Train Your Dragon: The Hidden World"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_with_synonyms_and_stop_words() {
+    let index = shared_index_with_documents().await;
+    let (response, code) = index
+        .search_post(json!({
+            "q": "superhero",
+            "synonyms": { "superhero": ["Captain Marvel", "shazam"] },
+            "rankingRules": ["words", "title:asc"],
+            "attributesToRetrieve": ["title"]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "Captain Marvel"
+      },
+      {
+        "title": "Shazam!"
+      }
+    ]
+    "###);
+
+    index
+        .search(
+            json!({
+                "q": "escape dragon",
+                "stopWords": ["escape"],
+                "matchingStrategy": "all",
+                "attributesToRetrieve": ["title"]
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "title": "How to Train Your Dragon: The Hidden World"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
 #[actix_rt::test]
 async fn search_facet_distribution() {
     let index = shared_index_with_documents().await;
@@ -721,6 +936,551 @@ async fn faceting_max_values_per_facet() {
         .await;
 }
 
+#[actix_rt::test]
+async fn faceting_extended_stats() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "filterableAttributes": ["number"] })).await;
+
+    let documents: Vec<_> = (0..100).map(|id| json!({ "id": id, "number": id * 10 })).collect();
+    index.add_documents(json!(documents), None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({
+                "facets": ["number"]
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(
+                    response["facetStats"],
+                    json!({ "number": { "min": 0.0, "max": 990.0 } })
+                );
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "facets": ["number"],
+                "extendedFacetStats": true,
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(
+                    response["facetStats"],
+                    json!({ "number": {
+                        "min": 0.0,
+                        "max": 990.0,
+                        "sum": 49500.0,
+                        "avg": 495.0,
+                        "count": 100,
+                        "distinctCount": 100,
+                        "p50": 490.0,
+                        "p90": 890.0,
+                        "p99": 980.0,
+                    }})
+                );
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn faceting_buckets() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "filterableAttributes": ["number"] })).await;
+
+    let documents: Vec<_> = (0..100).map(|id| json!({ "id": id, "number": id * 10 })).collect();
+    index.add_documents(json!(documents), None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetBuckets": [{ "attribute": "number", "interval": 250 }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @"null");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetBuckets"]), @r###"
+    {
+      "number": [
+        {
+          "from": 0.0,
+          "to": 250.0,
+          "count": 25
+        },
+        {
+          "from": 250.0,
+          "to": 500.0,
+          "count": 25
+        },
+        {
+          "from": 500.0,
+          "to": 750.0,
+          "count": 25
+        },
+        {
+          "from": 750.0,
+          "to": 1000.0,
+          "count": 25
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "number >= 50",
+            "facets": ["number"],
+            "facetBuckets": [{ "attribute": "number", "ranges": [
+                { "to": 100 },
+                { "from": 100, "to": 300 },
+                { "from": 900 },
+            ]}]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(response["facetDistribution"]["number"].as_object().unwrap().len(), @"95");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetBuckets"]), @r###"
+    {
+      "number": [
+        {
+          "to": 100.0,
+          "count": 5
+        },
+        {
+          "from": 100.0,
+          "to": 300.0,
+          "count": 20
+        },
+        {
+          "from": 900.0,
+          "count": 10
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetBuckets": [{ "attribute": "number", "interval": 10, "ranges": [] }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetBuckets[0]`: `ranges`, `interval` and `calendarInterval` cannot be used together.",
+      "code": "invalid_search_facet_buckets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_buckets"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetBuckets": [{ "attribute": "id", "interval": 10 }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid facet distribution, attribute `id` is not filterable. The available filterable attribute is `number`.",
+      "code": "invalid_search_facet_buckets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_buckets"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_on_date_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["date"],
+            "sortableAttributes": ["date"],
+            "dateAttributes": ["date"],
+        }))
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 0, "date": "2024-01-30T10:00:00Z" },
+        { "id": 1, "date": "2024-01-31" },
+        { "id": 2, "date": "2024-02-01T23:59:59Z" },
+        { "id": 3, "date": "2024-02-05" },
+        { "id": 4, "date": "2024-03-10T00:00:00+01:00" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "date 2024-01-31 TO '2024-02-05T00:00:00Z' AND date < NOW() - 7d",
+            "sort": ["date:desc"],
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"], json!([{ "id": 3 }, { "id": 2 }, { "id": 1 }]));
+
+    let (response, code) = index
+        .search_post(json!({
+            "facets": ["date"],
+            "facetBuckets": [{ "attribute": "date", "calendarInterval": "month" }],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["facetDistribution"]["date"]["2024-01-31"], json!(1));
+    assert_eq!(
+        response["facetBuckets"],
+        json!({ "date": [
+            { "from": 1704067200.0, "to": 1706745600.0, "count": 2 },
+            { "from": 1706745600.0, "to": 1709251200.0, "count": 2 },
+            { "from": 1709251200.0, "to": 1711929600.0, "count": 1 },
+        ]})
+    );
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetBuckets": [{ "attribute": "date", "calendarInterval": "year" }],
+        }))
+        .await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_facet_buckets", "{}", response);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetBuckets": [{ "attribute": "date", "calendarInterval": "day", "interval": 1 }],
+        }))
+        .await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_facet_buckets", "{}", response);
+
+    let (response, code) = index.search_post(json!({ "filter": "date > NOW() - 7y" })).await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_filter", "{}", response);
+}
+
+#[actix_rt::test]
+async fn faceting_hierarchies() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "filterableAttributes": ["categories", "path"] })).await;
+
+    let documents = json!([
+        {
+            "id": 0,
+            "categories": { "lvl0": "Books", "lvl1": "Books > Fiction" },
+            "path": "Books > Fiction > Crime",
+        },
+        {
+            "id": 1,
+            "categories": { "lvl0": "Books", "lvl1": "Books > Poetry" },
+            "path": "Books > Poetry",
+        },
+        {
+            "id": 2,
+            "categories": { "lvl0": "Music", "lvl1": "Music > Jazz" },
+            "path": "Music > Jazz",
+        },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetHierarchies": [{
+                "attributes": ["categories.lvl0", "categories.lvl1"],
+                "selected": "Books",
+            }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @"null");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHierarchies"]), @r###"
+    {
+      "categories.lvl0,categories.lvl1": [
+        {
+          "value": "Books",
+          "count": 2,
+          "children": [
+            {
+              "value": "Books > Fiction",
+              "count": 1
+            },
+            {
+              "value": "Books > Poetry",
+              "count": 1
+            }
+          ]
+        },
+        {
+          "value": "Music",
+          "count": 1
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "id != 1",
+            "facetHierarchies": [
+                {
+                    "attributes": ["path"],
+                    "separator": " > ",
+                    "selected": "Books > Fiction",
+                },
+                { "attributes": ["categories.lvl0"] },
+            ]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHierarchies"]), @r###"
+    {
+      "categories.lvl0": [
+        {
+          "value": "Books",
+          "count": 1
+        },
+        {
+          "value": "Music",
+          "count": 1
+        }
+      ],
+      "path": [
+        {
+          "value": "Books",
+          "count": 1,
+          "children": [
+            {
+              "value": "Books > Fiction",
+              "count": 1,
+              "children": [
+                {
+                  "value": "Books > Fiction > Crime",
+                  "count": 1
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "value": "Music",
+          "count": 1
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetHierarchies": [{ "attributes": [] }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetHierarchies[0]`: `attributes` must contain at least one attribute.",
+      "code": "invalid_search_facet_hierarchies",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchies"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetHierarchies": [{ "attributes": ["path"], "separator": "" }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetHierarchies[0]`: `separator` cannot be an empty string.",
+      "code": "invalid_search_facet_hierarchies",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchies"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetHierarchies": [
+                { "attributes": ["path"] },
+                { "attributes": ["path"], "separator": " > " },
+            ]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "The hierarchy of the attributes `path` is requested several times.",
+      "code": "invalid_search_facet_hierarchies",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchies"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetHierarchies": [{ "attributes": ["id"] }]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid facet distribution, attribute `id` is not filterable. The available filterable attributes are `categories, path`.",
+      "code": "invalid_search_facet_hierarchies",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchies"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn faceting_disjunctive_facet_filters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "filterableAttributes": ["brand", "color", "size"] })).await;
+
+    let documents = json!([
+        { "id": 0, "brand": "Apple", "color": "red", "size": "S" },
+        { "id": 1, "brand": "Apple", "color": "blue", "size": "M" },
+        { "id": 2, "brand": "Samsung", "color": "red", "size": "M" },
+        { "id": 3, "brand": "Samsung", "color": "black", "size": "S" },
+        { "id": 4, "brand": "Google", "color": "red", "size": "S" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetFilters": { "brand": "brand = Apple", "color": ["color = red"] },
+            "facets": ["brand", "color", "size"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 1, "{}", response);
+    assert_eq!(
+        response["facetDistribution"],
+        json!({
+            "brand": { "Apple": 1, "Google": 1, "Samsung": 1 },
+            "color": { "blue": 1, "red": 1 },
+            "size": { "S": 1 },
+        })
+    );
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "size = S",
+            "facetFilters": { "brand": "brand = Apple OR brand = Samsung" },
+            "facets": ["brand", "color"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 2, "{}", response);
+    assert_eq!(
+        response["facetDistribution"],
+        json!({
+            "brand": { "Apple": 1, "Google": 1, "Samsung": 1 },
+            "color": { "black": 1, "red": 1 },
+        })
+    );
+
+    // only the facet filters of the requested facets are ignored while counting them
+    let (response, code) = index
+        .search_post(json!({
+            "facetFilters": { "brand": "brand = Apple", "color": "color = red" },
+            "facets": ["color"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 1, "{}", response);
+    assert_eq!(response["facetDistribution"], json!({ "color": { "blue": 1, "red": 1 } }));
+
+    // the facets are counted among the documents matching the query
+    let (response, code) = index
+        .search_post(json!({
+            "q": "samsung",
+            "facetFilters": { "color": "color = red" },
+            "facets": ["color"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 1, "{}", response);
+    assert_eq!(response["facetDistribution"], json!({ "color": { "black": 1, "red": 1 } }));
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetFilters": { "brand": 42 },
+        }))
+        .await;
+    assert_eq!(code, 400, "{}", response);
+    assert_eq!(response["code"], "invalid_search_facet_filters", "{}", response);
+}
+
+#[actix_rt::test]
+async fn faceting_nested_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["variants"],
+            "nestedAttributes": ["variants"],
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 0, "variants": [{ "color": "red", "size": "M" }, { "color": "blue", "size": "S" }] },
+        { "id": 1, "variants": [{ "color": "red", "size": "S" }, { "color": "blue", "size": "M" }] },
+        { "id": 2, "variants": [{ "color": "red", "size": "M" }] },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["nestedAttributes"], json!(["variants"]), "{}", response);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "variants.color = red AND variants.size = M",
+            "facets": ["variants.color", "variants.size"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 2, "{}", response);
+    assert_eq!(
+        response["facetDistribution"],
+        json!({
+            "variants.color": { "red": 2 },
+            "variants.size": { "M": 2 },
+        })
+    );
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "variants.size = S",
+            "facets": ["variants.color"],
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["estimatedTotalHits"], 2, "{}", response);
+    assert_eq!(response["facetDistribution"], json!({ "variants.color": { "blue": 1, "red": 1 } }));
+}
+
 #[actix_rt::test]
 async fn test_score_details() {
     let server = Server::new().await;
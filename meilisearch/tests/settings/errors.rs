@@ -57,6 +57,36 @@ async fn settings_bad_searchable_attributes() {
       "link": "https://docs.meilisearch.com/errors#invalid_settings_searchable_attributes"
     }
     "###);
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "searchableAttributes": [{ "attribute": "title", "weight": 1 }, true] }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.searchableAttributes[1]`: expected a string or an object, but found a boolean: `true`",
+      "code": "invalid_settings_searchable_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_searchable_attributes"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings_searchable_attributes(
+            json!([{ "attribute": "title", "weight": 2 }, "tags"]),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The searchable attribute `tags` must have a weight because other searchable attributes have one.",
+      "code": "invalid_settings_searchable_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_searchable_attributes"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -141,6 +171,46 @@ async fn settings_bad_ranking_rules() {
       "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
     }
     "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "rankingRules": ["words", "score:log(popularity) +"] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[1]`: `score:log(popularity) +` ranking rule is invalid: `log` is not a valid function. Valid functions are abs, exp, ln, log1p, log10, sqrt, pow, min, max.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "rankingRules": ["words", "decay(published_at, origin=now, scale=7y)"] }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[1]`: `decay(published_at, origin=now, scale=7y)` ranking rule is invalid: `7y` is not a valid `scale`, expected a positive number or a duration like `7d`.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "rankingRules": ["words", "boost()"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[1]`: `boost()` ranking rule is invalid: the filter must not be empty.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -37,6 +37,11 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
         }),
     );
     map.insert("search_cutoff_ms", json!(null));
+    map.insert("nested_attributes", json!(null));
+    map.insert("date_attributes", json!(null));
+    map.insert("phonetic_attributes", json!(null));
+    map.insert("synonym_rules", json!(null));
+    map.insert("rules", json!(null));
     map
 });
 
@@ -422,7 +427,12 @@ test_setting_routes!(
     synonyms put,
     pagination patch,
     faceting patch,
-    search_cutoff_ms put
+    search_cutoff_ms put,
+    nested_attributes put,
+    date_attributes put,
+    phonetic_attributes put,
+    synonym_rules put,
+    rules put
 );
 
 #[actix_rt::test]
@@ -463,3 +473,31 @@ async fn set_and_reset_distinct_attribute_with_dedicated_route() {
 
     assert_eq!(response, json!(null));
 }
+
+#[actix_rt::test]
+async fn set_and_get_weighted_searchable_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let searchable_attributes = json!([
+        { "attribute": "title", "weight": 3 },
+        { "attribute": "description", "weight": 3 },
+        { "attribute": "tags", "weight": 1 },
+    ]);
+    let (task, _code) =
+        index.update_settings_searchable_attributes(searchable_attributes.clone()).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["searchableAttributes"], searchable_attributes);
+
+    // the weights are removed when the attributes are only ranked by their order
+    let (task, _code) =
+        index.update_settings(json!({ "searchableAttributes": ["title", "tags"] })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["searchableAttributes"], json!(["title", "tags"]));
+}
@@ -1,10 +1,14 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{AscDesc, Member};
+use crate::{
+    AscDesc, AscDescError, DecayFunction, DecayFunctionError, Filter, Member, ScoreExpression,
+    ScoreExpressionError,
+};
 
 #[derive(Error, Debug)]
 pub enum CriterionError {
@@ -22,6 +26,20 @@ pub enum CriterionError {
 `{name}` can only be used for filtering at search time"
     )]
     ReservedNameForFilter { name: String },
+    #[error("`score:{expression}` ranking rule is invalid: {error}.")]
+    InvalidScoreExpression { expression: String, error: ScoreExpressionError },
+    #[error("`decay({function})` ranking rule is invalid: {error}.")]
+    InvalidDecayFunction { function: String, error: DecayFunctionError },
+    #[error("`boost({filter})` ranking rule is invalid: {error}")]
+    InvalidBoostFilter { filter: String, error: String },
+    #[error(
+        "`{rule}` ranking rule is invalid: the `{field}` attribute must be filterable or sortable."
+    )]
+    NotFacetedField { rule: String, field: String },
+    #[error("`{rule}` ranking rule is invalid: the `{field}` attribute must be filterable.")]
+    NotFilterableField { rule: String, field: String },
+    #[error("`termRarity` ranking rule is invalid: it replaces `words` and must be placed before the `{rule}` ranking rule.")]
+    TermRarityAfterWords { rule: String },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -29,6 +47,9 @@ pub enum Criterion {
     /// Sorted by decreasing number of matched query terms.
     /// Query words at the front of an attribute is considered better than if it was at the back.
     Words,
+    /// Sorted by decreasing rarity of the matched query terms, a document matching the terms
+    /// appearing in the fewest documents is considered better. Replaces the `words` criterion.
+    TermRarity,
     /// Sorted by increasing number of typos.
     Typo,
     /// Sorted by increasing distance between matched query terms.
@@ -45,6 +66,15 @@ pub enum Criterion {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the decreasing value of the expression specified,
+    /// computed from the number values of the fields of the documents.
+    Score(String),
+    /// Sorted by the decreasing closeness of the value of a field to an origin,
+    /// the arguments of the decay function are specified.
+    Decay(String),
+    /// The documents matching the filter specified are ranked before the others,
+    /// the attributes of the filter must be filterable.
+    Boost(String),
 }
 
 impl Criterion {
@@ -55,6 +85,109 @@ impl Criterion {
             _otherwise => None,
         }
     }
+
+    /// Returns the parsed expression of this criterion if it is a `score:` criterion.
+    pub fn score_expression(&self) -> Option<Result<ScoreExpression, CriterionError>> {
+        match self {
+            Criterion::Score(expression) => Some(parse_score_expression(expression)),
+            _otherwise => None,
+        }
+    }
+
+    /// Returns the parsed decay function of this criterion if it is a `decay(...)` criterion.
+    pub fn decay_function(&self) -> Option<Result<DecayFunction, CriterionError>> {
+        match self {
+            Criterion::Decay(function) => Some(parse_decay_function(function)),
+            _otherwise => None,
+        }
+    }
+}
+
+pub(crate) fn parse_score_expression(expression: &str) -> Result<ScoreExpression, CriterionError> {
+    expression.parse().map_err(|error| CriterionError::InvalidScoreExpression {
+        expression: expression.to_string(),
+        error,
+    })
+}
+
+/// Parses the filter of a `boost(...)` criterion, which must not be empty.
+pub(crate) fn parse_boost_filter(filter: &str) -> Result<Filter<'_>, CriterionError> {
+    match Filter::from_str(filter) {
+        Ok(Some(parsed)) => Ok(parsed),
+        Ok(None) => Err(CriterionError::InvalidBoostFilter {
+            filter: filter.to_string(),
+            error: "the filter must not be empty.".to_string(),
+        }),
+        Err(error) => Err(CriterionError::InvalidBoostFilter {
+            filter: filter.to_string(),
+            error: error.to_string(),
+        }),
+    }
+}
+
+pub(crate) fn parse_decay_function(function: &str) -> Result<DecayFunction, CriterionError> {
+    function.parse().map_err(|error| CriterionError::InvalidDecayFunction {
+        function: function.to_string(),
+        error,
+    })
+}
+
+/// Checks the ranking rules against each other and against the fields of the index:
+/// - `termRarity` replaces `words` and must be placed before it,
+/// - the numbers of the fields of the sort, `score:` and `decay(...)` ranking rules are read
+///   from the facet databases, these fields must be faceted,
+/// - the attributes of the filters of the `boost(...)` ranking rules must be filterable.
+pub(crate) fn validate_criteria(
+    criteria: &[Criterion],
+    filterable_fields: &HashSet<String>,
+    faceted_fields: &HashSet<String>,
+) -> Result<(), CriterionError> {
+    if let Some(position) = criteria.iter().position(|c| *c == Criterion::TermRarity) {
+        // these ranking rules add the `words` ranking rule before them
+        let words = criteria[..position].iter().find(|criterion| {
+            matches!(
+                criterion,
+                Criterion::Words
+                    | Criterion::Typo
+                    | Criterion::Proximity
+                    | Criterion::Attribute
+                    | Criterion::Exactness
+            )
+        });
+        if let Some(words) = words {
+            return Err(CriterionError::TermRarityAfterWords { rule: words.to_string() });
+        }
+    }
+
+    for criterion in criteria {
+        let (fields, valid_fields): (Vec<String>, _) = match criterion {
+            Criterion::Asc(field) | Criterion::Desc(field) => (vec![field.clone()], faceted_fields),
+            Criterion::Score(expression) => {
+                let expression = parse_score_expression(expression)?;
+                (expression.fields().into_iter().map(String::from).collect(), faceted_fields)
+            }
+            Criterion::Decay(function) => {
+                (vec![parse_decay_function(function)?.field], faceted_fields)
+            }
+            Criterion::Boost(filter) => {
+                let filter = parse_boost_filter(filter)?;
+                (filter.fields().into_iter().map(String::from).collect(), filterable_fields)
+            }
+            _otherwise => continue,
+        };
+
+        let invalid_field =
+            fields.into_iter().find(|field| !crate::is_faceted(field, valid_fields));
+        if let Some(field) = invalid_field {
+            let rule = criterion.to_string();
+            return Err(match criterion {
+                Criterion::Boost(_) => CriterionError::NotFilterableField { rule, field },
+                _otherwise => CriterionError::NotFacetedField { rule, field },
+            });
+        }
+    }
+
+    Ok(())
 }
 
 impl FromStr for Criterion {
@@ -63,17 +196,39 @@ impl FromStr for Criterion {
     fn from_str(text: &str) -> Result<Criterion, Self::Err> {
         match text {
             "words" => Ok(Criterion::Words),
+            "termRarity" => Ok(Criterion::TermRarity),
             "typo" => Ok(Criterion::Typo),
             "proximity" => Ok(Criterion::Proximity),
             "attribute" => Ok(Criterion::Attribute),
             "sort" => Ok(Criterion::Sort),
             "exactness" => Ok(Criterion::Exactness),
-            text => match AscDesc::from_str(text)? {
-                AscDesc::Asc(Member::Field(field)) => Ok(Criterion::Asc(field)),
-                AscDesc::Desc(Member::Field(field)) => Ok(Criterion::Desc(field)),
-                AscDesc::Asc(Member::Geo(_)) | AscDesc::Desc(Member::Geo(_)) => {
+            text => match AscDesc::from_str(text) {
+                Ok(AscDesc::Asc(Member::Field(field))) => Ok(Criterion::Asc(field)),
+                Ok(AscDesc::Desc(Member::Field(field))) => Ok(Criterion::Desc(field)),
+                Ok(AscDesc::Asc(Member::Geo(_)) | AscDesc::Desc(Member::Geo(_))) => {
                     Err(CriterionError::ReservedNameForSort { name: "_geoPoint".to_string() })?
                 }
+                // `score:price:desc` is still a descending sort on the `score:price` field
+                Err(AscDescError::InvalidSyntax { .. }) if text.starts_with("score:") => {
+                    let expression = &text["score:".len()..];
+                    parse_score_expression(expression)?;
+                    Ok(Criterion::Score(expression.to_string()))
+                }
+                Err(AscDescError::InvalidSyntax { .. })
+                    if text.starts_with("decay(") && text.ends_with(')') =>
+                {
+                    let function = &text["decay(".len()..text.len() - 1];
+                    parse_decay_function(function)?;
+                    Ok(Criterion::Decay(function.to_string()))
+                }
+                Err(AscDescError::InvalidSyntax { .. })
+                    if text.starts_with("boost(") && text.ends_with(')') =>
+                {
+                    let filter = &text["boost(".len()..text.len() - 1];
+                    parse_boost_filter(filter)?;
+                    Ok(Criterion::Boost(filter.to_string()))
+                }
+                Err(error) => Err(error.into()),
             },
         }
     }
@@ -96,6 +251,7 @@ impl fmt::Display for Criterion {
 
         match self {
             Words => f.write_str("words"),
+            TermRarity => f.write_str("termRarity"),
             Typo => f.write_str("typo"),
             Proximity => f.write_str("proximity"),
             Attribute => f.write_str("attribute"),
@@ -103,6 +259,9 @@ impl fmt::Display for Criterion {
             Exactness => f.write_str("exactness"),
             Asc(attr) => write!(f, "{}:asc", attr),
             Desc(attr) => write!(f, "{}:desc", attr),
+            Score(expression) => write!(f, "score:{}", expression),
+            Decay(function) => write!(f, "decay({})", function),
+            Boost(filter) => write!(f, "boost({})", filter),
         }
     }
 }
@@ -118,6 +277,7 @@ mod tests {
     fn parse_criterion() {
         let valid_criteria = [
             ("words", Criterion::Words),
+            ("termRarity", Criterion::TermRarity),
             ("typo", Criterion::Typo),
             ("proximity", Criterion::Proximity),
             ("attribute", Criterion::Attribute),
@@ -129,6 +289,21 @@ mod tests {
             ("truc:machin:desc", Criterion::Desc(S("truc:machin"))),
             ("hello-world!:desc", Criterion::Desc(S("hello-world!"))),
             ("it's spacy over there:asc", Criterion::Asc(S("it's spacy over there"))),
+            ("score:price:desc", Criterion::Desc(S("score:price"))),
+            ("score:rating", Criterion::Score(S("rating"))),
+            (
+                "score:log1p(popularity) * 0.3 + rating",
+                Criterion::Score(S("log1p(popularity) * 0.3 + rating")),
+            ),
+            (
+                "decay(published_at, origin=now, scale=7d, shape=gauss)",
+                Criterion::Decay(S("published_at, origin=now, scale=7d, shape=gauss")),
+            ),
+            ("boost(brand = \"acme\")", Criterion::Boost(S("brand = \"acme\""))),
+            (
+                "boost(is_promoted = true OR (price < 10 AND stock > 0))",
+                Criterion::Boost(S("is_promoted = true OR (price < 10 AND stock > 0)")),
+            ),
         ];
 
         for (input, expected) in valid_criteria {
@@ -164,6 +339,30 @@ mod tests {
                 "_geoBoundingBox([42, 75], [75, 59]):asc",
                 ReservedNameForFilter { name: S("_geoBoundingBox") },
             ),
+            (
+                "score:",
+                InvalidScoreExpression { expression: S(""), error: ScoreExpressionError::Empty },
+            ),
+            (
+                "score:log(popularity)",
+                InvalidScoreExpression {
+                    expression: S("log(popularity)"),
+                    error: ScoreExpressionError::UnknownFunction { name: S("log") },
+                },
+            ),
+            (
+                "decay(published_at, scale=7d)",
+                InvalidDecayFunction {
+                    function: S("published_at, scale=7d"),
+                    error: DecayFunctionError::MissingParameter { name: "origin" },
+                },
+            ),
+            ("decay(published_at", InvalidName { name: S("decay(published_at") }),
+            (
+                "boost()",
+                InvalidBoostFilter { filter: S(""), error: S("the filter must not be empty.") },
+            ),
+            ("boost(brand = acme", InvalidName { name: S("boost(brand = acme") }),
         ];
 
         for (input, expected) in invalid_criteria {
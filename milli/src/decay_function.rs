@@ -0,0 +1,288 @@
+//! This module provides the `DecayFunction` type used by the `decay(...)` ranking rule.
+//!
+//! The rule is written `decay(field, origin=now, scale=7d, offset=1d, decay=0.5, shape=gauss)`,
+//! only the field, the origin and the scale are mandatory.
+
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use filter_parser::parse_date;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecayFunctionError {
+    #[error("the first argument must be the name of a field")]
+    MissingField,
+    #[error("`{name}` is not a valid parameter. Valid parameters are origin, scale, offset, decay and shape")]
+    UnknownParameter { name: String },
+    #[error("the `{name}` parameter is specified more than once")]
+    DuplicateParameter { name: String },
+    #[error("the `{name}` parameter is missing")]
+    MissingParameter { name: &'static str },
+    #[error("`{value}` is not a valid `{name}`, expected {expected}")]
+    InvalidValue { name: &'static str, value: String, expected: &'static str },
+}
+
+/// Scores the documents by how close the value of a field is to an origin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayFunction {
+    pub field: String,
+    pub origin: DecayOrigin,
+    /// The distance from the origin, after the offset, at which the score is `decay`.
+    pub scale: f64,
+    /// The distance from the origin under which the score is 1.
+    pub offset: f64,
+    pub decay: f64,
+    pub shape: DecayShape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecayOrigin {
+    /// The time at which the search is executed, as a Unix timestamp in seconds.
+    Now,
+    Value(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecayShape {
+    #[default]
+    Gauss,
+    Linear,
+    Exp,
+}
+
+impl DecayFunction {
+    /// Returns the value of the origin, `now` being the current Unix timestamp in seconds.
+    pub fn origin(&self) -> f64 {
+        match self.origin {
+            DecayOrigin::Now => {
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+            }
+            DecayOrigin::Value(value) => value,
+        }
+    }
+
+    /// Returns the score, between 0 and 1, of a value given the value of the origin.
+    pub fn score(&self, origin: f64, value: f64) -> f64 {
+        let distance = ((value - origin).abs() - self.offset).max(0.0) / self.scale;
+        match self.shape {
+            DecayShape::Gauss => self.decay.powf(distance * distance),
+            DecayShape::Exp => self.decay.powf(distance),
+            DecayShape::Linear => (1.0 - (1.0 - self.decay) * distance).max(0.0),
+        }
+    }
+}
+
+impl FromStr for DecayFunction {
+    type Err = DecayFunctionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut arguments = text.split(',').map(str::trim);
+        let field = match arguments.next() {
+            Some(field) if !field.is_empty() && !field.contains('=') => field.to_string(),
+            _ => return Err(DecayFunctionError::MissingField),
+        };
+
+        let mut origin = None;
+        let mut scale = None;
+        let mut offset = None;
+        let mut decay = None;
+        let mut shape = None;
+        for argument in arguments {
+            let (name, value) = argument.split_once('=').unwrap_or((argument, ""));
+            let (name, value) = (name.trim(), value.trim());
+            let parameter = match name {
+                "origin" => &mut origin,
+                "scale" => &mut scale,
+                "offset" => &mut offset,
+                "decay" => &mut decay,
+                "shape" => &mut shape,
+                _ => return Err(DecayFunctionError::UnknownParameter { name: name.to_string() }),
+            };
+            if parameter.replace(value).is_some() {
+                return Err(DecayFunctionError::DuplicateParameter { name: name.to_string() });
+            }
+        }
+
+        let origin = match origin.ok_or(DecayFunctionError::MissingParameter { name: "origin" })? {
+            "now" => DecayOrigin::Now,
+            value => value
+                .parse()
+                .ok()
+                .or_else(|| parse_date(value))
+                .map(DecayOrigin::Value)
+                .ok_or_else(|| DecayFunctionError::InvalidValue {
+                    name: "origin",
+                    value: value.to_string(),
+                    expected: "`now`, a number or a date",
+                })?,
+        };
+        let scale = scale.ok_or(DecayFunctionError::MissingParameter { name: "scale" })?;
+        let scale = parse_distance(scale).filter(|scale| *scale > 0.0).ok_or_else(|| {
+            DecayFunctionError::InvalidValue {
+                name: "scale",
+                value: scale.to_string(),
+                expected: "a positive number or a duration like `7d`",
+            }
+        })?;
+        let offset = match offset {
+            Some(offset) => {
+                parse_distance(offset).ok_or_else(|| DecayFunctionError::InvalidValue {
+                    name: "offset",
+                    value: offset.to_string(),
+                    expected: "a number or a duration like `7d`",
+                })?
+            }
+            None => 0.0,
+        };
+        let decay = match decay {
+            Some(decay) => {
+                decay.parse::<f64>().ok().filter(|decay| *decay > 0.0 && *decay < 1.0).ok_or_else(
+                    || DecayFunctionError::InvalidValue {
+                        name: "decay",
+                        value: decay.to_string(),
+                        expected: "a number between 0 and 1",
+                    },
+                )?
+            }
+            None => 0.5,
+        };
+        let shape = match shape {
+            Some("gauss") => DecayShape::Gauss,
+            Some("linear") => DecayShape::Linear,
+            Some("exp") => DecayShape::Exp,
+            Some(shape) => {
+                return Err(DecayFunctionError::InvalidValue {
+                    name: "shape",
+                    value: shape.to_string(),
+                    expected: "`gauss`, `linear` or `exp`",
+                })
+            }
+            None => DecayShape::default(),
+        };
+
+        Ok(DecayFunction { field, origin, scale, offset, decay, shape })
+    }
+}
+
+/// Parses a positive number, or a duration made of digits followed by `s`, `m`, `h`, `d`
+/// or `w` into its number of seconds.
+fn parse_distance(text: &str) -> Option<f64> {
+    if let Ok(distance) = text.parse::<f64>() {
+        return Some(distance).filter(|distance| *distance >= 0.0);
+    }
+    let unit = match text.chars().last()? {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    let count = &text[..text.len() - 1];
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    count.parse::<u64>().ok()?.checked_mul(unit).map(|seconds| seconds as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+
+    use super::*;
+
+    #[test]
+    fn parse_decay_function() {
+        let decay: DecayFunction =
+            "published_at, origin=now, scale=7d, shape=gauss".parse().unwrap();
+        assert_eq!(
+            decay,
+            DecayFunction {
+                field: S("published_at"),
+                origin: DecayOrigin::Now,
+                scale: 604800.0,
+                offset: 0.0,
+                decay: 0.5,
+                shape: DecayShape::Gauss,
+            }
+        );
+
+        let decay: DecayFunction =
+            "price,origin=2024-01-01,scale=10.5,offset=2,decay=0.2,shape=linear".parse().unwrap();
+        assert_eq!(
+            decay,
+            DecayFunction {
+                field: S("price"),
+                origin: DecayOrigin::Value(1704067200.0),
+                scale: 10.5,
+                offset: 2.0,
+                decay: 0.2,
+                shape: DecayShape::Linear,
+            }
+        );
+
+        let invalid_functions = [
+            ("", "the first argument must be the name of a field"),
+            ("origin=now, scale=7d", "the first argument must be the name of a field"),
+            ("date, scale=7d", "the `origin` parameter is missing"),
+            ("date, origin=now", "the `scale` parameter is missing"),
+            ("date, origin=now, scale=1d, scale=2d", "the `scale` parameter is specified more than once"),
+            (
+                "date, origin=now, scale=7d, sigma=2",
+                "`sigma` is not a valid parameter. Valid parameters are origin, scale, offset, decay and shape",
+            ),
+            (
+                "date, origin=yesterday, scale=7d",
+                "`yesterday` is not a valid `origin`, expected `now`, a number or a date",
+            ),
+            (
+                "date, origin=now, scale=0",
+                "`0` is not a valid `scale`, expected a positive number or a duration like `7d`",
+            ),
+            (
+                "date, origin=now, scale=7y",
+                "`7y` is not a valid `scale`, expected a positive number or a duration like `7d`",
+            ),
+            (
+                "date, origin=now, scale=7d, offset=-1",
+                "`-1` is not a valid `offset`, expected a number or a duration like `7d`",
+            ),
+            (
+                "date, origin=now, scale=7d, decay=1",
+                "`1` is not a valid `decay`, expected a number between 0 and 1",
+            ),
+            (
+                "date, origin=now, scale=7d, shape=square",
+                "`square` is not a valid `shape`, expected `gauss`, `linear` or `exp`",
+            ),
+        ];
+        for (input, expected) in invalid_functions {
+            let error = input.parse::<DecayFunction>();
+            assert!(error.is_err(), "Should not be able to parse `{input}`: {error:?}");
+            assert_eq!(error.unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn decay_function_score() {
+        for shape in ["gauss", "linear", "exp"] {
+            let decay: DecayFunction =
+                format!("price, origin=100, scale=10, offset=5, decay=0.5, shape={shape}")
+                    .parse()
+                    .unwrap();
+            // the values under the offset have the best score
+            assert_eq!(decay.score(100.0, 100.0), 1.0, "{shape}");
+            assert_eq!(decay.score(100.0, 95.0), 1.0, "{shape}");
+            // the values at the scale after the offset have the decay as score
+            assert_eq!(decay.score(100.0, 115.0), 0.5, "{shape}");
+            assert_eq!(decay.score(100.0, 85.0), 0.5, "{shape}");
+            assert!(decay.score(100.0, 110.0) > 0.5, "{shape}");
+            assert!(decay.score(100.0, 120.0) < 0.5, "{shape}");
+        }
+
+        let linear: DecayFunction = "price, origin=0, scale=10, shape=linear".parse().unwrap();
+        assert_eq!(linear.score(0.0, 20.0), 0.0);
+        assert_eq!(linear.score(0.0, 30.0), 0.0);
+    }
+}
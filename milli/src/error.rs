@@ -173,10 +173,24 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         valid_fields: BTreeSet<String>,
         hidden_fields: bool,
     },
+    #[error("The searchable attribute `{attribute}` must have a weight because other searchable attributes have one.")]
+    MissingSearchableAttributeWeight { attribute: String },
     #[error("an environment is already opened with different options")]
     InvalidLmdbOpenOptions,
     #[error("You must specify where `sort` is listed in the rankingRules setting to use the sort parameter at search time.")]
     SortRankingRuleMissing,
+    #[error("Invalid ranking rules at search time: {0}")]
+    InvalidSearchRankingRules(CriterionError),
+    #[error("Too many synonyms: {count} words were given synonyms at search time, but at most {limit} are allowed.")]
+    TooManySearchSynonyms { count: usize, limit: usize },
+    #[error("Too many synonyms for `{word}`: {count} synonyms were given at search time, but at most {limit} are allowed per word.")]
+    TooManySearchSynonymsForWord { word: String, count: usize, limit: usize },
+    #[error("Synonym `{synonym}` is too long: the words and synonyms given at search time must be at most {limit} bytes long.")]
+    SearchSynonymTooLong { synonym: String, limit: usize },
+    #[error("Too many stop words: {count} stop words were given at search time, but at most {limit} are allowed.")]
+    TooManySearchStopWords { count: usize, limit: usize },
+    #[error("Stop word `{word}` is too long: the stop words given at search time must be at most {limit} bytes long.")]
+    SearchStopWordTooLong { word: String, limit: usize },
     #[error("The database file is in an invalid state.")]
     InvalidStoreFile,
     #[error("Maximum database size has been reached.")]
@@ -201,6 +215,23 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     UnknownInternalDocumentId { document_id: DocumentId },
     #[error("`minWordSizeForTypos` setting is invalid. `oneTypo` and `twoTypos` fields should be between `0` and `255`, and `twoTypos` should be greater or equals to `oneTypo` but found `oneTypo: {0}` and twoTypos: {1}`.")]
     InvalidMinTypoWordLenSetting(u8, u8),
+    #[error("`maxTyposPerAttribute` setting is invalid. The number of typos of an attribute should be between `0` and `2` but found `{attribute}: {max_typos}`.")]
+    InvalidMaxTyposPerAttribute { attribute: String, max_typos: u8 },
+    #[error("`maxTyposPerAttribute` setting is invalid. Attribute `{}` is not searchable. Available searchable attributes are: `{}`.",
+        .attribute,
+        .valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+    )]
+    InvalidMaxTyposAttribute { attribute: String, valid_fields: BTreeSet<String> },
+    #[error("`rules` setting is invalid. The `{}` filter of a rule is about the `{}` attribute, which is not filterable. Available filterable attributes are: `{}`.",
+        .filter,
+        .attribute,
+        .valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+    )]
+    InvalidMerchandisingRuleFilter {
+        filter: &'static str,
+        attribute: String,
+        valid_fields: BTreeSet<String>,
+    },
     #[error(transparent)]
     VectorEmbeddingError(#[from] crate::vector::Error),
     #[error(transparent)]
@@ -327,6 +358,8 @@ pub enum GeoError {
     BadLatitude { document_id: Value, value: Value },
     #[error("Could not parse longitude in the document with the id: `{document_id}`. Was expecting a finite number but instead got `{value}`.")]
     BadLongitude { document_id: Value, value: Value },
+    #[error("Could not parse the GeoJSON geometry in the `_geo` field of the document with the id: `{document_id}`: {reason}.")]
+    InvalidGeoJson { document_id: Value, reason: String },
 }
 
 fn format_invalid_filter_distribution(
@@ -0,0 +1,369 @@
+use std::cell::OnceCell;
+
+use heed::RoTxn;
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{distance_between_two_points, lat_lng_bounding_box_to_xyz_envelope, DocumentId, Index};
+
+/// A GeoShapeEnvelope is the cartesian envelope of a [`GeoShape`], called xyz_envelope in the code.
+/// Its metadata is a tuple composed of 1. the DocumentId of the associated document and 2. the
+/// original shape expressed in terms of latitude and longitude.
+pub type GeoShapeEnvelope = GeomWithData<Rectangle<[f64; 3]>, (DocumentId, GeoShape)>;
+
+/// The geo shapes R-tree of an index, only deserialized the first time it is needed.
+///
+/// It is kept in the search context so that the filters and the geo sort of a search share it.
+#[derive(Default)]
+pub(crate) struct LazyGeoShapesRtree(OnceCell<Option<RTree<GeoShapeEnvelope>>>);
+
+impl LazyGeoShapesRtree {
+    pub fn get(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn<'_>,
+    ) -> crate::Result<Option<&RTree<GeoShapeEnvelope>>> {
+        if let Some(rtree) = self.0.get() {
+            return Ok(rtree.as_ref());
+        }
+        let rtree = index.geo_shapes_rtree(rtxn)?;
+        Ok(self.0.get_or_init(|| rtree).as_ref())
+    }
+}
+
+/// A geometry given in the `_geo` field of a document under the GeoJSON format.
+///
+/// The coordinates are stored as `[latitude, longitude]`, unlike GeoJSON which uses
+/// `[longitude, latitude]`, to be consistent with the rest of the geo features.
+/// The edges are considered to be straight lines in the latitude/longitude plane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GeoShape {
+    Point([f64; 2]),
+    LineString(Vec<[f64; 2]>),
+    /// The first ring is the exterior of the polygon, the following ones are its holes.
+    /// The rings are implicitly closed, their last point is not a copy of the first one.
+    /// A polygon without any ring is empty, it contains no point.
+    Polygon(Vec<Vec<[f64; 2]>>),
+}
+
+impl GeoShape {
+    /// Returns `true` if the `_geo` value looks like a GeoJSON geometry rather than a
+    /// `{ "lat": .., "lng": .. }` object.
+    pub fn is_geojson(value: &Value) -> bool {
+        value.as_object().map_or(false, |object| object.contains_key("type"))
+    }
+
+    /// Parses a GeoJSON `Point`, `LineString` or `Polygon` geometry.
+    pub fn from_geojson(value: &Value) -> Result<Self, String> {
+        let object =
+            value.as_object().ok_or_else(|| format!("expected an object, got `{value}`"))?;
+        if let Some(key) = object.keys().find(|k| !["type", "coordinates", "bbox"].contains(&&***k))
+        {
+            return Err(format!("unexpected field `{key}`"));
+        }
+        let coordinates = object
+            .get("coordinates")
+            .ok_or_else(|| "missing the `coordinates` field".to_string())?;
+
+        match object.get("type").and_then(Value::as_str) {
+            Some("Point") => Ok(GeoShape::Point(parse_position(coordinates)?)),
+            Some("LineString") => {
+                let line = parse_positions(coordinates)?;
+                if line.len() < 2 {
+                    return Err("a `LineString` must contain at least two positions".to_string());
+                }
+                Ok(GeoShape::LineString(line))
+            }
+            Some("Polygon") => {
+                let rings = coordinates
+                    .as_array()
+                    .ok_or_else(|| format!("expected an array of rings, got `{coordinates}`"))?;
+                if rings.is_empty() {
+                    return Err("a `Polygon` must contain at least one ring".to_string());
+                }
+                let rings = rings
+                    .iter()
+                    .map(|ring| {
+                        let mut ring = parse_positions(ring)?;
+                        if ring.len() < 4 || ring.first() != ring.last() {
+                            return Err("the rings of a `Polygon` must contain at least four positions and be closed".to_string());
+                        }
+                        ring.pop();
+                        Ok(ring)
+                    })
+                    .collect::<Result<_, String>>()?;
+                Ok(GeoShape::Polygon(rings))
+            }
+            Some(other) => Err(format!(
+                "only the `Point`, `LineString` and `Polygon` types are supported, got `{other}`"
+            )),
+            None => Err("missing the `type` field".to_string()),
+        }
+    }
+
+    /// Returns all the points that define this shape.
+    pub fn points(&self) -> impl Iterator<Item = &[f64; 2]> {
+        let points: Box<dyn Iterator<Item = &[f64; 2]>> = match self {
+            GeoShape::Point(point) => Box::new(std::iter::once(point)),
+            GeoShape::LineString(line) => Box::new(line.iter()),
+            GeoShape::Polygon(rings) => Box::new(rings.iter().flatten()),
+        };
+        points
+    }
+
+    /// Returns all the edges of this shape, the rings of a polygon are closed.
+    fn edges(&self) -> Vec<([f64; 2], [f64; 2])> {
+        match self {
+            GeoShape::Point(_) => Vec::new(),
+            GeoShape::LineString(line) => line.windows(2).map(|w| (w[0], w[1])).collect(),
+            GeoShape::Polygon(rings) => rings.iter().flat_map(|ring| ring_edges(ring)).collect(),
+        }
+    }
+
+    /// Returns the envelope of this shape that can be stored in the geo shapes R-tree.
+    pub fn to_envelope(&self, docid: DocumentId) -> GeoShapeEnvelope {
+        let points: Vec<[f64; 2]> = self.points().copied().collect();
+        let envelope = lat_lng_bounding_box_to_xyz_envelope(&points);
+        GeoShapeEnvelope::new(Rectangle::from_aabb(envelope), (docid, self.clone()))
+    }
+
+    /// Returns `true` if the point is on this shape or inside of it.
+    pub fn contains(&self, point: &[f64; 2]) -> bool {
+        match self {
+            GeoShape::Point(p) => p == point,
+            GeoShape::LineString(_) => self
+                .edges()
+                .iter()
+                .any(|(a, b)| orientation(*a, *b, *point) == 0.0 && on_segment(*a, *b, *point)),
+            GeoShape::Polygon(rings) => {
+                let Some((exterior, holes)) = rings.split_first() else { return false };
+                point_in_polygon(point, exterior)
+                    && holes.iter().all(|hole| {
+                        !point_in_polygon(point, hole)
+                            || ring_edges(hole).any(|(a, b)| {
+                                orientation(a, b, *point) == 0.0 && on_segment(a, b, *point)
+                            })
+                    })
+            }
+        }
+    }
+
+    /// Returns `true` if this shape and the implicitly closed polygon have at least one point in common.
+    pub fn intersects_polygon(&self, polygon: &[[f64; 2]]) -> bool {
+        if self.points().any(|point| point_in_polygon(point, polygon)) {
+            return true;
+        }
+        let edges = self.edges();
+        if edges
+            .iter()
+            .any(|edge| ring_edges(polygon).any(|other| segments_intersect(*edge, other)))
+        {
+            return true;
+        }
+        // the polygon may be entirely contained in this shape
+        polygon.iter().any(|point| self.contains(point))
+    }
+
+    /// Returns the point of this shape that is the closest to the provided point,
+    /// which is the point itself if it is inside of this shape, or `None` if this shape is empty.
+    pub fn nearest_point(&self, point: &[f64; 2]) -> Option<[f64; 2]> {
+        match self {
+            GeoShape::Point(p) => Some(*p),
+            _ if self.contains(point) => Some(*point),
+            _ => self
+                .edges()
+                .into_iter()
+                .map(|(a, b)| nearest_point_on_segment(a, b, *point))
+                .map(|p| (distance_between_two_points(point, &p), p))
+                .min_by(|(left, _), (right, _)| left.total_cmp(right))
+                .map(|(_, p)| p),
+        }
+    }
+
+    /// Returns the distance in meters between the provided point and the nearest point of this shape,
+    /// or `None` if this shape is empty.
+    pub fn distance_to(&self, point: &[f64; 2]) -> Option<f64> {
+        self.nearest_point(point).map(|nearest| distance_between_two_points(point, &nearest))
+    }
+}
+
+/// Parses a GeoJSON position, `[longitude, latitude]`, into a `[latitude, longitude]` point.
+fn parse_position(value: &Value) -> Result<[f64; 2], String> {
+    let invalid = || format!("expected a `[longitude, latitude]` position, got `{value}`");
+    match value.as_array().map(Vec::as_slice) {
+        Some([lng, lat, ..]) => {
+            let lat = lat.as_f64().filter(|lat| lat.is_finite()).ok_or_else(invalid)?;
+            let lng = lng.as_f64().filter(|lng| lng.is_finite()).ok_or_else(invalid)?;
+            if !(-90.0..=90.0).contains(&lat) {
+                return Err(format!("bad latitude `{lat}`, it must be between -90 and 90 degrees"));
+            }
+            if !(-180.0..=180.0).contains(&lng) {
+                return Err(format!(
+                    "bad longitude `{lng}`, it must be between -180 and 180 degrees"
+                ));
+            }
+            Ok([lat, lng])
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_positions(value: &Value) -> Result<Vec<[f64; 2]>, String> {
+    match value.as_array() {
+        Some(positions) => positions.iter().map(parse_position).collect(),
+        None => Err(format!("expected an array of positions, got `{value}`")),
+    }
+}
+
+fn ring_edges(ring: &[[f64; 2]]) -> impl Iterator<Item = ([f64; 2], [f64; 2])> + '_ {
+    ring.iter().enumerate().map(|(i, a)| (*a, ring[(i + 1) % ring.len()]))
+}
+
+/// Returns the orientation of the `c` point relative to the `a`-`b` line:
+/// positive when counter-clockwise, negative when clockwise and zero when collinear.
+fn orientation(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Returns `true` if `c` is in the bounding box of the `a`-`b` segment.
+fn on_segment(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    (a[0].min(b[0])..=a[0].max(b[0])).contains(&c[0])
+        && (a[1].min(b[1])..=a[1].max(b[1])).contains(&c[1])
+}
+
+fn segments_intersect((a, b): ([f64; 2], [f64; 2]), (c, d): ([f64; 2], [f64; 2])) -> bool {
+    let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+    let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+    if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+        return true;
+    }
+    (o1 == 0.0 && on_segment(a, b, c))
+        || (o2 == 0.0 && on_segment(a, b, d))
+        || (o3 == 0.0 && on_segment(c, d, a))
+        || (o4 == 0.0 && on_segment(c, d, b))
+}
+
+/// Returns the point of the `a`-`b` segment that is the closest to `point`.
+///
+/// The projection is made on a plane tangent to the earth at `point` so that
+/// a degree of longitude and a degree of latitude weigh the same.
+fn nearest_point_on_segment(a: [f64; 2], b: [f64; 2], point: [f64; 2]) -> [f64; 2] {
+    let scale = point[0].to_radians().cos();
+    let project = |p: [f64; 2]| [p[0] - point[0], (p[1] - point[1]) * scale];
+    let (pa, pb) = (project(a), project(b));
+    let (dx, dy) = (pb[0] - pa[0], pb[1] - pa[1]);
+    let length = dx * dx + dy * dy;
+    if length == 0.0 {
+        return a;
+    }
+    let t = (-(pa[0] * dx + pa[1] * dy) / length).clamp(0.0, 1.0);
+    [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])]
+}
+
+/// Returns `true` if the point is inside of the polygon or on one of its edges.
+///
+/// The polygon is implicitly closed and the test is made by casting a ray along the
+/// latitude axis and counting the number of edges it crosses.
+pub(crate) fn point_in_polygon(point: &[f64; 2], polygon: &[[f64; 2]]) -> bool {
+    let [lat, lng] = *point;
+    let mut inside = false;
+    for (a, b) in ring_edges(polygon) {
+        // the point is on the edge
+        if orientation(a, b, *point) == 0.0 && on_segment(a, b, *point) {
+            return true;
+        }
+        if (a[1] > lng) != (b[1] > lng) {
+            let crossing_lat = a[0] + (lng - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if lat < crossing_lat {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_geojson() {
+        let point = GeoShape::from_geojson(&json!({ "type": "Point", "coordinates": [2, 1] }));
+        assert_eq!(point, Ok(GeoShape::Point([1.0, 2.0])));
+
+        let line = GeoShape::from_geojson(
+            &json!({ "type": "LineString", "coordinates": [[0, 0], [2, 1]] }),
+        );
+        assert_eq!(line, Ok(GeoShape::LineString(vec![[0.0, 0.0], [1.0, 2.0]])));
+
+        let polygon = GeoShape::from_geojson(
+            &json!({ "type": "Polygon", "coordinates": [[[0, 0], [2, 0], [2, 2], [0, 0]]] }),
+        );
+        assert_eq!(polygon, Ok(GeoShape::Polygon(vec![vec![[0.0, 0.0], [0.0, 2.0], [2.0, 2.0]]])));
+
+        insta::assert_snapshot!(
+            GeoShape::from_geojson(&json!({ "type": "Polygon", "coordinates": [[[0, 0], [2, 0], [2, 2]]] })).unwrap_err(),
+            @"the rings of a `Polygon` must contain at least four positions and be closed"
+        );
+        insta::assert_snapshot!(
+            GeoShape::from_geojson(&json!({ "type": "Point", "coordinates": [0, 100] })).unwrap_err(),
+            @"bad latitude `100`, it must be between -90 and 90 degrees"
+        );
+        insta::assert_snapshot!(
+            GeoShape::from_geojson(&json!({ "type": "MultiPoint", "coordinates": [[0, 0]] })).unwrap_err(),
+            @"only the `Point`, `LineString` and `Polygon` types are supported, got `MultiPoint`"
+        );
+        insta::assert_snapshot!(
+            GeoShape::from_geojson(&json!({ "type": "Point", "coordinates": [0, 0], "lat": 0 })).unwrap_err(),
+            @"unexpected field `lat`"
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let polygon = GeoShape::Polygon(vec![
+            vec![[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]],
+            vec![[4.0, 4.0], [4.0, 6.0], [6.0, 6.0], [6.0, 4.0]],
+        ]);
+        assert!(polygon.contains(&[1.0, 1.0]));
+        assert!(!polygon.contains(&[5.0, 5.0]));
+        // the edges of the hole are part of the polygon
+        assert!(polygon.contains(&[4.0, 5.0]));
+        assert!(!polygon.contains(&[11.0, 5.0]));
+
+        // a polygon inside of the hole doesn't intersect
+        assert!(!polygon.intersects_polygon(&[[4.5, 4.5], [4.5, 5.5], [5.5, 5.5]]));
+        // a polygon containing the whole shape intersects
+        assert!(polygon.intersects_polygon(&[
+            [-1.0, -1.0],
+            [-1.0, 11.0],
+            [11.0, 11.0],
+            [11.0, -1.0]
+        ]));
+        // a polygon crossing an edge without containing any of its points intersects
+        assert!(polygon.intersects_polygon(&[[-1.0, 5.0], [1.0, 4.0], [1.0, 6.0]]));
+
+        assert_eq!(polygon.nearest_point(&[1.0, 1.0]), Some([1.0, 1.0]));
+        assert_eq!(polygon.nearest_point(&[4.5, 5.0]), Some([4.0, 5.0]));
+        assert_eq!(polygon.nearest_point(&[5.0, -3.0]), Some([5.0, 0.0]));
+
+        // a polygon without any ring is empty
+        let empty = GeoShape::Polygon(Vec::new());
+        assert!(!empty.contains(&[0.0, 0.0]));
+        assert!(!empty.intersects_polygon(&[[-1.0, -1.0], [-1.0, 1.0], [1.0, 1.0]]));
+        assert_eq!(empty.nearest_point(&[0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn line_string_distance() {
+        let line = GeoShape::LineString(vec![[0.0, 0.0], [0.0, 10.0]]);
+        assert_eq!(line.nearest_point(&[1.0, 5.0]), Some([0.0, 5.0]));
+        assert_eq!(line.nearest_point(&[1.0, 12.0]), Some([0.0, 10.0]));
+        assert_eq!(line.distance_to(&[0.0, 5.0]), Some(0.0));
+        assert!(line.contains(&[0.0, 3.0]));
+    }
+}
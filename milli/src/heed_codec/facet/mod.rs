@@ -1,4 +1,6 @@
 mod field_doc_id_facet_codec;
+mod field_id_bool_codec;
+mod nested_facet_value_codec;
 mod ordered_f64_codec;
 
 use std::borrow::Cow;
@@ -10,6 +12,8 @@ use heed::{BoxedError, BytesDecode, BytesEncode};
 use roaring::RoaringBitmap;
 
 pub use self::field_doc_id_facet_codec::FieldDocIdFacetCodec;
+pub use self::field_id_bool_codec::FieldIdBoolCodec;
+pub use self::nested_facet_value_codec::{NestedFacetValue, NestedFacetValueCodec};
 pub use self::ordered_f64_codec::OrderedF64Codec;
 use super::StrRefCodec;
 use crate::{CboRoaringBitmapCodec, BEU16};
@@ -17,6 +21,7 @@ use crate::{CboRoaringBitmapCodec, BEU16};
 pub type FieldDocIdFacetF64Codec = FieldDocIdFacetCodec<OrderedF64Codec>;
 pub type FieldDocIdFacetStringCodec = FieldDocIdFacetCodec<StrRefCodec>;
 pub type FieldDocIdFacetIgnoreCodec = FieldDocIdFacetCodec<DecodeIgnore>;
+pub type FieldDocIdNestedFacetValueCodec = FieldDocIdFacetCodec<NestedFacetValueCodec>;
 
 pub type FieldIdCodec = BEU16;
 
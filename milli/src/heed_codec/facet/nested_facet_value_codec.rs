@@ -0,0 +1,86 @@
+use std::borrow::Cow;
+
+use heed::{BoxedError, BytesDecode, BytesEncode};
+use thiserror::Error;
+
+use super::OrderedF64Codec;
+use crate::heed_codec::SliceTooShortError;
+
+/// A facet value of a field of the objects of a nested field, or the objects themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NestedFacetValue<'a> {
+    /// Stands for all the objects of the nested field of a document.
+    Object,
+    Number(f64),
+    /// A normalized string.
+    String(&'a str),
+    Bool(bool),
+    Null,
+    Empty,
+}
+
+impl NestedFacetValue<'_> {
+    const OBJECT: u8 = 0;
+    const NUMBER: u8 = 1;
+    const STRING: u8 = 2;
+    const BOOL: u8 = 3;
+    const NULL: u8 = 4;
+    const EMPTY: u8 = 5;
+}
+
+/// Encodes a [`NestedFacetValue`] as a tag byte followed by the value.
+pub struct NestedFacetValueCodec;
+
+impl<'a> BytesDecode<'a> for NestedFacetValueCodec {
+    type DItem = NestedFacetValue<'a>;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        let (&tag, bytes) = bytes.split_first().ok_or(SliceTooShortError)?;
+        match tag {
+            NestedFacetValue::OBJECT => Ok(NestedFacetValue::Object),
+            NestedFacetValue::NUMBER => {
+                OrderedF64Codec::bytes_decode(bytes).map(NestedFacetValue::Number)
+            }
+            NestedFacetValue::STRING => Ok(NestedFacetValue::String(std::str::from_utf8(bytes)?)),
+            NestedFacetValue::BOOL => {
+                let &boolean = bytes.first().ok_or(SliceTooShortError)?;
+                Ok(NestedFacetValue::Bool(boolean != 0))
+            }
+            NestedFacetValue::NULL => Ok(NestedFacetValue::Null),
+            NestedFacetValue::EMPTY => Ok(NestedFacetValue::Empty),
+            tag => Err(InvalidNestedFacetValueTagError { tag }.into()),
+        }
+    }
+}
+
+impl<'a> BytesEncode<'a> for NestedFacetValueCodec {
+    type EItem = NestedFacetValue<'a>;
+
+    fn bytes_encode(value: &Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        let mut bytes = Vec::new();
+        match value {
+            NestedFacetValue::Object => bytes.push(NestedFacetValue::OBJECT),
+            NestedFacetValue::Number(number) => {
+                bytes.push(NestedFacetValue::NUMBER);
+                bytes.extend_from_slice(&OrderedF64Codec::bytes_encode(number)?);
+            }
+            NestedFacetValue::String(string) => {
+                bytes.push(NestedFacetValue::STRING);
+                bytes.extend_from_slice(string.as_bytes());
+            }
+            NestedFacetValue::Bool(boolean) => {
+                bytes.push(NestedFacetValue::BOOL);
+                bytes.push(*boolean as u8);
+            }
+            NestedFacetValue::Null => bytes.push(NestedFacetValue::NULL),
+            NestedFacetValue::Empty => bytes.push(NestedFacetValue::EMPTY),
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("the tag {tag} does not correspond to a nested facet value")]
+pub struct InvalidNestedFacetValueTagError {
+    tag: u8,
+}
@@ -15,7 +15,7 @@ use crate::error::{InternalError, UserError};
 use crate::fields_ids_map::FieldsIdsMap;
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
-    FieldIdCodec, OrderedF64Codec,
+    FieldDocIdNestedFacetValueCodec, FieldIdBoolCodec, FieldIdCodec, OrderedF64Codec,
 };
 use crate::heed_codec::{BEU16StrCodec, FstSetCodec, StrBEU16Codec, StrRefCodec};
 use crate::order_by_map::OrderByMap;
@@ -25,12 +25,19 @@ use crate::vector::{ArroyWrapper, Embedding, EmbeddingConfig};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldIdWordCountCodec,
-    FieldidsWeightsMap, GeoPoint, LocalizedAttributesRule, ObkvCodec, Result, RoaringBitmapCodec,
-    RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight, BEU16, BEU32, BEU64,
+    FieldidsWeightsMap, GeoPoint, GeoShapeEnvelope, LocalizedAttributesRule, MerchandisingRule,
+    ObkvCodec, Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight,
+    BEU16, BEU32, BEU64,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
 pub const DEFAULT_MIN_WORD_LEN_TWO_TYPOS: u8 = 9;
+/// The version of the layout of the facet databases, the facets of the indexes with an older
+/// version are extracted again when they are explicitly upgraded, see
+/// [`Settings::upgrade_facets`](crate::update::Settings::upgrade_facets).
+///
+/// 1: the booleans are only stored in the `facet-id-bool-docids` database.
+pub const FACETS_VERSION: u32 = 1;
 
 pub mod main_key {
     pub const CRITERIA_KEY: &str = "criteria";
@@ -40,29 +47,40 @@ pub mod main_key {
     pub const HIDDEN_FACETED_FIELDS_KEY: &str = "hidden-faceted-fields";
     pub const FILTERABLE_FIELDS_KEY: &str = "filterable-fields";
     pub const SORTABLE_FIELDS_KEY: &str = "sortable-fields";
+    pub const NESTED_FIELDS_KEY: &str = "nested-fields";
+    pub const DATE_FIELDS_KEY: &str = "date-fields";
+    pub const PHONETIC_FIELDS_KEY: &str = "phonetic-fields";
     pub const FIELD_DISTRIBUTION_KEY: &str = "fields-distribution";
     pub const FIELDS_IDS_MAP_KEY: &str = "fields-ids-map";
     pub const FIELDIDS_WEIGHTS_MAP_KEY: &str = "fieldids-weights-map";
     pub const GEO_FACETED_DOCUMENTS_IDS_KEY: &str = "geo-faceted-documents-ids";
     pub const GEO_RTREE_KEY: &str = "geo-rtree";
+    pub const GEO_SHAPES_RTREE_KEY: &str = "geo-shapes-rtree";
     pub const PRIMARY_KEY_KEY: &str = "primary-key";
     pub const SEARCHABLE_FIELDS_KEY: &str = "searchable-fields";
     pub const USER_DEFINED_SEARCHABLE_FIELDS_KEY: &str = "user-defined-searchable-fields";
+    pub const USER_DEFINED_SEARCHABLE_FIELDS_WEIGHTS_KEY: &str =
+        "user-defined-searchable-fields-weights";
     pub const STOP_WORDS_KEY: &str = "stop-words";
     pub const NON_SEPARATOR_TOKENS_KEY: &str = "non-separator-tokens";
     pub const SEPARATOR_TOKENS_KEY: &str = "separator-tokens";
     pub const DICTIONARY_KEY: &str = "dictionary";
     pub const SYNONYMS_KEY: &str = "synonyms";
     pub const USER_DEFINED_SYNONYMS_KEY: &str = "user-defined-synonyms";
+    pub const SYNONYM_RULES_KEY: &str = "synonym-rules";
+    pub const USER_DEFINED_SYNONYM_RULES_KEY: &str = "user-defined-synonym-rules";
     pub const WORDS_FST_KEY: &str = "words-fst";
     pub const WORDS_PREFIXES_FST_KEY: &str = "words-prefixes-fst";
     pub const CREATED_AT_KEY: &str = "created-at";
     pub const UPDATED_AT_KEY: &str = "updated-at";
+    pub const FACETS_VERSION_KEY: &str = "facets-version";
     pub const AUTHORIZE_TYPOS: &str = "authorize-typos";
     pub const ONE_TYPO_WORD_LEN: &str = "one-typo-word-len";
     pub const TWO_TYPOS_WORD_LEN: &str = "two-typos-word-len";
     pub const EXACT_WORDS: &str = "exact-words";
     pub const EXACT_ATTRIBUTES: &str = "exact-attributes";
+    pub const MAX_TYPOS_PER_ATTRIBUTE: &str = "max-typos-per-attribute";
+    pub const MAX_TYPOS_PER_QUERY: &str = "max-typos-per-query";
     pub const MAX_VALUES_PER_FACET: &str = "max-values-per-facet";
     pub const SORT_FACET_VALUES_BY: &str = "sort-facet-values-by";
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
@@ -70,6 +88,7 @@ pub mod main_key {
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
     pub const LOCALIZED_ATTRIBUTES_RULES: &str = "localized_attributes_rules";
+    pub const MERCHANDISING_RULES_KEY: &str = "merchandising-rules";
 }
 
 pub mod db_name {
@@ -85,16 +104,19 @@ pub mod db_name {
     pub const WORD_FIELD_ID_DOCIDS: &str = "word-field-id-docids";
     pub const WORD_PREFIX_POSITION_DOCIDS: &str = "word-prefix-position-docids";
     pub const WORD_PREFIX_FIELD_ID_DOCIDS: &str = "word-prefix-field-id-docids";
+    pub const PHONETIC_WORD_DOCIDS: &str = "phonetic-word-docids";
     pub const FIELD_ID_WORD_COUNT_DOCIDS: &str = "field-id-word-count-docids";
     pub const FACET_ID_F64_DOCIDS: &str = "facet-id-f64-docids";
     pub const FACET_ID_EXISTS_DOCIDS: &str = "facet-id-exists-docids";
     pub const FACET_ID_IS_NULL_DOCIDS: &str = "facet-id-is-null-docids";
     pub const FACET_ID_IS_EMPTY_DOCIDS: &str = "facet-id-is-empty-docids";
+    pub const FACET_ID_BOOL_DOCIDS: &str = "facet-id-bool-docids";
     pub const FACET_ID_STRING_DOCIDS: &str = "facet-id-string-docids";
     pub const FACET_ID_NORMALIZED_STRING_STRINGS: &str = "facet-id-normalized-string-strings";
     pub const FACET_ID_STRING_FST: &str = "facet-id-string-fst";
     pub const FIELD_ID_DOCID_FACET_F64S: &str = "field-id-docid-facet-f64s";
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
+    pub const FIELD_ID_DOCID_NESTED_ELEMENT_IDS: &str = "field-id-docid-nested-element-ids";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
     pub const DOCUMENTS: &str = "documents";
@@ -137,6 +159,9 @@ pub struct Index {
     pub word_prefix_position_docids: Database<StrBEU16Codec, CboRoaringBitmapCodec>,
     /// Maps the word prefix and a field id with all the docids where the prefix appears inside the field
     pub word_prefix_fid_docids: Database<StrBEU16Codec, CboRoaringBitmapCodec>,
+    /// Maps the phonetic code of a word followed by the word itself with the docids
+    /// containing the word in a phonetic attribute, e.g. `S500shaun`.
+    pub phonetic_word_docids: Database<Str, CboRoaringBitmapCodec>,
 
     /// Maps the facet field id and the docids for which this field exists
     pub facet_id_exists_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
@@ -144,6 +169,8 @@ pub struct Index {
     pub facet_id_is_null_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
     /// Maps the facet field id and the docids for which this field is considered empty
     pub facet_id_is_empty_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
+    /// Maps the facet field id and a boolean with the docids for which this field contains it
+    pub facet_id_bool_docids: Database<FieldIdBoolCodec, CboRoaringBitmapCodec>,
 
     /// Maps the facet field id and ranges of numbers with the docids that corresponds to them.
     pub facet_id_f64_docids: Database<FacetGroupKeyCodec<OrderedF64Codec>, FacetGroupValueCodec>,
@@ -158,6 +185,10 @@ pub struct Index {
     pub field_id_docid_facet_f64s: Database<FieldDocIdFacetF64Codec, Unit>,
     /// Maps the document id, the facet field id and the strings.
     pub field_id_docid_facet_strings: Database<FieldDocIdFacetStringCodec, Str>,
+    /// Maps the field id, the document id and a value of a field of the objects of a nested field
+    /// with the indexes of the objects containing it in the document.
+    pub field_id_docid_nested_element_ids:
+        Database<FieldDocIdNestedFacetValueCodec, CboRoaringBitmapCodec>,
 
     /// Maps an embedder name to its id in the arroy store.
     pub embedder_category_id: Database<Str, U8>,
@@ -177,7 +208,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(27);
 
         let env = unsafe { options.open(path) }?;
         let mut wtxn = env.write_txn()?;
@@ -199,6 +230,7 @@ impl Index {
             env.create_database(&mut wtxn, Some(WORD_PREFIX_POSITION_DOCIDS))?;
         let word_prefix_fid_docids =
             env.create_database(&mut wtxn, Some(WORD_PREFIX_FIELD_ID_DOCIDS))?;
+        let phonetic_word_docids = env.create_database(&mut wtxn, Some(PHONETIC_WORD_DOCIDS))?;
         let facet_id_f64_docids = env.create_database(&mut wtxn, Some(FACET_ID_F64_DOCIDS))?;
         let facet_id_string_docids =
             env.create_database(&mut wtxn, Some(FACET_ID_STRING_DOCIDS))?;
@@ -211,10 +243,13 @@ impl Index {
             env.create_database(&mut wtxn, Some(FACET_ID_IS_NULL_DOCIDS))?;
         let facet_id_is_empty_docids =
             env.create_database(&mut wtxn, Some(FACET_ID_IS_EMPTY_DOCIDS))?;
+        let facet_id_bool_docids = env.create_database(&mut wtxn, Some(FACET_ID_BOOL_DOCIDS))?;
         let field_id_docid_facet_f64s =
             env.create_database(&mut wtxn, Some(FIELD_ID_DOCID_FACET_F64S))?;
         let field_id_docid_facet_strings =
             env.create_database(&mut wtxn, Some(FIELD_ID_DOCID_FACET_STRINGS))?;
+        let field_id_docid_nested_element_ids =
+            env.create_database(&mut wtxn, Some(FIELD_ID_DOCID_NESTED_ELEMENT_IDS))?;
         // vector stuff
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
@@ -238,6 +273,7 @@ impl Index {
             word_fid_docids,
             word_prefix_position_docids,
             word_prefix_fid_docids,
+            phonetic_word_docids,
             field_id_word_count_docids,
             facet_id_f64_docids,
             facet_id_string_docids,
@@ -246,8 +282,10 @@ impl Index {
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
+            facet_id_bool_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
+            field_id_docid_nested_element_ids,
             vector_arroy,
             embedder_category_id,
             documents,
@@ -271,6 +309,8 @@ impl Index {
         if main.get(&txn, main_key::CREATED_AT_KEY)?.is_none() {
             main.put(&mut txn, main_key::UPDATED_AT_KEY, &OffsetDateTime(updated_at))?;
             main.put(&mut txn, main_key::CREATED_AT_KEY, &OffsetDateTime(created_at))?;
+            let main = main.remap_data_type::<BEU32>();
+            main.put(&mut txn, main_key::FACETS_VERSION_KEY, &FACETS_VERSION)?;
             txn.commit()?;
         }
         Ok(())
@@ -420,7 +460,9 @@ impl Index {
 
     /* fieldids weights map */
     // This maps the fields ids to their weights.
-    // Their weights is defined by the ordering of the searchable attributes.
+    // Their weights is defined by the ordering of the searchable attributes,
+    // or by the user defined weights of the searchable attributes if any.
+    // The lower the weight, the more important the field.
 
     /// Writes the fieldids weights map which associates the field ids to their weights
     pub(crate) fn put_fieldids_weights_map(
@@ -509,6 +551,41 @@ impl Index {
         }
     }
 
+    /* geo shapes rtree */
+
+    /// Writes the provided `rtree` which associates geo shapes envelopes to documents ids.
+    ///
+    /// It is not merged into the geo rtree: an rtree only stores one kind of geometry and the
+    /// geo rtree of the existing indexes is serialized with points, not envelopes.
+    pub(crate) fn put_geo_shapes_rtree(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rtree: &RTree<GeoShapeEnvelope>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<RTree<GeoShapeEnvelope>>>().put(
+            wtxn,
+            main_key::GEO_SHAPES_RTREE_KEY,
+            rtree,
+        )
+    }
+
+    /// Delete the `rtree` which associates geo shapes envelopes to documents ids.
+    pub(crate) fn delete_geo_shapes_rtree(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::GEO_SHAPES_RTREE_KEY)
+    }
+
+    /// Returns the `rtree` which associates geo shapes envelopes to documents ids.
+    pub fn geo_shapes_rtree(&self, rtxn: &RoTxn<'_>) -> Result<Option<RTree<GeoShapeEnvelope>>> {
+        match self
+            .main
+            .remap_types::<Str, SerdeBincode<RTree<GeoShapeEnvelope>>>()
+            .get(rtxn, main_key::GEO_SHAPES_RTREE_KEY)?
+        {
+            Some(rtree) => Ok(Some(rtree)),
+            None => Ok(None),
+        }
+    }
+
     /* geo faceted */
 
     /// Writes the documents ids that are faceted with a _geo field.
@@ -651,6 +728,29 @@ impl Index {
 
         let mut weights = FieldidsWeightsMap::default();
 
+        // When the user defined the weights of the searchable fields, the most important
+        // fields have the highest weight whereas the ranking rules expect them to be the lowest.
+        // The weights are converted to dense ranks, the fields with the highest weight have the
+        // rank 0, to keep the costs of the attribute ranking rule as low as with the positions.
+        let user_ranks = self.user_defined_searchable_fields_weights(wtxn)?.map(|user_weights| {
+            let distinct_weights: BTreeSet<Weight> = user_weights.values().copied().collect();
+            user_weights
+                .into_iter()
+                .map(|(field, weight)| {
+                    let higher_weights = distinct_weights.range(weight..).count() - 1;
+                    (field, higher_weights as Weight)
+                })
+                .collect::<BTreeMap<String, Weight>>()
+        });
+        let field_weight = |position: usize, user_field: &str| -> Result<Weight> {
+            match &user_ranks {
+                Some(user_ranks) => Ok(*user_ranks
+                    .get(user_field)
+                    .expect("the weighted searchable fields must all have a weight")),
+                None => position.try_into().map_err(|_| UserError::AttributeLimitReached.into()),
+            }
+        };
+
         // Now we generate the real searchable fields:
         // 1. Take the user defined searchable fields as-is to keep the priority defined by the attributes criterion.
         // 2. Iterate over the user defined searchable fields.
@@ -659,16 +759,13 @@ impl Index {
         let mut real_fields = Vec::new();
 
         for (id, field_from_map) in fields_ids_map.iter() {
-            for (weight, user_field) in user_fields.iter().enumerate() {
+            for (position, user_field) in user_fields.iter().enumerate() {
                 if crate::is_faceted_by(field_from_map, user_field)
                     && !real_fields.contains(&field_from_map)
                     && !non_searchable_fields_ids.contains(&id)
                 {
                     real_fields.push(field_from_map);
-
-                    let weight: u16 =
-                        weight.try_into().map_err(|_| UserError::AttributeLimitReached)?;
-                    weights.insert(id, weight);
+                    weights.insert(id, field_weight(position, user_field)?);
                 }
             }
         }
@@ -681,6 +778,7 @@ impl Index {
     pub(crate) fn delete_all_searchable_fields(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
         let did_delete_searchable = self.delete_searchable_fields(wtxn)?;
         let did_delete_user_defined = self.delete_user_defined_searchable_fields(wtxn)?;
+        self.delete_user_defined_searchable_fields_weights(wtxn)?;
         self.delete_fieldids_weights_map(wtxn)?;
         Ok(did_delete_searchable || did_delete_user_defined)
     }
@@ -759,6 +857,41 @@ impl Index {
             .get(rtxn, main_key::USER_DEFINED_SEARCHABLE_FIELDS_KEY)
     }
 
+    /// Writes the weights of the user defined searchable fields,
+    /// the higher the weight, the more important the field.
+    pub(crate) fn put_user_defined_searchable_fields_weights(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        weights: &BTreeMap<String, Weight>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::USER_DEFINED_SEARCHABLE_FIELDS_WEIGHTS_KEY,
+            weights,
+        )
+    }
+
+    /// Deletes the weights of the user defined searchable fields,
+    /// the searchable fields are then weighted by their order.
+    pub(crate) fn delete_user_defined_searchable_fields_weights(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main
+            .remap_key_type::<Str>()
+            .delete(wtxn, main_key::USER_DEFINED_SEARCHABLE_FIELDS_WEIGHTS_KEY)
+    }
+
+    /// Returns the weights of the user defined searchable fields, if they were specified.
+    pub fn user_defined_searchable_fields_weights(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<Option<BTreeMap<String, Weight>>> {
+        self.main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::USER_DEFINED_SEARCHABLE_FIELDS_WEIGHTS_KEY)
+    }
+
     /// Identical to `user_defined_searchable_fields`, but returns ids instead.
     pub fn user_defined_searchable_fields_ids(
         &self,
@@ -859,6 +992,117 @@ impl Index {
         Ok(fields.into_iter().filter_map(|name| fields_ids_map.id(&name)).collect())
     }
 
+    /* nested fields */
+
+    /// Writes the nested fields names in the database.
+    pub(crate) fn put_nested_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        fields: &BTreeSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(wtxn, main_key::NESTED_FIELDS_KEY, fields)
+    }
+
+    /// Deletes the nested fields names in the database.
+    pub(crate) fn delete_nested_fields(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::NESTED_FIELDS_KEY)
+    }
+
+    /// Returns the nested fields names, the arrays of objects whose objects are filtered
+    /// and faceted independently of each other.
+    pub fn nested_fields(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::NESTED_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the ids of the nested fields.
+    pub fn nested_fields_ids(&self, rtxn: &RoTxn<'_>) -> Result<HashSet<FieldId>> {
+        let fields = self.nested_fields(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields.iter().filter_map(|name| fields_ids_map.id(name)).collect())
+    }
+
+    /* date fields */
+
+    /// Writes the date fields names in the database.
+    pub(crate) fn put_date_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        fields: &BTreeSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(wtxn, main_key::DATE_FIELDS_KEY, fields)
+    }
+
+    /// Deletes the date fields names in the database.
+    pub(crate) fn delete_date_fields(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::DATE_FIELDS_KEY)
+    }
+
+    /// Returns the date fields names, the fields whose RFC 3339 strings are also
+    /// indexed as their Unix timestamp to be filtered and sorted as numbers.
+    pub fn date_fields(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::DATE_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the ids of the date fields and of the fields nested in them.
+    pub fn date_fields_ids(&self, rtxn: &RoTxn<'_>) -> Result<HashSet<FieldId>> {
+        let fields = self.date_fields(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields_ids_map
+            .iter()
+            .filter(|(_, name)| crate::is_faceted(name, &fields))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /* phonetic fields */
+
+    /// Writes the phonetic fields names in the database.
+    pub(crate) fn put_phonetic_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        fields: &BTreeSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::PHONETIC_FIELDS_KEY,
+            fields,
+        )
+    }
+
+    /// Deletes the phonetic fields names in the database.
+    pub(crate) fn delete_phonetic_fields(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::PHONETIC_FIELDS_KEY)
+    }
+
+    /// Returns the phonetic fields names, the searchable fields whose words can
+    /// be found by the query words sounding alike.
+    pub fn phonetic_fields(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::PHONETIC_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the ids of the phonetic fields and of the fields nested in them.
+    pub fn phonetic_fields_ids(&self, rtxn: &RoTxn<'_>) -> Result<HashSet<FieldId>> {
+        let fields = self.phonetic_fields(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields_ids_map
+            .iter()
+            .filter(|(_, name)| crate::is_faceted(name, &fields))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
     /* faceted fields */
 
     /// Writes the faceted fields in the database.
@@ -902,20 +1146,27 @@ impl Index {
 
     /// Returns the user defined faceted fields names.
     ///
-    /// The user faceted fields are the union of all the filterable, sortable, distinct, and Asc/Desc fields.
+    /// The user faceted fields are the union of all the filterable, sortable, distinct, and Asc/Desc fields,
+    /// and of the fields used by the `decay(...)` ranking rules.
     pub fn user_defined_faceted_fields(&self, rtxn: &RoTxn<'_>) -> Result<HashSet<String>> {
         let filterable_fields = self.filterable_fields(rtxn)?;
         let sortable_fields = self.sortable_fields(rtxn)?;
         let distinct_field = self.distinct_field(rtxn)?;
-        let asc_desc_fields =
-            self.criteria(rtxn)?.into_iter().filter_map(|criterion| match criterion {
-                Criterion::Asc(field) | Criterion::Desc(field) => Some(field),
-                _otherwise => None,
-            });
+        let criteria = self.criteria(rtxn)?;
+        let asc_desc_fields = criteria.iter().filter_map(|criterion| match criterion {
+            Criterion::Asc(field) | Criterion::Desc(field) => Some(field.clone()),
+            _otherwise => None,
+        });
+        let decay_fields = criteria
+            .iter()
+            .filter_map(|criterion| criterion.decay_function())
+            .map(|function| function.map(|function| function.field))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         let mut faceted_fields = filterable_fields;
         faceted_fields.extend(sortable_fields);
         faceted_fields.extend(asc_desc_fields);
+        faceted_fields.extend(decay_fields);
         if let Some(field) = distinct_field {
             faceted_fields.insert(field.to_owned());
         }
@@ -964,6 +1215,19 @@ impl Index {
         }
     }
 
+    /// Retrieve all the documents which contain this boolean in this field id
+    pub fn bool_faceted_documents_ids(
+        &self,
+        rtxn: &RoTxn<'_>,
+        field_id: FieldId,
+        value: bool,
+    ) -> heed::Result<RoaringBitmap> {
+        match self.facet_id_bool_docids.get(rtxn, &(field_id, value))? {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
     /// Retrieve all the documents which contain this field id
     pub fn exists_faceted_documents_ids(
         &self,
@@ -1218,6 +1482,55 @@ impl Index {
         Ok(self.synonyms(rtxn)?.remove(&words))
     }
 
+    /* synonym rules */
+
+    pub(crate) fn put_synonym_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        synonyms: &HashMap<Vec<String>, Vec<Vec<String>>>,
+        user_defined_rules: &BTreeMap<String, Vec<String>>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<_>>().put(
+            wtxn,
+            main_key::SYNONYM_RULES_KEY,
+            synonyms,
+        )?;
+        self.main.remap_types::<Str, SerdeBincode<_>>().put(
+            wtxn,
+            main_key::USER_DEFINED_SYNONYM_RULES_KEY,
+            user_defined_rules,
+        )
+    }
+
+    pub(crate) fn delete_synonym_rules(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SYNONYM_RULES_KEY)?;
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::USER_DEFINED_SYNONYM_RULES_KEY)
+    }
+
+    /// Returns the synonym rules as written by the user.
+    pub fn user_defined_synonym_rules(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, Vec<String>>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeBincode<_>>()
+            .get(rtxn, main_key::USER_DEFINED_SYNONYM_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the normalized synonyms derived from the synonym rules.
+    pub fn synonym_rules(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<HashMap<Vec<String>, Vec<Vec<String>>>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeBincode<_>>()
+            .get(rtxn, main_key::SYNONYM_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
     /* words prefixes fst */
 
     /// Writes the FST which is the words prefixes dictionary of the engine.
@@ -1359,6 +1672,24 @@ impl Index {
         )
     }
 
+    /// Returns the version of the layout of the facet databases, see [`FACETS_VERSION`].
+    pub fn facets_version(&self, rtxn: &RoTxn<'_>) -> heed::Result<u32> {
+        // the indexes created before the versioning of the facets have no version
+        Ok(self
+            .main
+            .remap_types::<Str, BEU32>()
+            .get(rtxn, main_key::FACETS_VERSION_KEY)?
+            .unwrap_or(0))
+    }
+
+    pub(crate) fn put_facets_version(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<()> {
+        self.main.remap_types::<Str, BEU32>().put(
+            wtxn,
+            main_key::FACETS_VERSION_KEY,
+            &FACETS_VERSION,
+        )
+    }
+
     pub fn authorize_typos(&self, txn: &RoTxn<'_>) -> heed::Result<bool> {
         // It is not possible to put a bool in heed with OwnedType, so we put a u8 instead. We
         // identify 0 as being false, and anything else as true. The absence of a value is true,
@@ -1476,6 +1807,54 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::EXACT_ATTRIBUTES)
     }
 
+    /// Returns the maximum number of typos allowed in the attributes that restrict it.
+    pub fn max_typos_per_attribute(&self, txn: &RoTxn<'_>) -> heed::Result<BTreeMap<String, u8>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::MAX_TYPOS_PER_ATTRIBUTE)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the maximum number of typos allowed in the field ids that restrict it.
+    pub fn max_typos_per_field_id(&self, txn: &RoTxn<'_>) -> Result<HashMap<FieldId, u8>> {
+        let max_typos = self.max_typos_per_attribute(txn)?;
+        let fid_map = self.fields_ids_map(txn)?;
+        Ok(max_typos
+            .into_iter()
+            .filter_map(|(attr, max_typos)| Some((fid_map.id(&attr)?, max_typos)))
+            .collect())
+    }
+
+    pub(crate) fn put_max_typos_per_attribute(
+        &self,
+        txn: &mut RwTxn<'_>,
+        max_typos: &BTreeMap<String, u8>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            txn,
+            main_key::MAX_TYPOS_PER_ATTRIBUTE,
+            max_typos,
+        )
+    }
+
+    pub(crate) fn delete_max_typos_per_attribute(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::MAX_TYPOS_PER_ATTRIBUTE)
+    }
+
+    /// Returns the maximum number of typos allowed across all the words of a query, if any.
+    pub fn max_typos_per_query(&self, txn: &RoTxn<'_>) -> heed::Result<Option<u8>> {
+        self.main.remap_types::<Str, U8>().get(txn, main_key::MAX_TYPOS_PER_QUERY)
+    }
+
+    pub(crate) fn put_max_typos_per_query(&self, txn: &mut RwTxn<'_>, val: u8) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(txn, main_key::MAX_TYPOS_PER_QUERY, &val)
+    }
+
+    pub(crate) fn delete_max_typos_per_query(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::MAX_TYPOS_PER_QUERY)
+    }
+
     pub fn max_values_per_facet(&self, txn: &RoTxn<'_>) -> heed::Result<Option<u64>> {
         self.main.remap_types::<Str, BEU64>().get(txn, main_key::MAX_VALUES_PER_FACET)
     }
@@ -1582,6 +1961,31 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::LOCALIZED_ATTRIBUTES_RULES)
     }
 
+    /// Returns the merchandising rules applied to the searches matching their condition.
+    pub fn merchandising_rules(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<MerchandisingRule>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::MERCHANDISING_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_merchandising_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rules: &[MerchandisingRule],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::MERCHANDISING_RULES_KEY,
+            &rules,
+        )
+    }
+
+    pub(crate) fn delete_merchandising_rules(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::MERCHANDISING_RULES_KEY)
+    }
+
     /// Put the embedding configs:
     /// 1. The name of the embedder
     /// 2. The configuration option for this embedder
@@ -1680,19 +2084,22 @@ pub(crate) mod tests {
     use std::ops::Deref;
 
     use big_s::S;
+    use heed::types::Str;
     use heed::{EnvOpenOptions, RwTxn};
     use maplit::{btreemap, hashset};
     use tempfile::TempDir;
 
     use crate::documents::DocumentsBatchReader;
     use crate::error::{Error, InternalError};
-    use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
+    use crate::index::{
+        main_key, DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS, FACETS_VERSION,
+    };
     use crate::update::{
         self, IndexDocuments, IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, Setting,
         Settings,
     };
     use crate::vector::settings::{EmbedderSource, EmbeddingSettings};
-    use crate::{db_snap, obkv_to_json, Filter, Index, Search, SearchResult};
+    use crate::{db_snap, obkv_to_json, FacetValue, Filter, Index, Search, SearchResult};
 
     pub(crate) struct TempIndex {
         pub inner: Index,
@@ -2151,6 +2558,106 @@ pub(crate) mod tests {
         insta::assert_debug_snapshot!(search_result.candidates, @"RoaringBitmap<[2, 4, 5]>");
     }
 
+    #[test]
+    fn test_date_fields() {
+        use maplit::btreeset;
+
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("date") });
+                settings.set_sortable_fields(hashset! { S("date") });
+                settings.set_date_fields(btreeset! { S("date") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "date": "2024-01-30T10:00:00Z" },
+                { "id": 1, "date": "2024-01-31" },
+                { "id": 2, "date": "2024-02-01T23:59:59Z" },
+                { "id": 3, "date": "2024-02-05" },
+                { "id": 4, "date": "2024-03-10T00:00:00+01:00" },
+                { "id": 5, "date": "not a date" },
+            ]))
+            .unwrap();
+
+        let candidates = |filter: &str| {
+            let rtxn = index.read_txn().unwrap();
+            let mut search = index.search(&rtxn);
+            search.filter(Filter::from_str(filter).unwrap().unwrap()).execute().unwrap().candidates
+        };
+
+        insta::assert_debug_snapshot!(candidates("date >= 2024-02-01"), @"RoaringBitmap<[2, 3, 4]>");
+        insta::assert_debug_snapshot!(candidates("date < '2024-01-31T00:00:01Z'"), @"RoaringBitmap<[0, 1]>");
+        insta::assert_debug_snapshot!(candidates("date 2024-02-01 TO 2024-02-05"), @"RoaringBitmap<[2, 3]>");
+        insta::assert_debug_snapshot!(candidates("date = 2024-01-31"), @"RoaringBitmap<[1]>");
+        insta::assert_debug_snapshot!(candidates("date = 'not a date'"), @"RoaringBitmap<[5]>");
+        insta::assert_debug_snapshot!(candidates("date < NOW()"), @"RoaringBitmap<[0, 1, 2, 3, 4]>");
+        insta::assert_debug_snapshot!(candidates("date > NOW() - 1d"), @"RoaringBitmap<[]>");
+
+        let rtxn = index.read_txn().unwrap();
+        let mut search = index.search(&rtxn);
+        search.sort_criteria(vec![crate::AscDesc::Desc(crate::Member::Field(S("date")))]);
+        let SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(documents_ids, vec![4, 3, 2, 1, 0, 5]);
+        drop(rtxn);
+
+        // the dates are no longer indexed as numbers once the field is not a date field anymore
+        index.update_settings(|settings| settings.reset_date_fields()).unwrap();
+        insta::assert_debug_snapshot!(candidates("date >= 2024-02-01"), @"RoaringBitmap<[]>");
+        insta::assert_debug_snapshot!(candidates("date = 2024-01-31"), @"RoaringBitmap<[1]>");
+
+        index
+            .update_settings(|settings| settings.set_date_fields(btreeset! { S("date") }))
+            .unwrap();
+        insta::assert_debug_snapshot!(candidates("date >= 2024-02-01"), @"RoaringBitmap<[2, 3, 4]>");
+    }
+
+    #[test]
+    fn test_string_patterns() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("doggo") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "doggo": "kefir" },
+                { "id": 1, "doggo": "Kéfirounet" },
+                { "id": 2, "doggo": "kefkef" },
+                { "id": 3, "doggo": "fifir" },
+                { "id": 4, "doggo": "boubou" },
+                { "id": 5 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let candidates = |filter: &str| {
+            let mut search = index.search(&rtxn);
+            search.filter(Filter::from_str(filter).unwrap().unwrap()).execute().unwrap().candidates
+        };
+
+        insta::assert_debug_snapshot!(candidates("doggo ENDS WITH fir"), @"RoaringBitmap<[0, 3]>");
+        insta::assert_debug_snapshot!(candidates("doggo NOT ENDS WITH FIR"), @"RoaringBitmap<[1, 2, 4, 5]>");
+        insta::assert_debug_snapshot!(candidates("doggo LIKE 'kef%'"), @"RoaringBitmap<[0, 1, 2]>");
+        insta::assert_debug_snapshot!(candidates("doggo LIKE '_ef__'"), @"RoaringBitmap<[0]>");
+        insta::assert_debug_snapshot!(candidates("doggo LIKE '%ou%'"), @"RoaringBitmap<[1, 4]>");
+        insta::assert_debug_snapshot!(candidates("doggo NOT LIKE '%f%'"), @"RoaringBitmap<[4, 5]>");
+        insta::assert_debug_snapshot!(candidates("doggo MATCHES /(kef)+/"), @"RoaringBitmap<[2]>");
+        insta::assert_debug_snapshot!(candidates("doggo MATCHES /[a-z]+ir/"), @"RoaringBitmap<[0, 3]>");
+        insta::assert_debug_snapshot!(candidates("doggo NOT MATCHES /.*ou.*/"), @"RoaringBitmap<[0, 2, 3, 5]>");
+
+        let mut search = index.search(&rtxn);
+        let error = search
+            .filter(Filter::from_str("doggo MATCHES /kef(/").unwrap().unwrap())
+            .execute()
+            .unwrap_err();
+        assert!(error.to_string().ends_with("doggo MATCHES /kef(/"), "{error}");
+    }
+
     #[test]
     fn replace_documents_external_ids_and_soft_deletion_check() {
         use big_s::S;
@@ -2659,6 +3166,7 @@ pub(crate) mod tests {
         let SearchResult {
             matching_words: _,
             candidates: _,
+            disjunctive_candidates: _,
             document_scores: _,
             mut documents_ids,
             degraded: _,
@@ -2739,6 +3247,35 @@ pub(crate) mod tests {
         db_snap!(index, geo_faceted_documents_ids); // ensure that no documents were inserted
     }
 
+    #[test]
+    fn invalid_geojson_in_geo_field() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key("id".to_string());
+                settings.set_filterable_fields(HashSet::from(["_geo".to_string()]));
+            })
+            .unwrap();
+
+        let err = index
+            .add_documents(
+                documents!({ "id" : "doggo", "_geo": { "type": "Polygon", "coordinates": [[[0, 0], [1, 1], [0, 0]]] }}),
+            )
+            .unwrap_err();
+        insta::assert_snapshot!(err, @r###"Could not parse the GeoJSON geometry in the `_geo` field of the document with the id: `"\"doggo\""`: the rings of a `Polygon` must contain at least four positions and be closed."###);
+
+        let err = index
+            .add_documents(
+                documents!({ "id" : "doggo", "_geo": { "type": "LineString", "coordinates": [[0, 0], [200, 1]] }}),
+            )
+            .unwrap_err();
+        insta::assert_snapshot!(err, @r###"Could not parse the GeoJSON geometry in the `_geo` field of the document with the id: `"\"doggo\""`: bad longitude `200`, it must be between -180 and 180 degrees."###);
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.geo_shapes_rtree(&rtxn).unwrap().is_none());
+    }
+
     #[test]
     fn swapping_searchable_attributes() {
         // See https://github.com/meilisearch/meilisearch/issues/4484
@@ -2929,4 +3466,52 @@ pub(crate) mod tests {
             .unwrap();
         assert!(results.candidates.is_empty());
     }
+
+    #[test]
+    fn migrate_booleans_stored_as_strings() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("available") });
+            })
+            .unwrap();
+        index.add_documents(documents!([{ "id": 0, "available": "true" }])).unwrap();
+
+        // an index created before the booleans had their own database stored them as strings
+        let mut wtxn = index.write_txn().unwrap();
+        let fid = index.fields_ids_map(&wtxn).unwrap().id("available").unwrap();
+        let document = index.documents.get(&wtxn, &0).unwrap().unwrap();
+        let mut writer = obkv::KvWriter::memory();
+        for (id, value) in document.iter() {
+            writer.insert(id, if id == fid { &b"true"[..] } else { value }).unwrap();
+        }
+        index.documents.put(&mut wtxn, &0, &writer).unwrap();
+        index.main.remap_key_type::<Str>().delete(&mut wtxn, main_key::FACETS_VERSION_KEY).unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.facets_version(&rtxn).unwrap(), 0);
+        drop(rtxn);
+
+        // the other updates don't upgrade the facets
+        index.add_documents(documents!([{ "id": 1, "available": false }])).unwrap();
+        index
+            .update_settings(|settings| {
+                settings.set_searchable_fields(vec![S("available")]);
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.facets_version(&rtxn).unwrap(), 0);
+        drop(rtxn);
+
+        index.update_settings(|settings| settings.upgrade_facets()).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.facets_version(&rtxn).unwrap(), FACETS_VERSION);
+        let distribution = index.facets_distribution(&rtxn).execute_typed().unwrap();
+        assert_eq!(
+            distribution["available"].clone().into_iter().collect::<Vec<_>>(),
+            vec![(FacetValue::Bool(false), 1), (FacetValue::Bool(true), 1)]
+        );
+    }
 }
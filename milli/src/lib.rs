@@ -10,18 +10,24 @@ pub mod documents;
 
 mod asc_desc;
 mod criterion;
+mod decay_function;
 mod error;
 mod external_documents_ids;
 pub mod facet;
 mod fields_ids_map;
+mod geo_shape;
 pub mod heed_codec;
 pub mod index;
 mod localized_attributes_rules;
+mod merchandising_rules;
 pub mod order_by_map;
+mod phonetic;
 pub mod prompt;
 pub mod proximity;
 pub mod score_details;
+mod score_expression;
 mod search;
+mod synonym_rules;
 mod thread_pool_no_abort;
 pub mod update;
 pub mod vector;
@@ -50,12 +56,14 @@ pub use {charabia as tokenizer, heed, rhai};
 
 pub use self::asc_desc::{AscDesc, AscDescError, Member, SortError};
 pub use self::criterion::{default_criteria, Criterion, CriterionError};
+pub use self::decay_function::{DecayFunction, DecayFunctionError, DecayOrigin, DecayShape};
 pub use self::error::{
     Error, FieldIdMapMissingEntry, InternalError, SerializationError, UserError,
 };
 pub use self::external_documents_ids::ExternalDocumentsIds;
 pub use self::fieldids_weights_map::FieldidsWeightsMap;
 pub use self::fields_ids_map::FieldsIdsMap;
+pub use self::geo_shape::{GeoShape, GeoShapeEnvelope};
 pub use self::heed_codec::{
     BEU16StrCodec, BEU32StrCodec, BoRoaringBitmapCodec, BoRoaringBitmapLenCodec,
     CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, FieldIdWordCountCodec, ObkvCodec,
@@ -65,11 +73,17 @@ pub use self::heed_codec::{
 pub use self::index::Index;
 pub use self::localized_attributes_rules::LocalizedAttributesRule;
 use self::localized_attributes_rules::LocalizedFieldIds;
-pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
+pub use self::merchandising_rules::{
+    MerchandisingActions, MerchandisingCondition, MerchandisingRule, PinnedDocument,
+};
+pub use self::score_expression::{ScoreExpression, ScoreExpressionError};
+pub use self::search::facet::{stringify_facet_values, FacetValueHit, SearchForFacetValues};
 pub use self::search::similar::Similar;
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    CalendarInterval, FacetBuckets, FacetDistribution, FacetHierarchy, FacetHierarchyNode,
+    FacetNumberStats, FacetRange, FacetValue, Filter, FilterAnalysis, FilterIssue, FilterIssueKind,
+    FilterNode, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy, Search,
+    SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
 
 pub type Result<T> = std::result::Result<T, error::Error>;
@@ -328,6 +342,89 @@ pub fn lat_lng_to_xyz(coord: &[f64; 2]) -> [f64; 3] {
     [x, y, z]
 }
 
+/// Returns the smallest cartesian envelope containing all the points of the sphere whose latitude
+/// and longitude are within the bounding box of the provided points.
+///
+/// It can be used to retrieve the candidates of a shape from the geo R-tree before checking them
+/// precisely against the shape itself.
+pub fn lat_lng_bounding_box_to_xyz_envelope(points: &[[f64; 2]]) -> rstar::AABB<[f64; 3]> {
+    let (mut min_lat, mut max_lat) = (f64::INFINITY, f64::NEG_INFINITY);
+    let (mut min_lng, mut max_lng) = (f64::INFINITY, f64::NEG_INFINITY);
+    for [lat, lng] in points {
+        min_lat = min_lat.min(*lat);
+        max_lat = max_lat.max(*lat);
+        min_lng = min_lng.min(*lng);
+        max_lng = max_lng.max(*lng);
+    }
+    let (min_lat, max_lat) = (min_lat.to_radians(), max_lat.to_radians());
+    let (min_lng, max_lng) = (min_lng.to_radians(), max_lng.to_radians());
+
+    // the extremes of a sine or cosine over a range are either on the bounds of
+    // the range or on the multiples of a right angle that the range contains.
+    let extremes = |values: &[f64], peaks: &[(f64, f64)], min: f64, max: f64| {
+        let peaks = peaks.iter().filter(|(angle, _)| (min..=max).contains(angle));
+        let values = values.iter().copied().chain(peaks.map(|(_, value)| *value));
+        values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+    };
+    let right = std::f64::consts::FRAC_PI_2;
+    let lat_cos = extremes(&[min_lat.cos(), max_lat.cos()], &[(0.0, 1.0)], min_lat, max_lat);
+    let lng_cos = extremes(
+        &[min_lng.cos(), max_lng.cos()],
+        &[(0.0, 1.0), (-2.0 * right, -1.0), (2.0 * right, -1.0)],
+        min_lng,
+        max_lng,
+    );
+    let lng_sin = extremes(
+        &[min_lng.sin(), max_lng.sin()],
+        &[(right, 1.0), (-right, -1.0)],
+        min_lng,
+        max_lng,
+    );
+
+    // x = cos(lat) * cos(lng) and y = cos(lat) * sin(lng) where cos(lat) is always positive
+    let product = |(a_lo, a_hi): (f64, f64), (b_lo, b_hi): (f64, f64)| {
+        let products = [a_lo * b_lo, a_lo * b_hi, a_hi * b_lo, a_hi * b_hi];
+        products
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(*v), hi.max(*v)))
+    };
+    let (min_x, max_x) = product(lat_cos, lng_cos);
+    let (min_y, max_y) = product(lat_cos, lng_sin);
+    let (min_z, max_z) = (min_lat.sin(), max_lat.sin());
+
+    // we slightly enlarge the envelope to avoid missing points because of rounding errors
+    let epsilon = 1e-9;
+    rstar::AABB::from_corners(
+        [min_x - epsilon, min_y - epsilon, min_z - epsilon],
+        [max_x + epsilon, max_y + epsilon, max_z + epsilon],
+    )
+}
+
+/// Returns a cartesian envelope containing all the points of the sphere that are at most
+/// `radius` meters away from the provided point.
+///
+/// The polar radius of the earth is used to convert the distance into an angle so that
+/// the envelope is never too small.
+pub fn lat_lng_radius_to_xyz_envelope(center: &[f64; 2], radius: f64) -> rstar::AABB<[f64; 3]> {
+    use std::f64::consts::PI;
+
+    let angle = (radius / 6_356_752.0).min(PI);
+    let center = lat_lng_to_xyz(center);
+    // the points of the cap make an angle of at most `angle` with the center, we compute,
+    // for every axis, the range of the cosines of the angles they make with this axis.
+    let axis_range = |coordinate: f64| {
+        let axis_angle = coordinate.clamp(-1.0, 1.0).acos();
+        ((axis_angle + angle).min(PI).cos(), (axis_angle - angle).max(0.0).cos())
+    };
+    let [(min_x, max_x), (min_y, max_y), (min_z, max_z)] = center.map(axis_range);
+
+    let epsilon = 1e-9;
+    rstar::AABB::from_corners(
+        [min_x - epsilon, min_y - epsilon, min_z - epsilon],
+        [max_x + epsilon, max_y + epsilon, max_z + epsilon],
+    )
+}
+
 /// Returns `true` if the field match one of the faceted fields.
 /// See the function [`is_faceted_by`] below to see what “matching” means.
 pub fn is_faceted(field: &str, faceted_fields: impl IntoIterator<Item = impl AsRef<str>>) -> bool {
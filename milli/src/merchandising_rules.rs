@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Filter, Result};
+
+/// A rule that changes the results of the searches matching its condition.
+///
+/// It is used to pin documents at given positions, to hide documents,
+/// or to restrict and reorder the results with filters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingRule {
+    pub condition: MerchandisingCondition,
+    pub actions: MerchandisingActions,
+}
+
+/// The conditions a search must all match for the actions of a rule to be applied.
+///
+/// A condition without any constraint matches every search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingCondition {
+    /// The query must be these words, ignoring the case.
+    pub query_equals: Option<String>,
+    /// The query must contain these consecutive words, ignoring the case.
+    pub query_contains: Option<String>,
+    /// The filter of the search must contain the conditions of this filter, joined by `AND`.
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchandisingActions {
+    /// The documents to show at a given position, whether they match the query or not.
+    pub pin: Vec<PinnedDocument>,
+    /// The ids of the documents to remove from the results.
+    pub hide: Vec<String>,
+    /// A filter the results must match, in addition to the filter of the search.
+    pub filter: Option<String>,
+    /// A filter whose matching results are ranked before the others.
+    pub boost: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedDocument {
+    /// The external id of the document.
+    pub id: String,
+    /// The position of the document in the results, starting at 1.
+    pub position: usize,
+}
+
+impl MerchandisingCondition {
+    pub fn matches(&self, query: Option<&str>, filter: Option<&Filter>) -> Result<bool> {
+        let query = words(query.unwrap_or_default());
+        if let Some(query_equals) = &self.query_equals {
+            if query != words(query_equals) {
+                return Ok(false);
+            }
+        }
+        if let Some(query_contains) = &self.query_contains {
+            let query_contains = words(query_contains);
+            if !query_contains.is_empty()
+                && !query.windows(query_contains.len()).any(|window| window == query_contains)
+            {
+                return Ok(false);
+            }
+        }
+        if let Some(condition_filter) = self.filter.as_deref().map(Filter::from_str).transpose()? {
+            // the filters are compared once parsed, an empty filter matches every search
+            let found = match (condition_filter, filter) {
+                (None, _) => true,
+                (Some(condition_filter), Some(filter)) => filter.contains(&condition_filter),
+                (Some(_), None) => false,
+            };
+            if !found {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(str::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn matches(condition: &MerchandisingCondition, query: Option<&str>, filter: Value) -> bool {
+        let filter = Filter::from_json(&filter).unwrap();
+        condition.matches(query, filter.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn condition_matches() {
+        let condition =
+            MerchandisingCondition { query_equals: Some(S("TV")), ..Default::default() };
+        assert!(matches(&condition, Some("tv"), json!([])));
+        assert!(matches(&condition, Some("  Tv "), json!([])));
+        assert!(!matches(&condition, Some("smart tv"), json!([])));
+        assert!(!matches(&condition, None, json!([])));
+
+        let condition =
+            MerchandisingCondition { query_contains: Some(S("smart tv")), ..Default::default() };
+        assert!(matches(&condition, Some("cheap Smart  TV 4k"), json!([])));
+        assert!(!matches(&condition, Some("smart 4k tv"), json!([])));
+        assert!(!matches(&condition, Some("smartv"), json!([])));
+
+        let condition = MerchandisingCondition {
+            query_contains: Some(S("tv")),
+            filter: Some(S("category = electronics AND price < 500")),
+            ..Default::default()
+        };
+        assert!(matches(&condition, Some("tv"), json!("price < 500 AND category = electronics")));
+        assert!(matches(
+            &condition,
+            Some("tv"),
+            json!(["price   < 500", "(category = electronics)", "brand = acme"])
+        ));
+        assert!(matches(
+            &condition,
+            Some("tv"),
+            json!([["category = electronics"], "price < 500"])
+        ));
+        assert!(!matches(&condition, Some("tv"), json!("category = electronics")));
+        assert!(!matches(&condition, Some("tv"), json!("category = electronics OR price < 500")));
+        assert!(!matches(&condition, Some("tv"), json!("category = books AND price < 500")));
+        assert!(!matches(&condition, Some("tv"), json!([])));
+        assert!(!matches(
+            &condition,
+            Some("radio"),
+            json!("category = electronics AND price < 500")
+        ));
+
+        let condition = MerchandisingCondition::default();
+        assert!(matches(&condition, None, json!([])));
+        assert!(matches(&condition, Some("anything"), json!("category = books")));
+    }
+}
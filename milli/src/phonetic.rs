@@ -0,0 +1,73 @@
+/// The length of the phonetic codes.
+pub const PHONETIC_CODE_LENGTH: usize = 4;
+
+/// Computes the American Soundex code of a word, e.g. `S500` for both `shaun` and `sean`.
+///
+/// The words sounding alike in English share the same code, even when they are
+/// more than two typos away from each other. Returns `None` if the word isn't only
+/// made of ASCII letters, as the code is meaningless for other scripts.
+pub fn soundex(word: &str) -> Option<String> {
+    if word.is_empty() || !word.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut bytes = word.bytes().map(|b| b.to_ascii_lowercase());
+    let first = bytes.next()?;
+    let mut code = String::with_capacity(PHONETIC_CODE_LENGTH);
+    code.push(first.to_ascii_uppercase() as char);
+
+    let mut previous = soundex_digit(first);
+    for b in bytes {
+        if code.len() == PHONETIC_CODE_LENGTH {
+            break;
+        }
+        match soundex_digit(b) {
+            // `h` and `w` don't separate two consonants with the same digit
+            None if b == b'h' || b == b'w' => (),
+            None => previous = None,
+            Some(digit) => {
+                if previous != Some(digit) {
+                    code.push(digit);
+                }
+                previous = Some(digit);
+            }
+        }
+    }
+
+    while code.len() < PHONETIC_CODE_LENGTH {
+        code.push('0');
+    }
+    Some(code)
+}
+
+fn soundex_digit(b: u8) -> Option<char> {
+    match b {
+        b'b' | b'f' | b'p' | b'v' => Some('1'),
+        b'c' | b'g' | b'j' | b'k' | b'q' | b's' | b'x' | b'z' => Some('2'),
+        b'd' | b't' => Some('3'),
+        b'l' => Some('4'),
+        b'm' | b'n' => Some('5'),
+        b'r' => Some('6'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soundex_codes() {
+        assert_eq!(soundex("shaun").as_deref(), Some("S500"));
+        assert_eq!(soundex("Sean").as_deref(), Some("S500"));
+        assert_eq!(soundex("robert").as_deref(), Some("R163"));
+        assert_eq!(soundex("rupert").as_deref(), Some("R163"));
+        assert_eq!(soundex("ashcraft").as_deref(), Some("A261"));
+        assert_eq!(soundex("tymczak").as_deref(), Some("T522"));
+        assert_eq!(soundex("pfister").as_deref(), Some("P236"));
+        assert_eq!(soundex("a").as_deref(), Some("A000"));
+        assert_eq!(soundex("42"), None);
+        assert_eq!(soundex("josé"), None);
+        assert_eq!(soundex(""), None);
+    }
+}
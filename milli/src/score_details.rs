@@ -8,6 +8,7 @@ use crate::distance_between_two_points;
 #[derive(Debug, Clone, PartialEq)]
 pub enum ScoreDetails {
     Words(Words),
+    TermRarity(Rank),
     Typo(Typo),
     Proximity(Rank),
     Fid(Rank),
@@ -17,6 +18,10 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    FunctionScore(FunctionScore),
+    Decay(Decay),
+    Boost(Boost),
+    Pinned(Pinned),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
@@ -27,12 +32,14 @@ pub enum ScoreValue<'a> {
     Score(f64),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
+    FunctionScore(&'a FunctionScore),
 }
 
 enum RankOrValue<'a> {
     Rank(Rank),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
+    FunctionScore(&'a FunctionScore),
     Score(f64),
 }
 
@@ -44,6 +51,7 @@ impl ScoreDetails {
     pub fn rank(&self) -> Option<Rank> {
         match self {
             ScoreDetails::Words(details) => Some(details.rank()),
+            ScoreDetails::TermRarity(details) => Some(*details),
             ScoreDetails::Typo(details) => Some(details.rank()),
             ScoreDetails::Proximity(details) => Some(*details),
             ScoreDetails::Fid(details) => Some(*details),
@@ -52,6 +60,10 @@ impl ScoreDetails {
             ScoreDetails::ExactWords(details) => Some(details.rank()),
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
+            ScoreDetails::FunctionScore(_) => None,
+            ScoreDetails::Decay(_) => None,
+            ScoreDetails::Boost(details) => Some(details.rank()),
+            ScoreDetails::Pinned(details) => Some(details.rank()),
             ScoreDetails::Vector(_) => None,
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
         }
@@ -83,6 +95,7 @@ impl ScoreDetails {
                 RankOrValue::Rank(r) => ScoreValue::Score(r.local_score()),
                 RankOrValue::Sort(s) => ScoreValue::Sort(s),
                 RankOrValue::GeoSort(g) => ScoreValue::GeoSort(g),
+                RankOrValue::FunctionScore(f) => ScoreValue::FunctionScore(f),
                 RankOrValue::Score(s) => ScoreValue::Score(s),
             })
     }
@@ -90,6 +103,7 @@ impl ScoreDetails {
     fn rank_or_value(&self) -> RankOrValue<'_> {
         match self {
             ScoreDetails::Words(w) => RankOrValue::Rank(w.rank()),
+            ScoreDetails::TermRarity(t) => RankOrValue::Rank(*t),
             ScoreDetails::Typo(t) => RankOrValue::Rank(t.rank()),
             ScoreDetails::Proximity(p) => RankOrValue::Rank(*p),
             ScoreDetails::Fid(f) => RankOrValue::Rank(*f),
@@ -98,6 +112,12 @@ impl ScoreDetails {
             ScoreDetails::ExactWords(e) => RankOrValue::Rank(e.rank()),
             ScoreDetails::Sort(sort) => RankOrValue::Sort(sort),
             ScoreDetails::GeoSort(geosort) => RankOrValue::GeoSort(geosort),
+            ScoreDetails::FunctionScore(function_score) => {
+                RankOrValue::FunctionScore(function_score)
+            }
+            ScoreDetails::Decay(decay) => RankOrValue::Score(decay.score.unwrap_or(0.0f64)),
+            ScoreDetails::Boost(boost) => RankOrValue::Rank(boost.rank()),
+            ScoreDetails::Pinned(pinned) => RankOrValue::Rank(pinned.rank()),
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
@@ -137,6 +157,14 @@ impl ScoreDetails {
                     details_map.insert("typo".into(), typo_details);
                     order += 1;
                 }
+                ScoreDetails::TermRarity(term_rarity) => {
+                    let term_rarity_details = serde_json::json!({
+                        "order": order,
+                        "score": term_rarity.local_score(),
+                    });
+                    details_map.insert("termRarity".into(), term_rarity_details);
+                    order += 1;
+                }
                 ScoreDetails::Proximity(proximity) => {
                     let proximity_details = serde_json::json!({
                         "order": order,
@@ -248,6 +276,46 @@ impl ScoreDetails {
                     details_map.insert(sort, sort_details);
                     order += 1;
                 }
+                ScoreDetails::FunctionScore(details) => {
+                    let value = if details.redacted {
+                        "<hidden>".into()
+                    } else {
+                        serde_json::json!(details.value)
+                    };
+                    let function_score_details = serde_json::json!({
+                        "order": order,
+                        "value": value,
+                    });
+                    details_map
+                        .insert(format!("score:{}", details.expression), function_score_details);
+                    order += 1;
+                }
+                ScoreDetails::Decay(details) => {
+                    let decay_details = serde_json::json!({
+                        "order": order,
+                        "score": details.score,
+                    });
+                    details_map.insert(format!("decay({})", details.function), decay_details);
+                    order += 1;
+                }
+                ScoreDetails::Boost(details) => {
+                    let boost_details = serde_json::json!({
+                        "order": order,
+                        "matches": details.matches,
+                        "score": details.rank().local_score(),
+                    });
+                    details_map.insert(format!("boost({})", details.filter), boost_details);
+                    order += 1;
+                }
+                ScoreDetails::Pinned(details) => {
+                    let pinned_details = serde_json::json!({
+                        "order": order,
+                        "position": details.position,
+                        "score": details.rank().local_score(),
+                    });
+                    details_map.insert("pinned".into(), pinned_details);
+                    order += 1;
+                }
                 ScoreDetails::Vector(s) => {
                     let similarity = s.similarity.as_ref();
 
@@ -483,6 +551,59 @@ impl PartialOrd for GeoSort {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionScore {
+    pub expression: String,
+    pub redacted: bool,
+    /// `None` when a field of the expression has no number for the document.
+    pub value: Option<f64>,
+}
+
+impl PartialOrd for FunctionScore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.value, other.value) {
+            (None, None) => Some(Ordering::Equal),
+            (None, Some(_)) => Some(Ordering::Less),
+            (Some(_), None) => Some(Ordering::Greater),
+            // the highest value is the best
+            (Some(left), Some(right)) => left.partial_cmp(&right),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decay {
+    pub function: String,
+    /// The score between 0 and 1 of the document, `None` when the field has no number.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boost {
+    pub filter: String,
+    /// Whether the document matches the filter of the boost.
+    pub matches: bool,
+}
+
+impl Boost {
+    pub fn rank(&self) -> Rank {
+        Rank { rank: if self.matches { 2 } else { 1 }, max_rank: 2 }
+    }
+}
+
+/// A document shown at a given position by a merchandising rule, whatever its relevancy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pinned {
+    /// The position of the document in the results, starting at 1.
+    pub position: usize,
+}
+
+impl Pinned {
+    pub fn rank(&self) -> Rank {
+        Rank { rank: 1, max_rank: 1 }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Vector {
     pub similarity: Option<f32>,
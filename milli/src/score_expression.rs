@@ -0,0 +1,433 @@
+//! This module provides the `ScoreExpression` type used by the `score:` ranking rule.
+//!
+//! BNF grammar:
+//!
+//! ```text
+//! expression = term (("+" | "-") term)*
+//! term       = factor (("*" | "/") factor)*
+//! factor     = "-" factor | number | function "(" expression ("," expression)* ")" | field | "(" expression ")"
+//! field      = (letter | "_") (letter | digit | "_" | ".")*
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScoreExpressionError {
+    #[error("the expression is empty")]
+    Empty,
+    #[error("unexpected end of the expression")]
+    UnexpectedEnd,
+    #[error("unexpected `{found}` at position {position}")]
+    UnexpectedCharacter { found: char, position: usize },
+    #[error("`{name}` is not a valid function. Valid functions are {}", Function::NAMES.join(", "))]
+    UnknownFunction { name: String },
+    #[error("the `{function}` function expects {expected} but got {found}")]
+    InvalidArgumentCount { function: &'static str, expected: &'static str, found: usize },
+}
+
+/// A numeric expression combining the number values of the fields of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreExpression(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Field(String),
+    Negate(Box<Node>),
+    Operation { left: Box<Node>, operator: Operator, right: Box<Node> },
+    Function { function: Function, arguments: Vec<Node> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Abs,
+    Exp,
+    Ln,
+    Log1p,
+    Log10,
+    Sqrt,
+    Pow,
+    Min,
+    Max,
+}
+
+impl Function {
+    const NAMES: &'static [&'static str] =
+        &["abs", "exp", "ln", "log1p", "log10", "sqrt", "pow", "min", "max"];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(Function::Abs),
+            "exp" => Some(Function::Exp),
+            "ln" => Some(Function::Ln),
+            "log1p" => Some(Function::Log1p),
+            "log10" => Some(Function::Log10),
+            "sqrt" => Some(Function::Sqrt),
+            "pow" => Some(Function::Pow),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Function::Abs => "abs",
+            Function::Exp => "exp",
+            Function::Ln => "ln",
+            Function::Log1p => "log1p",
+            Function::Log10 => "log10",
+            Function::Sqrt => "sqrt",
+            Function::Pow => "pow",
+            Function::Min => "min",
+            Function::Max => "max",
+        }
+    }
+
+    fn check_argument_count(&self, found: usize) -> Result<(), ScoreExpressionError> {
+        let expected = match self {
+            Function::Pow if found != 2 => "2 arguments",
+            Function::Min | Function::Max if found == 0 => "at least 1 argument",
+            Function::Abs
+            | Function::Exp
+            | Function::Ln
+            | Function::Log1p
+            | Function::Log10
+            | Function::Sqrt
+                if found != 1 =>
+            {
+                "1 argument"
+            }
+            _ => return Ok(()),
+        };
+        Err(ScoreExpressionError::InvalidArgumentCount { function: self.name(), expected, found })
+    }
+
+    fn apply(&self, arguments: &[f64]) -> f64 {
+        match self {
+            Function::Abs => arguments[0].abs(),
+            Function::Exp => arguments[0].exp(),
+            Function::Ln => arguments[0].ln(),
+            Function::Log1p => arguments[0].ln_1p(),
+            Function::Log10 => arguments[0].log10(),
+            Function::Sqrt => arguments[0].sqrt(),
+            Function::Pow => arguments[0].powf(arguments[1]),
+            Function::Min => arguments.iter().copied().fold(f64::INFINITY, f64::min),
+            Function::Max => arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+impl ScoreExpression {
+    /// Returns the fields used by the expression, without duplicates.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.0.fields(&mut fields);
+        fields
+    }
+
+    /// Computes the value of the expression, using `field_value` to get the value of the fields.
+    ///
+    /// Returns `None` if a field has no value or if the result is not a finite number,
+    /// e.g. when computing the logarithm of a negative number.
+    pub fn evaluate(&self, field_value: &mut impl FnMut(&str) -> Option<f64>) -> Option<f64> {
+        self.0.evaluate(field_value).filter(|value| value.is_finite())
+    }
+}
+
+impl Node {
+    fn fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Node::Number(_) => (),
+            Node::Field(field) => {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field.as_str());
+                }
+            }
+            Node::Negate(node) => node.fields(fields),
+            Node::Operation { left, operator: _, right } => {
+                left.fields(fields);
+                right.fields(fields);
+            }
+            Node::Function { function: _, arguments } => {
+                arguments.iter().for_each(|argument| argument.fields(fields))
+            }
+        }
+    }
+
+    fn evaluate(&self, field_value: &mut impl FnMut(&str) -> Option<f64>) -> Option<f64> {
+        match self {
+            Node::Number(number) => Some(*number),
+            Node::Field(field) => field_value(field.as_str()),
+            Node::Negate(node) => node.evaluate(field_value).map(|value| -value),
+            Node::Operation { left, operator, right } => {
+                let left = left.evaluate(field_value)?;
+                let right = right.evaluate(field_value)?;
+                Some(match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                })
+            }
+            Node::Function { function, arguments } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate(field_value))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(function.apply(&arguments))
+            }
+        }
+    }
+}
+
+impl FromStr for ScoreExpression {
+    type Err = ScoreExpressionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { chars: text.char_indices().collect(), position: 0 };
+        if parser.peek().is_none() {
+            return Err(ScoreExpressionError::Empty);
+        }
+        let node = parser.expression()?;
+        match parser.peek() {
+            None => Ok(ScoreExpression(node)),
+            Some((position, found)) => {
+                Err(ScoreExpressionError::UnexpectedCharacter { found, position })
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<(usize, char)>,
+    position: usize,
+}
+
+impl Parser {
+    /// Returns the next non-whitespace character and its position in the text.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.get(self.position).map_or(false, |(_, c)| c.is_whitespace()) {
+            self.position += 1;
+        }
+        self.chars.get(self.position).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ScoreExpressionError> {
+        match self.peek() {
+            Some((_, c)) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some((position, found)) => {
+                Err(ScoreExpressionError::UnexpectedCharacter { found, position })
+            }
+            None => Err(ScoreExpressionError::UnexpectedEnd),
+        }
+    }
+
+    /// Consumes the characters following the current one as long as they match the predicate.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some((_, c)) = self.chars.get(self.position).copied() {
+            if !predicate(c) {
+                break;
+            }
+            taken.push(c);
+            self.position += 1;
+        }
+        taken
+    }
+
+    /// expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Node, ScoreExpressionError> {
+        let mut left = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some((_, '+')) => Operator::Add,
+                Some((_, '-')) => Operator::Subtract,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.term()?;
+            left = Node::Operation { left: Box::new(left), operator, right: Box::new(right) };
+        }
+    }
+
+    /// term = factor (("*" | "/") factor)*
+    fn term(&mut self) -> Result<Node, ScoreExpressionError> {
+        let mut left = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some((_, '*')) => Operator::Multiply,
+                Some((_, '/')) => Operator::Divide,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.factor()?;
+            left = Node::Operation { left: Box::new(left), operator, right: Box::new(right) };
+        }
+    }
+
+    /// factor = "-" factor | number | function "(" expression ("," expression)* ")" | field | "(" expression ")"
+    fn factor(&mut self) -> Result<Node, ScoreExpressionError> {
+        match self.peek() {
+            None => Err(ScoreExpressionError::UnexpectedEnd),
+            Some((_, '-')) => {
+                self.position += 1;
+                Ok(Node::Negate(Box::new(self.factor()?)))
+            }
+            Some((_, '(')) => {
+                self.position += 1;
+                let node = self.expression()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some((position, c)) if c.is_ascii_digit() || c == '.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                number
+                    .parse()
+                    .map(Node::Number)
+                    .map_err(|_| ScoreExpressionError::UnexpectedCharacter { found: c, position })
+            }
+            Some((_, c)) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '.');
+                if !matches!(self.peek(), Some((_, '('))) {
+                    return Ok(Node::Field(name));
+                }
+                let function = Function::from_name(&name)
+                    .ok_or(ScoreExpressionError::UnknownFunction { name })?;
+                self.position += 1;
+                let mut arguments = Vec::new();
+                if !matches!(self.peek(), Some((_, ')'))) {
+                    arguments.push(self.expression()?);
+                    while matches!(self.peek(), Some((_, ','))) {
+                        self.position += 1;
+                        arguments.push(self.expression()?);
+                    }
+                }
+                self.expect(')')?;
+                function.check_argument_count(arguments.len())?;
+                Ok(Node::Function { function, arguments })
+            }
+            Some((position, found)) => {
+                Err(ScoreExpressionError::UnexpectedCharacter { found, position })
+            }
+        }
+    }
+}
+
+impl fmt::Display for ScoreExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Number(number) => write!(f, "{number}"),
+            Node::Field(field) => f.write_str(field),
+            Node::Negate(node) => write!(f, "-{node}"),
+            Node::Operation { left, operator, right } => {
+                let operator = match operator {
+                    Operator::Add => "+",
+                    Operator::Subtract => "-",
+                    Operator::Multiply => "*",
+                    Operator::Divide => "/",
+                };
+                write!(f, "({left} {operator} {right})")
+            }
+            Node::Function { function, arguments } => {
+                write!(f, "{}(", function.name())?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{argument}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn parse_score_expression() {
+        let valid_expressions = [
+            ("rating", "rating"),
+            ("log1p(popularity) * 0.3 + rating", "((log1p(popularity) * 0.3) + rating)"),
+            ("a - b - c", "((a - b) - c)"),
+            ("a + b * c", "(a + (b * c))"),
+            ("(a + b) * c", "((a + b) * c)"),
+            ("-price / 2", "(-price / 2)"),
+            ("max(stock.count, 1, pow(2, rating))", "max(stock.count, 1, pow(2, rating))"),
+            ("  sqrt( _views )  ", "sqrt(_views)"),
+        ];
+        for (input, expected) in valid_expressions {
+            let expression = input.parse::<ScoreExpression>();
+            assert!(expression.is_ok(), "Failed to parse `{input}`: {expression:?}");
+            assert_eq!(expression.unwrap().to_string(), expected);
+        }
+
+        let invalid_expressions = [
+            ("", "the expression is empty"),
+            ("   ", "the expression is empty"),
+            ("rating +", "unexpected end of the expression"),
+            ("rating rating", "unexpected `r` at position 7"),
+            ("(rating", "unexpected end of the expression"),
+            ("1.2.3", "unexpected `1` at position 0"),
+            ("rating $ 2", "unexpected `$` at position 7"),
+            (
+                "log(rating)",
+                "`log` is not a valid function. Valid functions are abs, exp, ln, log1p, log10, sqrt, pow, min, max",
+            ),
+            ("pow(rating)", "the `pow` function expects 2 arguments but got 1"),
+            ("sqrt()", "the `sqrt` function expects 1 argument but got 0"),
+            ("max()", "the `max` function expects at least 1 argument but got 0"),
+        ];
+        for (input, expected) in invalid_expressions {
+            let error = input.parse::<ScoreExpression>();
+            assert!(error.is_err(), "Should not be able to parse `{input}`: {error:?}");
+            assert_eq!(error.unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn evaluate_score_expression() {
+        let values = HashMap::from([("popularity", 99.0), ("rating", 4.5), ("debt", -1.0)]);
+        let evaluate = |input: &str| {
+            let expression = input.parse::<ScoreExpression>().unwrap();
+            expression.evaluate(&mut |field| values.get(field).copied())
+        };
+
+        assert_eq!(evaluate("rating"), Some(4.5));
+        assert_eq!(evaluate("log10(popularity + 1) * 0.5 + rating"), Some(5.5));
+        assert_eq!(evaluate("max(rating, 2 * 3) - -1"), Some(7.0));
+        assert_eq!(evaluate("abs(debt)"), Some(1.0));
+        // a missing field or a result that is not a number give no value
+        assert_eq!(evaluate("rating + missing"), None);
+        assert_eq!(evaluate("ln(debt)"), None);
+        assert_eq!(evaluate("rating / 0"), None);
+
+        let expression = "a * b + log1p(a) - c".parse::<ScoreExpression>().unwrap();
+        assert_eq!(expression.fields(), vec!["a", "b", "c"]);
+    }
+}
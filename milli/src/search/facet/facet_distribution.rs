@@ -1,24 +1,30 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Display;
-use std::ops::ControlFlow;
+use std::ops::{Bound, ControlFlow};
 use std::{fmt, mem};
 
+use filter_parser::parse_date;
 use heed::types::Bytes;
 use heed::BytesDecode;
 use indexmap::IndexMap;
+use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
+use time::{Date, Duration, Month, OffsetDateTime};
 
 use crate::error::UserError;
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{
-    FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FieldDocIdFacetF64Codec,
+    FieldDocIdFacetStringCodec, OrderedF64Codec,
 };
 use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
-    count_iterate_over_facet_distribution, lexicographically_iterate_over_facet_distribution,
+    count_candidates_in_facet_ranges, count_iterate_over_facet_distribution,
+    lexicographically_iterate_over_facet_distribution,
 };
-use crate::{FieldId, Index, Result};
+use crate::search::facet::nested;
+use crate::{FieldId, FieldsIdsMap, Filter, Index, Result};
 
 /// The default number of values by facets that will
 /// be fetched from the key-value store.
@@ -38,6 +44,25 @@ pub enum OrderBy {
     Count,
 }
 
+/// A value of a facet distribution. The values are ordered the way the distributions return
+/// them: the numbers first, then the booleans and the strings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FacetValue {
+    Number(OrderedFloat<f64>),
+    Bool(bool),
+    String(String),
+}
+
+impl Display for FacetValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacetValue::Number(number) => write!(f, "{}", number.0),
+            FacetValue::Bool(boolean) => write!(f, "{boolean}"),
+            FacetValue::String(string) => f.write_str(string),
+        }
+    }
+}
+
 impl Display for OrderBy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -47,9 +72,114 @@ impl Display for OrderBy {
     }
 }
 
+/// How should the values of a numeric facet be grouped into buckets?
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacetBuckets {
+    /// Into the given ranges, that can overlap...
+    Ranges(Vec<FacetRange>),
+    /// Or into contiguous ranges of the given width, aligned on its multiples...
+    Interval(f64),
+    /// Or into the calendar days, weeks or months of the timestamps of a date facet.
+    Calendar(CalendarInterval),
+}
+
+/// A calendar unit in UTC, the weeks starting on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarInterval {
+    Day,
+    Week,
+    Month,
+}
+
+impl CalendarInterval {
+    /// Returns the first day of the unit containing the date.
+    fn start(&self, date: Date) -> Date {
+        match self {
+            CalendarInterval::Day => date,
+            CalendarInterval::Week => {
+                date - Duration::days(date.weekday().number_days_from_monday().into())
+            }
+            CalendarInterval::Month => date.replace_day(1).unwrap(),
+        }
+    }
+
+    /// Returns the first day of the next unit, the date being the first day of a unit.
+    fn next(&self, date: Date) -> Option<Date> {
+        match self {
+            CalendarInterval::Day => date.next_day(),
+            CalendarInterval::Week => date.checked_add(Duration::weeks(1)),
+            CalendarInterval::Month => match date.month() {
+                Month::December => {
+                    Date::from_calendar_date(date.year() + 1, Month::January, 1).ok()
+                }
+                month => Date::from_calendar_date(date.year(), month.next(), 1).ok(),
+            },
+        }
+    }
+}
+
+/// A range of facet values, including `from` and excluding `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FacetRange {
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+}
+
+impl FacetRange {
+    fn bounds(&self) -> (Bound<f64>, Bound<f64>) {
+        let from = self.from.map_or(Bound::Unbounded, Bound::Included);
+        let to = self.to.map_or(Bound::Unbounded, Bound::Excluded);
+        (from, to)
+    }
+}
+
+/// Statistics about the numeric values of a facet among the candidates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FacetNumberStats {
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub avg: f64,
+    /// The number of values, a document can contain several of them.
+    pub count: u64,
+    pub distinct_count: u64,
+    /// The percentiles are computed with the nearest-rank method.
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+/// Describes how the string values of one or several facets form a tree.
+///
+/// The values must contain their whole path from the root of the tree, e.g. `Books > Fiction`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetHierarchy {
+    /// The facets containing the values of each level of the tree, starting from the root.
+    /// The last facet is used for the deeper levels, a single facet can thus contain every level.
+    pub attributes: Vec<String>,
+    /// The string between the components of a path.
+    pub separator: String,
+    /// The components of the path of the selected value. Only the children of the selected value
+    /// and of its ancestors are returned.
+    pub selected: Vec<String>,
+}
+
+/// A facet value along with its number of candidates and the children requested in the tree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FacetHierarchyNode {
+    /// The whole path of the value, from the root of the tree.
+    pub value: String,
+    pub count: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FacetHierarchyNode>,
+}
+
 pub struct FacetDistribution<'a> {
     facets: Option<HashMap<String, OrderBy>>,
+    buckets: HashMap<String, FacetBuckets>,
+    hierarchies: HashMap<String, FacetHierarchy>,
     candidates: Option<RoaringBitmap>,
+    filter: Option<Filter<'a>>,
     max_values_per_facet: usize,
     default_order_by: OrderBy,
     rtxn: &'a heed::RoTxn<'a>,
@@ -60,7 +190,10 @@ impl<'a> FacetDistribution<'a> {
     pub fn new(rtxn: &'a heed::RoTxn<'a>, index: &'a Index) -> FacetDistribution<'a> {
         FacetDistribution {
             facets: None,
+            buckets: HashMap::new(),
+            hierarchies: HashMap::new(),
             candidates: None,
+            filter: None,
             max_values_per_facet: DEFAULT_VALUES_PER_FACET,
             default_order_by: OrderBy::default(),
             rtxn,
@@ -81,6 +214,28 @@ impl<'a> FacetDistribution<'a> {
         self
     }
 
+    pub fn buckets<I: IntoIterator<Item = (A, FacetBuckets)>, A: AsRef<str>>(
+        &mut self,
+        names_buckets: I,
+    ) -> &mut Self {
+        self.buckets = names_buckets
+            .into_iter()
+            .map(|(name, buckets)| (name.as_ref().to_string(), buckets))
+            .collect();
+        self
+    }
+
+    pub fn hierarchies<I: IntoIterator<Item = (A, FacetHierarchy)>, A: AsRef<str>>(
+        &mut self,
+        names_hierarchies: I,
+    ) -> &mut Self {
+        self.hierarchies = names_hierarchies
+            .into_iter()
+            .map(|(name, hierarchy)| (name.as_ref().to_string(), hierarchy))
+            .collect();
+        self
+    }
+
     pub fn max_values_per_facet(&mut self, max: usize) -> &mut Self {
         self.max_values_per_facet = max;
         self
@@ -96,6 +251,13 @@ impl<'a> FacetDistribution<'a> {
         self
     }
 
+    /// The filter the candidates were computed with, the values of the fields of the nested
+    /// fields are then only counted in the objects matching its conditions.
+    pub fn filter(&mut self, filter: Filter<'a>) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// There is a small amount of candidates OR we ask for facet string values so we
     /// decide to iterate over the facet values of each one of them, one by one.
     fn facet_distribution_from_documents(
@@ -103,7 +265,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         facet_type: FacetType,
         candidates: &RoaringBitmap,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         match facet_type {
             FacetType::Number => {
@@ -121,15 +283,18 @@ impl<'a> FacetDistribution<'a> {
 
                     for result in iter {
                         let ((_, _, value), ()) = result?;
-                        *lexicographic_distribution.entry(value.to_string()).or_insert(0) += 1;
+                        let (_, count) = lexicographic_distribution
+                            .entry(value.to_string())
+                            .or_insert((value, 0));
+                        *count += 1;
                     }
                 }
 
-                distribution.extend(
-                    lexicographic_distribution
-                        .into_iter()
-                        .take(self.max_values_per_facet.saturating_sub(distribution.len())),
-                );
+                let iter = lexicographic_distribution
+                    .into_values()
+                    .take(self.max_values_per_facet.saturating_sub(distribution.len()))
+                    .map(|(value, count)| (FacetValue::Number(OrderedFloat(value)), count));
+                distribution.extend(iter);
             }
             FacetType::String => {
                 let mut normalized_distribution = BTreeMap::new();
@@ -159,7 +324,9 @@ impl<'a> FacetDistribution<'a> {
                 let iter = normalized_distribution
                     .into_iter()
                     .take(self.max_values_per_facet.saturating_sub(distribution.len()))
-                    .map(|(_normalized, (original, count))| (original.to_string(), count));
+                    .map(|(_normalized, (original, count))| {
+                        (FacetValue::String(original.to_string()), count)
+                    });
                 distribution.extend(iter);
             }
         }
@@ -174,7 +341,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         candidates: &RoaringBitmap,
         order_by: OrderBy,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         let search_function = match order_by {
             OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
@@ -188,7 +355,7 @@ impl<'a> FacetDistribution<'a> {
             candidates,
             |facet_key, nbr_docids, _| {
                 let facet_key = OrderedF64Codec::bytes_decode(facet_key).unwrap();
-                distribution.insert(facet_key.to_string(), nbr_docids);
+                distribution.insert(FacetValue::Number(OrderedFloat(facet_key)), nbr_docids);
                 if distribution.len() == self.max_values_per_facet {
                     Ok(ControlFlow::Break(()))
                 } else {
@@ -203,7 +370,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         candidates: &RoaringBitmap,
         order_by: OrderBy,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         let search_function = match order_by {
             OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
@@ -226,7 +393,7 @@ impl<'a> FacetDistribution<'a> {
                     .unwrap()
                     .to_owned();
 
-                distribution.insert(original_string, nbr_docids);
+                distribution.insert(FacetValue::String(original_string), nbr_docids);
                 if distribution.len() == self.max_values_per_facet {
                     Ok(ControlFlow::Break(()))
                 } else {
@@ -236,11 +403,98 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
+    /// Counts the numbers of a date field that are not only the timestamps of its dates.
+    fn date_numbers_distribution(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        order_by: OrderBy,
+        distribution: &mut IndexMap<FacetValue, u64>,
+    ) -> heed::Result<()> {
+        // the documents whose dates have each timestamp
+        let mut dates_docids = HashMap::<u64, RoaringBitmap>::new();
+        let base = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        for result in self.index.facet_id_string_docids.prefix_iter(self.rtxn, &base)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            if let Some(timestamp) = date_timestamp(left_bound) {
+                *dates_docids.entry(timestamp.to_bits()).or_default() |= bitmap;
+            }
+        }
+
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let remaining = self.max_values_per_facet.saturating_sub(distribution.len());
+        if remaining == 0 {
+            return Ok(());
+        }
+        let mut numbers = Vec::new();
+        lexicographically_iterate_over_facet_distribution(
+            self.rtxn,
+            db,
+            field_id,
+            candidates,
+            |facet_key, nbr_docids, _| {
+                let number = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+                let count = match dates_docids.get(&number.to_bits()) {
+                    Some(dates) => {
+                        let key = FacetGroupKey { field_id, level: 0, left_bound: facet_key };
+                        let docids = db.get(self.rtxn, &key)?.map(|v| v.bitmap).unwrap_or_default();
+                        ((docids & candidates) - dates).len()
+                    }
+                    None => nbr_docids,
+                };
+                if count != 0 {
+                    numbers.push((FacetValue::Number(OrderedFloat(number)), count));
+                }
+                if order_by == OrderBy::Lexicographic && numbers.len() == remaining {
+                    Ok(ControlFlow::Break(()))
+                } else {
+                    Ok(ControlFlow::Continue(()))
+                }
+            },
+        )?;
+
+        if order_by == OrderBy::Count {
+            numbers.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+        distribution.extend(numbers.into_iter().take(remaining));
+        Ok(())
+    }
+
+    /// The booleans have their own database, they are counted after the numbers and before the
+    /// strings, `false` first when ordered lexicographically.
+    fn facet_booleans_distribution(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        order_by: OrderBy,
+        distribution: &mut IndexMap<FacetValue, u64>,
+    ) -> heed::Result<()> {
+        let mut booleans = Vec::new();
+        for boolean in [false, true] {
+            let docids = self.index.bool_faceted_documents_ids(self.rtxn, field_id, boolean)?;
+            let count = docids.intersection_len(candidates);
+            if count != 0 {
+                booleans.push((FacetValue::Bool(boolean), count));
+            }
+        }
+        if order_by == OrderBy::Count {
+            booleans.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+
+        let remaining = self.max_values_per_facet.saturating_sub(distribution.len());
+        distribution.extend(booleans.into_iter().take(remaining));
+        Ok(())
+    }
+
+    /// The dates of a date field are also indexed as their timestamps, only their strings are
+    /// counted.
     fn facet_values(
         &self,
         field_id: FieldId,
         order_by: OrderBy,
-    ) -> heed::Result<IndexMap<String, u64>> {
+        is_date: bool,
+    ) -> heed::Result<IndexMap<FacetValue, u64>> {
         use FacetType::{Number, String};
 
         let mut distribution = IndexMap::new();
@@ -248,7 +502,17 @@ impl<'a> FacetDistribution<'a> {
             (OrderBy::Lexicographic, Some(cnd)) if cnd.len() <= CANDIDATES_THRESHOLD => {
                 // Classic search, candidates were specified, we must return facet values only related
                 // to those candidates. We also enter here for facet strings for performance reasons.
-                self.facet_distribution_from_documents(field_id, Number, cnd, &mut distribution)?;
+                if is_date {
+                    self.date_numbers_distribution(field_id, cnd, order_by, &mut distribution)?;
+                } else {
+                    self.facet_distribution_from_documents(
+                        field_id,
+                        Number,
+                        cnd,
+                        &mut distribution,
+                    )?;
+                }
+                self.facet_booleans_distribution(field_id, cnd, order_by, &mut distribution)?;
                 self.facet_distribution_from_documents(field_id, String, cnd, &mut distribution)?;
             }
             _ => {
@@ -261,7 +525,22 @@ impl<'a> FacetDistribution<'a> {
                     }
                 };
 
-                self.facet_numbers_distribution_from_facet_levels(
+                if is_date {
+                    self.date_numbers_distribution(
+                        field_id,
+                        candidates,
+                        order_by,
+                        &mut distribution,
+                    )?;
+                } else {
+                    self.facet_numbers_distribution_from_facet_levels(
+                        field_id,
+                        candidates,
+                        order_by,
+                        &mut distribution,
+                    )?;
+                }
+                self.facet_booleans_distribution(
                     field_id,
                     candidates,
                     order_by,
@@ -279,35 +558,86 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
-    pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
-        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
-        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
-        let candidates = if let Some(candidates) = self.candidates.clone() {
-            candidates
-        } else {
-            return Ok(Default::default());
+    /// Counts the values of a field of the objects of a nested field in the objects matching the
+    /// conditions of the filter, returns `None` if the filter has no condition about these objects.
+    fn correlated_facet_values(
+        &self,
+        nested_fields: &BTreeSet<String>,
+        name: &str,
+        order_by: OrderBy,
+        is_date: bool,
+    ) -> Result<Option<IndexMap<FacetValue, u64>>> {
+        let Some(filter) = &self.filter else { return Ok(None) };
+        let Some(nested_field) = nested::nested_field_of(name, nested_fields) else {
+            return Ok(None);
         };
+        let conditions = filter.nested_conditions(nested_field);
+        if conditions.is_empty() {
+            return Ok(None);
+        }
 
-        let fields = match &self.facets {
-            Some(facets) => {
+        let universe;
+        let candidates = match &self.candidates {
+            Some(cnd) => cnd,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
+            }
+        };
+
+        let mut values = nested::correlated_facet_values(
+            self.index,
+            self.rtxn,
+            nested_field,
+            &conditions,
+            name,
+            is_date,
+            candidates,
+        )?;
+        if order_by == OrderBy::Count {
+            // the sort is stable, the values with the same count stay in lexicographic order
+            values.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+        values.truncate(self.max_values_per_facet);
+
+        Ok(Some(values.into_iter().collect()))
+    }
+
+    /// Returns the facets given to [`Self::facets`] or all the filterable fields.
+    fn requested_fields(&self) -> Result<HashSet<String>> {
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+
+        match self.facets {
+            Some(ref facets) => {
                 let invalid_fields: HashSet<_> = facets
                     .iter()
                     .map(|(name, _)| name)
                     .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
                     .collect();
                 if !invalid_fields.is_empty() {
-                    return Err(UserError::InvalidFacetsDistribution {
+                    Err(UserError::InvalidFacetsDistribution {
                         invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
                         valid_facets_name: filterable_fields.into_iter().collect(),
                     }
-                    .into());
+                    .into())
                 } else {
-                    facets.iter().map(|(name, _)| name).cloned().collect()
+                    Ok(facets.iter().map(|(name, _)| name).cloned().collect())
                 }
             }
-            None => filterable_fields,
+            None => Ok(filterable_fields),
+        }
+    }
+
+    pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = if let Some(candidates) = self.candidates.clone() {
+            candidates
+        } else {
+            return Ok(Default::default());
         };
 
+        let fields = self.requested_fields()?;
+
         let mut distribution = BTreeMap::new();
         for (fid, name) in fields_ids_map.iter() {
             if crate::is_faceted(name, &fields) {
@@ -339,30 +669,401 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
-    pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+    /// Compute the extended statistics of the numeric facets of the candidates.
+    ///
+    /// The minimum and maximum are read from the facet levels like in [`Self::compute_stats`],
+    /// the other statistics are computed from the number of candidates of each facet value.
+    pub fn compute_extended_stats(&self) -> Result<BTreeMap<String, FacetNumberStats>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
-        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        let candidates = if let Some(candidates) = self.candidates.as_ref() {
+            candidates
+        } else {
+            return Ok(Default::default());
+        };
 
-        let fields = match self.facets {
-            Some(ref facets) => {
-                let invalid_fields: HashSet<_> = facets
-                    .iter()
-                    .map(|(name, _)| name)
-                    .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
-                    .collect();
-                if !invalid_fields.is_empty() {
-                    return Err(UserError::InvalidFacetsDistribution {
-                        invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
-                        valid_facets_name: filterable_fields.into_iter().collect(),
-                    }
-                    .into());
+        let fields = self.requested_fields()?;
+
+        let mut distribution = BTreeMap::new();
+        for (fid, name) in fields_ids_map.iter() {
+            if crate::is_faceted(name, &fields) {
+                if let Some(stats) = self.facet_number_stats(fid, candidates)? {
+                    distribution.insert(name.to_string(), stats);
+                }
+            }
+        }
+
+        Ok(distribution)
+    }
+
+    fn facet_number_stats(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+    ) -> Result<Option<FacetNumberStats>> {
+        let min = crate::search::facet::facet_min_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let max = crate::search::facet::facet_max_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let (min, max) = match min.zip(max) {
+            Some(min_max) => min_max,
+            None => return Ok(None),
+        };
+
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+
+        let (mut count, mut sum, mut distinct_count) = (0, 0.0, 0);
+        lexicographically_iterate_over_facet_distribution(
+            self.rtxn,
+            db,
+            field_id,
+            candidates,
+            |facet_key, nbr_candidates, _| {
+                let value = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+                count += nbr_candidates;
+                sum += value * nbr_candidates as f64;
+                distinct_count += 1;
+                Ok(ControlFlow::Continue(()))
+            },
+        )?;
+
+        // The percentiles are the values reached by the nearest ranks of the candidates,
+        // we stop iterating once the highest one is found.
+        let rank = |p: f64| ((p * count as f64 / 100.0).ceil() as u64).max(1);
+        let ranks = [rank(50.0), rank(90.0), rank(99.0)];
+        let mut percentiles = [max; 3];
+        let (mut seen, mut found) = (0, 0);
+        lexicographically_iterate_over_facet_distribution(
+            self.rtxn,
+            db,
+            field_id,
+            candidates,
+            |facet_key, nbr_candidates, _| {
+                let value = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+                seen += nbr_candidates;
+                while found < ranks.len() && ranks[found] <= seen {
+                    percentiles[found] = value;
+                    found += 1;
+                }
+                if found == ranks.len() {
+                    Ok(ControlFlow::Break(()))
                 } else {
-                    facets.iter().map(|(name, _)| name).cloned().collect()
+                    Ok(ControlFlow::Continue(()))
+                }
+            },
+        )?;
+        let [p50, p90, p99] = percentiles;
+
+        Ok(Some(FacetNumberStats {
+            min,
+            max,
+            sum,
+            avg: sum / count as f64,
+            count,
+            distinct_count,
+            p50,
+            p90,
+            p99,
+        }))
+    }
+
+    /// Count the candidates in each bucket of the numeric facets given to [`Self::buckets`].
+    ///
+    /// An interval never produces more buckets than the maximum number of values per facet.
+    pub fn compute_buckets(&self) -> Result<BTreeMap<String, Vec<(FacetRange, u64)>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        let candidates = if let Some(candidates) = self.candidates.clone() {
+            candidates
+        } else {
+            return Ok(Default::default());
+        };
+
+        let invalid_fields: HashSet<_> = self
+            .buckets
+            .keys()
+            .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
+            .collect();
+        if !invalid_fields.is_empty() {
+            return Err(UserError::InvalidFacetsDistribution {
+                invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
+                valid_facets_name: filterable_fields.into_iter().collect(),
+            }
+            .into());
+        }
+
+        let mut distribution = BTreeMap::new();
+        for (name, buckets) in &self.buckets {
+            let fid = fields_ids_map.id(name);
+            let ranges = match buckets {
+                FacetBuckets::Ranges(ranges) => ranges.clone(),
+                FacetBuckets::Interval(interval) => match fid {
+                    Some(fid) => self.interval_ranges(fid, &candidates, *interval)?,
+                    None => Vec::new(),
+                },
+                FacetBuckets::Calendar(unit) => match fid {
+                    Some(fid) => self.calendar_ranges(fid, &candidates, *unit)?,
+                    None => Vec::new(),
+                },
+            };
+
+            let counts = match fid {
+                Some(fid) => {
+                    let bounds: Vec<_> = ranges.iter().map(FacetRange::bounds).collect();
+                    count_candidates_in_facet_ranges(
+                        self.rtxn,
+                        self.index
+                            .facet_id_f64_docids
+                            .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                        fid,
+                        &candidates,
+                        &bounds,
+                    )?
                 }
+                None => vec![0; ranges.len()],
+            };
+
+            distribution.insert(name.to_string(), ranges.into_iter().zip(counts).collect());
+        }
+
+        Ok(distribution)
+    }
+
+    /// Split the values of the candidates into ranges of the given width.
+    fn interval_ranges(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        interval: f64,
+    ) -> Result<Vec<FacetRange>> {
+        if !interval.is_finite() || interval <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let min = crate::search::facet::facet_min_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let max = crate::search::facet::facet_max_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let (min, max) = match min.zip(max) {
+            Some(min_max) => min_max,
+            None => return Ok(Vec::new()),
+        };
+
+        let start = (min / interval).floor() * interval;
+        let bound = |i: usize| start + i as f64 * interval;
+        let mut ranges = Vec::new();
+        while ranges.len() < self.max_values_per_facet && bound(ranges.len()) <= max {
+            let i = ranges.len();
+            ranges.push(FacetRange { from: Some(bound(i)), to: Some(bound(i + 1)) });
+        }
+
+        Ok(ranges)
+    }
+
+    /// Split the timestamps of the candidates into the calendar units they belong to.
+    fn calendar_ranges(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+        unit: CalendarInterval,
+    ) -> Result<Vec<FacetRange>> {
+        let min = crate::search::facet::facet_min_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let max = crate::search::facet::facet_max_value(
+            self.index,
+            self.rtxn,
+            field_id,
+            candidates.clone(),
+        )?;
+        let (min, max) = match min.zip(max) {
+            Some(min_max) => min_max,
+            None => return Ok(Vec::new()),
+        };
+        let Ok(min) = OffsetDateTime::from_unix_timestamp(min.floor() as i64) else {
+            return Ok(Vec::new());
+        };
+
+        let timestamp = |date: Date| date.midnight().assume_utc().unix_timestamp() as f64;
+        let mut ranges = Vec::new();
+        let mut start = unit.start(min.date());
+        while ranges.len() < self.max_values_per_facet && timestamp(start) <= max {
+            let Some(end) = unit.next(start) else { break };
+            ranges.push(FacetRange { from: Some(timestamp(start)), to: Some(timestamp(end)) });
+            start = end;
+        }
+
+        Ok(ranges)
+    }
+
+    /// Compute the trees of values of the facet hierarchies given to [`Self::hierarchies`].
+    ///
+    /// The order of the values of a hierarchy is the one of the facet with the same name.
+    pub fn compute_hierarchies(&self) -> Result<BTreeMap<String, Vec<FacetHierarchyNode>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+
+        let invalid_fields: HashSet<_> = self
+            .hierarchies
+            .values()
+            .flat_map(|hierarchy| &hierarchy.attributes)
+            .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
+            .collect();
+        if !invalid_fields.is_empty() {
+            return Err(UserError::InvalidFacetsDistribution {
+                invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
+                valid_facets_name: filterable_fields.into_iter().collect(),
+            }
+            .into());
+        }
+
+        let universe;
+        let candidates = match &self.candidates {
+            Some(cnd) => cnd,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
             }
-            None => filterable_fields,
         };
 
+        let mut distribution = BTreeMap::new();
+        for (name, hierarchy) in &self.hierarchies {
+            let order_by = self
+                .facets
+                .as_ref()
+                .zip(hierarchy.attributes.first())
+                .and_then(|(facets, root)| facets.get(root).copied())
+                .unwrap_or(self.default_order_by);
+            let tree = self.facet_hierarchy(&fields_ids_map, hierarchy, candidates, order_by)?;
+            distribution.insert(name.to_string(), tree);
+        }
+
+        Ok(distribution)
+    }
+
+    fn facet_hierarchy(
+        &self,
+        fields_ids_map: &FieldsIdsMap,
+        hierarchy: &FacetHierarchy,
+        candidates: &RoaringBitmap,
+        order_by: OrderBy,
+    ) -> Result<Vec<FacetHierarchyNode>> {
+        let FacetHierarchy { attributes, separator, selected } = hierarchy;
+        let separator = separator.as_str();
+
+        // The values of each level of the tree, from the root to the children of the selected value.
+        let mut levels = Vec::new();
+        for depth in 0..=selected.len() {
+            let attribute = match attributes.get(depth).or(attributes.last()) {
+                Some(attribute) => attribute,
+                None => break,
+            };
+
+            let mut docids_by_path: BTreeMap<String, RoaringBitmap> = BTreeMap::new();
+            if let Some(fid) = fields_ids_map.id(attribute) {
+                for (value, docids) in self.facet_strings_docids(fid, candidates)? {
+                    let components: Vec<_> = value.split(separator).collect();
+                    if components.len() > depth && components[..depth] == selected[..depth] {
+                        let path = components[..=depth].join(separator);
+                        *docids_by_path.entry(path).or_default() |= docids;
+                    }
+                }
+            }
+
+            let mut nodes: Vec<_> = docids_by_path
+                .into_iter()
+                .map(|(value, docids)| FacetHierarchyNode {
+                    value,
+                    count: docids.len(),
+                    children: Vec::new(),
+                })
+                .collect();
+            if order_by == OrderBy::Count {
+                nodes.sort_by(|left, right| right.count.cmp(&left.count));
+            }
+
+            // We never remove the selected value, even when there are too many values
+            let selected_path = selected.get(depth).map(|_| selected[..=depth].join(separator));
+            let selected_node = nodes
+                .iter()
+                .position(|node| Some(&node.value) == selected_path.as_ref())
+                .filter(|&position| position >= self.max_values_per_facet)
+                .map(|position| nodes.swap_remove(position));
+            nodes.truncate(self.max_values_per_facet);
+            nodes.extend(selected_node);
+
+            levels.push(nodes);
+        }
+
+        let mut children = Vec::new();
+        for (depth, mut nodes) in levels.into_iter().enumerate().rev() {
+            if let Some(selected) = selected.get(..=depth) {
+                let selected_path = selected.join(separator);
+                if let Some(node) = nodes.iter_mut().find(|node| node.value == selected_path) {
+                    node.children = mem::take(&mut children);
+                }
+            }
+            children = nodes;
+        }
+
+        Ok(children)
+    }
+
+    /// Returns one original value of each string of the facet along with the candidates containing it.
+    fn facet_strings_docids(
+        &self,
+        field_id: FieldId,
+        candidates: &RoaringBitmap,
+    ) -> Result<Vec<(String, RoaringBitmap)>> {
+        let mut values = Vec::new();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        for result in self.index.facet_id_string_docids.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let docids = bitmap & candidates;
+            if let Some(any_docid) = docids.min() {
+                let key: (FieldId, _, &str) = (field_id, any_docid, left_bound);
+                let original = self.index.field_id_docid_facet_strings.get(self.rtxn, &key)?;
+                values.push((original.unwrap_or(left_bound).to_string(), docids));
+            }
+        }
+        Ok(values)
+    }
+
+    /// Returns the distributions with the values as strings, see [`Self::execute_typed`].
+    pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        let distribution = self.execute_typed()?;
+        Ok(distribution
+            .into_iter()
+            .map(|(name, values)| (name, stringify_facet_values(values)))
+            .collect())
+    }
+
+    /// Returns the distributions with the numbers and the booleans kept as such.
+    pub fn execute_typed(&self) -> Result<BTreeMap<String, IndexMap<FacetValue, u64>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let fields = self.requested_fields()?;
+        let nested_fields = self.index.nested_fields(self.rtxn)?;
+        let date_fields_ids = self.index.date_fields_ids(self.rtxn)?;
+
         let mut distribution = BTreeMap::new();
         for (fid, name) in fields_ids_map.iter() {
             if crate::is_faceted(name, &fields) {
@@ -371,7 +1072,12 @@ impl<'a> FacetDistribution<'a> {
                     .as_ref()
                     .and_then(|facets| facets.get(name).copied())
                     .unwrap_or(self.default_order_by);
-                let values = self.facet_values(fid, order_by)?;
+                let is_date = date_fields_ids.contains(&fid);
+                let values =
+                    match self.correlated_facet_values(&nested_fields, name, order_by, is_date)? {
+                        Some(values) => values,
+                        None => self.facet_values(fid, order_by, is_date)?,
+                    };
                 distribution.insert(name.to_string(), values);
             }
         }
@@ -380,11 +1086,24 @@ impl<'a> FacetDistribution<'a> {
     }
 }
 
+/// Converts the values of a distribution to their strings, keeping their order.
+pub fn stringify_facet_values(values: IndexMap<FacetValue, u64>) -> IndexMap<String, u64> {
+    values.into_iter().map(|(value, count)| (value.to_string(), count)).collect()
+}
+
+/// Returns the timestamp of the date of a facet string, these strings are lowercased.
+pub(crate) fn date_timestamp(normalized: &str) -> Option<f64> {
+    parse_date(&normalized.to_uppercase())
+}
+
 impl fmt::Debug for FacetDistribution<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let FacetDistribution {
             facets,
+            buckets,
+            hierarchies,
             candidates,
+            filter,
             max_values_per_facet,
             default_order_by,
             rtxn: _,
@@ -393,7 +1112,10 @@ impl fmt::Debug for FacetDistribution<'_> {
 
         f.debug_struct("FacetDistribution")
             .field("facets", facets)
+            .field("buckets", buckets)
+            .field("hierarchies", hierarchies)
             .field("candidates", candidates)
+            .field("filter", filter)
             .field("max_values_per_facet", max_values_per_facet)
             .field("default_order_by", default_order_by)
             .finish()
@@ -405,11 +1127,14 @@ mod tests {
     use std::iter;
 
     use big_s::S;
-    use maplit::hashset;
+    use maplit::{btreeset, hashset};
 
     use crate::documents::documents_batch_reader_from_objects;
     use crate::index::tests::TempIndex;
-    use crate::{milli_snap, FacetDistribution, OrderBy};
+    use crate::{
+        milli_snap, CalendarInterval, FacetBuckets, FacetDistribution, FacetHierarchy,
+        FacetHierarchyNode, FacetNumberStats, FacetRange, Filter, OrderBy,
+    };
 
     #[test]
     fn few_candidates_few_facet_values() {
@@ -684,6 +1409,319 @@ mod tests {
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (217.0, 776.0)}"###);
     }
 
+    #[test]
+    fn facet_extended_stats() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        let documents = (0..1000)
+            .map(|i| serde_json::json!({ "colour": i % 100 }).as_object().unwrap().clone());
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .compute_extended_stats()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "no_candidates", @"{}");
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((0..1000).collect())
+            .compute_extended_stats()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "candidates_0_1000", @r###"{"colour": FacetNumberStats { min: 0.0, max: 99.0, sum: 49500.0, avg: 49.5, count: 1000, distinct_count: 100, p50: 49.0, p90: 89.0, p99: 98.0 }}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((10..20).collect())
+            .compute_extended_stats()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "candidates_10_20", @r###"{"colour": FacetNumberStats { min: 10.0, max: 19.0, sum: 145.0, avg: 14.5, count: 10, distinct_count: 10, p50: 14.0, p90: 18.0, p99: 19.0 }}"###);
+    }
+
+    #[test]
+    fn facet_buckets() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("price") }))
+            .unwrap();
+
+        let documents =
+            (0..1000).map(|i| serde_json::json!({ "price": i }).as_object().unwrap().clone());
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("price", FacetBuckets::Interval(250.))))
+            .compute_buckets()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "no_candidates", @"{}");
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once((
+                "price",
+                FacetBuckets::Ranges(vec![
+                    FacetRange { from: None, to: Some(100.) },
+                    FacetRange { from: Some(100.), to: Some(300.) },
+                    FacetRange { from: Some(250.), to: Some(251.) },
+                    FacetRange { from: Some(950.), to: None },
+                ]),
+            )))
+            .candidates((0..1000).collect())
+            .compute_buckets()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "ranges_candidates_0_1000", @r###"{"price": [(FacetRange { from: None, to: Some(100.0) }, 100), (FacetRange { from: Some(100.0), to: Some(300.0) }, 200), (FacetRange { from: Some(250.0), to: Some(251.0) }, 1), (FacetRange { from: Some(950.0), to: None }, 50)]}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("price", FacetBuckets::Interval(250.))))
+            .candidates((217..777).collect())
+            .compute_buckets()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "interval_candidates_217_777", @r###"{"price": [(FacetRange { from: Some(0.0), to: Some(250.0) }, 33), (FacetRange { from: Some(250.0), to: Some(500.0) }, 250), (FacetRange { from: Some(500.0), to: Some(750.0) }, 250), (FacetRange { from: Some(750.0), to: Some(1000.0) }, 27)]}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("price", FacetBuckets::Interval(1.))))
+            .max_values_per_facet(10)
+            .candidates((0..1000).collect())
+            .compute_buckets()
+            .unwrap();
+
+        milli_snap!(format!("{}", map["price"].len()), "interval_with_max_10", @"10");
+
+        let error = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("colour", FacetBuckets::Interval(1.))))
+            .candidates((0..1000).collect())
+            .compute_buckets()
+            .unwrap_err();
+
+        milli_snap!(error.to_string(), "unknown_field", @"Invalid facet distribution, attribute `colour` is not filterable. The available filterable attribute is `price`.");
+    }
+
+    #[test]
+    fn facet_calendar_buckets() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("date") });
+                settings.set_date_fields(btreeset! { S("date") });
+            })
+            .unwrap();
+
+        let documents = [
+            "2024-01-30T10:00:00Z",
+            "2024-01-31",
+            "2024-02-01T23:59:59Z",
+            "2024-02-05",
+            "2024-03-10T00:00:00+01:00",
+            "not a date",
+        ];
+        let documents = documents
+            .iter()
+            .map(|date| serde_json::json!({ "date": date }).as_object().unwrap().clone());
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let range = |from, to| FacetRange { from: Some(from), to: Some(to) };
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("date", FacetBuckets::Calendar(CalendarInterval::Month))))
+            .candidates((0..6).collect())
+            .compute_buckets()
+            .unwrap();
+
+        assert_eq!(
+            map["date"],
+            vec![
+                (range(1704067200., 1706745600.), 2),
+                (range(1706745600., 1709251200.), 2),
+                (range(1709251200., 1711929600.), 1),
+            ]
+        );
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("date", FacetBuckets::Calendar(CalendarInterval::Week))))
+            .candidates((0..6).collect())
+            .compute_buckets()
+            .unwrap();
+
+        let counts: Vec<_> = map["date"].iter().map(|(_, count)| *count).collect();
+        assert_eq!(map["date"][0].0, range(1706486400., 1707091200.));
+        assert_eq!(map["date"][5].0, range(1709510400., 1710115200.));
+        assert_eq!(counts, vec![3, 1, 0, 0, 0, 1]);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .buckets(iter::once(("date", FacetBuckets::Calendar(CalendarInterval::Day))))
+            .candidates((0..6).collect())
+            .max_values_per_facet(3)
+            .compute_buckets()
+            .unwrap();
+
+        let counts: Vec<_> = map["date"].iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![1, 1, 1]);
+
+        drop(txn);
+
+        // the numbers of a date field are not dates, even if one is the timestamp of a date
+        let documents =
+            [serde_json::json!({ "date": 12 }), serde_json::json!({ "date": 1707091200 })];
+        let documents = documents.iter().map(|document| document.as_object().unwrap().clone());
+        index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        // the dates are only counted as strings in the facet distribution
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("date", OrderBy::Count)))
+            .candidates((0..8).collect())
+            .execute()
+            .unwrap();
+
+        assert_eq!(map["date"].len(), 8);
+        assert_eq!(map["date"]["2024-02-05"], 1);
+        assert_eq!(map["date"]["1707091200"], 1);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("date", OrderBy::Lexicographic)))
+            .candidates((0..8).collect())
+            .execute()
+            .unwrap();
+
+        let values: Vec<_> = map["date"].keys().take(3).map(String::as_str).collect();
+        assert_eq!(values, vec!["12", "1707091200", "2024-01-30T10:00:00Z"]);
+    }
+
+    #[test]
+    fn facet_hierarchies() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("categories"), S("path") })
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                {
+                    "id": 0,
+                    "categories": { "lvl0": "Books", "lvl1": "Books > Fiction", "lvl2": "Books > Fiction > Crime" },
+                    "path": "Books > Fiction > Crime",
+                },
+                {
+                    "id": 1,
+                    "categories": { "lvl0": "Books", "lvl1": "Books > Fiction", "lvl2": "Books > Fiction > Fantasy" },
+                    "path": "Books > Fiction > Fantasy",
+                },
+                {
+                    "id": 2,
+                    "categories": { "lvl0": "Books", "lvl1": "Books > Poetry" },
+                    "path": "Books > Poetry",
+                },
+                {
+                    "id": 3,
+                    "categories": { "lvl0": "Music", "lvl1": "Music > Jazz" },
+                    "path": "Music > Jazz",
+                },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let node = |value: &str, count, children| FacetHierarchyNode {
+            value: value.to_string(),
+            count,
+            children,
+        };
+
+        let hierarchy = FacetHierarchy {
+            attributes: vec![S("categories.lvl0"), S("categories.lvl1"), S("categories.lvl2")],
+            separator: S(" > "),
+            selected: vec![S("Books"), S("Fiction")],
+        };
+        let map = FacetDistribution::new(&txn, &index)
+            .hierarchies(iter::once(("categories", hierarchy)))
+            .compute_hierarchies()
+            .unwrap();
+
+        assert_eq!(
+            map["categories"],
+            vec![
+                node(
+                    "Books",
+                    3,
+                    vec![
+                        node(
+                            "Books > Fiction",
+                            2,
+                            vec![
+                                node("Books > Fiction > Crime", 1, vec![]),
+                                node("Books > Fiction > Fantasy", 1, vec![]),
+                            ]
+                        ),
+                        node("Books > Poetry", 1, vec![]),
+                    ]
+                ),
+                node("Music", 1, vec![]),
+            ]
+        );
+
+        let hierarchy = FacetHierarchy {
+            attributes: vec![S("path")],
+            separator: S(" > "),
+            selected: vec![S("Books")],
+        };
+        let map = FacetDistribution::new(&txn, &index)
+            .hierarchies(iter::once(("path", hierarchy.clone())))
+            .candidates([1, 2, 3].into_iter().collect())
+            .compute_hierarchies()
+            .unwrap();
+
+        assert_eq!(
+            map["path"],
+            vec![
+                node(
+                    "Books",
+                    2,
+                    vec![node("Books > Fiction", 1, vec![]), node("Books > Poetry", 1, vec![])]
+                ),
+                node("Music", 1, vec![]),
+            ]
+        );
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("path", OrderBy::Count)))
+            .hierarchies(iter::once(("path", hierarchy)))
+            .max_values_per_facet(1)
+            .compute_hierarchies()
+            .unwrap();
+
+        assert_eq!(map["path"], vec![node("Books", 3, vec![node("Books > Fiction", 2, vec![])])]);
+    }
+
     #[test]
     fn facet_stats_array() {
         let mut index = TempIndex::new_with_map_size(4096 * 10_000);
@@ -843,4 +1881,102 @@ mod tests {
 
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (218.0, 1776.0)}"###);
     }
+
+    #[test]
+    fn facet_nested_fields() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("variants") });
+                settings.set_nested_fields(btreeset! { S("variants") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "variants": [{ "color": "red", "size": 38 }, { "color": "blue", "size": 40 }] },
+                { "id": 1, "variants": [{ "color": "red", "size": 40 }, { "color": "blue", "size": 38 }] },
+                { "id": 2, "variants": { "color": "blue", "size": 40 } },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("variants.size = 40").unwrap().unwrap();
+        let candidates = filter.evaluate(&txn, &index).unwrap();
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("variants.color", OrderBy::Count)))
+            .candidates(candidates.clone())
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "uncorrelated", @r###"{"variants.color": {"blue": 3, "red": 2}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("variants.color", OrderBy::Count)))
+            .candidates(candidates)
+            .filter(filter)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "correlated", @r###"{"variants.color": {"blue": 2, "red": 1}}"###);
+
+        let filter =
+            Filter::from_str("variants.color = red AND variants.size > 38").unwrap().unwrap();
+        let candidates = filter.evaluate(&txn, &index).unwrap();
+        let map = FacetDistribution::new(&txn, &index)
+            .facets([("variants.color", OrderBy::default()), ("variants.size", OrderBy::default())])
+            .candidates(candidates)
+            .filter(filter)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "correlated_and", @r###"{"variants.color": {"red": 1}, "variants.size": {"40": 1}}"###);
+    }
+
+    #[test]
+    fn facet_booleans() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("in_stock") }))
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "in_stock": true },
+                { "id": 1, "in_stock": false },
+                { "id": 2, "in_stock": [true, false] },
+                { "id": 3, "in_stock": "maybe" },
+                { "id": 4, "in_stock": 12 },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("in_stock", OrderBy::default())))
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"in_stock": {"12": 1, "false": 2, "true": 2, "maybe": 1}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("in_stock", OrderBy::default())))
+            .candidates([0, 3].iter().copied().collect())
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"in_stock": {"true": 1, "maybe": 1}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("in_stock", OrderBy::Count)))
+            .candidates([0, 2].iter().copied().collect())
+            .max_values_per_facet(1)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"in_stock": {"true": 2}}"###);
+    }
 }
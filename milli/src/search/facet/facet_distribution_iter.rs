@@ -1,13 +1,14 @@
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::ops::ControlFlow;
+use std::ops::{Bound, ControlFlow, RangeBounds};
 
-use heed::Result;
+use heed::{BytesDecode, Result};
 use roaring::RoaringBitmap;
 
-use super::{get_first_facet_value, get_highest_level};
+use super::{get_first_facet_value, get_highest_level, get_last_facet_value};
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupLazyValueCodec, FacetGroupValueCodec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupLazyValue, FacetGroupLazyValueCodec,
+    FacetGroupValueCodec, OrderedF64Codec,
 };
 use crate::heed_codec::BytesRefCodec;
 use crate::{CboRoaringBitmapCodec, DocumentId};
@@ -142,6 +143,188 @@ where
     Ok(())
 }
 
+/// Count the candidates whose numeric facet value falls within each of the given ranges.
+///
+/// The returned counts are in the same order as the ranges. The ranges can overlap, and a
+/// document with several values in the same range is only counted once.
+pub fn count_candidates_in_facet_ranges<'t>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: &RoaringBitmap,
+    ranges: &[(Bound<f64>, Bound<f64>)],
+) -> Result<Vec<u64>> {
+    let db = db.remap_data_type::<FacetGroupLazyValueCodec>();
+    let mut docids = vec![RoaringBitmap::new(); ranges.len()];
+    let highest_level = get_highest_level(rtxn, db, field_id)?;
+
+    if let Some(first_bound) = get_first_facet_value::<BytesRefCodec, _>(rtxn, db, field_id)? {
+        // will not fail because get_first_facet_value succeeded
+        let last_value = get_last_facet_value::<OrderedF64Codec, _>(rtxn, db, field_id)?.unwrap();
+        let all_ranges: Vec<_> = (0..ranges.len()).collect();
+        let mut fd = FacetRangesDistribution { rtxn, db, field_id, ranges, docids: &mut docids };
+        fd.iterate(
+            candidates,
+            &all_ranges,
+            highest_level,
+            first_bound,
+            usize::MAX,
+            Bound::Included(last_value),
+        )?;
+    }
+
+    Ok(docids.iter().map(RoaringBitmap::len).collect())
+}
+
+/// How a range of facet values covers a group of the facet levels.
+enum RangeCoverage {
+    /// All the values of the group are in the range.
+    Full,
+    /// Some values of the group may be in the range.
+    Partial,
+    /// None of the values of the group are in the range.
+    Disjoint,
+}
+
+/// Returns how the `range` covers a group whose values are greater than or equal to
+/// `group_left` and bounded by `group_right`.
+fn range_coverage(
+    range: &(Bound<f64>, Bound<f64>),
+    group_left: f64,
+    group_right: Bound<f64>,
+) -> RangeCoverage {
+    let (start, end) = *range;
+
+    let ends_before_group = match end {
+        Bound::Included(end) => end < group_left,
+        Bound::Excluded(end) => end <= group_left,
+        Bound::Unbounded => false,
+    };
+    let starts_after_group = match (start, group_right) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+        (Bound::Included(start), Bound::Included(right)) => start > right,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(right) | Bound::Excluded(right),
+        ) => start >= right,
+    };
+    if ends_before_group || starts_after_group {
+        return RangeCoverage::Disjoint;
+    }
+
+    let starts_before_group = match start {
+        Bound::Included(start) => start <= group_left,
+        Bound::Excluded(start) => start < group_left,
+        Bound::Unbounded => true,
+    };
+    let ends_after_group = match (end, group_right) {
+        (Bound::Unbounded, _) => true,
+        (_, Bound::Unbounded) => false,
+        (Bound::Excluded(end), Bound::Included(right)) => right < end,
+        (
+            Bound::Included(end) | Bound::Excluded(end),
+            Bound::Included(right) | Bound::Excluded(right),
+        ) => right <= end,
+    };
+    if starts_before_group && ends_after_group {
+        RangeCoverage::Full
+    } else {
+        RangeCoverage::Partial
+    }
+}
+
+/// Iterate over the facet levels, only going down the groups that are partially covered by a range.
+struct FacetRangesDistribution<'t, 'r> {
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupLazyValueCodec>,
+    field_id: u16,
+    ranges: &'r [(Bound<f64>, Bound<f64>)],
+    /// The candidates found in each range, in the same order as the ranges.
+    docids: &'r mut [RoaringBitmap],
+}
+
+impl<'t, 'r> FacetRangesDistribution<'t, 'r> {
+    /// Explore the groups of the given level, starting at `starting_bound`.
+    ///
+    /// `ranges` are the indexes of the ranges that are still relevant for these groups
+    /// and `right_bound` is the bound of the values of the last explored group.
+    fn iterate(
+        &mut self,
+        candidates: &RoaringBitmap,
+        ranges: &[usize],
+        level: u8,
+        starting_bound: &'t [u8],
+        group_size: usize,
+        right_bound: Bound<f64>,
+    ) -> Result<()> {
+        let starting_key =
+            FacetGroupKey { field_id: self.field_id, level, left_bound: starting_bound };
+        let mut groups: Vec<(&'t [u8], FacetGroupLazyValue<'t>)> = Vec::new();
+        for el in self.db.range(self.rtxn, &(&starting_key..))?.take(group_size) {
+            let (key, value) = el?;
+            // The range is unbounded on the right and the group size for the highest level is MAX,
+            // so we need to check that we are not iterating over the next field id
+            if key.field_id != self.field_id {
+                break;
+            }
+            groups.push((key.left_bound, value));
+        }
+
+        for (i, (left_bound, value)) in groups.iter().enumerate() {
+            let left_bound = *left_bound;
+            let docids_in_common = CboRoaringBitmapCodec::intersection_with_serialized(
+                value.bitmap_bytes,
+                candidates,
+            )?;
+            if docids_in_common.is_empty() {
+                continue;
+            }
+
+            let group_left =
+                OrderedF64Codec::bytes_decode(left_bound).map_err(heed::Error::Decoding)?;
+            if level == 0 {
+                for &range in ranges {
+                    if self.ranges[range].contains(&group_left) {
+                        self.docids[range] |= &docids_in_common;
+                    }
+                }
+                continue;
+            }
+
+            // The values of a group are lower than the left bound of the next group
+            let group_right = match groups.get(i + 1) {
+                Some((next_left_bound, _)) => Bound::Excluded(
+                    OrderedF64Codec::bytes_decode(next_left_bound)
+                        .map_err(heed::Error::Decoding)?,
+                ),
+                None => right_bound,
+            };
+
+            let mut partial_ranges = Vec::new();
+            for &range in ranges {
+                match range_coverage(&self.ranges[range], group_left, group_right) {
+                    RangeCoverage::Full => self.docids[range] |= &docids_in_common,
+                    RangeCoverage::Partial => partial_ranges.push(range),
+                    RangeCoverage::Disjoint => (),
+                }
+            }
+
+            if !partial_ranges.is_empty() {
+                self.iterate(
+                    &docids_in_common,
+                    &partial_ranges,
+                    level - 1,
+                    left_bound,
+                    value.size as usize,
+                    group_right,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Iterate over the facets values by lexicographic order.
 struct LexicographicFacetDistribution<'t, CB>
 where
@@ -233,12 +416,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::ops::ControlFlow;
+    use std::ops::{Bound, ControlFlow};
 
     use heed::BytesDecode;
     use roaring::RoaringBitmap;
 
-    use super::lexicographically_iterate_over_facet_distribution;
+    use super::{
+        count_candidates_in_facet_ranges, lexicographically_iterate_over_facet_distribution,
+    };
     use crate::heed_codec::facet::OrderedF64Codec;
     use crate::milli_snap;
     use crate::search::facet::tests::{get_random_looking_index, get_simple_index};
@@ -298,4 +483,31 @@ mod tests {
             txn.commit().unwrap();
         }
     }
+
+    #[test]
+    fn count_candidates_in_ranges() {
+        let ranges = [
+            (Bound::Unbounded, Bound::Excluded(10.)),
+            (Bound::Included(10.), Bound::Excluded(100.)),
+            (Bound::Included(37.5), Bound::Included(212.)),
+            (Bound::Excluded(100.), Bound::Unbounded),
+            (Bound::Excluded(3.), Bound::Excluded(4.)),
+            (Bound::Included(300.), Bound::Unbounded),
+        ];
+        let indexes = [get_simple_index(), get_random_looking_index()];
+        for (i, index) in indexes.iter().enumerate() {
+            let txn = index.env.read_txn().unwrap();
+            let candidates = (0..=255).step_by(3).collect::<RoaringBitmap>();
+            let counts =
+                count_candidates_in_facet_ranges(&txn, index.content, 0, &candidates, &ranges)
+                    .unwrap();
+            let mut results = String::new();
+            for ((start, end), count) in ranges.iter().zip(counts) {
+                results.push_str(&format!("{start:?}..{end:?}: {count}\n"));
+            }
+            milli_snap!(results, i);
+
+            txn.commit().unwrap();
+        }
+    }
 }
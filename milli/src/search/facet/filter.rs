@@ -1,23 +1,29 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::ops::Bound::{self, Excluded, Included};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use either::Either;
+use filter_parser::Expression;
 pub use filter_parser::{Condition, Error as FPError, FilterCondition, Token};
-use heed::types::LazyDecode;
+use heed::types::{Bytes, LazyDecode};
 use memchr::memmem::Finder;
 use roaring::{MultiOps, RoaringBitmap};
 use serde_json::Value;
 
-use super::facet_range_search;
+use super::pattern::Pattern;
+use super::{facet_range_search, nested};
 use crate::error::{Error, UserError};
+use crate::geo_shape::{point_in_polygon, LazyGeoShapesRtree};
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
+    FieldDocIdFacetF64Codec, OrderedF64Codec,
 };
 use crate::index::db_name::FACET_ID_STRING_DOCIDS;
 use crate::{
-    distance_between_two_points, lat_lng_to_xyz, FieldId, Index, InternalError, Result,
-    SerializationError,
+    distance_between_two_points, lat_lng_bounding_box_to_xyz_envelope,
+    lat_lng_radius_to_xyz_envelope, lat_lng_to_xyz, DocumentId, FieldId, GeoShape, Index,
+    InternalError, Result, SerializationError,
 };
 
 /// The maximum number of filters the filter AST can process.
@@ -206,6 +212,25 @@ impl<'a> Filter<'a> {
         Ok(Some(Self { condition: and }))
     }
 
+    /// Returns a filter matching the documents matched by every one of the given filters.
+    pub fn and<I>(filters: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = Filter<'a>>,
+    {
+        let mut ands: Vec<_> = filters.into_iter().map(|filter| filter.condition).collect();
+        let and = match ands.len() {
+            0 => return Ok(None),
+            1 => ands.pop().unwrap(),
+            _ => FilterCondition::And(ands),
+        };
+
+        if let Some(token) = and.token_at_depth(MAX_FILTER_DEPTH) {
+            return Err(token.as_external_error(FilterError::TooDeep).into());
+        }
+
+        Ok(Some(Self { condition: and }))
+    }
+
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(expression: &'a str) -> Result<Option<Self>> {
         let condition = match FilterCondition::parse(expression) {
@@ -224,13 +249,87 @@ impl<'a> Filter<'a> {
     pub fn use_contains_operator(&self) -> Option<&Token> {
         self.condition.use_contains_operator()
     }
+
+    pub fn use_pattern_operator(&self) -> Option<&Token> {
+        self.condition.use_pattern_operator()
+    }
+
+    /// Returns the conditions of the filter that must be matched by the objects of the nested field.
+    pub(crate) fn nested_conditions(&self, nested_field: &str) -> Vec<&FilterCondition<'a>> {
+        let conditions: Vec<_> = match &self.condition {
+            FilterCondition::And(conditions) => conditions.iter().collect(),
+            condition => vec![condition],
+        };
+        conditions
+            .into_iter()
+            .filter(|condition| nested::is_about_nested_field(condition, nested_field))
+            .collect()
+    }
+
+    /// Returns `true` if the conditions of the other filter joined by `AND` are all conditions
+    /// of this filter, whatever their order and the spaces or the parentheses around them.
+    pub fn contains(&self, other: &Filter<'a>) -> bool {
+        fn conjuncts<'f, 'a>(
+            condition: &'f FilterCondition<'a>,
+            output: &mut Vec<&'f FilterCondition<'a>>,
+        ) {
+            match condition {
+                FilterCondition::And(conditions) => {
+                    conditions.iter().for_each(|condition| conjuncts(condition, output))
+                }
+                condition => output.push(condition),
+            }
+        }
+
+        let mut conditions = Vec::new();
+        conjuncts(&self.condition, &mut conditions);
+        let mut other_conditions = Vec::new();
+        conjuncts(&other.condition, &mut other_conditions);
+        other_conditions.iter().all(|condition| conditions.contains(condition))
+    }
+
+    /// Returns the attributes the filter is about, `_geo` for the geo conditions.
+    pub fn fields(&self) -> Vec<&str> {
+        fn inner<'c>(condition: &'c FilterCondition<'_>, fields: &mut Vec<&'c str>) {
+            match condition {
+                FilterCondition::Not(condition) => inner(condition, fields),
+                FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+                    fields.push(fid.value())
+                }
+                FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+                    conditions.iter().for_each(|condition| inner(condition, fields))
+                }
+                FilterCondition::GeoLowerThan { .. }
+                | FilterCondition::GeoBoundingBox { .. }
+                | FilterCondition::GeoPolygon { .. } => fields.push("_geo"),
+                FilterCondition::Comparison { left, op: _, right } => {
+                    fields.extend(left.fields().into_iter().chain(right.fields()).map(Token::value))
+                }
+            }
+        }
+
+        let mut fields = Vec::new();
+        inner(&self.condition, &mut fields);
+        fields
+    }
 }
 
 impl<'a> Filter<'a> {
     pub fn evaluate(&self, rtxn: &heed::RoTxn<'_>, index: &Index) -> Result<RoaringBitmap> {
+        self.evaluate_with_geo_shapes(rtxn, index, &LazyGeoShapesRtree::default())
+    }
+
+    /// Evaluates the filter, reading the geo shapes from the given R-tree when it needs them.
+    pub(crate) fn evaluate_with_geo_shapes(
+        &self,
+        rtxn: &heed::RoTxn<'_>,
+        index: &Index,
+        geo_shapes_rtree: &LazyGeoShapesRtree,
+    ) -> Result<RoaringBitmap> {
         // to avoid doing this for each recursive call we're going to do it ONCE ahead of time
         let filterable_fields = index.filterable_fields(rtxn)?;
-        self.inner_evaluate(rtxn, index, &filterable_fields, None)
+        let nested_fields = index.nested_fields(rtxn)?;
+        self.inner_evaluate(rtxn, index, &filterable_fields, &nested_fields, geo_shapes_rtree, None)
     }
 
     fn evaluate_operator(
@@ -248,18 +347,16 @@ impl<'a> Filter<'a> {
         // field id and the level.
 
         let (left, right) = match operator {
-            Condition::GreaterThan(val) => {
-                (Excluded(val.parse_finite_float()?), Included(f64::MAX))
-            }
+            Condition::GreaterThan(val) => (Excluded(parse_comparable(val)?), Included(f64::MAX)),
             Condition::GreaterThanOrEqual(val) => {
-                (Included(val.parse_finite_float()?), Included(f64::MAX))
+                (Included(parse_comparable(val)?), Included(f64::MAX))
             }
-            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(val.parse_finite_float()?)),
+            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(parse_comparable(val)?)),
             Condition::LowerThanOrEqual(val) => {
-                (Included(f64::MIN), Included(val.parse_finite_float()?))
+                (Included(f64::MIN), Included(parse_comparable(val)?))
             }
             Condition::Between { from, to } => {
-                (Included(from.parse_finite_float()?), Included(to.parse_finite_float()?))
+                (Included(parse_comparable(from)?), Included(parse_comparable(to)?))
             }
             Condition::Null => {
                 let is_null = index.null_faceted_documents_ids(rtxn, field_id)?;
@@ -269,23 +366,30 @@ impl<'a> Filter<'a> {
                 let is_empty = index.empty_faceted_documents_ids(rtxn, field_id)?;
                 return Ok(is_empty);
             }
+            Condition::True => {
+                let is_true = index.bool_faceted_documents_ids(rtxn, field_id, true)?;
+                return Ok(is_true);
+            }
+            Condition::False => {
+                let is_false = index.bool_faceted_documents_ids(rtxn, field_id, false)?;
+                return Ok(is_false);
+            }
             Condition::Exists => {
                 let exist = index.exists_faceted_documents_ids(rtxn, field_id)?;
                 return Ok(exist);
             }
             Condition::Equal(val) => {
+                let normalized = crate::normalize_facet(val.value());
                 let string_docids = strings_db
-                    .get(
-                        rtxn,
-                        &FacetGroupKey {
-                            field_id,
-                            level: 0,
-                            left_bound: &crate::normalize_facet(val.value()),
-                        },
-                    )?
+                    .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: &normalized })?
                     .map(|v| v.bitmap)
                     .unwrap_or_default();
-                let number = val.parse_finite_float().ok();
+                // `field = true` also matches the booleans, as it did when they were strings
+                let bool_docids = match normalized.parse() {
+                    Ok(boolean) => index.bool_faceted_documents_ids(rtxn, field_id, boolean)?,
+                    Err(_) => RoaringBitmap::new(),
+                };
+                let number = parse_comparable(val).ok();
                 let number_docids = match number {
                     Some(n) => numbers_db
                         .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n })?
@@ -293,7 +397,7 @@ impl<'a> Filter<'a> {
                         .unwrap_or_default(),
                     None => RoaringBitmap::new(),
                 };
-                return Ok(string_docids | number_docids);
+                return Ok(string_docids | number_docids | bool_docids);
             }
             Condition::NotEqual(val) => {
                 let operator = Condition::Equal(val.clone());
@@ -354,6 +458,10 @@ impl<'a> Filter<'a> {
 
                 return Ok(docids);
             }
+            Condition::EndsWith { .. } | Condition::Like { .. } | Condition::Matches { .. } => {
+                let pattern = Pattern::from_condition(operator)?.unwrap();
+                return pattern.facet_string_docids(index, rtxn, field_id);
+            }
         };
 
         let mut output = RoaringBitmap::new();
@@ -400,6 +508,8 @@ impl<'a> Filter<'a> {
         rtxn: &heed::RoTxn<'_>,
         index: &Index,
         filterable_fields: &HashSet<String>,
+        nested_fields: &BTreeSet<String>,
+        geo_shapes_rtree: &LazyGeoShapesRtree,
         universe: Option<&RoaringBitmap>,
     ) -> Result<RoaringBitmap> {
         if universe.map_or(false, |u| u.is_empty()) {
@@ -413,6 +523,8 @@ impl<'a> Filter<'a> {
                     rtxn,
                     index,
                     filterable_fields,
+                    nested_fields,
+                    geo_shapes_rtree,
                     universe,
                 )?;
                 match universe {
@@ -459,37 +571,138 @@ impl<'a> Filter<'a> {
             FilterCondition::Or(subfilters) => subfilters
                 .iter()
                 .cloned()
-                .map(|f| Self::inner_evaluate(&f.into(), rtxn, index, filterable_fields, universe))
+                .map(|f| {
+                    Self::inner_evaluate(
+                        &f.into(),
+                        rtxn,
+                        index,
+                        filterable_fields,
+                        nested_fields,
+                        geo_shapes_rtree,
+                        universe,
+                    )
+                })
                 .union(),
             FilterCondition::And(subfilters) => {
-                let mut subfilters_iter = subfilters.iter();
-                if let Some(first_subfilter) = subfilters_iter.next() {
-                    let mut bitmap = Self::inner_evaluate(
+                // the conditions about the same nested field must be matched by the same object
+                let (groups, mut subfilters) =
+                    nested::group_by_nested_field(subfilters, nested_fields);
+                let mut correlated = Vec::new();
+                for (nested_field, conditions) in groups {
+                    if conditions.len() == 1 {
+                        subfilters.extend(conditions);
+                    } else {
+                        correlated.push((nested_field, conditions));
+                    }
+                }
+
+                let mut subfilters_iter = subfilters.into_iter();
+                let mut bitmap = match subfilters_iter.next() {
+                    Some(first_subfilter) => Self::inner_evaluate(
                         &(first_subfilter.clone()).into(),
                         rtxn,
                         index,
                         filterable_fields,
+                        nested_fields,
+                        geo_shapes_rtree,
                         universe,
+                    )?,
+                    None if correlated.is_empty() => return Ok(RoaringBitmap::new()),
+                    None => match universe {
+                        Some(universe) => universe.clone(),
+                        None => index.documents_ids(rtxn)?,
+                    },
+                };
+                for f in subfilters_iter {
+                    if bitmap.is_empty() {
+                        return Ok(bitmap);
+                    }
+                    // TODO We are doing the intersections two times,
+                    //      it could be more efficient
+                    //      Can't I just replace this `&=` by an `=`?
+                    bitmap &= Self::inner_evaluate(
+                        &(f.clone()).into(),
+                        rtxn,
+                        index,
+                        filterable_fields,
+                        nested_fields,
+                        geo_shapes_rtree,
+                        Some(&bitmap),
                     )?;
-                    for f in subfilters_iter {
+                }
+
+                for (nested_field, conditions) in correlated {
+                    for fid in conditions.iter().filter_map(|c| nested::condition_fids(c)).flatten()
+                    {
+                        if !crate::is_faceted(fid.value(), filterable_fields) {
+                            return Err(fid.as_external_error(
+                                FilterError::AttributeNotFilterable {
+                                    attribute: fid.value(),
+                                    filterable_fields: filterable_fields.clone(),
+                                },
+                            ))?;
+                        }
+                    }
+                    // reduce the candidates with the facet databases before reading the element ids
+                    for condition in conditions.iter().filter(|c| nested::can_prefilter(c)) {
                         if bitmap.is_empty() {
                             return Ok(bitmap);
                         }
-                        // TODO We are doing the intersections two times,
-                        //      it could be more efficient
-                        //      Can't I just replace this `&=` by an `=`?
                         bitmap &= Self::inner_evaluate(
-                            &(f.clone()).into(),
+                            &(*condition).clone().into(),
                             rtxn,
                             index,
                             filterable_fields,
+                            nested_fields,
+                            geo_shapes_rtree,
                             Some(&bitmap),
                         )?;
                     }
-                    Ok(bitmap)
-                } else {
-                    Ok(RoaringBitmap::new())
+                    if bitmap.is_empty() {
+                        return Ok(bitmap);
+                    }
+                    bitmap =
+                        nested::correlated_docids(index, rtxn, nested_field, &conditions, &bitmap)?;
+                }
+
+                Ok(bitmap)
+            }
+            FilterCondition::Comparison { left, op, right } => {
+                let field_ids_map = index.fields_ids_map(rtxn)?;
+                let mut candidates = match universe {
+                    Some(universe) => universe.clone(),
+                    None => index.documents_ids(rtxn)?,
+                };
+                let mut field_ids = HashMap::new();
+                for field in left.fields().into_iter().chain(right.fields()) {
+                    if !crate::is_faceted(field.value(), filterable_fields) {
+                        return Err(field.as_external_error(
+                            FilterError::AttributeNotFilterable {
+                                attribute: field.value(),
+                                filterable_fields: filterable_fields.clone(),
+                            },
+                        ))?;
+                    }
+                    // only the documents having all the fields can match
+                    match field_ids_map.id(field.value()) {
+                        Some(field_id) => {
+                            candidates &= index.exists_faceted_documents_ids(rtxn, field_id)?;
+                            field_ids.insert(field.value(), field_id);
+                        }
+                        None => return Ok(RoaringBitmap::new()),
+                    }
                 }
+
+                let mut docids = RoaringBitmap::new();
+                for docid in candidates {
+                    let left = expression_values(rtxn, index, &field_ids, left, docid)?;
+                    let right = expression_values(rtxn, index, &field_ids, right, docid)?;
+                    if left.iter().any(|&l| right.iter().any(|&r| op.compare(l, r))) {
+                        docids.insert(docid);
+                    }
+                }
+
+                Ok(docids)
             }
             FilterCondition::GeoLowerThan { point, radius } => {
                 if filterable_fields.contains("_geo") {
@@ -502,21 +715,33 @@ impl<'a> Filter<'a> {
                         return Err(point[1].as_external_error(BadGeoError::Lng(base_point[1])))?;
                     }
                     let radius = radius.parse_finite_float()?;
+                    let mut result = geo_shapes_docids(
+                        rtxn,
+                        index,
+                        geo_shapes_rtree,
+                        lat_lng_radius_to_xyz_envelope(&base_point, radius),
+                        |shape| {
+                            shape
+                                .distance_to(&base_point)
+                                .map_or(false, |distance| distance <= radius + f64::EPSILON)
+                        },
+                    )?;
                     let rtree = match index.geo_rtree(rtxn)? {
                         Some(rtree) => rtree,
-                        None => return Ok(RoaringBitmap::new()),
+                        None => return Ok(result),
                     };
 
                     let xyz_base_point = lat_lng_to_xyz(&base_point);
 
-                    let result = rtree
-                        .nearest_neighbor_iter(&xyz_base_point)
-                        .take_while(|point| {
-                            distance_between_two_points(&base_point, &point.data.1)
-                                <= radius + f64::EPSILON
-                        })
-                        .map(|point| point.data.0)
-                        .collect();
+                    result.extend(
+                        rtree
+                            .nearest_neighbor_iter(&xyz_base_point)
+                            .take_while(|point| {
+                                distance_between_two_points(&base_point, &point.data.1)
+                                    <= radius + f64::EPSILON
+                            })
+                            .map(|point| point.data.0),
+                    );
 
                     Ok(result)
                 } else {
@@ -560,97 +785,44 @@ impl<'a> Filter<'a> {
                         ))?;
                     }
 
-                    // Instead of writing a custom `GeoBoundingBox` filter we're simply going to re-use the range
-                    // filter to create the following filter;
-                    // `_geo.lat {top_right[0]} TO {bottom_left[0]} AND _geo.lng {top_right[1]} TO {bottom_left[1]}`
-                    // As we can see, we need to use a bunch of tokens that don't exist in the original filter,
-                    // thus we're going to create tokens that point to a random span but contain our text.
-
-                    let geo_lat_token = Token::new(
-                        top_right_point[0].original_span(),
-                        Some("_geo.lat".to_string()),
-                    );
-
-                    let condition_lat = FilterCondition::Condition {
-                        fid: geo_lat_token,
-                        op: Condition::Between {
-                            from: bottom_left_point[0].clone(),
-                            to: top_right_point[0].clone(),
-                        },
-                    };
-
-                    let selected_lat = Filter { condition: condition_lat }.inner_evaluate(
-                        rtxn,
-                        index,
-                        filterable_fields,
-                        universe,
-                    )?;
-
-                    let geo_lng_token = Token::new(
-                        top_right_point[1].original_span(),
-                        Some("_geo.lng".to_string()),
-                    );
-                    let selected_lng = if top_right[1] < bottom_left[1] {
-                        // In this case the bounding box is wrapping around the earth (going from 180 to -180).
-                        // We need to update the lng part of the filter from;
-                        // `_geo.lng {top_right[1]} TO {bottom_left[1]}` to
-                        // `_geo.lng {bottom_left[1]} TO 180 AND _geo.lng -180 TO {top_right[1]}`
-
-                        let min_lng_token = Token::new(
-                            top_right_point[1].original_span(),
-                            Some("-180.0".to_string()),
-                        );
-                        let max_lng_token = Token::new(
-                            top_right_point[1].original_span(),
-                            Some("180.0".to_string()),
-                        );
-
-                        let condition_left = FilterCondition::Condition {
-                            fid: geo_lng_token.clone(),
-                            op: Condition::Between {
-                                from: bottom_left_point[1].clone(),
-                                to: max_lng_token,
-                            },
-                        };
-                        let left = Filter { condition: condition_left }.inner_evaluate(
-                            rtxn,
-                            index,
-                            filterable_fields,
-                            universe,
-                        )?;
-
-                        let condition_right = FilterCondition::Condition {
-                            fid: geo_lng_token,
-                            op: Condition::Between {
-                                from: min_lng_token,
-                                to: top_right_point[1].clone(),
-                            },
-                        };
-                        let right = Filter { condition: condition_right }.inner_evaluate(
-                            rtxn,
-                            index,
-                            filterable_fields,
-                            universe,
-                        )?;
-
-                        left | right
+                    // A document can be located at multiple points, thus we can't simply filter the
+                    // `_geo.lat` and `_geo.lng` facets as they lose the association between the
+                    // latitudes and longitudes. We look for the points and shapes that are in the
+                    // bounding box, or on both sides of it when it wraps around the earth.
+                    let boxes = if top_right[1] < bottom_left[1] {
+                        vec![(bottom_left[1], 180.0), (-180.0, top_right[1])]
                     } else {
-                        let condition_lng = FilterCondition::Condition {
-                            fid: geo_lng_token,
-                            op: Condition::Between {
-                                from: bottom_left_point[1].clone(),
-                                to: top_right_point[1].clone(),
-                            },
-                        };
-                        Filter { condition: condition_lng }.inner_evaluate(
-                            rtxn,
-                            index,
-                            filterable_fields,
-                            universe,
-                        )?
+                        vec![(bottom_left[1], top_right[1])]
                     };
+                    let rtree = index.geo_rtree(rtxn)?;
+                    let mut result = RoaringBitmap::new();
+                    for (left, right) in boxes {
+                        let polygon = [
+                            [bottom_left[0], left],
+                            [top_right[0], left],
+                            [top_right[0], right],
+                            [bottom_left[0], right],
+                        ];
+                        let envelope = lat_lng_bounding_box_to_xyz_envelope(&polygon);
+                        if let Some(rtree) = &rtree {
+                            result.extend(
+                                rtree
+                                    .locate_in_envelope(&envelope)
+                                    .filter(|point| {
+                                        let [lat, lng] = point.data.1;
+                                        (bottom_left[0]..=top_right[0]).contains(&lat)
+                                            && (left..=right).contains(&lng)
+                                    })
+                                    .map(|point| point.data.0),
+                            );
+                        }
+                        result |=
+                            geo_shapes_docids(rtxn, index, geo_shapes_rtree, envelope, |shape| {
+                                shape.intersects_polygon(&polygon)
+                            })?;
+                    }
 
-                    Ok(selected_lat & selected_lng)
+                    Ok(result)
                 } else {
                     Err(top_right_point[0].as_external_error(
                         FilterError::AttributeNotFilterable {
@@ -660,6 +832,46 @@ impl<'a> Filter<'a> {
                     ))?
                 }
             }
+            FilterCondition::GeoPolygon { points } => {
+                if filterable_fields.contains("_geo") {
+                    let mut polygon = Vec::with_capacity(points.len());
+                    for [lat, lng] in points {
+                        let point = [lat.parse_finite_float()?, lng.parse_finite_float()?];
+                        if !(-90.0..=90.0).contains(&point[0]) {
+                            return Err(lat.as_external_error(BadGeoError::Lat(point[0])))?;
+                        }
+                        if !(-180.0..=180.0).contains(&point[1]) {
+                            return Err(lng.as_external_error(BadGeoError::Lng(point[1])))?;
+                        }
+                        polygon.push(point);
+                    }
+                    // The R-trees give us the points and shapes that are in the bounding box of the
+                    // polygon, we then only keep the ones that are really inside of it.
+                    let envelope = lat_lng_bounding_box_to_xyz_envelope(&polygon);
+                    let mut result =
+                        geo_shapes_docids(rtxn, index, geo_shapes_rtree, envelope, |shape| {
+                            shape.intersects_polygon(&polygon)
+                        })?;
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(result),
+                    };
+
+                    result.extend(
+                        rtree
+                            .locate_in_envelope(&envelope)
+                            .filter(|point| point_in_polygon(&point.data.1, &polygon))
+                            .map(|point| point.data.0),
+                    );
+
+                    Ok(result)
+                } else {
+                    Err(points[0][0].as_external_error(FilterError::AttributeNotFilterable {
+                        attribute: "_geo",
+                        filterable_fields: filterable_fields.clone(),
+                    }))?
+                }
+            }
         }
     }
 }
@@ -670,6 +882,73 @@ impl<'a> From<FilterCondition<'a>> for Filter<'a> {
     }
 }
 
+/// Parses the value of a comparison or of a range, a number or a date. `NOW()` is the time
+/// at which the filter is evaluated.
+pub(crate) fn parse_comparable(token: &Token) -> Result<f64> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+    Ok(token.parse_finite_float_or_date(now)?)
+}
+
+/// Returns the values of the expression for the document, one for every combination of the
+/// numbers of its fields. The non-finite values, like the result of a division by zero, are
+/// ignored.
+fn expression_values(
+    rtxn: &heed::RoTxn<'_>,
+    index: &Index,
+    field_ids: &HashMap<&str, FieldId>,
+    expression: &Expression,
+    docid: DocumentId,
+) -> Result<Vec<f64>> {
+    match expression {
+        Expression::Value(value) => Ok(vec![parse_comparable(value)?]),
+        Expression::Field(field) => {
+            let mut key = field_ids[field.value()].to_be_bytes().to_vec();
+            key.extend_from_slice(&docid.to_be_bytes());
+            let iter = index
+                .field_id_docid_facet_f64s
+                .remap_key_type::<Bytes>()
+                .prefix_iter(rtxn, &key)?
+                .remap_key_type::<FieldDocIdFacetF64Codec>();
+
+            let mut numbers = Vec::new();
+            for result in iter {
+                let ((_, _, number), ()) = result?;
+                numbers.push(number);
+            }
+            Ok(numbers)
+        }
+        Expression::Operation { left, operator, right } => {
+            let left = expression_values(rtxn, index, field_ids, left, docid)?;
+            let right = expression_values(rtxn, index, field_ids, right, docid)?;
+            Ok(left
+                .iter()
+                .flat_map(|&l| right.iter().map(move |&r| operator.apply(l, r)))
+                .filter(|number| number.is_finite())
+                .collect())
+        }
+    }
+}
+
+/// Returns the documents whose geo shape is in the envelope and matches the predicate.
+fn geo_shapes_docids(
+    rtxn: &heed::RoTxn<'_>,
+    index: &Index,
+    geo_shapes_rtree: &LazyGeoShapesRtree,
+    envelope: rstar::AABB<[f64; 3]>,
+    predicate: impl Fn(&GeoShape) -> bool,
+) -> Result<RoaringBitmap> {
+    let rtree = match geo_shapes_rtree.get(index, rtxn)? {
+        Some(rtree) => rtree,
+        None => return Ok(RoaringBitmap::new()),
+    };
+
+    Ok(rtree
+        .locate_in_envelope_intersecting(&envelope)
+        .filter(|envelope| predicate(&envelope.data.1))
+        .map(|envelope| envelope.data.0)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Write;
@@ -677,7 +956,7 @@ mod tests {
 
     use big_s::S;
     use either::Either;
-    use maplit::hashset;
+    use maplit::{btreeset, hashset};
     use roaring::RoaringBitmap;
 
     use crate::index::tests::TempIndex;
@@ -766,6 +1045,26 @@ mod tests {
         assert_eq!(condition, expected);
     }
 
+    #[test]
+    fn and() {
+        assert_eq!(Filter::and(None).unwrap(), None);
+
+        let filter = Filter::from_str("channel = mv").unwrap().unwrap();
+        let condition = Filter::and(Some(filter.clone())).unwrap().unwrap();
+        assert_eq!(condition, filter);
+
+        let filters = [
+            Filter::from_str("channel = gotaga").unwrap().unwrap(),
+            Filter::from_str("timestamp = 44 OR channel != ponce").unwrap().unwrap(),
+        ];
+        let condition = Filter::and(filters).unwrap().unwrap();
+        let expected =
+            Filter::from_str("channel = gotaga AND (timestamp = 44 OR channel != ponce)")
+                .unwrap()
+                .unwrap();
+        assert_eq!(condition, expected);
+    }
+
     #[test]
     fn not_filterable() {
         let index = TempIndex::new();
@@ -912,6 +1211,137 @@ mod tests {
         assert_eq!(documents_ids, vec![0]);
     }
 
+    #[test]
+    fn geo_polygon() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_geo": { "lat": 1.0, "lng": 1.0 } },
+                { "id": 1, "_geo": { "lat": 1.0, "lng": 3.0 } },
+                { "id": 2, "_geo": { "lat": 3.0, "lng": 3.0 } },
+                { "id": 3, "_geo": { "lat": 0.0, "lng": 2.0 } },
+                { "id": 4, "_geo": { "lat": -1.0, "lng": 1.0 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // a concave `L` shaped polygon that leaves out the top right corner of its bounding box
+        let filter =
+            Filter::from_str("_geoPolygon([[0, 0], [0, 4], [2, 4], [2, 2], [4, 2], [4, 0]])")
+                .unwrap()
+                .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        // the document 3 is on an edge of the polygon
+        assert_eq!(result, RoaringBitmap::from_iter([0, 1, 3]));
+
+        let filter =
+            Filter::from_str("NOT _geoPolygon([[0, 0], [0, 4], [2, 4], [2, 2], [4, 2], [4, 0]])")
+                .unwrap()
+                .unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([2, 4]));
+
+        let filter = Filter::from_str("_geoPolygon([[100, 0], [0, 4], [2, 4]])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().contains(
+            "Bad latitude `100`. Latitude must be contained between -90 and 90 degrees."
+        ));
+    }
+
+    #[test]
+    fn geo_shapes() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_geo": { "type": "Polygon", "coordinates": [[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]]] } },
+                { "id": 1, "_geo": { "type": "LineString", "coordinates": [[0, 10], [10, 10]] } },
+                { "id": 2, "_geo": { "type": "Point", "coordinates": [20, 20] } },
+                { "id": 3, "_geo": { "lat": 1.0, "lng": 1.0 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        // the nearest point of the polygon is at ~333km, the document 3 is at ~444km
+        let filter = Filter::from_str("_geoRadius(1, 5, 350000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0]));
+
+        // the line crosses the bounding box but none of its points are inside of it
+        let filter = Filter::from_str("_geoBoundingBox([11, 5], [9, 4])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([1]));
+
+        let filter =
+            Filter::from_str("_geoPolygon([[19, 19], [19, 21], [21, 21]])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([2]));
+
+        let filter =
+            Filter::from_str("_geoPolygon([[-1, -1], [-1, 3], [3, 3], [3, -1]])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 3]));
+        drop(rtxn);
+
+        index.delete_document("0");
+        let rtxn = index.read_txn().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([3]));
+    }
+
+    #[test]
+    fn geo_multiple_points() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_geo": [{ "lat": 1.0, "lng": 1.0 }, { "lat": 50.0, "lng": 50.0 }] },
+                { "id": 1, "_geo": { "lat": 1.0, "lng": 50.0 } },
+                { "id": 2, "_geo": [{ "lat": 50.0, "lng": 1.0 }] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("_geoRadius(50, 50, 1000)").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0]));
+
+        let filter = Filter::from_str("_geoBoundingBox([2, 2], [0, 0])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0]));
+
+        // no point of the document 0 is in the box even though its latitudes and longitudes are
+        let filter = Filter::from_str("_geoBoundingBox([2, 51], [0, 49])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([1]));
+
+        let filter = Filter::from_str("_geoPolygon([[49, 0], [51, 0], [51, 2]])").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([2]));
+    }
+
     #[test]
     fn geo_radius_error() {
         let index = TempIndex::new();
@@ -1208,4 +1638,189 @@ mod tests {
         let result = filter.evaluate(&rtxn, &index).unwrap();
         assert_eq!(result, RoaringBitmap::from_iter((0..100).filter(|x| x % 10 != 0)));
     }
+
+    #[test]
+    fn nested_fields() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("id"), S("variants") });
+                settings.set_nested_fields(btreeset! { S("variants") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "variants": [{ "color": "red", "size": 38 }, { "color": "blue", "size": 40 }] },
+                { "id": 1, "variants": [{ "color": "red", "size": 40 }, { "color": "blue", "size": 38 }] },
+                { "id": 2, "variants": { "color": "blue", "size": 40 } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+
+        // the conditions must be matched by the same object
+        assert_eq!(
+            evaluate("variants.color = red AND variants.size = 40"),
+            RoaringBitmap::from_iter([1])
+        );
+        assert_eq!(
+            evaluate("variants.color = blue AND variants.size > 38"),
+            RoaringBitmap::from_iter([0, 2])
+        );
+        assert_eq!(
+            evaluate("variants.color = red AND variants.size != 38 AND id < 1"),
+            RoaringBitmap::new()
+        );
+        assert_eq!(
+            evaluate("(variants.color = red OR variants.size = 38) AND variants.color != red"),
+            RoaringBitmap::from_iter([1])
+        );
+        // a single condition is matched by any object
+        assert_eq!(evaluate("variants.color = red"), RoaringBitmap::from_iter([0, 1]));
+        assert_eq!(
+            evaluate("variants.color = red OR variants.size = 40"),
+            RoaringBitmap::from_iter([0, 1, 2])
+        );
+
+        let filter =
+            Filter::from_str("variants.color = red AND variants.size = 40").unwrap().unwrap();
+        assert_eq!(filter.nested_conditions("variants").len(), 2);
+        assert!(filter.nested_conditions("id").is_empty());
+        drop(rtxn);
+
+        // the element ids follow the updates of the documents and of the settings
+        index
+            .add_documents(documents!([
+                { "id": 1, "variants": [{ "color": "blue", "size": 40 }, { "color": "red", "size": 38 }] },
+            ]))
+            .unwrap();
+        let evaluate = |filter: &str| {
+            let rtxn = index.read_txn().unwrap();
+            Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+        assert_eq!(evaluate("variants.color = red AND variants.size = 40"), RoaringBitmap::new());
+
+        index.update_settings(|settings| settings.reset_nested_fields()).unwrap();
+        assert_eq!(
+            evaluate("variants.color = red AND variants.size = 40"),
+            RoaringBitmap::from_iter([0, 1])
+        );
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.field_id_docid_nested_element_ids.is_empty(&rtxn).unwrap());
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| settings.set_nested_fields(btreeset! { S("variants") }))
+            .unwrap();
+        assert_eq!(
+            evaluate("variants.color = blue AND variants.size = 40"),
+            RoaringBitmap::from_iter([0, 1, 2])
+        );
+        assert_eq!(evaluate("variants.color = blue AND variants.size = 38"), RoaringBitmap::new());
+    }
+
+    #[test]
+    fn field_comparisons() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! {
+                    S("price"), S("discounted_price"), S("stock"), S("reserved")
+                });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10, "discounted_price": 8, "stock": 5, "reserved": 5 },
+                { "id": 1, "price": 20, "discounted_price": 25, "stock": 3, "reserved": 1 },
+                { "id": 2, "price": 30, "discounted_price": 20, "stock": [0, 4], "reserved": 2 },
+                { "id": 3, "price": 40, "stock": 10, "reserved": "none" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+
+        assert_eq!(evaluate("discounted_price < $price"), RoaringBitmap::from_iter([0, 2]));
+        // any combination of the values of an array can match
+        assert_eq!(evaluate("$stock - $reserved > 0"), RoaringBitmap::from_iter([1, 2]));
+        assert_eq!(evaluate("stock = $reserved"), RoaringBitmap::from_iter([0]));
+        assert_eq!(
+            evaluate("$price - $discounted_price >= $price * 0.3"),
+            RoaringBitmap::from_iter([2])
+        );
+        assert_eq!(evaluate("$price - 10 / 2 > 25"), RoaringBitmap::from_iter([3]));
+        assert_eq!(evaluate("$price > 25"), RoaringBitmap::from_iter([2, 3]));
+        // the divisions by zero never match
+        assert_eq!(evaluate("$price / 0 > 0"), RoaringBitmap::new());
+        assert_eq!(evaluate("NOT discounted_price < $price"), RoaringBitmap::from_iter([1, 3]));
+        // the values without a `$` are literals
+        assert_eq!(evaluate("price = discounted_price"), RoaringBitmap::new());
+        for filter in ["price > discounted_price", "$price - discounted_price > 0"] {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            assert!(filter.evaluate(&rtxn, &index).is_err());
+        }
+
+        let error =
+            Filter::from_str("price > $id").unwrap().unwrap().evaluate(&rtxn, &index).unwrap_err();
+        insta::assert_snapshot!(error.to_string(), @r###"
+        Attribute `id` is not filterable. Available filterable attributes are: `discounted_price`, `price`, `reserved`, `stock`.
+        10:12 price > $id
+        "###);
+    }
+
+    #[test]
+    fn boolean_facets() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("in_stock") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "in_stock": true },
+                { "id": 1, "in_stock": false },
+                { "id": 2, "in_stock": [true, false] },
+                { "id": 3, "in_stock": "true" },
+                { "id": 4, "in_stock": null },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+
+        // only the booleans are true or false
+        assert_eq!(evaluate("in_stock IS TRUE"), RoaringBitmap::from_iter([0, 2]));
+        assert_eq!(evaluate("in_stock IS FALSE"), RoaringBitmap::from_iter([1, 2]));
+        assert_eq!(evaluate("NOT in_stock IS TRUE"), RoaringBitmap::from_iter([1, 3, 4]));
+        // while the equality matches both the booleans and the strings
+        assert_eq!(evaluate("in_stock = true"), RoaringBitmap::from_iter([0, 2, 3]));
+        assert_eq!(evaluate("in_stock = TRUE"), RoaringBitmap::from_iter([0, 2, 3]));
+        assert_eq!(evaluate("in_stock != false"), RoaringBitmap::from_iter([0, 3, 4]));
+
+        // the booleans are updated with the documents
+        index.add_documents(documents!([{ "id": 0, "in_stock": false }])).unwrap();
+        index.delete_document("2");
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+        assert_eq!(evaluate("in_stock IS TRUE"), RoaringBitmap::new());
+        assert_eq!(evaluate("in_stock IS FALSE"), RoaringBitmap::from_iter([0, 1]));
+    }
 }
@@ -0,0 +1,593 @@
+//! The static analysis of a filter: every clause of the filter is evaluated on its own against
+//! the facet databases so that we can report the clauses that would make a search fail, the ones
+//! that are probably not doing what the user expects, and the number of documents each one matches.
+
+use std::collections::HashSet;
+
+use filter_parser::{Condition, Expression, FilterCondition, Token};
+use heed::types::Bytes;
+use roaring::{MultiOps, RoaringBitmap};
+use serde::Serialize;
+
+use super::{get_first_facet_value, Filter};
+use crate::error::{Error, UserError};
+use crate::heed_codec::facet::FacetGroupKeyCodec;
+use crate::heed_codec::BytesRefCodec;
+use crate::{FieldId, FieldsIdsMap, Index, Result};
+
+/// The result of [`Filter::analyze`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterAnalysis {
+    /// The normalized syntax tree of the filter.
+    pub ast: FilterNode,
+    /// The issues that make the filter fail when it is used in a search.
+    pub errors: Vec<FilterIssue>,
+    /// The issues that don't make the filter fail but that are probably mistakes.
+    pub warnings: Vec<FilterIssue>,
+}
+
+/// A node of the normalized syntax tree of a filter.
+///
+/// The `AND` and `OR` nodes are flattened and the double negations are removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterNode {
+    /// `AND`, `OR`, `NOT`, the operator of the clause or the name of the geo function.
+    pub operator: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<FilterNode>,
+    /// The normalized filter expression of the node.
+    pub expression: String,
+    /// The number of documents matched by the node, `None` if the node is invalid.
+    ///
+    /// The conditions on the objects of a nested attribute are counted independently
+    /// of each other, the count of their `AND` is thus an upper bound.
+    pub estimated_candidates: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterIssue {
+    pub kind: FilterIssueKind,
+    /// The normalized expression of the clause the issue is about.
+    pub expression: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterIssueKind {
+    AttributeNotFilterable,
+    InvalidValue,
+    TypeMismatch,
+    UnusedClause,
+}
+
+impl<'a> Filter<'a> {
+    /// Analyzes every clause of the filter without running a search.
+    pub fn analyze(&self, rtxn: &heed::RoTxn<'_>, index: &Index) -> Result<FilterAnalysis> {
+        let condition = normalize(self.clone().into());
+        let mut analyzer = Analyzer {
+            rtxn,
+            index,
+            filterable_fields: index.filterable_fields(rtxn)?,
+            fields_ids_map: index.fields_ids_map(rtxn)?,
+            documents_ids: index.documents_ids(rtxn)?,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let (ast, _) = analyzer.analyze(&condition)?;
+
+        Ok(FilterAnalysis { ast, errors: analyzer.errors, warnings: analyzer.warnings })
+    }
+}
+
+struct Analyzer<'t, 'e> {
+    rtxn: &'t heed::RoTxn<'e>,
+    index: &'t Index,
+    filterable_fields: HashSet<String>,
+    fields_ids_map: FieldsIdsMap,
+    documents_ids: RoaringBitmap,
+    errors: Vec<FilterIssue>,
+    warnings: Vec<FilterIssue>,
+}
+
+impl<'t, 'e> Analyzer<'t, 'e> {
+    /// Returns the node of the condition and the documents it matches, if it is valid.
+    fn analyze(
+        &mut self,
+        condition: &FilterCondition,
+    ) -> Result<(FilterNode, Option<RoaringBitmap>)> {
+        let expression = expression(condition);
+        let (operator, conditions) = match condition {
+            FilterCondition::Not(inner) => {
+                let (node, docids) = self.analyze(inner)?;
+                let docids = docids.map(|docids| &self.documents_ids - docids);
+                let node = FilterNode::group("NOT", vec![node], expression, docids.as_ref());
+                return Ok((node, docids));
+            }
+            FilterCondition::And(conditions) => ("AND", conditions),
+            FilterCondition::Or(conditions) => ("OR", conditions),
+            clause => return self.analyze_clause(clause, expression),
+        };
+
+        let mut nodes = Vec::with_capacity(conditions.len());
+        let mut docids = Vec::with_capacity(conditions.len());
+        for condition in conditions {
+            let (node, condition_docids) = self.analyze(condition)?;
+            nodes.push(node);
+            docids.push(condition_docids);
+        }
+
+        let docids = docids.into_iter().collect::<Option<Vec<_>>>().map(|docids| {
+            let is_and = operator == "AND";
+            self.unused_clauses(operator, &nodes, &docids);
+            if is_and {
+                docids.intersection()
+            } else {
+                docids.union()
+            }
+        });
+        let node = FilterNode::group(operator, nodes, expression, docids.as_ref());
+
+        Ok((node, docids))
+    }
+
+    fn analyze_clause(
+        &mut self,
+        clause: &FilterCondition,
+        expression: String,
+    ) -> Result<(FilterNode, Option<RoaringBitmap>)> {
+        let (operator, attributes, values) = match clause {
+            FilterCondition::Comparison { left, op, right } => {
+                let fields = left.fields().into_iter().chain(right.fields());
+                let attributes = fields.map(|field| field.value()).collect();
+                (op.to_string(), attributes, vec![arithmetic(left), arithmetic(right)])
+            }
+            clause => {
+                let (operator, attribute, values) = describe_clause(clause);
+                let values = values.iter().map(|value| value.value().to_string()).collect();
+                (operator.to_string(), vec![attribute], values)
+            }
+        };
+        let docids = match Filter::from(clause.clone()).evaluate(self.rtxn, self.index) {
+            Ok(docids) => Some(docids),
+            Err(Error::UserError(UserError::InvalidFilter(message))) => {
+                let all_filterable = attributes
+                    .iter()
+                    .all(|attribute| crate::is_faceted(attribute, &self.filterable_fields));
+                let kind = if all_filterable {
+                    FilterIssueKind::InvalidValue
+                } else {
+                    FilterIssueKind::AttributeNotFilterable
+                };
+                self.errors.push(FilterIssue { kind, expression: expression.clone(), message });
+                None
+            }
+            Err(error) => return Err(error),
+        };
+
+        if docids.is_some() {
+            if let Some(message) = self.type_mismatch(clause)? {
+                let kind = FilterIssueKind::TypeMismatch;
+                self.warnings.push(FilterIssue { kind, expression: expression.clone(), message });
+            }
+        }
+
+        let node = FilterNode {
+            operator,
+            // a comparison between several fields is not about a single attribute
+            attribute: match attributes.as_slice() {
+                [attribute] => Some(attribute.to_string()),
+                _ => None,
+            },
+            values,
+            conditions: Vec::new(),
+            expression,
+            estimated_candidates: docids.as_ref().map(RoaringBitmap::len),
+        };
+
+        Ok((node, docids))
+    }
+
+    /// Reports the conditions of an `AND` or an `OR` that can be removed without changing
+    /// the documents it matches.
+    fn unused_clauses(&mut self, operator: &str, nodes: &[FilterNode], docids: &[RoaringBitmap]) {
+        // every clause would be reported on an empty index
+        if self.documents_ids.is_empty() {
+            return;
+        }
+
+        let mut used = vec![true; docids.len()];
+        for (i, (node, clause_docids)) in nodes.iter().zip(docids).enumerate() {
+            let others: Vec<_> =
+                docids.iter().enumerate().filter(|(j, _)| *j != i && used[*j]).collect();
+            if others.is_empty() {
+                continue;
+            }
+            let others = others.into_iter().map(|(_, docids)| docids);
+            let unused = if operator == "AND" {
+                others.intersection().is_subset(clause_docids)
+            } else {
+                clause_docids.is_subset(&others.union())
+            };
+            if unused {
+                used[i] = false;
+                self.warnings.push(FilterIssue {
+                    kind: FilterIssueKind::UnusedClause,
+                    expression: node.expression.clone(),
+                    message: format!(
+                        "The clause `{}` does not change the documents matched by the `{operator}` it belongs to.",
+                        node.expression
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Returns a message when the operator of the clause can't match the type of the values
+    /// of its attribute.
+    fn type_mismatch(&self, clause: &FilterCondition) -> Result<Option<String>> {
+        let (fid, op) = match clause {
+            FilterCondition::Condition { fid, op } => (fid, Some(op)),
+            FilterCondition::In { fid, .. } => (fid, None),
+            _ => return Ok(None),
+        };
+        let attribute = fid.value();
+        let Some(field_id) = self.fields_ids_map.id(attribute) else { return Ok(None) };
+        let has_numbers = self.has_facet_values(field_id, true)?;
+        let has_strings = self.has_facet_values(field_id, false)?;
+        let has_booleans = self.has_boolean_values(field_id)?;
+        let (operator, _, values) = describe_clause(clause);
+
+        let message = match op {
+            Some(
+                Condition::GreaterThan(_)
+                | Condition::GreaterThanOrEqual(_)
+                | Condition::LowerThan(_)
+                | Condition::LowerThanOrEqual(_)
+                | Condition::Between { .. },
+            ) if has_strings && !has_numbers =>
+            {
+                Some(format!(
+                    "The attribute `{attribute}` only contains strings but `{operator}` only matches numbers."
+                ))
+            }
+            Some(
+                Condition::Contains { .. }
+                | Condition::StartsWith { .. }
+                | Condition::EndsWith { .. }
+                | Condition::Like { .. }
+                | Condition::Matches { .. },
+            ) if has_numbers && !has_strings =>
+            {
+                Some(format!(
+                    "The attribute `{attribute}` only contains numbers but `{operator}` only matches strings."
+                ))
+            }
+            Some(Condition::True | Condition::False)
+                if !has_booleans && (has_numbers || has_strings) =>
+            {
+                Some(format!(
+                    "The attribute `{attribute}` contains no booleans but `{operator}` only matches booleans."
+                ))
+            }
+            None | Some(Condition::Equal(_) | Condition::NotEqual(_))
+                if has_numbers && !has_strings && !has_booleans =>
+            {
+                values.iter().find(|value| value.parse_finite_float_or_date(0.0).is_err()).map(|value| {
+                    format!(
+                        "The attribute `{attribute}` only contains numbers but `{}` is not a number.",
+                        value.value()
+                    )
+                })
+            }
+            _ => None,
+        };
+
+        Ok(message)
+    }
+
+    fn has_facet_values(&self, field_id: FieldId, numbers: bool) -> Result<bool> {
+        let db = if numbers {
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        } else {
+            self.index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        };
+        Ok(get_first_facet_value::<BytesRefCodec, _>(self.rtxn, db, field_id)?.is_some())
+    }
+
+    fn has_boolean_values(&self, field_id: FieldId) -> Result<bool> {
+        let db = self.index.facet_id_bool_docids.remap_key_type::<Bytes>();
+        Ok(db.prefix_iter(self.rtxn, &field_id.to_be_bytes())?.next().is_some())
+    }
+}
+
+impl FilterNode {
+    fn group(
+        operator: &str,
+        conditions: Vec<FilterNode>,
+        expression: String,
+        docids: Option<&RoaringBitmap>,
+    ) -> Self {
+        FilterNode {
+            operator: operator.to_string(),
+            attribute: None,
+            values: Vec::new(),
+            conditions,
+            expression,
+            estimated_candidates: docids.map(RoaringBitmap::len),
+        }
+    }
+}
+
+/// Flattens the `AND` and `OR` conditions and removes the double negations.
+fn normalize(condition: FilterCondition) -> FilterCondition {
+    match condition {
+        FilterCondition::Not(inner) => match normalize(*inner) {
+            FilterCondition::Not(inner) => *inner,
+            inner => FilterCondition::Not(Box::new(inner)),
+        },
+        FilterCondition::And(conditions) => flatten(conditions, true),
+        FilterCondition::Or(conditions) => flatten(conditions, false),
+        condition => condition,
+    }
+}
+
+fn flatten(conditions: Vec<FilterCondition>, is_and: bool) -> FilterCondition {
+    let mut flattened = Vec::with_capacity(conditions.len());
+    for condition in conditions {
+        match normalize(condition) {
+            FilterCondition::And(conditions) if is_and => flattened.extend(conditions),
+            FilterCondition::Or(conditions) if !is_and => flattened.extend(conditions),
+            condition => flattened.push(condition),
+        }
+    }
+
+    match flattened.pop() {
+        Some(condition) if flattened.is_empty() => condition,
+        Some(condition) => {
+            flattened.push(condition);
+            if is_and {
+                FilterCondition::And(flattened)
+            } else {
+                FilterCondition::Or(flattened)
+            }
+        }
+        None if is_and => FilterCondition::And(flattened),
+        None => FilterCondition::Or(flattened),
+    }
+}
+
+/// Returns the operator, the attribute and the values of a clause, that is not an `AND`,
+/// an `OR`, a `NOT` or a comparison.
+fn describe_clause<'c, 'a>(
+    clause: &'c FilterCondition<'a>,
+) -> (&'static str, &'c str, Vec<&'c Token<'a>>) {
+    match clause {
+        FilterCondition::Condition { fid, op } => {
+            let (operator, values) = match op {
+                Condition::GreaterThan(value) => (">", vec![value]),
+                Condition::GreaterThanOrEqual(value) => (">=", vec![value]),
+                Condition::Equal(value) => ("=", vec![value]),
+                Condition::NotEqual(value) => ("!=", vec![value]),
+                Condition::Null => ("IS NULL", vec![]),
+                Condition::Empty => ("IS EMPTY", vec![]),
+                Condition::True => ("IS TRUE", vec![]),
+                Condition::False => ("IS FALSE", vec![]),
+                Condition::Exists => ("EXISTS", vec![]),
+                Condition::LowerThan(value) => ("<", vec![value]),
+                Condition::LowerThanOrEqual(value) => ("<=", vec![value]),
+                Condition::Between { from, to } => ("TO", vec![from, to]),
+                Condition::Contains { keyword: _, word } => ("CONTAINS", vec![word]),
+                Condition::StartsWith { keyword: _, word } => ("STARTS WITH", vec![word]),
+                Condition::EndsWith { keyword: _, word } => ("ENDS WITH", vec![word]),
+                Condition::Like { keyword: _, pattern } => ("LIKE", vec![pattern]),
+                Condition::Matches { keyword: _, regex } => ("MATCHES", vec![regex]),
+            };
+            (operator, fid.value(), values)
+        }
+        FilterCondition::In { fid, els } => ("IN", fid.value(), els.iter().collect()),
+        FilterCondition::GeoLowerThan { point, radius } => {
+            ("_geoRadius", "_geo", vec![&point[0], &point[1], radius])
+        }
+        FilterCondition::GeoBoundingBox { top_right_point, bottom_left_point } => (
+            "_geoBoundingBox",
+            "_geo",
+            top_right_point.iter().chain(bottom_left_point.iter()).collect(),
+        ),
+        FilterCondition::GeoPolygon { points } => {
+            ("_geoPolygon", "_geo", points.iter().flatten().collect())
+        }
+        FilterCondition::Not(_)
+        | FilterCondition::And(_)
+        | FilterCondition::Or(_)
+        | FilterCondition::Comparison { .. } => {
+            unreachable!("`NOT`, `AND`, `OR` and the comparisons are described separately")
+        }
+    }
+}
+
+/// Writes the condition back as a filter expression, the values are only quoted when needed.
+fn expression(condition: &FilterCondition) -> String {
+    match condition {
+        FilterCondition::Not(inner) => match inner.as_ref() {
+            FilterCondition::And(_) | FilterCondition::Or(_) => {
+                format!("NOT ({})", expression(inner))
+            }
+            inner => format!("NOT {}", expression(inner)),
+        },
+        FilterCondition::And(conditions) => conditions
+            .iter()
+            .map(|condition| match condition {
+                FilterCondition::Or(_) => format!("({})", expression(condition)),
+                condition => expression(condition),
+            })
+            .collect::<Vec<_>>()
+            .join(" AND "),
+        FilterCondition::Or(conditions) => {
+            conditions.iter().map(expression).collect::<Vec<_>>().join(" OR ")
+        }
+        FilterCondition::GeoLowerThan { point, radius } => {
+            format!("_geoRadius({}, {}, {})", point[0].value(), point[1].value(), radius.value())
+        }
+        FilterCondition::GeoBoundingBox { top_right_point: tr, bottom_left_point: bl } => format!(
+            "_geoBoundingBox([{}, {}], [{}, {}])",
+            tr[0].value(),
+            tr[1].value(),
+            bl[0].value(),
+            bl[1].value()
+        ),
+        FilterCondition::GeoPolygon { points } => {
+            let points: Vec<_> = points
+                .iter()
+                .map(|[lat, lng]| format!("[{}, {}]", lat.value(), lng.value()))
+                .collect();
+            format!("_geoPolygon([{}])", points.join(", "))
+        }
+        FilterCondition::Comparison { left, op, right } => {
+            format!("{} {op} {}", arithmetic(left), arithmetic(right))
+        }
+        clause => {
+            let (operator, attribute, values) = describe_clause(clause);
+            let attribute = quote(attribute);
+            let values: Vec<_> = values.iter().map(|value| quote(value.value())).collect();
+            match clause {
+                FilterCondition::In { .. } => format!("{attribute} IN [{}]", values.join(", ")),
+                FilterCondition::Condition { op: Condition::Between { .. }, .. } => {
+                    format!("{attribute} {} TO {}", values[0], values[1])
+                }
+                _ if values.is_empty() => format!("{attribute} {operator}"),
+                _ => format!("{attribute} {operator} {}", values[0]),
+            }
+        }
+    }
+}
+
+/// Writes the arithmetic expression back, the parentheses are not needed as the operations
+/// of the syntax tree already follow the precedence of the operators.
+fn arithmetic(expression: &Expression) -> String {
+    match expression {
+        Expression::Value(token) => quote(token.value()),
+        Expression::Field(token) => format!("${}", quote(token.value())),
+        Expression::Operation { left, operator, right } => {
+            format!("{} {operator} {}", arithmetic(left), arithmetic(right))
+        }
+    }
+}
+
+/// Quotes the value if it can't be written as is in a filter expression.
+fn quote(value: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "AND", "OR", "IN", "NOT", "TO", "EXISTS", "IS", "NULL", "EMPTY", "CONTAINS", "STARTS",
+        "ENDS", "WITH", "LIKE", "MATCHES",
+    ];
+
+    let is_bare = !value.is_empty()
+        && !value.starts_with("_geo")
+        && !KEYWORDS.contains(&value)
+        && value.chars().all(|c| c.is_alphanumeric() || ['_', '-', '.'].contains(&c));
+    if is_bare {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+
+    #[test]
+    fn analyze() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("color") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10, "color": "red" },
+                { "id": 1, "price": 20, "color": "blue" },
+                { "id": 2, "price": 30, "color": "light blue" },
+                { "id": 3, "color": "red" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let analyze = |expression: &str| {
+            Filter::from_str(expression).unwrap().unwrap().analyze(&rtxn, &index).unwrap()
+        };
+
+        let analysis = analyze(
+            "(price > 15 AND (color = red OR color = 'light blue')) AND NOT NOT price EXISTS",
+        );
+        assert!(analysis.errors.is_empty());
+        let ast = &analysis.ast;
+        assert_eq!(ast.operator, "AND");
+        assert_eq!(
+            ast.expression,
+            "price > 15 AND (color = red OR color = 'light blue') AND price EXISTS"
+        );
+        assert_eq!(ast.estimated_candidates, Some(1));
+        assert_eq!(ast.conditions.len(), 3);
+        assert_eq!(ast.conditions[0].attribute.as_deref(), Some("price"));
+        assert_eq!(ast.conditions[0].values, vec![S("15")]);
+        assert_eq!(ast.conditions[0].estimated_candidates, Some(2));
+        assert_eq!(ast.conditions[1].estimated_candidates, Some(3));
+        assert_eq!(ast.conditions[2].estimated_candidates, Some(3));
+        // `price EXISTS` is implied by `price > 15`
+        assert_eq!(analysis.warnings.len(), 1);
+        assert_eq!(analysis.warnings[0].kind, FilterIssueKind::UnusedClause);
+        assert_eq!(analysis.warnings[0].expression, "price EXISTS");
+
+        let analysis = analyze("title = hello OR price > abc OR color > 10 OR price CONTAINS 1");
+        assert_eq!(analysis.ast.estimated_candidates, None);
+        let kinds: Vec<_> = analysis.errors.iter().map(|issue| issue.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![FilterIssueKind::AttributeNotFilterable, FilterIssueKind::InvalidValue]
+        );
+        assert_eq!(analysis.errors[0].expression, "title = hello");
+        let kinds: Vec<_> = analysis.warnings.iter().map(|issue| issue.kind).collect();
+        assert_eq!(kinds, vec![FilterIssueKind::TypeMismatch, FilterIssueKind::TypeMismatch]);
+        insta::assert_snapshot!(
+            analysis.warnings[0].message,
+            @"The attribute `color` only contains strings but `>` only matches numbers."
+        );
+        assert_eq!(analysis.ast.conditions[2].estimated_candidates, Some(0));
+
+        let analysis = analyze("price IS TRUE");
+        assert_eq!(analysis.ast.expression, "price IS TRUE");
+        insta::assert_snapshot!(
+            analysis.warnings[0].message,
+            @"The attribute `price` contains no booleans but `IS TRUE` only matches booleans."
+        );
+    }
+
+    #[test]
+    fn expressions_round_trip() {
+        for filter in [
+            "_geoRadius(45.4, 1.2, 2000)",
+            "_geoBoundingBox([45.4, 1.2], [45.3, 1.1])",
+            "_geoPolygon([[45.4, 1.2], [45.3, 1.1], [45.2, 1.3]])",
+            "price 10 TO 20 AND color IN [red, 'light blue']",
+            "NOT (color = red OR price > 10)",
+        ] {
+            let condition = FilterCondition::parse(filter).unwrap().unwrap();
+            let written = expression(&condition);
+            assert_eq!(written, filter);
+            assert_eq!(FilterCondition::parse(&written).unwrap().unwrap(), condition);
+        }
+    }
+}
@@ -4,8 +4,13 @@ use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 use roaring::RoaringBitmap;
 
-pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_distribution::{
+    stringify_facet_values, CalendarInterval, FacetBuckets, FacetDistribution, FacetHierarchy,
+    FacetHierarchyNode, FacetNumberStats, FacetRange, FacetValue, OrderBy,
+    DEFAULT_VALUES_PER_FACET,
+};
 pub use self::filter::{BadGeoError, Filter};
+pub use self::filter_analysis::{FilterAnalysis, FilterIssue, FilterIssueKind, FilterNode};
 pub use self::search::{FacetValueHit, SearchForFacetValues};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
 use crate::heed_codec::BytesRefCodec;
@@ -17,6 +22,9 @@ mod facet_range_search;
 mod facet_sort_ascending;
 mod facet_sort_descending;
 mod filter;
+mod filter_analysis;
+mod nested;
+mod pattern;
 mod search;
 
 fn facet_extreme_value<'t>(
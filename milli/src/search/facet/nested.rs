@@ -0,0 +1,434 @@
+//! The nested fields are arrays of objects whose boundaries are preserved when filtering and
+//! faceting: the conditions on the fields of a nested field joined by an `AND` must all be
+//! matched by the same object, and the facet values of its fields are only counted in the
+//! objects matching the conditions of the filter.
+//!
+//! The facet databases only know about the flattened fields of the documents. At indexing time,
+//! the objects of the nested field of a document are given ids, the element ids, stored with the
+//! values of their fields in the `field_id_docid_nested_element_ids` database. The conditions are
+//! evaluated on these element ids once the candidates have been reduced by the facet databases.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use filter_parser::{Condition, FilterCondition, Token};
+use heed::types::Bytes;
+use ordered_float::OrderedFloat;
+use roaring::{MultiOps, RoaringBitmap};
+
+use super::facet_distribution::{date_timestamp, FacetValue};
+use super::filter::parse_comparable;
+use super::pattern::Pattern;
+use crate::heed_codec::facet::{FieldDocIdNestedFacetValueCodec, NestedFacetValue};
+use crate::{DocumentId, FieldId, FieldsIdsMap, Index, Result};
+
+/// Groups the conditions of an `AND` by the nested field containing all the fields they are
+/// about, and returns the other conditions separately.
+pub(crate) fn group_by_nested_field<'c, 'a, 'n>(
+    conditions: impl IntoIterator<Item = &'c FilterCondition<'a>>,
+    nested_fields: &'n BTreeSet<String>,
+) -> (BTreeMap<&'n str, Vec<&'c FilterCondition<'a>>>, Vec<&'c FilterCondition<'a>>)
+where
+    'a: 'c,
+{
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    let mut others = Vec::new();
+    for condition in conditions {
+        let nested_field = nested_fields
+            .iter()
+            .filter(|nested_field| is_about_nested_field(condition, nested_field))
+            .max_by_key(|nested_field| nested_field.len());
+        match nested_field {
+            Some(nested_field) => groups.entry(nested_field.as_str()).or_default().push(condition),
+            None => others.push(condition),
+        }
+    }
+    (groups, others)
+}
+
+/// Returns `true` if all the fields of the condition are fields of the objects of the nested field.
+pub(crate) fn is_about_nested_field(condition: &FilterCondition, nested_field: &str) -> bool {
+    match condition_fids(condition) {
+        Some(fids) => {
+            !fids.is_empty() && fids.iter().all(|fid| is_nested_in(fid.value(), nested_field))
+        }
+        None => false,
+    }
+}
+
+/// Returns the fields the condition is about, or `None` if it is about the geo fields or
+/// compares fields to each other.
+pub(crate) fn condition_fids<'c, 'a>(
+    condition: &'c FilterCondition<'a>,
+) -> Option<Vec<&'c Token<'a>>> {
+    fn inner<'c, 'a>(condition: &'c FilterCondition<'a>, fids: &mut Vec<&'c Token<'a>>) -> bool {
+        match condition {
+            FilterCondition::Not(condition) => inner(condition, fids),
+            FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+                fids.push(fid);
+                true
+            }
+            FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+                conditions.iter().all(|condition| inner(condition, fids))
+            }
+            FilterCondition::GeoLowerThan { .. }
+            | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. }
+            | FilterCondition::Comparison { .. } => false,
+        }
+    }
+
+    let mut fids = Vec::new();
+    inner(condition, &mut fids).then_some(fids)
+}
+
+/// Returns the deepest nested field whose objects contain the field.
+pub(crate) fn nested_field_of<'n>(
+    field: &str,
+    nested_fields: &'n BTreeSet<String>,
+) -> Option<&'n str> {
+    nested_fields
+        .iter()
+        .filter(|nested_field| is_nested_in(field, nested_field))
+        .max_by_key(|nested_field| nested_field.len())
+        .map(String::as_str)
+}
+
+fn is_nested_in(field: &str, nested_field: &str) -> bool {
+    field.strip_prefix(nested_field).map_or(false, |rest| rest.starts_with('.'))
+}
+
+/// Returns `true` if the documents matching the condition contain all the documents having an
+/// object matching it, the condition can then be evaluated with the facet databases first.
+pub(crate) fn can_prefilter(condition: &FilterCondition) -> bool {
+    match condition {
+        FilterCondition::Condition { op, .. } => {
+            !matches!(op, Condition::NotEqual(_) | Condition::Null | Condition::Empty)
+        }
+        FilterCondition::In { .. } => true,
+        FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+            conditions.iter().all(can_prefilter)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the candidates having an object of the nested field matching all the conditions.
+pub(crate) fn correlated_docids(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    nested_field: &str,
+    conditions: &[&FilterCondition],
+    candidates: &RoaringBitmap,
+) -> Result<RoaringBitmap> {
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let Some(nested_field_id) = fields_ids_map.id(nested_field) else {
+        return Ok(RoaringBitmap::new());
+    };
+    let elements = NestedElements { index, rtxn, fields_ids_map: &fields_ids_map, nested_field_id };
+
+    let mut docids = RoaringBitmap::new();
+    for docid in candidates {
+        if !elements.matching_all(conditions, docid)?.is_empty() {
+            docids.insert(docid);
+        }
+    }
+
+    Ok(docids)
+}
+
+/// Counts the candidates per value of the field, only looking at the objects of the nested
+/// field matching all the conditions.
+///
+/// The values are returned in lexicographic order, the numbers first.
+pub(crate) fn correlated_facet_values(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    nested_field: &str,
+    conditions: &[&FilterCondition],
+    field: &str,
+    is_date: bool,
+    candidates: &RoaringBitmap,
+) -> Result<Vec<(FacetValue, u64)>> {
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let (Some(nested_field_id), Some(field_id)) =
+        (fields_ids_map.id(nested_field), fields_ids_map.id(field))
+    else {
+        return Ok(Vec::new());
+    };
+    let elements = NestedElements { index, rtxn, fields_ids_map: &fields_ids_map, nested_field_id };
+
+    let mut numbers_distribution = BTreeMap::<OrderedFloat<f64>, u64>::new();
+    let mut booleans_distribution = BTreeMap::<bool, u64>::new();
+    let mut strings_distribution = BTreeMap::<String, (String, u64)>::new();
+    for docid in candidates {
+        let matching = elements.matching_all(conditions, docid)?;
+        if matching.is_empty() {
+            continue;
+        }
+
+        let values = elements.values(field_id, docid)?;
+        // the objects whose dates have each timestamp, these timestamps are not counted
+        let mut dates_element_ids = HashMap::<u64, RoaringBitmap>::new();
+        if is_date {
+            for (value, element_ids) in &values {
+                if let NestedFacetValue::String(normalized) = value {
+                    if let Some(timestamp) = date_timestamp(normalized) {
+                        *dates_element_ids.entry(timestamp.to_bits()).or_default() |= element_ids;
+                    }
+                }
+            }
+        }
+
+        // the values are unique per document, a document is only counted once per value
+        for (value, element_ids) in values {
+            let element_ids = element_ids & &matching;
+            if element_ids.is_empty() {
+                continue;
+            }
+            match value {
+                NestedFacetValue::Number(number) => {
+                    let dates = dates_element_ids.get(&number.to_bits());
+                    if dates.map_or(true, |dates| !element_ids.is_subset(dates)) {
+                        *numbers_distribution.entry(OrderedFloat(number)).or_default() += 1
+                    }
+                }
+                NestedFacetValue::Bool(boolean) => {
+                    *booleans_distribution.entry(boolean).or_default() += 1
+                }
+                NestedFacetValue::String(normalized) => {
+                    match strings_distribution.get_mut(normalized) {
+                        Some((_original, count)) => *count += 1,
+                        None => {
+                            let key = (field_id, docid, normalized);
+                            let original = index.field_id_docid_facet_strings.get(rtxn, &key)?;
+                            let original = original.unwrap_or(normalized).to_string();
+                            strings_distribution.insert(normalized.to_string(), (original, 1));
+                        }
+                    }
+                }
+                NestedFacetValue::Object | NestedFacetValue::Null | NestedFacetValue::Empty => (),
+            }
+        }
+    }
+
+    let numbers =
+        numbers_distribution.into_iter().map(|(number, count)| (FacetValue::Number(number), count));
+    let booleans = booleans_distribution
+        .into_iter()
+        .map(|(boolean, count)| (FacetValue::Bool(boolean), count));
+    let strings = strings_distribution
+        .into_values()
+        .map(|(original, count)| (FacetValue::String(original), count));
+    Ok(numbers.chain(booleans).chain(strings).collect())
+}
+
+/// Reads the element ids of the objects of a nested field, given at indexing time to each object
+/// of the nested field of a document along with the values of its fields.
+struct NestedElements<'t> {
+    index: &'t Index,
+    rtxn: &'t heed::RoTxn<'t>,
+    fields_ids_map: &'t FieldsIdsMap,
+    nested_field_id: FieldId,
+}
+
+impl<'t> NestedElements<'t> {
+    /// Returns the values of the field in the objects of the document with their element ids.
+    fn values(
+        &self,
+        field_id: FieldId,
+        docid: DocumentId,
+    ) -> Result<Vec<(NestedFacetValue<'t>, RoaringBitmap)>> {
+        let prefix = concat_arrays::concat_arrays!(field_id.to_be_bytes(), docid.to_be_bytes());
+        let iter = self
+            .index
+            .field_id_docid_nested_element_ids
+            .remap_key_type::<Bytes>()
+            .prefix_iter(self.rtxn, &prefix)?
+            .remap_key_type::<FieldDocIdNestedFacetValueCodec>();
+
+        let mut values = Vec::new();
+        for result in iter {
+            let ((_field_id, _docid, value), element_ids) = result?;
+            values.push((value, element_ids));
+        }
+        Ok(values)
+    }
+
+    /// Returns the element ids of all the objects of the nested field of the document.
+    fn objects(&self, docid: DocumentId) -> Result<RoaringBitmap> {
+        let key = (self.nested_field_id, docid, NestedFacetValue::Object);
+        Ok(self.index.field_id_docid_nested_element_ids.get(self.rtxn, &key)?.unwrap_or_default())
+    }
+
+    /// Returns the element ids of the objects of the document matching all the conditions.
+    fn matching_all(
+        &self,
+        conditions: &[&FilterCondition],
+        docid: DocumentId,
+    ) -> Result<RoaringBitmap> {
+        let mut element_ids = self.objects(docid)?;
+        for condition in conditions {
+            if element_ids.is_empty() {
+                break;
+            }
+            element_ids &= self.matching(condition, docid)?;
+        }
+        Ok(element_ids)
+    }
+
+    /// Returns the element ids of the objects of the document matching the condition.
+    fn matching(&self, condition: &FilterCondition, docid: DocumentId) -> Result<RoaringBitmap> {
+        let values_of = |fid: &Token| match self.fields_ids_map.id(fid.value()) {
+            Some(field_id) => self.values(field_id, docid),
+            None => Ok(Vec::new()),
+        };
+
+        match condition {
+            FilterCondition::Not(condition) => {
+                Ok(self.objects(docid)? - self.matching(condition, docid)?)
+            }
+            FilterCondition::Or(conditions) => {
+                let mut element_ids = RoaringBitmap::new();
+                for condition in conditions {
+                    element_ids |= self.matching(condition, docid)?;
+                }
+                Ok(element_ids)
+            }
+            FilterCondition::And(conditions) => {
+                let conditions: Vec<_> = conditions.iter().collect();
+                self.matching_all(&conditions, docid)
+            }
+            FilterCondition::In { fid, els } => {
+                let values = values_of(fid)?;
+                let mut element_ids = RoaringBitmap::new();
+                for el in els {
+                    element_ids |= values_matching(&values, &Condition::Equal(el.clone()))?;
+                }
+                Ok(element_ids)
+            }
+            FilterCondition::Condition { fid, op: Condition::NotEqual(val) } => {
+                let values = values_of(fid)?;
+                let equal = values_matching(&values, &Condition::Equal(val.clone()))?;
+                Ok(self.objects(docid)? - equal)
+            }
+            FilterCondition::Condition { fid, op } => values_matching(&values_of(fid)?, op),
+            // the conditions about the geo fields and the comparisons are never grouped by nested field
+            FilterCondition::GeoLowerThan { .. }
+            | FilterCondition::GeoBoundingBox { .. }
+            | FilterCondition::GeoPolygon { .. }
+            | FilterCondition::Comparison { .. } => self.objects(docid),
+        }
+    }
+}
+
+/// Evaluates the condition like the facet databases do, but on the values of a field of the
+/// objects of a document, returns the element ids of the objects matching it.
+fn values_matching(
+    values: &[(NestedFacetValue, RoaringBitmap)],
+    op: &Condition,
+) -> Result<RoaringBitmap> {
+    let element_ids_where = |predicate: &dyn Fn(&NestedFacetValue) -> bool| {
+        values
+            .iter()
+            .filter(|(value, _)| predicate(value))
+            .map(|(_, element_ids)| element_ids)
+            .union()
+    };
+    let any_number = |predicate: &dyn Fn(f64) -> bool| {
+        element_ids_where(&|value| matches!(value, NestedFacetValue::Number(n) if predicate(*n)))
+    };
+    let any_string = |predicate: &dyn Fn(&str) -> bool| {
+        element_ids_where(&|value| matches!(value, NestedFacetValue::String(s) if predicate(s)))
+    };
+
+    Ok(match op {
+        Condition::Exists => element_ids_where(&|value| *value != NestedFacetValue::Object),
+        Condition::Null => element_ids_where(&|value| *value == NestedFacetValue::Null),
+        Condition::Empty => element_ids_where(&|value| *value == NestedFacetValue::Empty),
+        Condition::GreaterThan(val) => {
+            let val = parse_comparable(val)?;
+            any_number(&|n| n > val)
+        }
+        Condition::GreaterThanOrEqual(val) => {
+            let val = parse_comparable(val)?;
+            any_number(&|n| n >= val)
+        }
+        Condition::LowerThan(val) => {
+            let val = parse_comparable(val)?;
+            any_number(&|n| n < val)
+        }
+        Condition::LowerThanOrEqual(val) => {
+            let val = parse_comparable(val)?;
+            any_number(&|n| n <= val)
+        }
+        Condition::Between { from, to } => {
+            let (from, to) = (parse_comparable(from)?, parse_comparable(to)?);
+            any_number(&|n| from <= n && n <= to)
+        }
+        Condition::True => element_ids_where(&|value| *value == NestedFacetValue::Bool(true)),
+        Condition::False => element_ids_where(&|value| *value == NestedFacetValue::Bool(false)),
+        Condition::Equal(val) => {
+            let normalized = crate::normalize_facet(val.value());
+            let number = parse_comparable(val).ok();
+            let boolean = normalized.parse::<bool>().ok();
+            element_ids_where(&|value| match *value {
+                NestedFacetValue::String(s) => s == normalized,
+                NestedFacetValue::Number(n) => number == Some(n),
+                NestedFacetValue::Bool(b) => boolean == Some(b),
+                _ => false,
+            })
+        }
+        Condition::Contains { keyword: _, word } => {
+            let word = crate::normalize_facet(word.value());
+            any_string(&|s| s.contains(word.as_str()))
+        }
+        Condition::StartsWith { keyword: _, word } => {
+            let word = crate::normalize_facet(word.value());
+            any_string(&|s| s.starts_with(word.as_str()))
+        }
+        Condition::EndsWith { .. } | Condition::Like { .. } | Condition::Matches { .. } => {
+            let pattern = Pattern::from_condition(op)?.unwrap();
+            any_string(&|s| pattern.matches(s))
+        }
+        // all the objects of the document are needed, see `NestedElements::matching`
+        Condition::NotEqual(_) => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_match_conditions() {
+        // the values of a field in two objects of a document
+        let values = vec![
+            (NestedFacetValue::Number(38.), RoaringBitmap::from_iter([0])),
+            (NestedFacetValue::Number(40.), RoaringBitmap::from_iter([1])),
+            (NestedFacetValue::String("red"), RoaringBitmap::from_iter([0])),
+            (NestedFacetValue::String("sale"), RoaringBitmap::from_iter([0, 1])),
+            (NestedFacetValue::Bool(true), RoaringBitmap::from_iter([1])),
+        ];
+
+        let matching = |filter: &str| {
+            let FilterCondition::Condition { op, .. } =
+                FilterCondition::parse(filter).unwrap().unwrap()
+            else {
+                panic!("not a condition: {filter}")
+            };
+            values_matching(&values, &op).unwrap().into_iter().collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("variants.color = red"), vec![0]);
+        assert_eq!(matching("variants.color = Sale"), vec![0, 1]);
+        assert_eq!(matching("variants.size = 40"), vec![1]);
+        assert_eq!(matching("variants.size 30 TO 39"), vec![0]);
+        assert_eq!(matching("variants.size > 38"), vec![1]);
+        assert_eq!(matching("variants.color STARTS WITH r"), vec![0]);
+        assert_eq!(matching("variants.color CONTAINS al"), vec![0, 1]);
+        assert_eq!(matching("variants.available IS TRUE"), vec![1]);
+        assert_eq!(matching("variants.available IS FALSE"), Vec::<u32>::new());
+        assert_eq!(matching("variants.color IS NULL"), Vec::<u32>::new());
+        assert_eq!(matching("variants.color EXISTS"), vec![0, 1]);
+    }
+}
@@ -0,0 +1,350 @@
+//! The `ENDS WITH`, `LIKE` and `MATCHES` filters are evaluated by searching the FST of the
+//! normalized string facet values of a field with an automaton, these values are lowercased
+//! and stripped of their diacritics.
+//!
+//! The literals of a `MATCHES` regex are normalized the same way, but the characters of its
+//! classes are only case-folded: `[é]` doesn't match any normalized value, while `é` does.
+
+use filter_parser::Condition;
+use fst::{Automaton, IntoStreamer, Streamer};
+use regex_automata::dfa::{dense, Automaton as _, StartKind};
+use regex_automata::nfa::thompson;
+use regex_automata::util::primitives::StateID;
+use regex_automata::{Anchored, Input, MatchKind};
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+use roaring::RoaringBitmap;
+use thiserror::Error;
+
+use super::search::normalize_facet_string;
+use crate::heed_codec::facet::{FacetGroupKey, FacetGroupValue};
+use crate::{FieldId, Index, Result};
+
+/// The automaton of an `ENDS WITH`, `LIKE` or `MATCHES` condition.
+pub(crate) enum Pattern {
+    Like(Like),
+    Regex(Regex),
+}
+
+impl Pattern {
+    /// Returns the pattern of the condition, or `None` if it is not a string pattern condition.
+    pub fn from_condition(op: &Condition) -> Result<Option<Pattern>> {
+        let pattern = match op {
+            Condition::EndsWith { keyword: _, word } => {
+                let suffix = normalize_facet_string(word.value(), None);
+                Pattern::Like(Like::ends_with(&suffix))
+            }
+            Condition::Like { keyword: _, pattern } => Pattern::Like(Like::new(pattern.value())),
+            Condition::Matches { keyword: _, regex } => match Regex::new(regex.value()) {
+                Ok(regex) => Pattern::Regex(regex),
+                Err(error) => return Err(regex.as_external_error(error).into()),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(pattern))
+    }
+
+    /// Returns `true` if the normalized form of the string value matches the pattern.
+    pub fn matches(&self, value: &str) -> bool {
+        let value = normalize_facet_string(value, None);
+        match self {
+            Pattern::Like(like) => automaton_matches(like, &value),
+            Pattern::Regex(regex) => automaton_matches(regex, &value),
+        }
+    }
+
+    /// Returns the documents having a string value of the field matching the pattern.
+    pub fn facet_string_docids(
+        &self,
+        index: &Index,
+        rtxn: &heed::RoTxn<'_>,
+        field_id: FieldId,
+    ) -> Result<RoaringBitmap> {
+        match self {
+            Pattern::Like(like) => search_facet_strings(index, rtxn, field_id, like),
+            Pattern::Regex(regex) => search_facet_strings(index, rtxn, field_id, regex),
+        }
+    }
+}
+
+fn automaton_matches<A: Automaton>(automaton: A, value: &str) -> bool {
+    let mut state = automaton.start();
+    for &byte in value.as_bytes() {
+        if !automaton.can_match(&state) {
+            return false;
+        }
+        state = automaton.accept(&state, byte);
+    }
+    automaton.is_match(&state)
+}
+
+fn search_facet_strings<A: Automaton>(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    field_id: FieldId,
+    automaton: A,
+) -> Result<RoaringBitmap> {
+    let fst = match index.facet_id_string_fst.get(rtxn, &field_id)? {
+        Some(fst) => fst,
+        None => return Ok(RoaringBitmap::new()),
+    };
+
+    let mut docids = RoaringBitmap::new();
+    let mut stream = fst.search(automaton).into_stream();
+    while let Some(normalized) = stream.next() {
+        let normalized = std::str::from_utf8(normalized)?;
+        let key = (field_id, normalized);
+        let originals = match index.facet_id_normalized_string_strings.get(rtxn, &key)? {
+            Some(originals) => originals,
+            None => continue,
+        };
+        for original in originals {
+            let key = FacetGroupKey { field_id, level: 0, left_bound: original.as_str() };
+            if let Some(FacetGroupValue { bitmap, .. }) =
+                index.facet_id_string_docids.get(rtxn, &key)?
+            {
+                docids |= bitmap;
+            }
+        }
+    }
+
+    Ok(docids)
+}
+
+/// The maximum size in bytes of the automaton of a `MATCHES` regex, and of the memory used
+/// to build it.
+const MAX_REGEX_AUTOMATON_SIZE: usize = 2 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+enum RegexError {
+    #[error(transparent)]
+    Syntax(#[from] regex_syntax::Error),
+    #[error(transparent)]
+    Nfa(#[from] thompson::BuildError),
+    #[error(transparent)]
+    Dfa(#[from] dense::BuildError),
+    #[error(transparent)]
+    Start(#[from] regex_automata::MatchError),
+}
+
+/// An automaton matching the normalized strings entirely matched by a `MATCHES` regex.
+pub(crate) struct Regex {
+    dfa: dense::DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl Regex {
+    /// Compiles the regex, failing if its automaton is too large.
+    fn new(regex: &str) -> std::result::Result<Regex, RegexError> {
+        let hir = regex_syntax::parse(regex)?;
+        let hir = Hir::concat(vec![normalize_hir(hir), Hir::look(Look::End)]);
+        let nfa = thompson::Compiler::new()
+            .configure(thompson::Config::new().nfa_size_limit(Some(MAX_REGEX_AUTOMATON_SIZE)))
+            .build_from_hir(&hir)?;
+        let dfa = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .start_kind(StartKind::Anchored)
+                    .match_kind(MatchKind::All)
+                    .dfa_size_limit(Some(MAX_REGEX_AUTOMATON_SIZE))
+                    .determinize_size_limit(Some(MAX_REGEX_AUTOMATON_SIZE)),
+            )
+            .build_from_nfa(&nfa)?;
+        let start = dfa.start_state_forward(&Input::new("").anchored(Anchored::Yes))?;
+        Ok(Regex { dfa, start })
+    }
+}
+
+/// Normalizes the literals of the regex like the facet values and case-folds its classes.
+fn normalize_hir(hir: Hir) -> Hir {
+    match hir.into_kind() {
+        HirKind::Empty => Hir::empty(),
+        HirKind::Literal(literal) => match std::str::from_utf8(&literal.0) {
+            Ok(literal) => Hir::literal(normalize_facet_string(literal, None).into_bytes()),
+            Err(_) => Hir::literal(literal.0),
+        },
+        HirKind::Class(Class::Unicode(mut class)) => {
+            class.case_fold_simple();
+            Hir::class(Class::Unicode(class))
+        }
+        HirKind::Class(Class::Bytes(mut class)) => {
+            class.case_fold_simple();
+            Hir::class(Class::Bytes(class))
+        }
+        HirKind::Look(look) => Hir::look(look),
+        HirKind::Repetition(mut repetition) => {
+            repetition.sub = Box::new(normalize_hir(*repetition.sub));
+            Hir::repetition(repetition)
+        }
+        HirKind::Capture(mut capture) => {
+            capture.sub = Box::new(normalize_hir(*capture.sub));
+            Hir::capture(capture)
+        }
+        HirKind::Concat(hirs) => Hir::concat(hirs.into_iter().map(normalize_hir).collect()),
+        HirKind::Alternation(hirs) => {
+            Hir::alternation(hirs.into_iter().map(normalize_hir).collect())
+        }
+    }
+}
+
+impl Automaton for Regex {
+    type State = StateID;
+
+    fn start(&self) -> Self::State {
+        self.start
+    }
+
+    /// The DFA reports the matches one byte late, hence the end of input transition.
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.dfa.is_match_state(self.dfa.next_eoi_state(*state))
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        !self.dfa.is_dead_state(*state) && !self.dfa.is_quit_state(*state)
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        self.dfa.next_state(*state, byte)
+    }
+}
+
+/// An automaton matching the strings described by a `LIKE` pattern, where `%` matches any
+/// sequence of characters and `_` exactly one character.
+#[derive(Debug, Clone)]
+pub(crate) struct Like(Vec<LikePart>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LikePart {
+    Byte(u8),
+    AnyChar,
+    AnyString,
+}
+
+impl Like {
+    fn new(pattern: &str) -> Like {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        for c in pattern.chars() {
+            let wildcard = match c {
+                '%' => LikePart::AnyString,
+                '_' => LikePart::AnyChar,
+                c => {
+                    literal.push(c);
+                    continue;
+                }
+            };
+            parts.extend(normalize_facet_string(&literal, None).bytes().map(LikePart::Byte));
+            parts.push(wildcard);
+            literal.clear();
+        }
+        parts.extend(normalize_facet_string(&literal, None).bytes().map(LikePart::Byte));
+        Like(parts)
+    }
+
+    fn ends_with(suffix: &str) -> Like {
+        let mut parts = vec![LikePart::AnyString];
+        parts.extend(suffix.bytes().map(LikePart::Byte));
+        Like(parts)
+    }
+
+    /// Adds the positions reachable by skipping the `%` without consuming a byte.
+    fn closure(&self, mut states: Vec<(usize, u8)>) -> Vec<(usize, u8)> {
+        let mut i = 0;
+        while let Some(&(position, continuation_bytes)) = states.get(i) {
+            if continuation_bytes == 0 && self.0.get(position) == Some(&LikePart::AnyString) {
+                states.push((position + 1, 0));
+            }
+            i += 1;
+        }
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+}
+
+impl Automaton for Like {
+    /// The positions in the pattern the automaton is at, along with the number of
+    /// continuation bytes of the character matched by a `_` that remain to be read.
+    type State = Vec<(usize, u8)>;
+
+    fn start(&self) -> Self::State {
+        self.closure(vec![(0, 0)])
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state.contains(&(self.0.len(), 0))
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        !state.is_empty()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let mut next = Vec::new();
+        for &(position, continuation_bytes) in state {
+            if continuation_bytes != 0 {
+                if byte & 0b1100_0000 == 0b1000_0000 {
+                    next.push((position, continuation_bytes - 1));
+                }
+                continue;
+            }
+            match self.0.get(position) {
+                Some(&LikePart::Byte(b)) if b == byte => next.push((position + 1, 0)),
+                // the number of leading ones of the first byte of a character is its length
+                Some(LikePart::AnyChar) => {
+                    let continuation_bytes = (byte.leading_ones() as u8).saturating_sub(1);
+                    next.push((position + 1, continuation_bytes));
+                }
+                Some(LikePart::AnyString) => next.push((position, 0)),
+                _ => (),
+            }
+        }
+        self.closure(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like() {
+        let matches = |pattern: &str, value: &str| Pattern::Like(Like::new(pattern)).matches(value);
+
+        assert!(matches("h%o", "hello"));
+        assert!(matches("h%o", "ho"));
+        assert!(matches("H_LLO", "hello"));
+        assert!(matches("%ll%", "hello"));
+        assert!(matches("%", ""));
+        assert!(matches("h_llo", "hällo"));
+        assert!(matches("_é_", "été"));
+        assert!(!matches("h_o", "hello"));
+        assert!(!matches("h%l", "hello"));
+        assert!(!matches("hello_", "hello"));
+
+        let ends_with =
+            |suffix: &str, value: &str| Pattern::Like(Like::ends_with(suffix)).matches(value);
+        assert!(ends_with("llo", "Hello"));
+        assert!(ends_with("", "hello"));
+        assert!(!ends_with("hell", "hello"));
+    }
+
+    #[test]
+    fn regex() {
+        let matches =
+            |regex: &str, value: &str| Pattern::Regex(Regex::new(regex).unwrap()).matches(value);
+
+        assert!(matches("h[a-z]+o", "Hello"));
+        assert!(matches("H[A-Z]+O", "hello"));
+        assert!(matches("a|ab", "ab"));
+        assert!(matches("[0-9]{3}-[0-9]{4}", "555-1234"));
+        assert!(matches("Kéf.*", "kefir"));
+        assert!(matches("kef.*", "Kéfir"));
+        assert!(!matches("h[a-z]+o", "hello world"));
+        assert!(!matches("ell", "hello"));
+        // the classes are not stripped of their diacritics
+        assert!(!matches("k[é]fir", "Kéfir"));
+
+        assert!(Regex::new("[ab]*a[ab]{20}").is_err());
+        assert!(Regex::new("(").is_err());
+    }
+}
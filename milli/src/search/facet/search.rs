@@ -24,6 +24,8 @@ pub struct SearchForFacetValues<'a> {
     max_values: usize,
     is_hybrid: bool,
     locales: Option<Vec<Language>>,
+    /// The path of the parent value and the separator of the components of the paths.
+    parent: Option<(String, String)>,
 }
 
 impl<'a> SearchForFacetValues<'a> {
@@ -39,6 +41,7 @@ impl<'a> SearchForFacetValues<'a> {
             max_values: DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET,
             is_hybrid,
             locales: None,
+            parent: None,
         }
     }
 
@@ -57,6 +60,24 @@ impl<'a> SearchForFacetValues<'a> {
         self
     }
 
+    /// Only search among the descendants of a value of a hierarchy of facet values, the query
+    /// then matches the components following the path of this parent value.
+    ///
+    /// See [`crate::FacetHierarchy`].
+    pub fn parent(&mut self, path: impl Into<String>, separator: impl Into<String>) -> &mut Self {
+        self.parent = Some((path.into(), separator.into()));
+        self
+    }
+
+    fn is_under_parent(&self, value: &str) -> bool {
+        match &self.parent {
+            Some((path, separator)) => value
+                .strip_prefix(path.as_str())
+                .map_or(false, |value| value.starts_with(separator.as_str())),
+            None => true,
+        }
+    }
+
     fn one_original_value_of(
         &self,
         field_id: FieldId,
@@ -94,9 +115,11 @@ impl<'a> SearchForFacetValues<'a> {
             None => return Ok(Vec::new()),
         };
 
+        let empty_fst = fst::Set::default();
         let fst = match self.search_query.index.facet_id_string_fst.get(rtxn, &fid)? {
             Some(fst) => fst,
-            None => return Ok(Vec::new()),
+            // the field may only contain booleans
+            None => fst::Set::new(empty_fst.as_fst().as_bytes())?,
         };
 
         let search_candidates = self.search_query.execute_for_candidates(
@@ -114,10 +137,29 @@ impl<'a> SearchForFacetValues<'a> {
             OrderBy::Count => ValuesCollection::by_count(self.max_values),
         };
 
-        match self.query.as_ref() {
+        let query = match (&self.query, &self.parent) {
+            (Some(query), Some((path, separator))) => Some(format!("{path}{separator}{query}")),
+            (query, _) => query.clone(),
+        };
+
+        let normalized_query =
+            query.as_deref().map(|query| normalize_facet_string(query, self.locales.as_deref()));
+        if self
+            .fetch_booleans(fid, normalized_query.as_deref(), &search_candidates, &mut results)?
+            .is_break()
+        {
+            return Ok(results.into_sorted_vec());
+        }
+
+        match normalized_query.as_deref() {
             Some(query) => {
-                let query = normalize_facet_string(query, self.locales.as_deref());
-                let query = query.as_ref();
+                // The number of typos only depends on the part of the query given by the user
+                let query_len = match (&self.query, &self.parent) {
+                    (Some(user_query), Some(_)) => {
+                        normalize_facet_string(user_query, self.locales.as_deref()).len()
+                    }
+                    _ => query.len(),
+                };
 
                 let authorize_typos = self.search_query.index.authorize_typos(rtxn)?;
                 let field_authorizes_typos =
@@ -140,9 +182,9 @@ impl<'a> SearchForFacetValues<'a> {
                         let two_typos = self.search_query.index.min_word_len_two_typos(rtxn)?;
 
                         let is_prefix = true;
-                        let automaton = if query.len() < one_typo as usize {
+                        let automaton = if query_len < one_typo as usize {
                             build_dfa(query, 0, is_prefix)
-                        } else if query.len() < two_typos as usize {
+                        } else if query_len < two_typos as usize {
                             build_dfa(query, 1, is_prefix)
                         } else {
                             build_dfa(query, 2, is_prefix)
@@ -195,6 +237,9 @@ impl<'a> SearchForFacetValues<'a> {
                         let value = self
                             .one_original_value_of(fid, left_bound, bitmap.min().unwrap())?
                             .unwrap_or_else(|| left_bound.to_string());
+                        if !self.is_under_parent(&value) {
+                            continue;
+                        }
                         if results.insert(FacetValueHit { value, count }).is_break() {
                             break;
                         }
@@ -206,6 +251,37 @@ impl<'a> SearchForFacetValues<'a> {
         Ok(results.into_sorted_vec())
     }
 
+    /// The booleans have their own database, they are searched before the strings and match the
+    /// queries that are prefixes of `false` or `true`.
+    fn fetch_booleans(
+        &self,
+        fid: FieldId,
+        query: Option<&str>,
+        search_candidates: &RoaringBitmap,
+        results: &mut ValuesCollection,
+    ) -> Result<ControlFlow<()>> {
+        // the hierarchies of facet values are made of strings
+        if self.parent.is_some() {
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+        for boolean in [false, true] {
+            let value = boolean.to_string();
+            if query.map_or(false, |query| !value.starts_with(query)) {
+                continue;
+            }
+            let docids = index.bool_faceted_documents_ids(rtxn, fid, boolean)?;
+            let count = search_candidates.intersection_len(&docids);
+            if count != 0 && results.insert(FacetValueHit { value, count }).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
     fn fetch_original_facets_using_normalized(
         &self,
         fid: FieldId,
@@ -240,6 +316,9 @@ impl<'a> SearchForFacetValues<'a> {
                 let value = self
                     .one_original_value_of(fid, &original, docids.min().unwrap())?
                     .unwrap_or_else(|| query.to_string());
+                if !self.is_under_parent(&value) {
+                    continue;
+                }
                 if results.insert(FacetValueHit { value, count }).is_break() {
                     break;
                 }
@@ -336,7 +415,7 @@ impl ValuesCollection {
         }
     }
 }
-fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> String {
+pub(crate) fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> String {
     let options = NormalizerOption { lossy: true, ..Default::default() };
     let mut detection = StrDetection::new(facet_string, locales);
 
@@ -10,6 +10,7 @@ use crate::{MatchingWords, Result, Search, SearchResult};
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
     candidates: RoaringBitmap,
+    disjunctive_candidates: Vec<RoaringBitmap>,
     document_scores: Vec<(u32, ScoreWithRatio)>,
     degraded: bool,
     used_negative_operator: bool,
@@ -53,6 +54,12 @@ fn compare_scores(
                     order => return order,
                 }
             }
+            (Some(ScoreValue::FunctionScore(left)), Some(ScoreValue::FunctionScore(right))) => {
+                match left.partial_cmp(right).unwrap() {
+                    Ordering::Equal => continue,
+                    order => return order,
+                }
+            }
             (Some(ScoreValue::Score(x)), Some(_)) => {
                 return if x == 0. { Ordering::Less } else { Ordering::Greater }
             }
@@ -64,6 +71,10 @@ fn compare_scores(
             | (Some(ScoreValue::Sort(_)), Some(ScoreValue::GeoSort(_))) => {
                 unreachable!("Unexpected geo and sort comparison")
             }
+            (Some(ScoreValue::FunctionScore(_)), Some(_))
+            | (Some(_), Some(ScoreValue::FunctionScore(_))) => {
+                unreachable!("Unexpected function score and sort comparison")
+            }
         }
     }
 }
@@ -79,6 +90,7 @@ impl ScoreWithRatioResult {
         Self {
             matching_words: results.matching_words,
             candidates: results.candidates,
+            disjunctive_candidates: results.disjunctive_candidates,
             document_scores,
             degraded: results.degraded,
             used_negative_operator: results.used_negative_operator,
@@ -140,6 +152,12 @@ impl ScoreWithRatioResult {
             SearchResult {
                 matching_words: keyword_results.matching_words,
                 candidates: vector_results.candidates | keyword_results.candidates,
+                disjunctive_candidates: vector_results
+                    .disjunctive_candidates
+                    .into_iter()
+                    .zip(keyword_results.disjunctive_candidates)
+                    .map(|(vector, keyword)| vector | keyword)
+                    .collect(),
                 documents_ids,
                 document_scores,
                 degraded: vector_results.degraded | keyword_results.degraded,
@@ -159,11 +177,15 @@ impl<'a> Search<'a> {
         let mut search = Search {
             query: self.query.clone(),
             filter: self.filter.clone(),
+            disjunctive_filters: self.disjunctive_filters.clone(),
             offset: 0,
             limit: self.limit + self.offset,
             sort_criteria: self.sort_criteria.clone(),
+            ranking_rules: self.ranking_rules.clone(),
             distinct: self.distinct.clone(),
             searchable_attributes: self.searchable_attributes,
+            synonyms: self.synonyms.clone(),
+            stop_words: self.stop_words.clone(),
             geo_strategy: self.geo_strategy,
             terms_matching_strategy: self.terms_matching_strategy,
             scoring_strategy: ScoringStrategy::Detailed,
@@ -253,6 +275,7 @@ fn return_keyword_results(
     SearchResult {
         matching_words,
         candidates,
+        disjunctive_candidates,
         mut documents_ids,
         mut document_scores,
         degraded,
@@ -279,6 +302,7 @@ fn return_keyword_results(
         SearchResult {
             matching_words,
             candidates,
+            disjunctive_candidates,
             documents_ids,
             document_scores,
             degraded,
@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::sync::Arc;
 
@@ -6,14 +7,18 @@ use levenshtein_automata::{LevenshteinAutomatonBuilder as LevBuilder, DFA};
 use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
-pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet::{
+    CalendarInterval, FacetBuckets, FacetDistribution, FacetHierarchy, FacetHierarchyNode,
+    FacetNumberStats, FacetRange, FacetValue, Filter, FilterAnalysis, FilterIssue, FilterIssueKind,
+    FilterNode, OrderBy, DEFAULT_VALUES_PER_FACET,
+};
 pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
 use self::new::{execute_vector_search, PartialSearchResult};
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::vector::Embedder;
 use crate::{
-    execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Error, Index,
-    Result, SearchContext, TimeBudget, UserError,
+    execute_search, AscDesc, Criterion, DefaultSearchLogger, DocumentId, Error, Index, Result,
+    SearchContext, TimeBudget, UserError,
 };
 
 // Building these factories is not free.
@@ -39,11 +44,15 @@ pub struct Search<'a> {
     query: Option<String>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
+    disjunctive_filters: Vec<Filter<'a>>,
     offset: usize,
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
+    ranking_rules: Option<Vec<Criterion>>,
     distinct: Option<String>,
     searchable_attributes: Option<&'a [String]>,
+    synonyms: Option<BTreeMap<String, Vec<String>>>,
+    stop_words: Option<BTreeSet<String>>,
     geo_strategy: new::GeoSortStrategy,
     terms_matching_strategy: TermsMatchingStrategy,
     scoring_strategy: ScoringStrategy,
@@ -62,11 +71,15 @@ impl<'a> Search<'a> {
         Search {
             query: None,
             filter: None,
+            disjunctive_filters: Vec::new(),
             offset: 0,
             limit: 20,
             sort_criteria: None,
+            ranking_rules: None,
             distinct: None,
             searchable_attributes: None,
+            synonyms: None,
+            stop_words: None,
             geo_strategy: new::GeoSortStrategy::default(),
             terms_matching_strategy: TermsMatchingStrategy::default(),
             scoring_strategy: Default::default(),
@@ -112,6 +125,12 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Overrides the ranking rules of the index for this search only.
+    pub fn ranking_rules(&mut self, ranking_rules: Vec<Criterion>) -> &mut Search<'a> {
+        self.ranking_rules = Some(ranking_rules);
+        self
+    }
+
     pub fn distinct(&mut self, distinct: String) -> &mut Search<'a> {
         self.distinct = Some(distinct);
         self
@@ -122,6 +141,18 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Adds synonyms to the ones of the index for this search only.
+    pub fn synonyms(&mut self, synonyms: BTreeMap<String, Vec<String>>) -> &mut Search<'a> {
+        self.synonyms = Some(synonyms);
+        self
+    }
+
+    /// Adds stop words to the ones of the index for this search only.
+    pub fn stop_words(&mut self, stop_words: BTreeSet<String>) -> &mut Search<'a> {
+        self.stop_words = Some(stop_words);
+        self
+    }
+
     pub fn terms_matching_strategy(&mut self, value: TermsMatchingStrategy) -> &mut Search<'a> {
         self.terms_matching_strategy = value;
         self
@@ -142,6 +173,14 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Restricts the search to the documents matching every one of the given filters, and returns
+    /// for each one of them the candidates matching all the other ones,
+    /// see [`SearchResult::disjunctive_candidates`].
+    pub fn disjunctive_filters(&mut self, filters: Vec<Filter<'a>>) -> &mut Search<'a> {
+        self.disjunctive_filters = filters;
+        self
+    }
+
     #[cfg(test)]
     pub fn geo_sort_strategy(&mut self, strategy: new::GeoSortStrategy) -> &mut Search<'a> {
         self.geo_strategy = strategy;
@@ -173,7 +212,7 @@ impl<'a> Search<'a> {
     pub fn execute_for_candidates(&self, has_vector_search: bool) -> Result<RoaringBitmap> {
         if has_vector_search {
             let ctx = SearchContext::new(self.index, self.rtxn)?;
-            filtered_universe(ctx.index, ctx.txn, &self.filter)
+            ctx.filtered_universe(&self.filter)
         } else {
             Ok(self.execute()?.candidates)
         }
@@ -186,6 +225,18 @@ impl<'a> Search<'a> {
             ctx.attributes_to_search_on(searchable_attributes)?;
         }
 
+        if let Some(ranking_rules) = &self.ranking_rules {
+            ctx.ranking_rules(ranking_rules.clone())?;
+        }
+
+        if let Some(synonyms) = &self.synonyms {
+            ctx.synonyms(synonyms)?;
+        }
+
+        if let Some(stop_words) = &self.stop_words {
+            ctx.stop_words(stop_words)?;
+        }
+
         if let Some(distinct) = &self.distinct {
             let filterable_fields = ctx.index.filterable_fields(ctx.txn)?;
             if !crate::is_faceted(distinct, &filterable_fields) {
@@ -199,10 +250,15 @@ impl<'a> Search<'a> {
             }
         }
 
-        let universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
+        if !self.disjunctive_filters.is_empty() {
+            ctx.disjunctive_filters(&self.disjunctive_filters)?;
+        }
+
+        let universe = ctx.filtered_universe(&self.filter)?;
         let PartialSearchResult {
             located_query_terms,
             candidates,
+            disjunctive_candidates,
             documents_ids,
             document_scores,
             degraded,
@@ -256,6 +312,7 @@ impl<'a> Search<'a> {
         Ok(SearchResult {
             matching_words,
             candidates,
+            disjunctive_candidates,
             document_scores,
             documents_ids,
             degraded,
@@ -269,11 +326,15 @@ impl fmt::Debug for Search<'_> {
         let Search {
             query,
             filter,
+            disjunctive_filters,
             offset,
             limit,
             sort_criteria,
+            ranking_rules,
             distinct,
             searchable_attributes,
+            synonyms,
+            stop_words,
             geo_strategy: _,
             terms_matching_strategy,
             scoring_strategy,
@@ -290,11 +351,15 @@ impl fmt::Debug for Search<'_> {
             .field("query", query)
             .field("vector", &"[...]")
             .field("filter", filter)
+            .field("disjunctive_filters", disjunctive_filters)
             .field("offset", offset)
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
+            .field("ranking_rules", ranking_rules)
             .field("distinct", distinct)
             .field("searchable_attributes", searchable_attributes)
+            .field("synonyms", synonyms)
+            .field("stop_words", stop_words)
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("scoring_strategy", scoring_strategy)
             .field("exhaustive_number_hits", exhaustive_number_hits)
@@ -314,6 +379,8 @@ impl fmt::Debug for Search<'_> {
 pub struct SearchResult {
     pub matching_words: MatchingWords,
     pub candidates: RoaringBitmap,
+    /// For each one of the disjunctive filters, the candidates matching all the other ones.
+    pub disjunctive_candidates: Vec<RoaringBitmap>,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
     pub degraded: bool,
@@ -410,4 +477,47 @@ mod test {
 
         assert_eq!(documents_ids, vec![1]);
     }
+
+    #[test]
+    fn disjunctive_filters() {
+        use std::collections::HashSet;
+
+        use crate::index::tests::TempIndex;
+
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(HashSet::from_iter([
+                    "brand".to_owned(),
+                    "color".to_owned(),
+                ]));
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "title": "phone", "brand": "apple", "color": "red" },
+                { "id": 1, "title": "phone", "brand": "apple", "color": "blue" },
+                { "id": 2, "title": "phone", "brand": "samsung", "color": "red" },
+                { "id": 3, "title": "tablet", "brand": "apple", "color": "red" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let mut search = Search::new(&txn, &index);
+
+        search.query("phone");
+        search.disjunctive_filters(vec![
+            Filter::from_str("brand = apple").unwrap().unwrap(),
+            Filter::from_str("color = red").unwrap().unwrap(),
+        ]);
+        let SearchResult { documents_ids, candidates, disjunctive_candidates, .. } =
+            search.execute().unwrap();
+
+        assert_eq!(documents_ids, vec![0]);
+        assert_eq!(candidates.iter().collect::<Vec<_>>(), vec![0]);
+        let disjunctive_candidates: Vec<Vec<_>> =
+            disjunctive_candidates.iter().map(|candidates| candidates.iter().collect()).collect();
+        assert_eq!(disjunctive_candidates, vec![vec![0, 2], vec![0, 1]]);
+    }
 }
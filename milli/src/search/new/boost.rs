@@ -0,0 +1,76 @@
+use roaring::RoaringBitmap;
+
+use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::criterion::parse_boost_filter;
+use crate::score_details::{self, ScoreDetails};
+use crate::{Result, SearchContext, SearchLogger};
+
+/// Ranks the documents matching a filter before the other ones, without excluding any of them.
+pub struct Boost<Q: RankingRuleQueryTrait> {
+    query: Option<Q>,
+    filter: String,
+    /// The documents of the index matching the filter.
+    matching: RoaringBitmap,
+    /// The buckets left to return, the documents matching the filter being the first one.
+    buckets: Vec<(bool, RoaringBitmap)>,
+}
+
+impl<Q: RankingRuleQueryTrait> Boost<Q> {
+    pub fn new(ctx: &SearchContext<'_>, filter: String) -> Result<Self> {
+        let matching = parse_boost_filter(&filter)?.evaluate(ctx.txn, ctx.index)?;
+        Ok(Self { query: None, filter, matching, buckets: Vec::new() })
+    }
+}
+
+impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for Boost<Q> {
+    fn id(&self) -> String {
+        format!("boost({})", self.filter)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        universe: &RoaringBitmap,
+        query: &Q,
+    ) -> Result<()> {
+        assert!(self.query.is_none());
+        self.query = Some(query.clone());
+        // the buckets are popped, the last one is returned first
+        self.buckets = vec![(false, universe - &self.matching), (true, universe & &self.matching)];
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Q>>> {
+        let query = self.query.as_ref().unwrap().clone();
+
+        while let Some((matches, docids)) = self.buckets.pop() {
+            let candidates = docids & universe;
+            if !candidates.is_empty() {
+                return Ok(Some(RankingRuleOutput {
+                    query,
+                    candidates,
+                    score: ScoreDetails::Boost(score_details::Boost {
+                        filter: self.filter.clone(),
+                        matches,
+                    }),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::boost")]
+    fn end_iteration(&mut self, _ctx: &mut SearchContext<'ctx>, _logger: &mut dyn SearchLogger<Q>) {
+        self.query = None;
+        self.buckets.clear();
+    }
+}
@@ -0,0 +1,153 @@
+use std::ops::Bound;
+
+use itertools::Itertools;
+use roaring::RoaringBitmap;
+
+use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::criterion::parse_decay_function;
+use crate::heed_codec::facet::FacetGroupKey;
+use crate::score_details::{self, ScoreDetails};
+use crate::{DecayFunction, FieldId, Result, SearchContext, SearchLogger};
+
+/// The numbers of a field with their documents, from the closest to the farthest of an origin.
+type OutwardNumbers<'ctx> = Box<dyn Iterator<Item = heed::Result<(f64, RoaringBitmap)>> + 'ctx>;
+
+/// Sorts the documents by the decreasing score of a [`DecayFunction`].
+///
+/// The score of a document is the best score among the numbers of its field, the
+/// documents without any number in the field are returned last.
+///
+/// The score only decreases with the distance to the origin, the numbers of the field are
+/// read from the facet database outward from the origin and a bucket is returned as soon as
+/// the score of the next number is lower.
+pub struct Decay<'ctx, Q: RankingRuleQueryTrait> {
+    query: Option<Q>,
+    source: String,
+    function: DecayFunction,
+    field_id: Option<FieldId>,
+    /// The origin is resolved once per search so that `now` is the same for every document.
+    origin: f64,
+    numbers: Option<OutwardNumbers<'ctx>>,
+    /// The documents of the first number with a lower score than the last returned bucket.
+    pending: Option<(f64, RoaringBitmap)>,
+}
+
+impl<'ctx, Q: RankingRuleQueryTrait> Decay<'ctx, Q> {
+    pub fn new(ctx: &SearchContext<'_>, source: String) -> Result<Self> {
+        let function = parse_decay_function(&source)?;
+        let field_id = ctx.index.fields_ids_map(ctx.txn)?.id(&function.field);
+        let origin = function.origin();
+
+        Ok(Self { query: None, source, function, field_id, origin, numbers: None, pending: None })
+    }
+
+    fn bucket(
+        &self,
+        query: Q,
+        candidates: RoaringBitmap,
+        score: Option<f64>,
+    ) -> RankingRuleOutput<Q> {
+        RankingRuleOutput {
+            query,
+            candidates,
+            score: ScoreDetails::Decay(score_details::Decay {
+                function: self.source.clone(),
+                score,
+            }),
+        }
+    }
+}
+
+impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for Decay<'ctx, Q> {
+    fn id(&self) -> String {
+        format!("decay({})", self.source)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::decay")]
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        _universe: &RoaringBitmap,
+        query: &Q,
+    ) -> Result<()> {
+        assert!(self.query.is_none());
+        self.query = Some(query.clone());
+
+        let Some(field_id) = self.field_id else {
+            return Ok(());
+        };
+
+        let origin = self.origin;
+        let key = |left_bound| FacetGroupKey { field_id, level: 0, left_bound };
+        let db = ctx.index.facet_id_f64_docids;
+        let above =
+            db.range(ctx.txn, &(Bound::Included(key(origin)), Bound::Included(key(f64::MAX))))?;
+        let below =
+            db.rev_range(ctx.txn, &(Bound::Included(key(f64::MIN)), Bound::Excluded(key(origin))))?;
+
+        let distance = move |result: &heed::Result<(FacetGroupKey<f64>, _)>| match result {
+            Ok((key, _)) => (key.left_bound - origin).abs(),
+            // the errors are returned as soon as possible
+            Err(_) => f64::NEG_INFINITY,
+        };
+        let numbers = above
+            .merge_by(below, move |left, right| distance(left) <= distance(right))
+            .map(|result| result.map(|(key, value)| (key.left_bound, value.bitmap)));
+        self.numbers = Some(Box::new(numbers));
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::decay")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Q>>> {
+        let query = self.query.as_ref().unwrap().clone();
+
+        // the returned documents are no longer in the universe, a document is only
+        // returned with the score of its closest number
+        let mut bucket = self
+            .pending
+            .take()
+            .map(|(score, docids)| (score, docids & universe))
+            .filter(|(_, docids)| !docids.is_empty());
+        if let Some(numbers) = self.numbers.as_mut() {
+            for result in numbers.by_ref() {
+                let (number, docids) = result?;
+                let docids = docids & universe;
+                if docids.is_empty() {
+                    continue;
+                }
+
+                let score = self.function.score(self.origin, number);
+                match &mut bucket {
+                    Some((bucket_score, bucket_docids)) if *bucket_score == score => {
+                        *bucket_docids |= docids
+                    }
+                    Some(_) => {
+                        self.pending = Some((score, docids));
+                        break;
+                    }
+                    None => bucket = Some((score, docids)),
+                }
+            }
+        }
+
+        match bucket {
+            Some((score, docids)) => Ok(Some(self.bucket(query, docids, Some(score)))),
+            // the remaining documents have no number in the field
+            None => Ok(Some(self.bucket(query, universe.clone(), None))),
+        }
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::decay")]
+    fn end_iteration(&mut self, _ctx: &mut SearchContext<'ctx>, _logger: &mut dyn SearchLogger<Q>) {
+        self.query = None;
+        self.numbers = None;
+        self.pending = None;
+    }
+}
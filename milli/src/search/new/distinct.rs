@@ -66,6 +66,12 @@ pub fn distinct_single_docid(
             *excluded |= facet_docids;
         }
     }
+    for boolean in [false, true] {
+        let facet_docids = index.bool_faceted_documents_ids(txn, field_id, boolean)?;
+        if facet_docids.contains(docid) {
+            *excluded |= facet_docids;
+        }
+    }
     Ok(())
 }
 
@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap};
+
+use heed::BytesDecode;
+use ordered_float::OrderedFloat;
+use roaring::{MultiOps, RoaringBitmap};
+
+use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::criterion::parse_score_expression;
+use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::BytesRefCodec;
+use crate::score_details::{self, ScoreDetails};
+use crate::search::facet::descending_facet_sort;
+use crate::{
+    DocumentId, FieldId, Result, ScoreExpression, SearchContext, SearchLogger, TimeBudget,
+};
+
+/// Sorts the documents by the decreasing value of a [`ScoreExpression`].
+///
+/// The value of a field is its highest number, the documents for which a field of
+/// the expression has no number, or for which the expression is not a finite number,
+/// are returned last. The numbers are read from the facet databases, the fields of the
+/// expression must be filterable or sortable.
+///
+/// When the time budget is exceeded the documents that are not scored yet are returned last.
+pub struct FunctionScore<Q: RankingRuleQueryTrait> {
+    query: Option<Q>,
+    source: String,
+    expression: ScoreExpression,
+    field_ids: Vec<(String, Option<FieldId>)>,
+    must_redact: bool,
+    time_budget: TimeBudget,
+    /// The candidates grouped by their score, sorted by increasing score.
+    buckets: Vec<(f64, RoaringBitmap)>,
+}
+
+impl<Q: RankingRuleQueryTrait> FunctionScore<Q> {
+    pub fn new(ctx: &SearchContext<'_>, source: String, time_budget: TimeBudget) -> Result<Self> {
+        let expression = parse_score_expression(&source)?;
+        let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
+        let field_ids: Vec<_> = expression
+            .fields()
+            .into_iter()
+            .map(|field| (field.to_string(), fields_ids_map.id(field)))
+            .collect();
+        let must_redact = match ctx.index.displayed_fields(ctx.txn)? {
+            Some(displayed_fields) => {
+                field_ids.iter().any(|(field, _)| !displayed_fields.contains(&field.as_str()))
+            }
+            None => false,
+        };
+
+        Ok(Self {
+            query: None,
+            source,
+            expression,
+            field_ids,
+            must_redact,
+            time_budget,
+            buckets: Vec::new(),
+        })
+    }
+
+    fn bucket(
+        &self,
+        query: Q,
+        candidates: RoaringBitmap,
+        value: Option<f64>,
+    ) -> RankingRuleOutput<Q> {
+        RankingRuleOutput {
+            query,
+            candidates,
+            score: ScoreDetails::FunctionScore(score_details::FunctionScore {
+                expression: self.source.clone(),
+                redacted: self.must_redact,
+                value,
+            }),
+        }
+    }
+}
+
+impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for FunctionScore<Q> {
+    fn id(&self) -> String {
+        format!("score:{}", self.source)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::function_score")]
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        universe: &RoaringBitmap,
+        query: &Q,
+    ) -> Result<()> {
+        assert!(self.query.is_none());
+        self.query = Some(query.clone());
+
+        let mut scores: BTreeMap<OrderedFloat<f64>, RoaringBitmap> = BTreeMap::new();
+        // the documents are grouped by the highest number of each field
+        let mut numbers_per_field = Vec::with_capacity(self.field_ids.len());
+        for (field, field_id) in &self.field_ids {
+            let numbers = match field_id {
+                Some(field_id) => highest_numbers(ctx, *field_id, universe, &self.time_budget)?,
+                None => Vec::new(),
+            };
+            numbers_per_field.push((field.as_str(), numbers));
+        }
+
+        match numbers_per_field.as_slice() {
+            // the expression is only evaluated once per number of its field
+            [(field, numbers)] => {
+                for (number, docids) in numbers {
+                    let score = self.expression.evaluate(&mut |name| {
+                        if name == *field {
+                            Some(*number)
+                        } else {
+                            None
+                        }
+                    });
+                    if let Some(score) = score {
+                        *scores.entry(OrderedFloat(score)).or_default() |= docids;
+                    }
+                }
+            }
+            numbers_per_field => {
+                // the expression only has a value for the documents with a number in each field
+                let mut scored = universe.clone();
+                for (_, numbers) in numbers_per_field {
+                    scored &= numbers.iter().map(|(_, docids)| docids).union();
+                }
+                let numbers_per_field: Vec<(&str, HashMap<DocumentId, f64>)> = numbers_per_field
+                    .iter()
+                    .map(|(field, numbers)| {
+                        let numbers = numbers
+                            .iter()
+                            .flat_map(|(number, docids)| {
+                                (docids & &scored).into_iter().map(move |docid| (docid, *number))
+                            })
+                            .collect();
+                        (*field, numbers)
+                    })
+                    .collect();
+                for docid in scored {
+                    if self.time_budget.exceeded() {
+                        break;
+                    }
+                    let score = self.expression.evaluate(&mut |name| {
+                        let (_, numbers) =
+                            numbers_per_field.iter().find(|(field, _)| *field == name)?;
+                        numbers.get(&docid).copied()
+                    });
+                    if let Some(score) = score {
+                        scores.entry(OrderedFloat(score)).or_default().insert(docid);
+                    }
+                }
+            }
+        }
+        self.buckets = scores.into_iter().map(|(score, docids)| (score.0, docids)).collect();
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::function_score")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Q>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Q>>> {
+        let query = self.query.as_ref().unwrap().clone();
+
+        while let Some((score, docids)) = self.buckets.pop() {
+            let candidates = docids & universe;
+            if !candidates.is_empty() {
+                return Ok(Some(self.bucket(query, candidates, Some(score))));
+            }
+        }
+
+        // the remaining documents have no score
+        Ok(Some(self.bucket(query, universe.clone(), None)))
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::function_score")]
+    fn end_iteration(&mut self, _ctx: &mut SearchContext<'ctx>, _logger: &mut dyn SearchLogger<Q>) {
+        self.query = None;
+        self.buckets.clear();
+    }
+}
+
+/// Returns the documents of the universe grouped by the highest number of the field, by
+/// decreasing number. The documents without number are not returned.
+fn highest_numbers(
+    ctx: &SearchContext<'_>,
+    field_id: FieldId,
+    universe: &RoaringBitmap,
+    time_budget: &TimeBudget,
+) -> Result<Vec<(f64, RoaringBitmap)>> {
+    let db = ctx.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+    let mut remaining = universe.clone();
+    let mut numbers = Vec::new();
+    for result in descending_facet_sort(ctx.txn, db, field_id, universe.clone())? {
+        if remaining.is_empty() || time_budget.exceeded() {
+            break;
+        }
+        let (docids, bytes) = result?;
+        // a document is only counted with its highest number
+        let docids = docids & &remaining;
+        if !docids.is_empty() {
+            remaining -= &docids;
+            let number = OrderedF64Codec::bytes_decode(bytes).map_err(heed::Error::Decoding)?;
+            numbers.push((number, docids));
+        }
+    }
+    Ok(numbers)
+}
@@ -1,18 +1,22 @@
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 use std::iter::FromIterator;
 
 use heed::types::{Bytes, Unit};
 use heed::{RoPrefix, RoTxn};
+use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
-use rstar::RTree;
+use rstar::{PointDistance, RTree};
 
 use super::facet_string_values;
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
+use crate::error::SerializationError;
 use crate::heed_codec::facet::{FieldDocIdFacetCodec, OrderedF64Codec};
+use crate::index::{db_name, main_key};
 use crate::score_details::{self, ScoreDetails};
 use crate::{
-    distance_between_two_points, lat_lng_to_xyz, GeoPoint, Index, Result, SearchContext,
-    SearchLogger,
+    distance_between_two_points, lat_lng_to_xyz, GeoPoint, Index, InternalError, Result,
+    SearchContext, SearchLogger,
 };
 
 const FID_SIZE: usize = 2;
@@ -24,7 +28,7 @@ fn facet_values_prefix_key(distinct: u16, id: u32) -> [u8; FID_SIZE + DOCID_SIZE
 }
 
 /// Return an iterator over each number value in the given field of the given document.
-fn facet_number_values<'a>(
+pub fn facet_number_values<'a>(
     docid: u32,
     field_id: u16,
     index: &Index,
@@ -84,6 +88,10 @@ pub struct GeoSort<Q: RankingRuleQueryTrait> {
 
     cached_sorted_docids: VecDeque<(u32, [f64; 2])>,
     geo_candidates: RoaringBitmap,
+    /// The next documents with a geo shape, their nearest point and its distance, in the order of the sort.
+    sorted_shapes: VecDeque<(u32, [f64; 2], f64)>,
+    /// `true` once all the shapes of the universe went through `sorted_shapes`.
+    shapes_exhausted: bool,
 }
 
 impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
@@ -102,9 +110,98 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
             field_ids: None,
             rtree: None,
             cached_sorted_docids: VecDeque::new(),
+            sorted_shapes: VecDeque::new(),
+            shapes_exhausted: false,
         })
     }
 
+    /// Refill the buffer of shapes with the next documents of the universe that have a geo shape,
+    /// in the order of the distance between the nearest point of their shape and the queried point.
+    ///
+    /// The shapes are read from the R-tree by increasing distance between their envelope and the
+    /// queried point, or its antipode for the descending sort. A shape is only buffered once no
+    /// farther envelope can contain a shape that comes before it.
+    fn fill_shapes_buffer(
+        &mut self,
+        ctx: &SearchContext<'_>,
+        universe: &RoaringBitmap,
+    ) -> Result<()> {
+        debug_assert!(self.sorted_shapes.is_empty());
+
+        let rtree = match ctx.geo_shapes_rtree.get(ctx.index, ctx.txn)? {
+            Some(rtree) => rtree,
+            None => {
+                self.shapes_exhausted = true;
+                return Ok(());
+            }
+        };
+
+        // The nearest point of a shape to the queried point is the farthest from its antipode,
+        // the shapes are thus sorted by the angle between this point and the target.
+        let target = if self.ascending { self.point } else { opposite_of(self.point) };
+        let xyz_point = lat_lng_to_xyz(&self.point);
+        let xyz_target = lat_lng_to_xyz(&target);
+        let cache_size = self.strategy.cache_size();
+
+        // a document has a single shape, they are ordered by the angle with their nearest point
+        let mut pending: BinaryHeap<Reverse<(OrderedFloat<f64>, u32, [OrderedFloat<f64>; 2])>> =
+            BinaryHeap::new();
+        let mut exhausted = true;
+        for envelope in rtree.nearest_neighbor_iter(&xyz_target) {
+            let (docid, shape) = &envelope.data;
+            if !universe.contains(*docid) {
+                continue;
+            }
+
+            // no shape in this envelope or in the following ones can be closer to the target
+            let bound = chord_to_angle(envelope.distance_2(&xyz_target).sqrt());
+            while let Some(Reverse((angle, _, _))) = pending.peek() {
+                if *angle > OrderedFloat(bound) {
+                    break;
+                }
+                let Some(Reverse((_, docid, point))) = pending.pop() else { break };
+                self.buffer_shape(docid, point);
+            }
+            if self.sorted_shapes.len() >= cache_size {
+                exhausted = false;
+                break;
+            }
+
+            // an empty shape has no point to sort its document with
+            let Some(point) = shape.nearest_point(&self.point) else { continue };
+            let angle = chord_to_angle(chord_length(&xyz_point, &lat_lng_to_xyz(&point)));
+            let angle = if self.ascending { angle } else { std::f64::consts::PI - angle };
+            pending.push(Reverse((OrderedFloat(angle), *docid, point.map(OrderedFloat))));
+        }
+
+        if exhausted {
+            while let Some(Reverse((_, docid, point))) = pending.pop() {
+                self.buffer_shape(docid, point);
+            }
+        }
+        self.shapes_exhausted = exhausted;
+        Ok(())
+    }
+
+    /// Buffers the nearest point of the shape of a document with its distance to the queried point.
+    fn buffer_shape(&mut self, docid: u32, point: [OrderedFloat<f64>; 2]) {
+        let point = point.map(|coordinate| coordinate.0);
+        let distance = distance_between_two_points(&self.point, &point);
+        self.sorted_shapes.push_back((docid, point, distance));
+    }
+
+    fn bucket(&self, query: Q, docid: u32, point: [f64; 2]) -> RankingRuleOutput<Q> {
+        RankingRuleOutput {
+            query,
+            candidates: RoaringBitmap::from_iter([docid]),
+            score: ScoreDetails::GeoSort(score_details::GeoSort {
+                target_point: self.point,
+                ascending: self.ascending,
+                value: Some(point),
+            }),
+        }
+    }
+
     /// Refill the internal buffer of cached docids based on the strategy.
     /// Drop the rtree if we don't need it anymore.
     fn fill_buffer(
@@ -121,7 +218,7 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
                 // get rtree from cache
                 Some(rtree)
             } else {
-                let rtree = ctx.index.geo_rtree(ctx.txn)?.expect("geo candidates but no rtree");
+                let rtree = load_geo_rtree(ctx.index, ctx.txn)?;
                 // insert rtree in cache and returns it.
                 // Can't use `get_or_insert_with` because getting the rtree from the DB is a fallible operation.
                 Some(&*self.rtree.insert(rtree))
@@ -144,10 +241,20 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
                 }
             } else {
                 // in the case of the desc geo sort we look for the closest point to the opposite of the queried point
-                // and we insert the points in reverse order they get reversed when emptying the cache later on
+                // and we insert the points in reverse order they get reversed when emptying the cache later on.
+                // A document located at multiple points is sorted by its closest point, which is the last one
+                // we encounter, we skip the others.
+                let [lat, lng] = self.field_ids.unwrap();
                 let point = lat_lng_to_xyz(&opposite_of(self.point));
                 for point in rtree.nearest_neighbor_iter(&point) {
                     if geo_candidates.contains(point.data.0) {
+                        let points = geo_values(point.data.0, lat, lng, ctx.index, ctx.txn, rtree)?;
+                        let closest = closest_point(&self.point, &points)?;
+                        if distance_between_two_points(&self.point, &closest)
+                            < distance_between_two_points(&self.point, &point.data.1)
+                        {
+                            continue;
+                        }
                         self.cached_sorted_docids.push_front(point.data);
                         if self.cached_sorted_docids.len() >= cache_size {
                             break;
@@ -159,10 +266,20 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
             // the iterative version
             let [lat, lng] = self.field_ids.unwrap();
 
-            let mut documents = geo_candidates
-                .iter()
-                .map(|id| -> Result<_> { Ok((id, geo_value(id, lat, lng, ctx.index, ctx.txn)?)) })
-                .collect::<Result<Vec<(u32, [f64; 2])>>>()?;
+            let mut documents = Vec::with_capacity(geo_candidates.len() as usize);
+            for id in geo_candidates {
+                let points = match geo_facet_values(id, lat, lng, ctx.index, ctx.txn)? {
+                    (lats, lngs) if lats.len() == 1 && lngs.len() == 1 => vec![[lats[0], lngs[0]]],
+                    (lats, lngs) => {
+                        // the rtree is only needed for the documents located at multiple points
+                        if self.rtree.is_none() {
+                            self.rtree = Some(load_geo_rtree(ctx.index, ctx.txn)?);
+                        }
+                        geo_points(id, &lats, &lngs, self.rtree.as_ref().unwrap())?
+                    }
+                };
+                documents.push((id, closest_point(&self.point, &points)?));
+            }
             // computing the distance between two points is expensive thus we cache the result
             documents
                 .sort_by_cached_key(|(_, p)| distance_between_two_points(&self.point, p) as usize);
@@ -173,35 +290,111 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
     }
 }
 
-/// Extracts the lat and long values from a single document.
+/// Returns the geo rtree of an index that has geo faceted documents.
+fn load_geo_rtree(index: &Index, rtxn: &RoTxn<'_>) -> Result<RTree<GeoPoint>> {
+    index.geo_rtree(rtxn)?.ok_or_else(|| missing_geo_rtree_entry().into())
+}
+
+fn missing_geo_rtree_entry() -> InternalError {
+    InternalError::DatabaseMissingEntry {
+        db_name: db_name::MAIN,
+        key: Some(main_key::GEO_RTREE_KEY),
+    }
+}
+
+/// Extracts the points, made of lat and long values, of a single document.
 ///
-/// If it is not able to find it in the facet number index it will extract it
-/// from the facet string index and parse it as f64 (as the geo extraction behaves).
-fn geo_value(
+/// When the document is located at a single point it is read from the facet databases,
+/// otherwise see [`geo_points`].
+fn geo_values(
     docid: u32,
     field_lat: u16,
     field_lng: u16,
     index: &Index,
     rtxn: &RoTxn<'_>,
-) -> Result<[f64; 2]> {
-    let extract_geo = |geo_field: u16| -> Result<f64> {
-        match facet_number_values(docid, geo_field, index, rtxn)?.next() {
-            Some(Ok(((_, _, geo), ()))) => Ok(geo),
-            Some(Err(e)) => Err(e.into()),
-            None => match facet_string_values(docid, geo_field, index, rtxn)?.next() {
-                Some(Ok((_, geo))) => {
-                    Ok(geo.parse::<f64>().expect("cannot parse geo field as f64"))
-                }
-                Some(Err(e)) => Err(e.into()),
-                None => panic!("A geo faceted document doesn't contain any lat or lng"),
-            },
+    rtree: &RTree<GeoPoint>,
+) -> Result<Vec<[f64; 2]>> {
+    match geo_facet_values(docid, field_lat, field_lng, index, rtxn)? {
+        (lats, lngs) if lats.len() == 1 && lngs.len() == 1 => Ok(vec![[lats[0], lngs[0]]]),
+        (lats, lngs) => geo_points(docid, &lats, &lngs, rtree),
+    }
+}
+
+/// Extracts the latitudes and longitudes of a single document from the facet databases.
+///
+/// If it is not able to find them in the facet number index it will extract them
+/// from the facet string index and parse them as f64 (as the geo extraction behaves).
+fn geo_facet_values(
+    docid: u32,
+    field_lat: u16,
+    field_lng: u16,
+    index: &Index,
+    rtxn: &RoTxn<'_>,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    let extract_geo = |geo_field: u16| -> Result<Vec<f64>> {
+        let numbers = facet_number_values(docid, geo_field, index, rtxn)?
+            .map(|result| result.map(|((_, _, geo), ())| geo))
+            .collect::<heed::Result<Vec<_>>>()?;
+        if !numbers.is_empty() {
+            return Ok(numbers);
+        }
+        let mut strings = Vec::new();
+        for result in facet_string_values(docid, geo_field, index, rtxn)? {
+            let (_, geo) = result?;
+            let geo = geo.parse::<f64>().map_err(|_| {
+                InternalError::Serialization(SerializationError::Decoding {
+                    db_name: Some(db_name::FIELD_ID_DOCID_FACET_STRINGS),
+                })
+            })?;
+            strings.push(geo);
         }
+        if strings.is_empty() {
+            return Err(InternalError::DatabaseMissingEntry {
+                db_name: db_name::FIELD_ID_DOCID_FACET_F64S,
+                key: None,
+            }
+            .into());
+        }
+        Ok(strings)
     };
 
-    let lat = extract_geo(field_lat)?;
-    let lng = extract_geo(field_lng)?;
+    Ok((extract_geo(field_lat)?, extract_geo(field_lng)?))
+}
+
+/// Returns the points of a document located at multiple points.
+///
+/// The facets lose the association between its latitudes and longitudes, each of their
+/// combinations is thus looked up in the rtree, which links the points to their documents.
+fn geo_points(
+    docid: u32,
+    lats: &[f64],
+    lngs: &[f64],
+    rtree: &RTree<GeoPoint>,
+) -> Result<Vec<[f64; 2]>> {
+    let mut points = Vec::new();
+    for &lat in lats {
+        for &lng in lngs {
+            let point = [lat, lng];
+            let xyz_point = lat_lng_to_xyz(&point);
+            if rtree.locate_all_at_point(&xyz_point).any(|geo_point| geo_point.data.0 == docid) {
+                points.push(point);
+            }
+        }
+    }
+    if points.is_empty() {
+        return Err(missing_geo_rtree_entry().into());
+    }
+    Ok(points)
+}
 
-    Ok([lat, lng])
+/// Returns the point that is the closest to the target.
+fn closest_point(target: &[f64; 2], points: &[[f64; 2]]) -> Result<[f64; 2]> {
+    points
+        .iter()
+        .map(|point| (distance_between_two_points(target, point), point))
+        .min_by(|(left, _), (right, _)| left.total_cmp(right))
+        .map(|(_, point)| *point)
+        .ok_or_else(|| missing_geo_rtree_entry().into())
 }
 
 impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
@@ -220,6 +413,8 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
         assert!(self.query.is_none());
 
         self.query = Some(query.clone());
+        self.shapes_exhausted = false;
+        self.fill_shapes_buffer(ctx, universe)?;
 
         let geo_candidates = &self.geo_candidates & universe;
 
@@ -245,9 +440,27 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
     ) -> Result<Option<RankingRuleOutput<Q>>> {
         let query = self.query.as_ref().unwrap().clone();
 
+        let ascending = self.ascending;
+        // skip the shapes of the documents that are not candidates anymore
+        loop {
+            match self.sorted_shapes.front() {
+                Some((id, _, _)) if !universe.contains(*id) => {
+                    self.sorted_shapes.pop_front();
+                }
+                Some(_) => break,
+                None if self.shapes_exhausted => break,
+                None => self.fill_shapes_buffer(ctx, universe)?,
+            }
+        }
+        let next_shape = self.sorted_shapes.front().copied();
+
         let geo_candidates = &self.geo_candidates & universe;
 
         if geo_candidates.is_empty() {
+            if let Some((id, point, _)) = next_shape {
+                self.sorted_shapes.pop_front();
+                return Ok(Some(self.bucket(query, id, point)));
+            }
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
@@ -259,25 +472,25 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
             }));
         }
 
-        let ascending = self.ascending;
-        let next = |cache: &mut VecDeque<_>| {
-            if ascending {
-                cache.pop_front()
-            } else {
-                cache.pop_back()
-            }
-        };
-        while let Some((id, point)) = next(&mut self.cached_sorted_docids) {
+        while let Some((id, point)) = pop_next(&mut self.cached_sorted_docids, ascending) {
             if geo_candidates.contains(id) {
-                return Ok(Some(RankingRuleOutput {
-                    query,
-                    candidates: RoaringBitmap::from_iter([id]),
-                    score: ScoreDetails::GeoSort(score_details::GeoSort {
-                        target_point: self.point,
-                        ascending: self.ascending,
-                        value: Some(point),
-                    }),
-                }));
+                // a shape may come before this point
+                if let Some((shape_id, shape_point, shape_distance)) = next_shape {
+                    let distance = distance_between_two_points(&self.point, &point);
+                    if (ascending && shape_distance < distance)
+                        || (!ascending && shape_distance > distance)
+                    {
+                        // we put the point back for the next bucket
+                        if ascending {
+                            self.cached_sorted_docids.push_front((id, point));
+                        } else {
+                            self.cached_sorted_docids.push_back((id, point));
+                        }
+                        self.sorted_shapes.pop_front();
+                        return Ok(Some(self.bucket(query, shape_id, shape_point)));
+                    }
+                }
+                return Ok(Some(self.bucket(query, id, point)));
             }
         }
 
@@ -292,9 +505,29 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
         // we do not reset the rtree here, it could be used in a next iteration
         self.query = None;
         self.cached_sorted_docids.clear();
+        self.sorted_shapes.clear();
+    }
+}
+
+/// Removes and returns the next element of a cache sorted by ascending distance.
+fn pop_next<T>(cache: &mut VecDeque<T>, ascending: bool) -> Option<T> {
+    if ascending {
+        cache.pop_front()
+    } else {
+        cache.pop_back()
     }
 }
 
+/// Returns the length of the chord between two points of the unit sphere.
+fn chord_length(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Returns the angle, in radians, between the ends of a chord of the unit sphere.
+fn chord_to_angle(chord: f64) -> f64 {
+    2.0 * (chord / 2.0).min(1.0).asin()
+}
+
 /// Compute the antipodal coordinate of `coord`
 fn opposite_of(mut coord: [f64; 2]) -> [f64; 2] {
     coord[0] *= -1.;
@@ -46,7 +46,7 @@ use super::logger::SearchLogger;
 use super::query_graph::QueryNode;
 use super::ranking_rule_graph::{
     ConditionDocIdsCache, DeadEndsCache, ExactnessGraph, FidGraph, PositionGraph, ProximityGraph,
-    RankingRuleGraph, RankingRuleGraphTrait, TypoGraph, WordsGraph,
+    RankingRuleGraph, RankingRuleGraphTrait, TermRarityGraph, TypoGraph, WordsGraph,
 };
 use super::small_bitmap::SmallBitmap;
 use super::{QueryGraph, RankingRule, RankingRuleOutput, SearchContext};
@@ -61,6 +61,12 @@ impl GraphBasedRankingRule<WordsGraph> {
         Self::new_with_id("words".to_owned(), Some(terms_matching_strategy))
     }
 }
+pub type TermRarity = GraphBasedRankingRule<TermRarityGraph>;
+impl GraphBasedRankingRule<TermRarityGraph> {
+    pub fn new(terms_matching_strategy: TermsMatchingStrategy) -> Self {
+        Self::new_with_id("termRarity".to_owned(), Some(terms_matching_strategy))
+    }
+}
 pub type Proximity = GraphBasedRankingRule<ProximityGraph>;
 impl GraphBasedRankingRule<ProximityGraph> {
     pub fn new(terms_matching_strategy: Option<TermsMatchingStrategy>) -> Self {
@@ -121,6 +127,8 @@ pub struct GraphBasedRankingRuleState<G: RankingRuleGraphTrait> {
     cur_cost: u64,
     /// One above the highest possible cost for this rule
     next_max_cost: u64,
+    /// The budget shared by the conditions of a path, see [`RankingRuleGraphTrait::path_budget`]
+    path_budget: Option<u32>,
 }
 
 impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBasedRankingRule<G> {
@@ -141,6 +149,9 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
         // When there is a matching strategy, it also factors the additional costs of:
         // 1. The words that are matched in phrases
         // 2. Skipping words (by adding them to the paths with a cost)
+        //
+        // The words that can be skipped are given by the matching strategy, but a graph
+        // can define the cost of skipping them by itself.
         let mut next_max_cost = 1;
         let removal_cost = if let Some(terms_matching_strategy) = self.terms_matching_strategy {
             // add the cost of the phrase to the next_max_cost
@@ -150,29 +161,21 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
                 // where only the phrase is matching, and none of the non-phrase words.
                 // With the `1` that `next_max_cost` is initialized with, this gets counted twice.
                 .saturating_sub(1) as u64;
-            match terms_matching_strategy {
+            let removal_order = match terms_matching_strategy {
                 TermsMatchingStrategy::Last => {
-                    let removal_order =
-                        query_graph.removal_order_for_terms_matching_strategy_last(ctx);
-                    let mut forbidden_nodes =
-                        SmallBitmap::for_interned_values_in(&query_graph.nodes);
-                    let mut costs = query_graph.nodes.map(|_| None);
-                    // FIXME: this works because only words uses termsmatchingstrategy at the moment.
-                    for ns in removal_order {
-                        for n in ns.iter() {
-                            *costs.get_mut(n) = Some((1, forbidden_nodes.clone()));
-                        }
-                        forbidden_nodes.union(&ns);
-                    }
-                    costs
+                    query_graph.removal_order_for_terms_matching_strategy_last(ctx)
                 }
                 TermsMatchingStrategy::Frequency => {
-                    let removal_order =
-                        query_graph.removal_order_for_terms_matching_strategy_frequency(ctx)?;
+                    query_graph.removal_order_for_terms_matching_strategy_frequency(ctx)?
+                }
+                TermsMatchingStrategy::All => Vec::new(),
+            };
+            match G::cost_of_ignoring_nodes(ctx, query_graph, &removal_order)? {
+                Some(costs) => costs,
+                None => {
                     let mut forbidden_nodes =
                         SmallBitmap::for_interned_values_in(&query_graph.nodes);
                     let mut costs = query_graph.nodes.map(|_| None);
-                    // FIXME: this works because only words uses termsmatchingstrategy at the moment.
                     for ns in removal_order {
                         for n in ns.iter() {
                             *costs.get_mut(n) = Some((1, forbidden_nodes.clone()));
@@ -181,13 +184,13 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
                     }
                     costs
                 }
-                TermsMatchingStrategy::All => query_graph.nodes.map(|_| None),
             }
         } else {
             query_graph.nodes.map(|_| None)
         };
 
         let graph = RankingRuleGraph::build(ctx, query_graph.clone(), removal_cost)?;
+        let path_budget = G::path_budget(ctx)?;
         let condition_docids_cache = ConditionDocIdsCache::default();
         let dead_ends_cache = DeadEndsCache::new(&graph.conditions_interner);
 
@@ -204,6 +207,7 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
             all_costs,
             cur_cost: 0,
             next_max_cost,
+            path_budget,
         };
 
         self.state = Some(state);
@@ -239,6 +243,7 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
             all_costs,
             cur_cost: _,
             next_max_cost,
+            path_budget,
         } = &mut state;
 
         let rank = *next_max_cost - cost;
@@ -268,6 +273,12 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
             if universe.is_empty() {
                 return Ok(ControlFlow::Break(()));
             }
+            if let Some(budget) = *path_budget {
+                let conditions = path.iter().map(|&c| graph.conditions_interner.get(c));
+                if conditions.map(G::budget_used_by).sum::<u32>() > budget {
+                    return Ok(ControlFlow::Continue(()));
+                }
+            }
             // `visit_paths` performs a depth-first search, so the previously visited path
             // is likely to share a prefix with the current one.
             // We stored the previous path and the docids associated to each of its prefixes in `subpaths_docids`.
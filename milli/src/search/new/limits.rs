@@ -8,6 +8,9 @@ pub const MAX_ONE_TYPO_COUNT: usize = 150;
 /// Maximum number of words that can be derived from a single word with a distance of two to that word.
 pub const MAX_TWO_TYPOS_COUNT: usize = 50;
 
+/// Maximum number of words sounding like a single word that can be derived from it.
+pub const MAX_PHONETIC_COUNT: usize = 50;
+
 /// Maximum amount of synonym phrases that can be derived from a single word.
 pub const MAX_SYNONYM_PHRASE_COUNT: usize = 50;
 
@@ -15,3 +18,15 @@ pub const MAX_SYNONYM_PHRASE_COUNT: usize = 50;
 ///
 /// This limit is meant to gracefully handle the case where a word would have very long phrases as synonyms.
 pub const MAX_SYNONYM_WORD_COUNT: usize = 100;
+
+/// Maximum number of words that can be given synonyms at search time.
+pub const MAX_SEARCH_SYNONYM_COUNT: usize = 100;
+
+/// Maximum number of synonyms that can be given to a single word at search time.
+pub const MAX_SEARCH_SYNONYMS_PER_WORD: usize = MAX_SYNONYM_PHRASE_COUNT;
+
+/// Maximum length in bytes of a word, or of one of its synonyms, given at search time.
+pub const MAX_SEARCH_SYNONYM_LENGTH: usize = 512;
+
+/// Maximum number of stop words that can be given at search time.
+pub const MAX_SEARCH_STOP_WORD_COUNT: usize = 1_000;
@@ -466,6 +466,10 @@ fill: \"#B6E2D3\"
                     let w = ctx.word_interner.get(w.interned());
                     writeln!(file, "{w}: word")?;
                 }
+                for w in term_subset.phonetic_words(ctx) {
+                    let w = ctx.word_interner.get(w);
+                    writeln!(file, "{w}: phonetic word")?;
+                }
                 for p in term_subset.all_phrases(ctx)? {
                     writeln!(file, "{}: phrase", p.description(ctx))?;
                 }
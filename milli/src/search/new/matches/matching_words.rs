@@ -261,10 +261,11 @@ pub(crate) mod tests {
         let mut ctx = SearchContext::new(&temp_index, &rtxn).unwrap();
         let mut builder = TokenizerBuilder::default();
         let tokenizer = builder.build();
-        let tokens = tokenizer.tokenize("split this world");
+        let query = "split this world";
+        let tokens = tokenizer.tokenize(query);
         let ExtractedTokens { query_terms, .. } =
-            located_query_terms_from_tokens(&mut ctx, tokens, None).unwrap();
-        let matching_words = MatchingWords::new(ctx, query_terms);
+            located_query_terms_from_tokens(&mut ctx, query, tokens, None).unwrap();
+        let matching_words = MatchingWords::new(ctx, query_terms.into_iter().flatten().collect());
 
         assert_eq!(
             matching_words
@@ -14,20 +14,24 @@ mod ranking_rules;
 mod resolve_query_graph;
 mod small_bitmap;
 
+mod boost;
+mod decay;
 mod exact_attribute;
+mod function_score;
 mod sort;
 mod vector_sort;
 
 #[cfg(test)]
 mod tests;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
 
 use bucket_sort::{bucket_sort, BucketSortOutput};
-use charabia::{Language, TokenizerBuilder};
+use charabia::{Language, Normalize, TokenizerBuilder};
 use db_cache::DatabaseCache;
 use exact_attribute::ExactAttribute;
-use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, Typo};
+use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, TermRarity, Typo};
 use heed::RoTxn;
 use interner::{DedupInterner, Interner};
 pub use logger::visual::VisualSearchLogger;
@@ -43,19 +47,23 @@ use resolve_query_graph::{compute_query_graph_docids, PhraseDocIdsCache};
 use roaring::RoaringBitmap;
 use sort::Sort;
 
+use self::boost::Boost;
+use self::decay::Decay;
 use self::distinct::facet_string_values;
+use self::function_score::FunctionScore;
 use self::geo_sort::GeoSort;
 pub use self::geo_sort::Strategy as GeoSortStrategy;
 use self::graph_based_ranking_rule::Words;
 use self::interner::Interned;
 use self::vector_sort::VectorSort;
+use crate::geo_shape::LazyGeoShapesRtree;
 use crate::localized_attributes_rules::LocalizedFieldIds;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::apply_distinct_rule;
 use crate::vector::Embedder;
 use crate::{
-    AscDesc, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy, TimeBudget,
-    UserError, Weight,
+    AscDesc, Criterion, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy,
+    TimeBudget, UserError, Weight, MAX_WORD_LENGTH,
 };
 
 /// A structure used throughout the execution of a search query.
@@ -68,6 +76,18 @@ pub struct SearchContext<'ctx> {
     pub term_interner: Interner<QueryTerm>,
     pub phrase_docids: PhraseDocIdsCache,
     pub restricted_fids: Option<RestrictedFids>,
+    pub ranking_rules: Option<Vec<Criterion>>,
+    /// The synonyms of the index merged with the ones given at search time,
+    /// read from the index once per search.
+    synonyms: Option<HashMap<Vec<String>, Vec<Vec<String>>>>,
+    /// The synonyms derived from the synonym rules of the index, read once per search.
+    synonym_rules: Option<HashMap<Vec<String>, Vec<Vec<String>>>>,
+    /// The stop words of the index merged with the ones given at search time.
+    stop_words: Option<Arc<fst::Set<Vec<u8>>>>,
+    /// The phonetic fields tolerating typos, read once per search.
+    phonetic_fids: Option<Vec<FieldId>>,
+    pub disjunctive_docids: Vec<RoaringBitmap>,
+    pub(crate) geo_shapes_rtree: LazyGeoShapesRtree,
 }
 
 impl<'ctx> SearchContext<'ctx> {
@@ -94,6 +114,13 @@ impl<'ctx> SearchContext<'ctx> {
             term_interner: <_>::default(),
             phrase_docids: <_>::default(),
             restricted_fids: None,
+            ranking_rules: None,
+            synonyms: None,
+            synonym_rules: None,
+            stop_words: None,
+            phonetic_fids: None,
+            disjunctive_docids: Vec::new(),
+            geo_shapes_rtree: <_>::default(),
         })
     }
 
@@ -153,6 +180,227 @@ impl<'ctx> SearchContext<'ctx> {
 
         Ok(())
     }
+
+    /// Overrides the ranking rules of the index for this search.
+    ///
+    /// They are validated like the ranking rules of the settings, but the attributes they use
+    /// must already be faceted by the index, as the facet databases are not updated at search time.
+    pub fn ranking_rules(&mut self, ranking_rules: Vec<Criterion>) -> Result<()> {
+        let filterable_fields = self.index.filterable_fields(self.txn)?;
+        let faceted_fields = self.index.user_defined_faceted_fields(self.txn)?;
+        crate::criterion::validate_criteria(&ranking_rules, &filterable_fields, &faceted_fields)
+            .map_err(UserError::InvalidSearchRankingRules)?;
+
+        self.ranking_rules = Some(ranking_rules);
+
+        Ok(())
+    }
+
+    /// Returns the ranking rules of the search, those of the index unless they were overridden.
+    fn criteria(&self) -> Result<Vec<Criterion>> {
+        match &self.ranking_rules {
+            Some(ranking_rules) => Ok(ranking_rules.clone()),
+            None => Ok(self.index.criteria(self.txn)?),
+        }
+    }
+
+    /// Adds synonyms to the ones of the index for this search.
+    ///
+    /// The words and their synonyms are normalized like the synonyms of the index.
+    pub fn synonyms(&mut self, synonyms: &BTreeMap<String, Vec<String>>) -> Result<()> {
+        if synonyms.len() > limits::MAX_SEARCH_SYNONYM_COUNT {
+            return Err(UserError::TooManySearchSynonyms {
+                count: synonyms.len(),
+                limit: limits::MAX_SEARCH_SYNONYM_COUNT,
+            }
+            .into());
+        }
+        for (word, word_synonyms) in synonyms {
+            if word_synonyms.len() > limits::MAX_SEARCH_SYNONYMS_PER_WORD {
+                return Err(UserError::TooManySearchSynonymsForWord {
+                    word: word.clone(),
+                    count: word_synonyms.len(),
+                    limit: limits::MAX_SEARCH_SYNONYMS_PER_WORD,
+                }
+                .into());
+            }
+            let too_long = std::iter::once(word)
+                .chain(word_synonyms)
+                .find(|synonym| synonym.len() > limits::MAX_SEARCH_SYNONYM_LENGTH);
+            if let Some(synonym) = too_long {
+                return Err(UserError::SearchSynonymTooLong {
+                    synonym: synonym.clone(),
+                    limit: limits::MAX_SEARCH_SYNONYM_LENGTH,
+                }
+                .into());
+            }
+        }
+
+        let mut builder = TokenizerBuilder::new();
+        let separators = self.index.allowed_separators(self.txn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
+        let dictionary = self.index.dictionary(self.txn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+        let tokenizer = builder.build();
+
+        let normalize = |text: &str| -> Vec<String> {
+            tokenizer
+                .tokenize(text)
+                .filter(|token| token.is_word() && !token.lemma().is_empty())
+                .map(|token| token.lemma().to_string())
+                .collect()
+        };
+
+        let mut normalized_synonyms: HashMap<_, Vec<_>> = HashMap::new();
+        for (word, synonyms) in synonyms {
+            let normalized_word = normalize(word);
+            let normalized_synonyms_of_word: Vec<_> = synonyms
+                .iter()
+                .map(|synonym| normalize(synonym))
+                .filter(|synonym| !synonym.is_empty() && *synonym != normalized_word)
+                .collect();
+
+            if !normalized_word.is_empty() && !normalized_synonyms_of_word.is_empty() {
+                normalized_synonyms
+                    .entry(normalized_word)
+                    .or_default()
+                    .extend(normalized_synonyms_of_word);
+            }
+        }
+
+        // The synonyms given at search time come first, so that they are kept when the number
+        // of synonyms of a word exceeds the limits.
+        let mut all_synonyms = self.index.synonyms(self.txn)?;
+        for (words, words_synonyms) in normalized_synonyms {
+            let entry = all_synonyms.entry(words).or_default();
+            let index_synonyms = std::mem::replace(entry, words_synonyms);
+            let index_synonyms: Vec<_> =
+                index_synonyms.into_iter().filter(|synonym| !entry.contains(synonym)).collect();
+            entry.extend(index_synonyms);
+        }
+        self.synonyms = Some(all_synonyms);
+
+        Ok(())
+    }
+
+    /// Returns the synonyms of the search, those given at search time merged with those of the index.
+    ///
+    /// The synonyms of the index are only read on the first call.
+    fn all_synonyms(&mut self) -> Result<&HashMap<Vec<String>, Vec<Vec<String>>>> {
+        if self.synonyms.is_none() {
+            self.synonyms = Some(self.index.synonyms(self.txn)?);
+        }
+        Ok(self.synonyms.as_ref().unwrap())
+    }
+
+    /// Returns the synonyms derived from the synonym rules of the index.
+    ///
+    /// They are only read on the first call.
+    fn synonym_rules(&mut self) -> Result<&HashMap<Vec<String>, Vec<Vec<String>>>> {
+        if self.synonym_rules.is_none() {
+            self.synonym_rules = Some(self.index.synonym_rules(self.txn)?);
+        }
+        Ok(self.synonym_rules.as_ref().unwrap())
+    }
+
+    /// Returns the ids of the phonetic fields that are not exact attributes, the only ones
+    /// in which the words sounding like the words of the query are searched.
+    ///
+    /// They are only read on the first call.
+    fn phonetic_fids(&mut self) -> Result<&[FieldId]> {
+        if self.phonetic_fids.is_none() {
+            let exact_fids = self.index.exact_attributes_ids(self.txn)?;
+            let mut fids: Vec<_> = self
+                .index
+                .phonetic_fields_ids(self.txn)?
+                .into_iter()
+                .filter(|fid| !exact_fids.contains(fid))
+                .collect();
+            fids.sort_unstable();
+            self.phonetic_fids = Some(fids);
+        }
+        Ok(self.phonetic_fids.as_deref().unwrap())
+    }
+
+    /// Adds stop words to the ones of the index for this search.
+    ///
+    /// Contrary to the stop words of the index, these words are still indexed,
+    /// they are only ignored in the query.
+    pub fn stop_words(&mut self, stop_words: &BTreeSet<String>) -> Result<()> {
+        if stop_words.len() > limits::MAX_SEARCH_STOP_WORD_COUNT {
+            return Err(UserError::TooManySearchStopWords {
+                count: stop_words.len(),
+                limit: limits::MAX_SEARCH_STOP_WORD_COUNT,
+            }
+            .into());
+        }
+
+        if let Some(word) = stop_words.iter().find(|word| word.len() > MAX_WORD_LENGTH) {
+            return Err(UserError::SearchStopWordTooLong {
+                word: word.clone(),
+                limit: MAX_WORD_LENGTH,
+            }
+            .into());
+        }
+
+        // Apply the same unlossy normalization as on the stop words of the index
+        let mut all_stop_words: BTreeSet<_> = stop_words
+            .iter()
+            .map(|w| w.as_str().normalize(&Default::default()).into_owned())
+            .collect();
+        if let Some(index_stop_words) = self.index.stop_words(self.txn)? {
+            all_stop_words.extend(index_stop_words.stream().into_strs()?);
+        }
+        self.stop_words = Some(Arc::new(fst::Set::from_iter(all_stop_words)?));
+
+        Ok(())
+    }
+
+    /// Restricts the results to the documents matching every one of the given filters.
+    ///
+    /// Unlike the filter of the search, they are applied once the query is resolved so that
+    /// the candidates matching all of them but one can also be returned.
+    pub fn disjunctive_filters(&mut self, filters: &[Filter<'_>]) -> Result<()> {
+        self.disjunctive_docids = filters
+            .iter()
+            .map(|filter| {
+                filter.evaluate_with_geo_shapes(self.txn, self.index, &self.geo_shapes_rtree)
+            })
+            .collect::<Result<_>>()?;
+        Ok(())
+    }
+
+    /// Restricts the universe with the disjunctive filters and returns, for each one of them,
+    /// the documents of the universe matching all the other ones.
+    fn apply_disjunctive_filters(&self, universe: &mut RoaringBitmap) -> Vec<RoaringBitmap> {
+        let candidates = (0..self.disjunctive_docids.len())
+            .map(|i| {
+                let others = self.disjunctive_docids.iter().enumerate().filter(|(j, _)| *j != i);
+                others.fold(universe.clone(), |candidates, (_, docids)| candidates & docids)
+            })
+            .collect();
+        for docids in &self.disjunctive_docids {
+            *universe &= docids;
+        }
+        candidates
+    }
+
+    /// Same as [`filtered_universe`], but the geo shapes read by the filters are kept for the geo sort.
+    pub fn filtered_universe(&self, filters: &Option<Filter<'_>>) -> Result<RoaringBitmap> {
+        Ok(if let Some(filters) = filters {
+            filters.evaluate_with_geo_shapes(self.txn, self.index, &self.geo_shapes_rtree)?
+        } else {
+            self.index.documents_ids(self.txn)?
+        })
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
@@ -265,16 +513,18 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
     ctx: &SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
+    time_budget: &TimeBudget,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     let mut sort = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>> = vec![];
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
     for rr in settings_ranking_rules {
         match rr {
             // These rules need a query to have an effect; ignore them in placeholder search
             crate::Criterion::Words
+            | crate::Criterion::TermRarity
             | crate::Criterion::Typo
             | crate::Criterion::Attribute
             | crate::Criterion::Proximity
@@ -307,6 +557,19 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Score(expression) => {
+                ranking_rules.push(Box::new(FunctionScore::new(
+                    ctx,
+                    expression,
+                    time_budget.clone(),
+                )?));
+            }
+            crate::Criterion::Decay(function) => {
+                ranking_rules.push(Box::new(Decay::new(ctx, function)?));
+            }
+            crate::Criterion::Boost(filter) => {
+                ranking_rules.push(Box::new(Boost::new(ctx, filter)?));
+            }
         }
     }
     Ok(ranking_rules)
@@ -322,6 +585,7 @@ fn get_ranking_rules_for_vector<'ctx>(
     embedder_name: &str,
     embedder: &Embedder,
     quantized: bool,
+    time_budget: &TimeBudget,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     // query graph search
 
@@ -332,10 +596,11 @@ fn get_ranking_rules_for_vector<'ctx>(
     let mut vector = false;
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>> = vec![];
 
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
     for rr in settings_ranking_rules {
         match rr {
             crate::Criterion::Words
+            | crate::Criterion::TermRarity
             | crate::Criterion::Typo
             | crate::Criterion::Proximity
             | crate::Criterion::Attribute
@@ -383,6 +648,19 @@ fn get_ranking_rules_for_vector<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Score(expression) => {
+                ranking_rules.push(Box::new(FunctionScore::new(
+                    ctx,
+                    expression,
+                    time_budget.clone(),
+                )?));
+            }
+            crate::Criterion::Decay(function) => {
+                ranking_rules.push(Box::new(Decay::new(ctx, function)?));
+            }
+            crate::Criterion::Boost(filter) => {
+                ranking_rules.push(Box::new(Boost::new(ctx, filter)?));
+            }
         }
     }
 
@@ -395,6 +673,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
     terms_matching_strategy: TermsMatchingStrategy,
+    time_budget: &TimeBudget,
 ) -> Result<Vec<BoxRankingRule<'ctx, QueryGraph>>> {
     // query graph search
     let mut words = false;
@@ -412,7 +691,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     }
 
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, QueryGraph>> = vec![];
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
     for rr in settings_ranking_rules {
         // Add Words before any of: typo, proximity, attribute
         match rr {
@@ -435,6 +714,14 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 ranking_rules.push(Box::new(Words::new(terms_matching_strategy)));
                 words = true;
             }
+            crate::Criterion::TermRarity => {
+                // `termRarity` replaces `words`, the settings reject it after `words`
+                if words {
+                    continue;
+                }
+                ranking_rules.push(Box::new(TermRarity::new(terms_matching_strategy)));
+                words = true;
+            }
             crate::Criterion::Typo => {
                 if typo {
                     continue;
@@ -493,6 +780,19 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Score(expression) => {
+                ranking_rules.push(Box::new(FunctionScore::new(
+                    ctx,
+                    expression,
+                    time_budget.clone(),
+                )?));
+            }
+            crate::Criterion::Decay(function) => {
+                ranking_rules.push(Box::new(Decay::new(ctx, function)?));
+            }
+            crate::Criterion::Boost(filter) => {
+                ranking_rules.push(Box::new(Boost::new(ctx, filter)?));
+            }
         }
     }
     Ok(ranking_rules)
@@ -571,7 +871,7 @@ pub fn execute_vector_search(
     ctx: &mut SearchContext<'_>,
     vector: &[f32],
     scoring_strategy: ScoringStrategy,
-    universe: RoaringBitmap,
+    mut universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     distinct: &Option<String>,
     geo_strategy: geo_sort::Strategy,
@@ -585,6 +885,8 @@ pub fn execute_vector_search(
 ) -> Result<PartialSearchResult> {
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
+    let disjunctive_candidates = ctx.apply_disjunctive_filters(&mut universe);
+
     // FIXME: input universe = universe & documents_with_vectors
     // for now if we're computing embeddings for ALL documents, we can assume that this is just universe
     let ranking_rules = get_ranking_rules_for_vector(
@@ -596,6 +898,7 @@ pub fn execute_vector_search(
         embedder_name,
         embedder,
         quantized,
+        &time_budget,
     )?;
 
     let mut placeholder_search_logger = logger::DefaultSearchLogger;
@@ -618,6 +921,7 @@ pub fn execute_vector_search(
 
     Ok(PartialSearchResult {
         candidates: all_candidates,
+        disjunctive_candidates,
         document_scores: scores,
         documents_ids: docids,
         located_query_terms: None,
@@ -658,8 +962,14 @@ pub fn execute_search(
         // We make sure that the analyzer is aware of the stop words
         // this ensures that the query builder is able to properly remove them.
         let mut tokbuilder = TokenizerBuilder::new();
-        let stop_words = ctx.index.stop_words(ctx.txn)?;
-        if let Some(ref stop_words) = stop_words {
+        let search_stop_words = ctx.stop_words.clone();
+        let stop_words = match search_stop_words {
+            Some(_) => None,
+            None => ctx.index.stop_words(ctx.txn)?,
+        };
+        if let Some(stop_words) = search_stop_words.as_deref() {
+            tokbuilder.stop_words(stop_words);
+        } else if let Some(ref stop_words) = stop_words {
             tokbuilder.stop_words(stop_words);
         }
 
@@ -725,7 +1035,7 @@ pub fn execute_search(
         drop(entered);
 
         let ExtractedTokens { query_terms, negative_words, negative_phrases } =
-            located_query_terms_from_tokens(ctx, tokens, words_limit)?;
+            located_query_terms_from_tokens(ctx, query, tokens, words_limit)?;
         used_negative_operator = !negative_words.is_empty() || !negative_phrases.is_empty();
 
         let ignored_documents = resolve_negative_words(ctx, Some(&universe), &negative_words)?;
@@ -744,6 +1054,8 @@ pub fn execute_search(
         None
     };
 
+    // the disjunctive filters are applied once the query is resolved on the universe
+    let disjunctive_candidates;
     let bucket_sort_output = if let Some(query_terms) = query_terms {
         let (graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
         located_query_terms = Some(new_located_query_terms);
@@ -753,10 +1065,12 @@ pub fn execute_search(
             sort_criteria,
             geo_strategy,
             terms_matching_strategy,
+            &time_budget,
         )?;
 
         universe &=
             resolve_universe(ctx, &universe, &graph, terms_matching_strategy, query_graph_logger)?;
+        disjunctive_candidates = ctx.apply_disjunctive_filters(&mut universe);
 
         bucket_sort(
             ctx,
@@ -772,8 +1086,13 @@ pub fn execute_search(
             ranking_score_threshold,
         )?
     } else {
-        let ranking_rules =
-            get_ranking_rules_for_placeholder_search(ctx, sort_criteria, geo_strategy)?;
+        let ranking_rules = get_ranking_rules_for_placeholder_search(
+            ctx,
+            sort_criteria,
+            geo_strategy,
+            &time_budget,
+        )?;
+        disjunctive_candidates = ctx.apply_disjunctive_filters(&mut universe);
         bucket_sort(
             ctx,
             ranking_rules,
@@ -809,6 +1128,7 @@ pub fn execute_search(
 
     Ok(PartialSearchResult {
         candidates: all_candidates,
+        disjunctive_candidates,
         document_scores: scores,
         documents_ids: docids,
         located_query_terms,
@@ -833,7 +1153,7 @@ fn check_sort_criteria(
 
     // We check that the sort ranking rule exists and throw an
     // error if we try to use it and that it doesn't.
-    let sort_ranking_rule_missing = !ctx.index.criteria(ctx.txn)?.contains(&crate::Criterion::Sort);
+    let sort_ranking_rule_missing = !ctx.criteria()?.contains(&crate::Criterion::Sort);
     if sort_ranking_rule_missing {
         return Err(UserError::SortRankingRuleMissing.into());
     }
@@ -875,6 +1195,8 @@ fn check_sort_criteria(
 pub struct PartialSearchResult {
     pub located_query_terms: Option<Vec<LocatedQueryTerm>>,
     pub candidates: RoaringBitmap,
+    /// For each disjunctive filter, the candidates matching all the other ones.
+    pub disjunctive_candidates: Vec<RoaringBitmap>,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
 
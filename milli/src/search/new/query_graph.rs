@@ -13,7 +13,7 @@ use super::small_bitmap::SmallBitmap;
 use super::SearchContext;
 use crate::search::new::interner::Interner;
 use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
-use crate::Result;
+use crate::{FieldId, Result};
 
 /// A node of the [`QueryGraph`].
 ///
@@ -92,12 +92,14 @@ pub struct QueryGraph {
 impl QueryGraph {
     /// Build the query graph from the parsed user search query, return an updated list of the located query terms
     /// which contains ngrams.
+    ///
+    /// The alternatives of a term, joined by the `OR` operator, become parallel nodes sharing the same term id.
     pub fn from_query(
         ctx: &mut SearchContext<'_>,
         // The terms here must be consecutive
-        terms: &[LocatedQueryTerm],
+        terms: &[Vec<LocatedQueryTerm>],
     ) -> Result<(QueryGraph, Vec<LocatedQueryTerm>)> {
-        let mut new_located_query_terms = terms.to_vec();
+        let mut new_located_query_terms: Vec<_> = terms.iter().flatten().cloned().collect();
 
         let nbr_typos = number_of_typos_allowed(ctx)?;
 
@@ -113,20 +115,24 @@ impl QueryGraph {
         for term_idx in 0..original_terms_len {
             let mut new_nodes = vec![];
 
-            let new_node_idx = add_node(
-                &mut nodes_data,
-                QueryNodeData::Term(LocatedQueryTermSubset {
-                    term_subset: QueryTermSubset::full(terms[term_idx].value),
-                    positions: terms[term_idx].positions.clone(),
-                    term_ids: term_idx as u8..=term_idx as u8,
-                }),
-            );
-            new_nodes.push(new_node_idx);
+            for term in &terms[term_idx] {
+                let new_node_idx = add_node(
+                    &mut nodes_data,
+                    QueryNodeData::Term(LocatedQueryTermSubset {
+                        term_subset: QueryTermSubset::full(term.value),
+                        positions: term.positions.clone(),
+                        term_ids: term_idx as u8..=term_idx as u8,
+                    }),
+                );
+                new_nodes.push(new_node_idx);
+            }
 
             if !prev1.is_empty() {
-                if let Some(ngram) =
-                    query_term::make_ngram(ctx, &terms[term_idx - 1..=term_idx], &nbr_typos)?
-                {
+                if let Some(ngram) = make_ngram_without_alternatives(
+                    ctx,
+                    &terms[term_idx - 1..=term_idx],
+                    &nbr_typos,
+                )? {
                     new_located_query_terms.push(ngram.clone());
                     let ngram_idx = add_node(
                         &mut nodes_data,
@@ -140,9 +146,11 @@ impl QueryGraph {
                 }
             }
             if !prev2.is_empty() {
-                if let Some(ngram) =
-                    query_term::make_ngram(ctx, &terms[term_idx - 2..=term_idx], &nbr_typos)?
-                {
+                if let Some(ngram) = make_ngram_without_alternatives(
+                    ctx,
+                    &terms[term_idx - 2..=term_idx],
+                    &nbr_typos,
+                )? {
                     new_located_query_terms.push(ngram.clone());
                     let ngram_idx = add_node(
                         &mut nodes_data,
@@ -414,6 +422,22 @@ impl QueryGraph {
     }
 }
 
+/// Make an ngram of the given terms, unless one of them has alternatives.
+fn make_ngram_without_alternatives(
+    ctx: &mut SearchContext<'_>,
+    terms: &[Vec<LocatedQueryTerm>],
+    number_of_typos_allowed: &impl Fn(&str, Option<FieldId>) -> u8,
+) -> Result<Option<LocatedQueryTerm>> {
+    let mut single_terms = Vec::with_capacity(terms.len());
+    for alternatives in terms {
+        match alternatives.as_slice() {
+            [term] => single_terms.push(term.clone()),
+            _ => return Ok(None),
+        }
+    }
+    query_term::make_ngram(ctx, &single_terms, number_of_typos_allowed)
+}
+
 fn add_node(nodes_data: &mut Vec<QueryNodeData>, node_data: QueryNodeData) -> u16 {
     let new_node_idx = nodes_data.len() as u16;
     nodes_data.push(node_data);
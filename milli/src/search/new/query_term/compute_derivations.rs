@@ -7,6 +7,7 @@ use fst::{Automaton, IntoStreamer, Streamer};
 use heed::types::DecodeIgnore;
 
 use super::{OneTypoTerm, Phrase, QueryTerm, ZeroTypoTerm};
+use crate::phonetic::{soundex, PHONETIC_CODE_LENGTH};
 use crate::search::fst_utils::{Complement, Intersection, StartsWith, Union};
 use crate::search::new::interner::{DedupInterner, Interned};
 use crate::search::new::query_term::{Lazy, TwoTypoTerm};
@@ -187,9 +188,11 @@ pub fn partially_initialized_term_from_word(
                 ngram_words: None,
                 is_prefix: false,
                 max_levenshtein_distance: 0,
+                restricted_fid: None,
                 zero_typo: <_>::default(),
                 one_typo: Lazy::Init(<_>::default()),
                 two_typo: Lazy::Init(<_>::default()),
+                phonetic: <_>::default(),
             }
         });
     }
@@ -234,37 +237,94 @@ pub fn partially_initialized_term_from_word(
             },
         )?;
     }
-    let synonyms = ctx.index.synonyms(ctx.txn)?;
-    let mut synonym_word_count = 0;
-    let synonyms = synonyms
-        .get(&vec![word.to_owned()])
-        .cloned()
-        .unwrap_or_default()
+    let key = vec![word.to_owned()];
+    let word_synonyms = ctx.all_synonyms()?.get(&key).cloned().unwrap_or_default();
+    let word_rule_synonyms = ctx.synonym_rules()?.get(&key).cloned().unwrap_or_default();
+    let all_synonyms = word_synonyms
         .into_iter()
-        .take(limits::MAX_SYNONYM_PHRASE_COUNT)
-        .filter_map(|words| {
-            if synonym_word_count + words.len() > limits::MAX_SYNONYM_WORD_COUNT {
-                return None;
-            }
-            synonym_word_count += words.len();
-            let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            Some(ctx.phrase_interner.insert(Phrase { words }))
-        })
-        .collect();
-    let zero_typo =
-        ZeroTypoTerm { phrase: None, exact: zero_typo, prefix_of, synonyms, use_prefix_db };
+        .map(|words| (words, false))
+        .chain(word_rule_synonyms.into_iter().map(|words| (words, true)));
+
+    let mut synonyms = BTreeSet::new();
+    let mut rule_synonyms = BTreeSet::new();
+    let mut synonym_word_count = 0;
+    for (words, from_rule) in all_synonyms.take(limits::MAX_SYNONYM_PHRASE_COUNT) {
+        if synonym_word_count + words.len() > limits::MAX_SYNONYM_WORD_COUNT {
+            continue;
+        }
+        synonym_word_count += words.len();
+        let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
+        let phrase = ctx.phrase_interner.insert(Phrase { words });
+        // a synonym also given outside of the synonym rules is not ranked after the original word
+        if synonyms.insert(phrase) && from_rule {
+            rule_synonyms.insert(phrase);
+        }
+    }
+    let zero_typo = ZeroTypoTerm {
+        phrase: None,
+        exact: zero_typo,
+        prefix_of,
+        synonyms,
+        rule_synonyms,
+        use_prefix_db,
+    };
+
+    // the ngrams are not words, they can't sound like the words of the documents
+    let phonetic =
+        if is_ngram { BTreeSet::new() } else { find_phonetic_derivations(ctx, word_interned)? };
 
     Ok(QueryTerm {
         original: word_interned,
         ngram_words: None,
         max_levenshtein_distance: max_typo,
         is_prefix,
+        restricted_fid: None,
         zero_typo,
         one_typo: Lazy::Uninit,
         two_typo: Lazy::Uninit,
+        phonetic,
     })
 }
 
+/// Find the words of the phonetic attributes sharing the phonetic code of the given word.
+///
+/// Like the typos, the words sounding alike are not derived when the typo tolerance is
+/// disabled or from the exact words, whatever the length of the word.
+fn find_phonetic_derivations(
+    ctx: &mut SearchContext<'_>,
+    word_interned: Interned<String>,
+) -> Result<BTreeSet<Interned<String>>> {
+    let word = ctx.word_interner.get(word_interned).to_owned();
+    let mut phonetic_words = BTreeSet::new();
+    if !ctx.index.authorize_typos(ctx.txn)? {
+        return Ok(phonetic_words);
+    }
+    let exact_words = ctx.index.exact_words(ctx.txn)?;
+    if exact_words.map_or(false, |fst| fst.contains(&word)) {
+        return Ok(phonetic_words);
+    }
+    let Some(code) = soundex(&word) else {
+        return Ok(phonetic_words);
+    };
+
+    let iter = ctx
+        .index
+        .phonetic_word_docids
+        .remap_data_type::<DecodeIgnore>()
+        .prefix_iter(ctx.txn, &code)?;
+    for result in iter {
+        let (key, ()) = result?;
+        let derived_word = &key[PHONETIC_CODE_LENGTH..];
+        if derived_word != word {
+            if phonetic_words.len() >= limits::MAX_PHONETIC_COUNT {
+                break;
+            }
+            phonetic_words.insert(ctx.word_interner.insert(derived_word.to_owned()));
+        }
+    }
+    Ok(phonetic_words)
+}
+
 fn find_split_words(ctx: &mut SearchContext<'_>, word: &str) -> Result<Option<Interned<Phrase>>> {
     if let Some((l, r)) = split_best_frequency(ctx, word)? {
         Ok(Some(ctx.phrase_interner.insert(Phrase { words: vec![Some(l), Some(r)] })))
@@ -16,7 +16,7 @@ pub use phrase::Phrase;
 
 use super::interner::{DedupInterner, Interned};
 use super::{limits, SearchContext, Word};
-use crate::Result;
+use crate::{FieldId, Result};
 
 /// A set of word derivations attached to a location in the search query.
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -32,6 +32,8 @@ pub struct QueryTermSubset {
     zero_typo_subset: NTypoTermSubset,
     one_typo_subset: NTypoTermSubset,
     two_typo_subset: NTypoTermSubset,
+    /// `true` if the words sounding like the term are part of the subset
+    phonetic: bool,
     /// `true` if the term cannot be deleted through the term matching strategy
     ///
     /// Note that there are other reasons for which a term cannot be deleted, such as
@@ -46,11 +48,17 @@ pub struct QueryTerm {
     ngram_words: Option<Vec<Interned<String>>>,
     max_levenshtein_distance: u8,
     is_prefix: bool,
+    /// The field the term is restricted to, if it was scoped with the `attribute:term` syntax
+    restricted_fid: Option<FieldId>,
     zero_typo: ZeroTypoTerm,
     // May not be computed yet
     one_typo: Lazy<OneTypoTerm>,
     // May not be computed yet
     two_typo: Lazy<TwoTypoTerm>,
+    /// The words of the phonetic attributes sounding like the original word
+    ///
+    /// They are ranked after all the typo derivations in the typo ranking rule.
+    phonetic: BTreeSet<Interned<String>>,
 }
 
 // SubTerms will be in a dedup interner
@@ -64,6 +72,11 @@ struct ZeroTypoTerm {
     prefix_of: BTreeSet<Interned<String>>,
     /// All the synonyms of the original word or phrase
     synonyms: BTreeSet<Interned<Phrase>>,
+    /// The synonyms only derived from the synonym rules, a subset of `synonyms`
+    ///
+    /// Matching them costs as much as a typo in the typo ranking rule,
+    /// so that the documents matching the original word are ranked first.
+    rule_synonyms: BTreeSet<Interned<Phrase>>,
     /// A prefix in the prefix databases matching the original word
     use_prefix_db: Option<Interned<String>>,
 }
@@ -148,6 +161,7 @@ impl QueryTermSubset {
             zero_typo_subset: NTypoTermSubset::Nothing,
             one_typo_subset: NTypoTermSubset::Nothing,
             two_typo_subset: NTypoTermSubset::Nothing,
+            phonetic: false,
             mandatory: false,
         }
     }
@@ -157,6 +171,7 @@ impl QueryTermSubset {
             zero_typo_subset: NTypoTermSubset::All,
             one_typo_subset: NTypoTermSubset::All,
             two_typo_subset: NTypoTermSubset::All,
+            phonetic: true,
             mandatory: false,
         }
     }
@@ -166,12 +181,14 @@ impl QueryTermSubset {
         self.zero_typo_subset.union(&other.zero_typo_subset);
         self.one_typo_subset.union(&other.one_typo_subset);
         self.two_typo_subset.union(&other.two_typo_subset);
+        self.phonetic |= other.phonetic;
     }
     pub fn intersect(&mut self, other: &Self) {
         assert!(self.original == other.original);
         self.zero_typo_subset.intersect(&other.zero_typo_subset);
         self.one_typo_subset.intersect(&other.one_typo_subset);
         self.two_typo_subset.intersect(&other.two_typo_subset);
+        self.phonetic &= other.phonetic;
     }
 
     pub fn use_prefix_db(&self, ctx: &SearchContext<'_>) -> Option<Word> {
@@ -213,6 +230,7 @@ impl QueryTermSubset {
                     exact: zero_typo,
                     prefix_of,
                     synonyms: _,
+                    rule_synonyms: _,
                     use_prefix_db: _,
                 } = &original.zero_typo;
                 result.extend(zero_typo.iter().copied().map(|w| {
@@ -236,6 +254,7 @@ impl QueryTermSubset {
                     exact: zero_typo,
                     prefix_of,
                     synonyms: _,
+                    rule_synonyms: _,
                     use_prefix_db: _,
                 } = &original.zero_typo;
                 if let Some(zero_typo) = zero_typo {
@@ -290,6 +309,14 @@ impl QueryTermSubset {
 
         Ok(result)
     }
+    /// Returns the words sounding like the term, which are only searched in the phonetic fields
+    pub fn phonetic_words(&self, ctx: &SearchContext<'_>) -> BTreeSet<Interned<String>> {
+        if self.phonetic {
+            ctx.term_interner.get(self.original).phonetic.clone()
+        } else {
+            BTreeSet::new()
+        }
+    }
     pub fn all_phrases(&self, ctx: &mut SearchContext<'_>) -> Result<BTreeSet<Interned<Phrase>>> {
         let mut result = BTreeSet::default();
 
@@ -298,8 +325,14 @@ impl QueryTermSubset {
         }
         let original = ctx.term_interner.get_mut(self.original);
 
-        let ZeroTypoTerm { phrase, exact: _, prefix_of: _, synonyms, use_prefix_db: _ } =
-            &original.zero_typo;
+        let ZeroTypoTerm {
+            phrase,
+            exact: _,
+            prefix_of: _,
+            synonyms,
+            rule_synonyms: _,
+            use_prefix_db: _,
+        } = &original.zero_typo;
         result.extend(phrase.iter().copied());
         result.extend(synonyms.iter().copied());
 
@@ -378,6 +411,7 @@ impl QueryTermSubset {
                     };
                     self.clear_one_typo_subset();
                     self.clear_two_typo_subset();
+                    self.clear_phonetic_subset();
                 }
                 ExactTerm::Word(w) => {
                     self.zero_typo_subset = NTypoTermSubset::Subset {
@@ -386,10 +420,66 @@ impl QueryTermSubset {
                     };
                     self.clear_one_typo_subset();
                     self.clear_two_typo_subset();
+                    self.clear_phonetic_subset();
                 }
             }
         }
     }
+    /// Returns `true` if the zero-typo subset contains any synonym derived from the synonym rules
+    pub fn has_rule_synonyms(&self, ctx: &SearchContext<'_>) -> bool {
+        let t = ctx.term_interner.get(self.original);
+        t.zero_typo
+            .rule_synonyms
+            .iter()
+            .any(|synonym| self.zero_typo_subset.contains_phrase(*synonym))
+    }
+    /// Removes the synonyms derived from the synonym rules from the zero-typo subset
+    pub fn clear_rule_synonyms(&mut self, ctx: &SearchContext<'_>) {
+        let t = ctx.term_interner.get(self.original);
+        let ZeroTypoTerm { phrase, exact, prefix_of, synonyms, rule_synonyms, use_prefix_db } =
+            &t.zero_typo;
+        if rule_synonyms.is_empty() {
+            return;
+        }
+        match &mut self.zero_typo_subset {
+            NTypoTermSubset::All => {
+                let words = exact.iter().chain(prefix_of).chain(use_prefix_db).copied().collect();
+                let phrases =
+                    phrase.iter().chain(synonyms.difference(rule_synonyms)).copied().collect();
+                self.zero_typo_subset = NTypoTermSubset::Subset { words, phrases };
+            }
+            NTypoTermSubset::Subset { words: _, phrases } => {
+                phrases.retain(|phrase| !rule_synonyms.contains(phrase));
+            }
+            NTypoTermSubset::Nothing => {}
+        }
+    }
+    /// Keeps only the synonyms derived from the synonym rules in the zero-typo subset
+    pub fn keep_only_rule_synonyms(&mut self, ctx: &SearchContext<'_>) {
+        let t = ctx.term_interner.get(self.original);
+        let phrases = t
+            .zero_typo
+            .rule_synonyms
+            .iter()
+            .copied()
+            .filter(|synonym| self.zero_typo_subset.contains_phrase(*synonym))
+            .collect();
+        self.zero_typo_subset = NTypoTermSubset::Subset { words: BTreeSet::new(), phrases };
+        self.clear_phonetic_subset();
+    }
+    /// Returns `true` if the subset contains any word sounding like the term
+    pub fn has_phonetic_words(&self, ctx: &SearchContext<'_>) -> bool {
+        self.phonetic && !ctx.term_interner.get(self.original).phonetic.is_empty()
+    }
+    /// Keeps only the words sounding like the term in the subset
+    pub fn keep_only_phonetic_words(&mut self) {
+        self.clear_zero_typo_subset();
+        self.clear_one_typo_subset();
+        self.clear_two_typo_subset();
+    }
+    pub fn clear_phonetic_subset(&mut self) {
+        self.phonetic = false;
+    }
     pub fn clear_zero_typo_subset(&mut self) {
         self.zero_typo_subset = NTypoTermSubset::Nothing;
     }
@@ -403,11 +493,22 @@ impl QueryTermSubset {
         let t = ctx.term_interner.get(self.original);
         ctx.word_interner.get(t.original).to_owned()
     }
+    /// The field the term is restricted to, if any
+    pub fn restricted_fid(&self, ctx: &SearchContext<'_>) -> Option<FieldId> {
+        ctx.term_interner.get(self.original).restricted_fid
+    }
 }
 
 impl ZeroTypoTerm {
     fn is_empty(&self) -> bool {
-        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db } = self;
+        let ZeroTypoTerm {
+            phrase,
+            exact: zero_typo,
+            prefix_of,
+            synonyms,
+            rule_synonyms: _,
+            use_prefix_db,
+        } = self;
         phrase.is_none()
             && zero_typo.is_none()
             && prefix_of.is_empty()
@@ -437,7 +538,10 @@ impl QueryTerm {
             return false;
         };
 
-        self.zero_typo.is_empty() && one_typo.is_empty() && two_typo.is_empty()
+        self.zero_typo.is_empty()
+            && one_typo.is_empty()
+            && two_typo.is_empty()
+            && self.phonetic.is_empty()
     }
     fn allows_split_words(&self) -> bool {
         self.zero_typo.phrase.is_none()
@@ -489,8 +593,14 @@ impl QueryTerm {
         let mut words = BTreeSet::new();
         let mut phrases = BTreeSet::new();
 
-        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db: _ } =
-            &self.zero_typo;
+        let ZeroTypoTerm {
+            phrase,
+            exact: zero_typo,
+            prefix_of,
+            synonyms,
+            rule_synonyms: _,
+            use_prefix_db: _,
+        } = &self.zero_typo;
         words.extend(zero_typo.iter().copied());
         words.extend(prefix_of.iter().copied());
         phrases.extend(phrase.iter().copied());
@@ -505,6 +615,8 @@ impl QueryTerm {
             words.extend(two_typos.iter().copied());
         };
 
+        words.extend(self.phonetic.iter().copied());
+
         (words.into_iter().collect(), phrases.into_iter().collect())
     }
 }
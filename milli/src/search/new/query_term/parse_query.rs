@@ -7,13 +7,19 @@ use super::compute_derivations::partially_initialized_term_from_word;
 use super::{LocatedQueryTerm, ZeroTypoTerm};
 use crate::search::new::query_term::{Lazy, Phrase, QueryTerm};
 use crate::search::new::Word;
-use crate::{Result, SearchContext, MAX_WORD_LENGTH};
+use crate::{FieldId, Result, SearchContext, MAX_WORD_LENGTH};
+
+/// The operator joining two alternative terms of a query, e.g. `red OR crimson`.
+const OR_OPERATOR: &str = "OR";
 
 #[derive(Clone)]
 /// Extraction of the content of a query.
 pub struct ExtractedTokens {
     /// The terms to search for in the database.
-    pub query_terms: Vec<LocatedQueryTerm>,
+    ///
+    /// Each entry contains the terms that can match at this location of the query:
+    /// a single term, or several terms joined by the `OR` operator.
+    pub query_terms: Vec<Vec<LocatedQueryTerm>>,
     /// The words that must not appear in the results.
     pub negative_words: Vec<Word>,
     /// The phrases that must not appear in the results.
@@ -21,13 +27,26 @@ pub struct ExtractedTokens {
 }
 
 /// Convert the tokenised search query into a list of located query terms.
+///
+/// On top of phrases and negative operators, the following syntax is understood:
+/// - `red OR crimson`: the terms are alternatives matching at the same location of the query
+/// - `title:nike`: the term must be found in the given searchable attribute
+/// - `title:(nike OR adidas)`: all the terms of the group must be found in the given attribute
+///
+/// Parentheses are only used to scope several terms to an attribute, the `OR` operator
+/// always joins the two terms next to it.
 #[tracing::instrument(level = "trace", skip_all, target = "search::query")]
 pub fn located_query_terms_from_tokens(
     ctx: &mut SearchContext<'_>,
+    original_query: &str,
     query: NormalizedTokenIter<'_, '_, '_, '_>,
     words_limit: Option<usize>,
 ) -> Result<ExtractedTokens> {
     let nbr_typos = number_of_typos_allowed(ctx)?;
+    let searchable_fields = ctx.index.searchable_fields_and_weights(ctx.txn)?;
+    let searchable_fid = |name: &str| {
+        searchable_fields.iter().find(|(field, _, _)| field == name).map(|(_, fid, _)| *fid)
+    };
 
     let mut query_terms = Vec::new();
 
@@ -37,6 +56,12 @@ pub fn located_query_terms_from_tokens(
     let mut negative_next_token = false;
     let mut negative_words = Vec::new();
     let mut negative_phrases = Vec::new();
+    // the next term is an alternative of the previous one
+    let mut disjunction_next_term = false;
+    // the attribute the next term is restricted to
+    let mut next_term_fid = None;
+    // the attribute the terms of the current group are restricted to
+    let mut group_fid = None;
 
     let parts_limit = words_limit.unwrap_or(usize::MAX);
 
@@ -56,6 +81,29 @@ pub fn located_query_terms_from_tokens(
 
         match token.kind {
             TokenKind::Word | TokenKind::StopWord => {
+                if phrase.is_none() && !negative_next_token {
+                    let original = original_query.get(token.byte_start..token.byte_end);
+                    // `OR` is only an operator when it is surrounded by terms
+                    if original == Some(OR_OPERATOR)
+                        && !query_terms.is_empty()
+                        && peekable.peek().is_some()
+                    {
+                        disjunction_next_term = true;
+                        continue;
+                    }
+                    // a searchable attribute directly followed by a colon scopes the next term
+                    let followed_by_colon = peekable.peek().map_or(false, |next| {
+                        matches!(next.kind, TokenKind::Separator(_))
+                            && next.lemma().starts_with(':')
+                    });
+                    if let Some(fid) =
+                        original.filter(|_| followed_by_colon).and_then(searchable_fid)
+                    {
+                        next_term_fid = Some(fid);
+                        continue;
+                    }
+                }
+
                 // On first loop, goes from u16::MAX to 0, then normal increment.
                 position = position.wrapping_add(1);
 
@@ -70,38 +118,46 @@ pub fn located_query_terms_from_tokens(
                     negative_words.push(word);
                     negative_next_token = false;
                 } else if peekable.peek().is_some() {
+                    let restricted_fid = next_term_fid.take().or(group_fid);
                     match token.kind {
                         TokenKind::Word => {
                             let word = token.lemma();
-                            let term = partially_initialized_term_from_word(
+                            let mut term = partially_initialized_term_from_word(
                                 ctx,
                                 word,
-                                nbr_typos(word),
+                                nbr_typos(word, restricted_fid),
                                 false,
                                 false,
                             )?;
+                            term.restricted_fid = restricted_fid;
                             let located_term = LocatedQueryTerm {
                                 value: ctx.term_interner.push(term),
                                 positions: position..=position,
                             };
-                            query_terms.push(located_term);
+                            push_query_term(
+                                &mut query_terms,
+                                located_term,
+                                &mut disjunction_next_term,
+                            );
                         }
                         TokenKind::StopWord | TokenKind::Separator(_) | TokenKind::Unknown => (),
                     }
                 } else {
+                    let restricted_fid = next_term_fid.take().or(group_fid);
                     let word = token.lemma();
-                    let term = partially_initialized_term_from_word(
+                    let mut term = partially_initialized_term_from_word(
                         ctx,
                         word,
-                        nbr_typos(word),
+                        nbr_typos(word, restricted_fid),
                         true,
                         false,
                     )?;
+                    term.restricted_fid = restricted_fid;
                     let located_term = LocatedQueryTerm {
                         value: ctx.term_interner.push(term),
                         positions: position..=position,
                     };
-                    query_terms.push(located_term);
+                    push_query_term(&mut query_terms, located_term, &mut disjunction_next_term);
                 }
             }
             TokenKind::Separator(separator_kind) => {
@@ -110,12 +166,24 @@ pub fn located_query_terms_from_tokens(
                     position = position.wrapping_add(7);
                 }
 
+                // an opening parenthesis starts a group of terms, scoped to the attribute preceding it
+                if phrase.is_none() {
+                    for c in token.lemma().chars() {
+                        match c {
+                            '(' => group_fid = next_term_fid.take(),
+                            ')' => group_fid = None,
+                            _ => (),
+                        }
+                    }
+                }
+
                 phrase = 'phrase: {
                     let phrase = phrase.take();
 
                     // If we have a hard separator inside a phrase, we immediately start a new phrase
                     let phrase = if separator_kind == SeparatorKind::Hard {
                         if let Some(phrase) = phrase {
+                            let restricted_fid = phrase.restricted_fid;
                             if let Some(located_query_term) = phrase.build(ctx) {
                                 // as we are evaluating a negative operator we put the phrase
                                 // in the negative one *but* we don't reset the negative operator
@@ -123,10 +191,14 @@ pub fn located_query_terms_from_tokens(
                                 if negative_phrase {
                                     negative_phrases.push(located_query_term);
                                 } else {
-                                    query_terms.push(located_query_term);
+                                    push_query_term(
+                                        &mut query_terms,
+                                        located_query_term,
+                                        &mut disjunction_next_term,
+                                    );
                                 }
                             }
-                            Some(PhraseBuilder::empty())
+                            Some(PhraseBuilder::empty(restricted_fid))
                         } else {
                             None
                         }
@@ -151,7 +223,11 @@ pub fn located_query_terms_from_tokens(
                                 negative_phrases.push(located_query_term);
                                 negative_phrase = false;
                             } else {
-                                query_terms.push(located_query_term);
+                                push_query_term(
+                                    &mut query_terms,
+                                    located_query_term,
+                                    &mut disjunction_next_term,
+                                );
                             }
                         }
                     }
@@ -159,7 +235,9 @@ pub fn located_query_terms_from_tokens(
                     // Start new phrase if the token ends with an opening quote
                     if quote_count % 2 == 1 {
                         negative_phrase = negative_next_token;
-                        Some(PhraseBuilder::empty())
+                        let restricted_fid =
+                            if negative_phrase { None } else { next_term_fid.take().or(group_fid) };
+                        Some(PhraseBuilder::empty(restricted_fid))
                     } else {
                         None
                     }
@@ -182,7 +260,7 @@ pub fn located_query_terms_from_tokens(
             if negative_phrase {
                 negative_phrases.push(located_query_term);
             } else {
-                query_terms.push(located_query_term);
+                push_query_term(&mut query_terms, located_query_term, &mut disjunction_next_term);
             }
         }
     }
@@ -190,17 +268,40 @@ pub fn located_query_terms_from_tokens(
     Ok(ExtractedTokens { query_terms, negative_words, negative_phrases })
 }
 
+/// Push the term as a new location of the query, or as an alternative of
+/// the previous one if they are joined by the `OR` operator.
+fn push_query_term(
+    query_terms: &mut Vec<Vec<LocatedQueryTerm>>,
+    term: LocatedQueryTerm,
+    disjunction: &mut bool,
+) {
+    if std::mem::take(disjunction) {
+        if let Some(alternatives) = query_terms.last_mut() {
+            alternatives.push(term);
+            return;
+        }
+    }
+    query_terms.push(vec![term]);
+}
+
+/// Returns a function giving the number of typos allowed for a word, when
+/// it is restricted to the given field, if any.
+///
+/// The number of typos is capped by the maximum number of typos of the query
+/// and by the maximum number of typos of the field.
 pub fn number_of_typos_allowed<'ctx>(
     ctx: &SearchContext<'ctx>,
-) -> Result<impl Fn(&str) -> u8 + 'ctx> {
+) -> Result<impl Fn(&str, Option<FieldId>) -> u8 + 'ctx> {
     let authorize_typos = ctx.index.authorize_typos(ctx.txn)?;
     let min_len_one_typo = ctx.index.min_word_len_one_typo(ctx.txn)?;
     let min_len_two_typos = ctx.index.min_word_len_two_typos(ctx.txn)?;
+    let max_typos_per_query = ctx.index.max_typos_per_query(ctx.txn)?;
+    let max_typos_per_fid = ctx.index.max_typos_per_field_id(ctx.txn)?;
 
     let exact_words = ctx.index.exact_words(ctx.txn)?;
 
-    Ok(Box::new(move |word: &str| {
-        if !authorize_typos
+    Ok(Box::new(move |word: &str, fid: Option<FieldId>| {
+        let nbr_typos = if !authorize_typos
             || word.len() < min_len_one_typo as usize
             || exact_words.as_ref().map_or(false, |fst| fst.contains(word))
         {
@@ -209,18 +310,25 @@ pub fn number_of_typos_allowed<'ctx>(
             1
         } else {
             2
-        }
+        };
+        let max_typos_of_fid = fid.and_then(|fid| max_typos_per_fid.get(&fid).copied());
+        [Some(nbr_typos), max_typos_per_query, max_typos_of_fid]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap()
     }))
 }
 
 pub fn make_ngram(
     ctx: &mut SearchContext<'_>,
     terms: &[LocatedQueryTerm],
-    number_of_typos_allowed: &impl Fn(&str) -> u8,
+    number_of_typos_allowed: &impl Fn(&str, Option<FieldId>) -> u8,
 ) -> Result<Option<LocatedQueryTerm>> {
     assert!(!terms.is_empty());
     for t in terms {
-        if ctx.term_interner.get(t.value).zero_typo.phrase.is_some() {
+        let term = ctx.term_interner.get(t.value);
+        if term.zero_typo.phrase.is_some() || term.restricted_fid.is_some() {
             return Ok(None);
         }
     }
@@ -251,29 +359,37 @@ pub fn make_ngram(
     let ngram_str_interned = ctx.word_interner.insert(ngram_str.clone());
 
     let max_nbr_typos =
-        number_of_typos_allowed(ngram_str.as_str()).saturating_sub(terms.len() as u8 - 1);
+        number_of_typos_allowed(ngram_str.as_str(), None).saturating_sub(terms.len() as u8 - 1);
 
     let mut term =
         partially_initialized_term_from_word(ctx, &ngram_str, max_nbr_typos, is_prefix, true)?;
 
-    // Now add the synonyms
-    let index_synonyms = ctx.index.synonyms(ctx.txn)?;
-
-    term.zero_typo.synonyms.extend(
-        index_synonyms.get(&words).cloned().unwrap_or_default().into_iter().map(|words| {
-            let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            ctx.phrase_interner.insert(Phrase { words })
-        }),
-    );
+    // Now add the synonyms, a synonym also given outside of the synonym rules
+    // is not ranked after the original words
+    let synonyms = ctx.all_synonyms()?.get(&words).cloned().unwrap_or_default();
+    let rule_synonyms = ctx.synonym_rules()?.get(&words).cloned().unwrap_or_default();
+    let all_synonyms = synonyms
+        .into_iter()
+        .map(|words| (words, false))
+        .chain(rule_synonyms.into_iter().map(|words| (words, true)));
+    for (words, from_rule) in all_synonyms {
+        let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
+        let phrase = ctx.phrase_interner.insert(Phrase { words });
+        if term.zero_typo.synonyms.insert(phrase) && from_rule {
+            term.zero_typo.rule_synonyms.insert(phrase);
+        }
+    }
 
     let term = QueryTerm {
         original: ngram_str_interned,
         ngram_words: Some(words_interned),
         is_prefix,
         max_levenshtein_distance: max_nbr_typos,
+        restricted_fid: None,
         zero_typo: term.zero_typo,
         one_typo: Lazy::Uninit,
         two_typo: Lazy::Uninit,
+        phonetic: BTreeSet::new(),
     };
 
     let term = LocatedQueryTerm { value: ctx.term_interner.push(term), positions: start..=end };
@@ -285,11 +401,12 @@ struct PhraseBuilder {
     words: Vec<Option<crate::search::new::Interned<String>>>,
     start: u16,
     end: u16,
+    restricted_fid: Option<FieldId>,
 }
 
 impl PhraseBuilder {
-    fn empty() -> Self {
-        Self { words: Default::default(), start: u16::MAX, end: u16::MAX }
+    fn empty(restricted_fid: Option<FieldId>) -> Self {
+        Self { words: Default::default(), start: u16::MAX, end: u16::MAX, restricted_fid }
     }
 
     fn is_empty(&self) -> bool {
@@ -329,15 +446,18 @@ impl PhraseBuilder {
                     ngram_words: None,
                     max_levenshtein_distance: 0,
                     is_prefix: false,
+                    restricted_fid: self.restricted_fid,
                     zero_typo: ZeroTypoTerm {
                         phrase: Some(phrase),
                         exact: None,
                         prefix_of: BTreeSet::default(),
                         synonyms: BTreeSet::default(),
+                        rule_synonyms: BTreeSet::default(),
                         use_prefix_db: None,
                     },
                     one_typo: Lazy::Uninit,
                     two_typo: Lazy::Uninit,
+                    phonetic: BTreeSet::default(),
                 }
             }),
             positions: self.start..=self.end,
@@ -374,7 +494,7 @@ mod tests {
         let mut ctx = SearchContext::new(&index, &rtxn)?;
         // panics with `attempt to add with overflow` before <https://github.com/meilisearch/meilisearch/issues/3785>
         let ExtractedTokens { query_terms, .. } =
-            located_query_terms_from_tokens(&mut ctx, tokens, None)?;
+            located_query_terms_from_tokens(&mut ctx, ".", tokens, None)?;
         assert!(query_terms.is_empty());
 
         Ok(())
@@ -62,6 +62,16 @@ impl RankingRuleGraphTrait for FidGraph {
             all_fields.extend(fields);
         }
 
+        // the words sounding like the term are only searched in the phonetic fields
+        let phonetic_words = term.term_subset.phonetic_words(ctx);
+        if !phonetic_words.is_empty() {
+            let phonetic_fids = ctx.phonetic_fids()?.to_vec();
+            for word in phonetic_words {
+                let fields = ctx.get_db_word_fids(word)?;
+                all_fields.extend(fields.into_iter().filter(|fid| phonetic_fids.contains(fid)));
+            }
+        }
+
         for phrase in term.term_subset.all_phrases(ctx)? {
             for &word in phrase.words(ctx).iter().flatten() {
                 let fields = ctx.get_db_word_fids(word)?;
@@ -74,6 +84,11 @@ impl RankingRuleGraphTrait for FidGraph {
             all_fields.extend(fields);
         }
 
+        // a term scoped to an attribute can only be found in this attribute
+        if let Some(fid) = term.term_subset.restricted_fid(ctx) {
+            all_fields.retain(|field| *field == fid);
+        }
+
         let weights_map = ctx.index.fieldids_weights_map(ctx.txn)?;
 
         let mut edges = vec![];
@@ -91,7 +106,8 @@ impl RankingRuleGraphTrait for FidGraph {
         let max_weight: Option<u16> = weights_map.max_weight();
 
         if let Some(max_weight) = max_weight {
-            if !all_fields.contains(&max_weight) {
+            // several fields can share the same weight
+            if !all_fields.iter().any(|fid| weights_map.weight(*fid) == Some(max_weight)) {
                 edges.push((
                     max_weight as u32 * term.term_ids.len() as u32, // TODO improve the fid score i.e. fid^10.
                     conditions_interner.insert(FidCondition {
@@ -18,6 +18,8 @@ mod fid;
 mod position;
 /// Implementation of the `proximity` ranking rule
 mod proximity;
+/// Implementation of the `termRarity` ranking rule
+mod term_rarity;
 /// Implementation of the `typo` ranking rule
 mod typo;
 /// Implementation of the `words` ranking rule
@@ -34,6 +36,7 @@ pub use fid::{FidCondition, FidGraph};
 pub use position::{PositionCondition, PositionGraph};
 pub use proximity::{ProximityCondition, ProximityGraph};
 use roaring::RoaringBitmap;
+pub use term_rarity::{TermRarityCondition, TermRarityGraph};
 pub use typo::{TypoCondition, TypoGraph};
 pub use words::{WordsCondition, WordsGraph};
 
@@ -112,6 +115,32 @@ pub trait RankingRuleGraphTrait: Sized + 'static {
         dest_node: &LocatedQueryTermSubset,
     ) -> Result<Vec<(u32, Interned<Self::Condition>)>>;
 
+    /// Return the cost of ignoring each node of the query graph, along with the nodes
+    /// that can no longer be matched once it is ignored.
+    ///
+    /// The nodes that can be ignored are the ones of the removal order of the terms matching
+    /// strategy. Returning `None` ignores them in this order, at a cost of 1 each.
+    fn cost_of_ignoring_nodes(
+        _ctx: &mut SearchContext<'_>,
+        _query_graph: &QueryGraph,
+        _removal_order: &[SmallBitmap<QueryNode>],
+    ) -> Result<Option<MappedInterner<QueryNode, Option<(u32, SmallBitmap<QueryNode>)>>>> {
+        Ok(None)
+    }
+
+    /// Return the budget shared by the conditions of a path, for constraints spanning the
+    /// whole path that its cost cannot express. It is read once per query.
+    ///
+    /// The documents of the paths whose conditions use more than the budget are never returned.
+    fn path_budget(_ctx: &mut SearchContext<'_>) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
+    /// Return the part of the path budget used by the given condition.
+    fn budget_used_by(_condition: &Self::Condition) -> u32 {
+        0
+    }
+
     /// Convert the rank of a path to its corresponding score for the ranking rule
     fn rank_to_score(rank: Rank) -> ScoreDetails;
 }
@@ -61,6 +61,11 @@ impl RankingRuleGraphTrait for PositionGraph {
             all_positions.extend(positions);
         }
 
+        for word in term.term_subset.phonetic_words(ctx) {
+            let positions = ctx.get_db_word_positions(word)?;
+            all_positions.extend(positions);
+        }
+
         for phrase in term.term_subset.all_phrases(ctx)? {
             // Only check the position of the first word in the phrase
             // this is not correct, but it is the best we can do, since
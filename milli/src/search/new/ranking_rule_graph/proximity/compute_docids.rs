@@ -220,6 +220,9 @@ fn last_words_of_term_derivations(
     for w in t.all_single_words_except_prefix_db(ctx)? {
         result.insert((None, w));
     }
+    for w in t.phonetic_words(ctx) {
+        result.insert((None, Word::Derived(w)));
+    }
     for p in t.all_phrases(ctx)? {
         let phrase = ctx.phrase_interner.get(p);
         let last_term_of_phrase = phrase.words.last().unwrap();
@@ -239,6 +242,9 @@ fn first_word_of_term_iter(
     for w in all_words {
         result.insert((w.interned(), None));
     }
+    for w in t.phonetic_words(ctx) {
+        result.insert((w, None));
+    }
     for p in t.all_phrases(ctx)? {
         let phrase = ctx.phrase_interner.get(p);
         let first_term_of_phrase = phrase.words.first().unwrap();
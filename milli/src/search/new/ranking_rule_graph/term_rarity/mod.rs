@@ -0,0 +1,86 @@
+use roaring::RoaringBitmap;
+
+use super::{ComputedCondition, RankingRuleGraphTrait};
+use crate::score_details::{Rank, ScoreDetails};
+use crate::search::new::interner::{DedupInterner, Interned, MappedInterner};
+use crate::search::new::query_graph::QueryNodeData;
+use crate::search::new::query_term::LocatedQueryTermSubset;
+use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
+use crate::search::new::small_bitmap::SmallBitmap;
+use crate::search::new::{QueryGraph, QueryNode, SearchContext};
+use crate::Result;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TermRarityCondition {
+    term: LocatedQueryTermSubset,
+}
+
+pub enum TermRarityGraph {}
+
+impl RankingRuleGraphTrait for TermRarityGraph {
+    type Condition = TermRarityCondition;
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::term_rarity")]
+    fn resolve_condition(
+        ctx: &mut SearchContext<'_>,
+        condition: &Self::Condition,
+        universe: &RoaringBitmap,
+    ) -> Result<ComputedCondition> {
+        let TermRarityCondition { term } = condition;
+        let docids = compute_query_term_subset_docids(ctx, Some(universe), &term.term_subset)?;
+
+        Ok(ComputedCondition {
+            docids,
+            universe_len: universe.len(),
+            start_term_subset: None,
+            end_term_subset: term.clone(),
+        })
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::term_rarity")]
+    fn build_edges(
+        _ctx: &mut SearchContext<'_>,
+        conditions_interner: &mut DedupInterner<Self::Condition>,
+        _from: Option<&LocatedQueryTermSubset>,
+        to_term: &LocatedQueryTermSubset,
+    ) -> Result<Vec<(u32, Interned<Self::Condition>)>> {
+        Ok(vec![(0, conditions_interner.insert(TermRarityCondition { term: to_term.clone() }))])
+    }
+
+    /// The terms that the matching strategy can remove can be ignored in any order,
+    /// at a cost growing with the rarity of the term.
+    ///
+    /// The cost of ignoring a term is `1 + idf`, where `idf` is the BM25 inverse document frequency
+    /// of the term computed from the number of documents containing it (any of its derivations).
+    /// Documents matching the rarest terms of the query are thus ranked first.
+    fn cost_of_ignoring_nodes(
+        ctx: &mut SearchContext<'_>,
+        query_graph: &QueryGraph,
+        removal_order: &[SmallBitmap<QueryNode>],
+    ) -> Result<Option<MappedInterner<QueryNode, Option<(u32, SmallBitmap<QueryNode>)>>>> {
+        let number_of_documents = ctx.index.number_of_documents(ctx.txn)? as f64;
+        let forbidden_nodes = SmallBitmap::for_interned_values_in(&query_graph.nodes);
+
+        let mut costs = query_graph.nodes.map(|_| None);
+        for node_id in removal_order.iter().flat_map(|nodes| nodes.iter()) {
+            let QueryNodeData::Term(term) = &query_graph.nodes.get(node_id).data else { continue };
+            let document_frequency =
+                compute_query_term_subset_docids(ctx, None, &term.term_subset)?.len() as f64;
+            let cost = 1 + inverse_document_frequency(number_of_documents, document_frequency);
+            *costs.get_mut(node_id) = Some((cost, forbidden_nodes.clone()));
+        }
+        Ok(Some(costs))
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::term_rarity")]
+    fn rank_to_score(rank: Rank) -> ScoreDetails {
+        ScoreDetails::TermRarity(rank)
+    }
+}
+
+/// The BM25 inverse document frequency of a term, rounded to the nearest integer.
+fn inverse_document_frequency(number_of_documents: f64, document_frequency: f64) -> u32 {
+    let idf =
+        (1.0 + (number_of_documents - document_frequency + 0.5) / (document_frequency + 0.5)).ln();
+    idf.round() as u32
+}
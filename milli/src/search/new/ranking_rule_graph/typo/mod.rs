@@ -3,8 +3,10 @@ use roaring::RoaringBitmap;
 use super::{ComputedCondition, RankingRuleGraphTrait};
 use crate::score_details::{self, Rank, ScoreDetails};
 use crate::search::new::interner::{DedupInterner, Interned};
-use crate::search::new::query_term::LocatedQueryTermSubset;
-use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
+use crate::search::new::query_term::{LocatedQueryTermSubset, QueryTermSubset};
+use crate::search::new::resolve_query_graph::{
+    compute_query_term_subset_docids, compute_query_term_subset_docids_within_field_id,
+};
 use crate::search::new::SearchContext;
 use crate::Result;
 
@@ -25,9 +27,13 @@ impl RankingRuleGraphTrait for TypoGraph {
         condition: &Self::Condition,
         universe: &RoaringBitmap,
     ) -> Result<ComputedCondition> {
-        let TypoCondition { term, .. } = condition;
+        let TypoCondition { term, nbr_typos } = condition;
         // maybe compute_query_term_subset_docids should accept a universe as argument
-        let docids = compute_query_term_subset_docids(ctx, Some(universe), &term.term_subset)?;
+        let docids = if *nbr_typos == 0 {
+            compute_query_term_subset_docids(ctx, Some(universe), &term.term_subset)?
+        } else {
+            compute_typo_docids(ctx, universe, &term.term_subset, *nbr_typos)?
+        };
 
         Ok(ComputedCondition {
             docids,
@@ -52,10 +58,13 @@ impl RankingRuleGraphTrait for TypoGraph {
         // 3-gram -> equivalent to 2 typos
         let base_cost = if term.term_ids.len() == 1 { 0 } else { term.term_ids.len() as u32 };
 
-        for nbr_typos in 0..=term.term_subset.max_typo_cost(ctx) {
+        let max_typo_cost = term.term_subset.max_typo_cost(ctx);
+        for nbr_typos in 0..=max_typo_cost {
             let mut term = term.clone();
+            term.term_subset.clear_phonetic_subset();
             match nbr_typos {
                 0 => {
+                    term.term_subset.clear_rule_synonyms(ctx);
                     term.term_subset.clear_one_typo_subset();
                     term.term_subset.clear_two_typo_subset();
                 }
@@ -75,11 +84,81 @@ impl RankingRuleGraphTrait for TypoGraph {
                 conditions_interner.insert(TypoCondition { term, nbr_typos }),
             ));
         }
+
+        // The synonyms derived from the synonym rules have the cost of one typo,
+        // so that the documents containing the original words are ranked first
+        if term.term_subset.has_rule_synonyms(ctx) {
+            let mut term = term.clone();
+            term.term_subset.keep_only_rule_synonyms(ctx);
+            term.term_subset.clear_one_typo_subset();
+            term.term_subset.clear_two_typo_subset();
+            edges.push((
+                1 + base_cost,
+                conditions_interner.insert(TypoCondition { term, nbr_typos: 0 }),
+            ));
+        }
+
+        // The words sounding like the term are ranked after all its typo derivations
+        if term.term_subset.has_phonetic_words(ctx) {
+            let mut term = term.clone();
+            term.term_subset.keep_only_phonetic_words();
+            edges.push((
+                max_typo_cost as u32 + 1 + base_cost,
+                conditions_interner.insert(TypoCondition { term, nbr_typos: 0 }),
+            ));
+        }
         Ok(edges)
     }
 
+    /// The paths with more typos than allowed in a single query are never returned.
+    fn path_budget(ctx: &mut SearchContext<'_>) -> Result<Option<u32>> {
+        Ok(ctx.index.max_typos_per_query(ctx.txn)?.map(u32::from))
+    }
+
+    fn budget_used_by(condition: &Self::Condition) -> u32 {
+        condition.nbr_typos as u32
+    }
+
     #[tracing::instrument(level = "trace", skip_all, target = "search::typo")]
     fn rank_to_score(rank: Rank) -> ScoreDetails {
         ScoreDetails::Typo(score_details::Typo::from_rank(rank))
     }
 }
+
+/// Compute the document ids of the term with the given number of typos, only keeping
+/// the attributes in which that many typos are allowed.
+fn compute_typo_docids(
+    ctx: &mut SearchContext<'_>,
+    universe: &RoaringBitmap,
+    term: &QueryTermSubset,
+    nbr_typos: u8,
+) -> Result<RoaringBitmap> {
+    let max_typos_per_fid = ctx.index.max_typos_per_field_id(ctx.txn)?;
+    let typos_allowed_in = |fid| max_typos_per_fid.get(&fid).map_or(true, |max| *max >= nbr_typos);
+
+    if max_typos_per_fid.values().all(|max| *max >= nbr_typos) {
+        return compute_query_term_subset_docids(ctx, Some(universe), term);
+    }
+    if let Some(fid) = term.restricted_fid(ctx) {
+        return if typos_allowed_in(fid) {
+            compute_query_term_subset_docids(ctx, Some(universe), term)
+        } else {
+            Ok(RoaringBitmap::new())
+        };
+    }
+
+    let fids = match &ctx.restricted_fids {
+        Some(restricted_fids) => restricted_fids.tolerant.iter().map(|(fid, _)| *fid).collect(),
+        None => ctx.index.searchable_fields_ids(ctx.txn)?,
+    };
+    let exact_fids = ctx.index.exact_attributes_ids(ctx.txn)?;
+
+    let mut docids = RoaringBitmap::new();
+    for fid in fids {
+        if typos_allowed_in(fid) && !exact_fids.contains(&fid) {
+            docids |=
+                compute_query_term_subset_docids_within_field_id(ctx, Some(universe), term, fid)?;
+        }
+    }
+    Ok(docids)
+}
@@ -29,12 +29,32 @@ impl<'ctx> SearchContext<'ctx> {
         let docids = &self.phrase_docids.cache[&phrase];
         Ok(docids)
     }
+
+    /// Get the document ids containing the given word in a phonetic field
+    pub fn get_phonetic_word_docids(
+        &mut self,
+        universe: Option<&RoaringBitmap>,
+        word: Interned<String>,
+    ) -> Result<RoaringBitmap> {
+        let mut docids = RoaringBitmap::new();
+        for fid in self.phonetic_fids()?.to_vec() {
+            if let Some(word_fid_docids) = self.get_db_word_fid_docids(universe, word, fid)? {
+                docids |= word_fid_docids;
+            }
+        }
+        Ok(docids)
+    }
 }
 pub fn compute_query_term_subset_docids(
     ctx: &mut SearchContext<'_>,
     universe: Option<&RoaringBitmap>,
     term: &QueryTermSubset,
 ) -> Result<RoaringBitmap> {
+    // a term scoped to an attribute only matches the documents containing it in this attribute
+    if let Some(fid) = term.restricted_fid(ctx) {
+        return compute_query_term_subset_docids_within_field_id(ctx, universe, term, fid);
+    }
+
     let mut docids = RoaringBitmap::new();
     // TODO use the MultiOps trait to do large intersections
     for word in term.all_single_words_except_prefix_db(ctx)? {
@@ -42,6 +62,9 @@ pub fn compute_query_term_subset_docids(
             docids |= word_docids;
         }
     }
+    for word in term.phonetic_words(ctx) {
+        docids |= ctx.get_phonetic_word_docids(universe, word)?;
+    }
     for phrase in term.all_phrases(ctx)? {
         docids |= ctx.get_phrase_docids(phrase)?;
     }
@@ -70,6 +93,13 @@ pub fn compute_query_term_subset_docids_within_field_id(
             docids |= word_fid_docids;
         }
     }
+    if ctx.phonetic_fids()?.contains(&fid) {
+        for word in term.phonetic_words(ctx) {
+            if let Some(word_fid_docids) = ctx.get_db_word_fid_docids(universe, word, fid)? {
+                docids |= word_fid_docids;
+            }
+        }
+    }
 
     for phrase in term.all_phrases(ctx)? {
         // There may be false positives when resolving a phrase, so we're not
@@ -106,6 +136,13 @@ pub fn compute_query_term_subset_docids_within_position(
             docids |= word_position_docids;
         }
     }
+    for word in term.phonetic_words(ctx) {
+        if let Some(word_position_docids) =
+            ctx.get_db_word_position_docids(universe, word, position)?
+        {
+            docids |= ctx.get_phonetic_word_docids(universe, word)? & word_position_docids;
+        }
+    }
 
     for phrase in term.all_phrases(ctx)? {
         // It's difficult to know the expected position of the words in the phrase,
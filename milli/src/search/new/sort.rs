@@ -1,4 +1,5 @@
 use heed::BytesDecode;
+use itertools::Itertools;
 use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
@@ -151,6 +152,19 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                         ),
                     ))
                 });
+                // the booleans are sorted as the `true` and `false` strings, like they
+                // were before having their own facet database
+                let booleans = if self.is_ascending { [false, true] } else { [true, false] };
+                let mut boolean_buckets = Vec::new();
+                for boolean in booleans {
+                    let docids =
+                        ctx.index.bool_faceted_documents_ids(ctx.txn, field_id, boolean)?;
+                    let docids = docids & parent_candidates;
+                    if !docids.is_empty() {
+                        let value = serde_json::Value::String(boolean.to_string());
+                        boolean_buckets.push(Ok((docids, value)));
+                    }
+                }
                 let string_iter = string_iter.map(|r| -> Result<_> {
                     let (docids, bytes) = r?;
                     Ok((
@@ -162,24 +176,36 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                 });
 
                 let query_graph = parent_query.clone();
-                let ascending = self.is_ascending;
                 let field_name = self.field_name.clone();
                 let must_redact = self.must_redact;
-                RankingRuleOutputIterWrapper::new(Box::new(number_iter.chain(string_iter).map(
-                    move |r| {
-                        let (docids, value) = r?;
-                        Ok(RankingRuleOutput {
-                            query: query_graph.clone(),
-                            candidates: docids,
-                            score: ScoreDetails::Sort(score_details::Sort {
-                                field_name: field_name.clone(),
-                                ascending,
-                                redacted: must_redact,
-                                value,
-                            }),
-                        })
-                    },
-                )))
+                let ascending = self.is_ascending;
+                let string_iter = string_iter.merge_by(boolean_buckets, move |string, boolean| {
+                    match (string, boolean) {
+                        (Ok((_, string)), Ok((_, boolean))) => {
+                            let (string, boolean) = (string.as_str(), boolean.as_str());
+                            if ascending {
+                                string <= boolean
+                            } else {
+                                string >= boolean
+                            }
+                        }
+                        _ => true,
+                    }
+                });
+                let iter = number_iter.chain(string_iter);
+                RankingRuleOutputIterWrapper::new(Box::new(iter.map(move |r| {
+                    let (docids, value) = r?;
+                    Ok(RankingRuleOutput {
+                        query: query_graph.clone(),
+                        candidates: docids,
+                        score: ScoreDetails::Sort(score_details::Sort {
+                            field_name: field_name.clone(),
+                            ascending,
+                            redacted: must_redact,
+                            value,
+                        }),
+                    })
+                })))
             }
             None => RankingRuleOutputIterWrapper::new(Box::new(std::iter::empty())),
         };
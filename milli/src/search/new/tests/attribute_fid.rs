@@ -157,3 +157,62 @@ fn test_attribute_fid_ngrams() {
     let document_ids_scores: Vec<_> = documents_ids.iter().zip(document_scores).collect();
     insta::assert_snapshot!(format!("{document_ids_scores:#?}"));
 }
+
+#[test]
+fn test_attribute_fid_weights() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_weighted_searchable_fields(vec![
+                ("title".to_owned(), 1000),
+                ("description".to_owned(), 1000),
+                ("plot".to_owned(), 1),
+            ]);
+            s.set_criteria(vec![Criterion::Attribute]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "", "description": "", "plot": "fox" },
+            { "id": 1, "title": "", "description": "fox", "plot": "" },
+            { "id": 2, "title": "fox", "description": "", "plot": "" },
+        ]))
+        .unwrap();
+
+    // the most important fields have the lowest weight, the title and description are tied,
+    // and the weights are dense ranks whatever the gaps between the user weights
+    db_snap!(index, fieldids_weights_map, @r###"
+    fid weight
+    1   0   |
+    2   0   |
+    3   1   |
+    "###);
+
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("fox");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 0]");
+    drop(txn);
+
+    // without weights, the fields are ranked by their order
+    index
+        .update_settings(|s| {
+            s.set_searchable_fields(vec![
+                "title".to_owned(),
+                "description".to_owned(),
+                "plot".to_owned(),
+            ]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+    assert_eq!(index.user_defined_searchable_fields_weights(&txn).unwrap(), None);
+    let mut s = Search::new(&txn, &index);
+    s.query("fox");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 1, 0]");
+}
@@ -0,0 +1,92 @@
+/*!
+This module tests the boolean syntax of the search query:
+
+1. terms joined by `OR` are alternatives matching at the same location of the query
+2. a lowercase `or` is a regular word
+3. `attribute:term` only matches the documents containing the term in the attribute
+4. `attribute:(term OR term)` scopes all the terms of the group to the attribute
+5. a prefix that isn't a searchable attribute is a regular word
+*/
+
+use crate::index::tests::TempIndex;
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned(), "description".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "red shoes", "description": "for running" },
+            { "id": 1, "title": "crimson shoes", "description": "for walking" },
+            { "id": 2, "title": "blue shoes", "description": "for running" },
+            { "id": 3, "title": "red hat", "description": "for the sun" },
+            { "id": 4, "title": "nike running shoes", "description": "not made by adidas" },
+            { "id": 5, "title": "adidas sneakers", "description": "better than nike" },
+            { "id": 6, "title": "sneakers", "description": "nike air size large" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query(query);
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    collect_field_values(index, &txn, "id", &documents_ids)
+}
+
+#[test]
+fn test_or_operator() {
+    let index = create_index();
+
+    let ids = search(&index, "(red OR crimson) shoes");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1"]"###);
+
+    let ids = search(&index, "red OR crimson");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "3"]"###);
+
+    let ids = search(&index, "shoes red OR crimson OR blue");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2"]"###);
+
+    // `or` is only an operator in uppercase
+    let ids = search(&index, "red or crimson");
+    insta::assert_snapshot!(format!("{ids:?}"), @"[]");
+}
+
+#[test]
+fn test_attribute_scope() {
+    let index = create_index();
+
+    let ids = search(&index, "nike");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4", "5", "6"]"###);
+
+    let ids = search(&index, "title:nike");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4"]"###);
+
+    let ids = search(&index, "description:nike sneakers");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["5", "6"]"###);
+
+    let ids = search(&index, "title:(nike OR adidas)");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4", "5"]"###);
+
+    let ids = search(&index, "running title:(red OR blue)");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "2"]"###);
+
+    // `size` isn't an attribute, so both words must be found in the document
+    let ids = search(&index, "size:nike");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["6"]"###);
+}
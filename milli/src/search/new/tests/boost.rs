@@ -0,0 +1,154 @@
+/*!
+This module tests the `boost(...)` ranking rule:
+
+1. the documents matching the filter are returned before the other ones
+2. the documents not matching the filter are not excluded
+3. multiple boosts rank the documents by the first boost, then by the next ones
+4. whether the document matches the filter is reported in the score details
+5. the attributes of the filter must be filterable, the settings are rejected otherwise
+*/
+
+use big_s::S;
+use maplit::hashset;
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{self, ScoreDetails};
+use crate::search::new::tests::collect_field_values;
+use crate::{
+    Criterion, CriterionError, Error, Search, SearchResult, TermsMatchingStrategy, UserError,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(hashset! { S("brand"), S("is_promoted") });
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello world", "brand": "acme", "is_promoted": false },
+            { "id": 1, "text": "hello", "brand": "bolt", "is_promoted": true },
+            { "id": 2, "text": "hello world", "brand": "bolt" },
+            { "id": 3, "text": "hello world", "brand": "acme", "is_promoted": true },
+            { "id": 4, "text": "hello", "brand": "acme" },
+            { "id": 5, "text": "hello world", "brand": "cheap", "is_promoted": true },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn boost_matches(document_scores: &[Vec<ScoreDetails>]) -> Vec<bool> {
+    document_scores
+        .iter()
+        .map(|scores| {
+            scores
+                .iter()
+                .find_map(|score| match score {
+                    ScoreDetails::Boost(score_details::Boost { matches, .. }) => Some(*matches),
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_boost_placeholder() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Boost(S("brand = acme"))]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "3", "4", "1", "2", "5"]"###);
+    let matches = boost_matches(&document_scores);
+    insta::assert_snapshot!(format!("{matches:?}"), @"[true, true, true, false, false, false]");
+}
+
+#[test]
+fn test_multiple_boosts() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Boost(S("brand = acme")),
+                Criterion::Boost(S("is_promoted = true")),
+            ]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    s.query("hello world");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3", "0", "5", "2", "4", "1"]"###);
+
+    let scores = ScoreDetails::to_json_map(document_scores[1].iter());
+    insta::assert_snapshot!(serde_json::to_string_pretty(&scores).unwrap(), @r###"
+    {
+      "words": {
+        "order": 0,
+        "matchingWords": 2,
+        "maxMatchingWords": 2,
+        "score": 1.0
+      },
+      "boost(brand = acme)": {
+        "order": 1,
+        "matches": true,
+        "score": 1.0
+      },
+      "boost(is_promoted = true)": {
+        "order": 2,
+        "matches": false,
+        "score": 0.5
+      }
+    }
+    "###);
+}
+
+#[test]
+fn test_boost_not_filterable() {
+    let index = create_index();
+
+    let error = index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Boost(S("brand = acme OR text = hello"))]);
+        })
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::UserError(UserError::CriterionError(CriterionError::NotFilterableField { ref field, .. }))
+            if field == "text"
+    ));
+    insta::assert_snapshot!(error, @"`boost(brand = acme OR text = hello)` ranking rule is invalid: the `text` attribute must be filterable.");
+
+    // the attributes of an existing boost can't stop being filterable
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Boost(S("is_promoted = true"))]);
+        })
+        .unwrap();
+    let error =
+        index.update_settings(|s| s.set_filterable_fields(hashset! { S("brand") })).unwrap_err();
+    insta::assert_snapshot!(error, @"`boost(is_promoted = true)` ranking rule is invalid: the `is_promoted` attribute must be filterable.");
+}
@@ -0,0 +1,134 @@
+/*!
+This module tests the `decay(...)` ranking rule:
+
+1. the documents are sorted by the decreasing score of their distance to the origin
+2. the documents within the offset all have the best score
+3. a multi-valued field is scored with its best number
+4. documents with the same score are in the same bucket
+5. the date attributes are scored by their timestamp, and the scale can be a duration
+6. documents without a number in the field appear at the end
+7. the score is reported in the score details
+*/
+
+use std::collections::BTreeSet;
+
+use big_s::S;
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{self, ScoreDetails};
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_date_fields(BTreeSet::from([S("published_at")]));
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello world", "price": 100, "published_at": "2024-01-10" },
+            { "id": 1, "text": "hello", "price": 104, "published_at": "2024-01-01" },
+            { "id": 2, "text": "hello world", "price": 80, "published_at": "2023-12-01" },
+            { "id": 3, "text": "hello world", "price": [20, 112], "published_at": "2024-01-14" },
+            { "id": 4, "text": "hello", "price": 130, "published_at": "2022-06-01" },
+            { "id": 5, "text": "hello world", "price": "cheap" },
+            { "id": 6, "text": "hello", "price": 97, "published_at": "2024-01-09" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn decay_scores(document_scores: &[Vec<ScoreDetails>]) -> Vec<Option<f64>> {
+    document_scores
+        .iter()
+        .map(|scores| {
+            scores
+                .iter()
+                .find_map(|score| match score {
+                    ScoreDetails::Decay(score_details::Decay { score, .. }) => {
+                        Some(score.map(|score| (score * 100.).round() / 100.))
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_decay_numbers() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Decay(S("price, origin=100, scale=10, offset=5")),
+            ]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "6", "3", "2", "4", "5"]"###);
+    let scores = decay_scores(&document_scores);
+    insta::assert_snapshot!(format!("{scores:?}"), @"[Some(1.0), Some(1.0), Some(1.0), Some(0.71), Some(0.21), Some(0.01), None]");
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    s.query("hello world");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "3", "2", "5", "1", "6", "4"]"###);
+
+    let scores = ScoreDetails::to_json_map(document_scores[1].iter());
+    insta::assert_snapshot!(serde_json::to_string_pretty(&scores).unwrap(), @r###"
+    {
+      "words": {
+        "order": 0,
+        "matchingWords": 2,
+        "maxMatchingWords": 2,
+        "score": 1.0
+      },
+      "decay(price, origin=100, scale=10, offset=5)": {
+        "order": 1,
+        "score": 0.712025097798536
+      }
+    }
+    "###);
+}
+
+#[test]
+fn test_decay_dates() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Decay(S(
+                "published_at, origin=2024-01-10, scale=7d, shape=linear",
+            ))]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "6", "3", "1", "2", "4", "5"]"###);
+    let scores = decay_scores(&document_scores);
+    insta::assert_snapshot!(format!("{scores:?}"), @"[Some(1.0), Some(0.93), Some(0.71), Some(0.36), Some(0.0), Some(0.0), None]");
+}
@@ -0,0 +1,143 @@
+/*!
+This module tests the `score:` ranking rule:
+
+1. the documents are sorted by the decreasing value of the expression
+2. the fields of the expression must be sortable or filterable
+3. a multi-valued field is evaluated with its highest number
+4. documents for which a field has no number, or for which the expression is not a finite number, appear at the end
+5. the value of the expression is reported in the score details
+*/
+
+use big_s::S;
+use maplit::hashset;
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{self, ScoreDetails};
+use crate::search::new::tests::collect_field_values;
+use crate::{
+    Criterion, CriterionError, Error, Search, SearchResult, TermsMatchingStrategy, UserError,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_sortable_fields(hashset! { S("popularity"), S("rating") });
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello world", "popularity": 0, "rating": 4 },
+            { "id": 1, "text": "hello", "popularity": 99, "rating": 3 },
+            { "id": 2, "text": "hello world", "popularity": 9, "rating": 2 },
+            { "id": 3, "text": "hello world", "rating": 5 },
+            { "id": 4, "text": "hello", "popularity": [1, 99], "rating": 3 },
+            { "id": 5, "text": "hello world", "popularity": "12", "rating": 1 },
+            { "id": 6, "text": "hello", "popularity": 0, "rating": -4.5 },
+            { "id": 7, "text": "hello world", "popularity": -3, "rating": 2 },
+        ]))
+        .unwrap();
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Score(S("log1p(popularity) * 0.3 + rating")),
+            ]);
+        })
+        .unwrap();
+
+    index
+}
+
+fn function_score_values(document_scores: &[Vec<ScoreDetails>]) -> Vec<Option<f64>> {
+    document_scores
+        .iter()
+        .map(|scores| {
+            scores
+                .iter()
+                .find_map(|score| match score {
+                    ScoreDetails::FunctionScore(score_details::FunctionScore { value, .. }) => {
+                        Some(value.map(|value| (value * 100.).round() / 100.))
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_function_score_placeholder() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "4", "0", "2", "6", "3", "5", "7"]"###);
+    let values = function_score_values(&document_scores);
+    insta::assert_snapshot!(format!("{values:?}"), @"[Some(4.38), Some(4.38), Some(4.0), Some(2.69), Some(-4.5), None, None, None]");
+}
+
+#[test]
+fn test_function_score_after_words() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+    s.query("hello world");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "2", "3", "5", "7", "1", "4", "6"]"###);
+    let values = function_score_values(&document_scores);
+    insta::assert_snapshot!(format!("{values:?}"), @"[Some(4.0), Some(2.69), None, None, None, Some(4.38), Some(4.38), Some(-4.5)]");
+
+    let scores = ScoreDetails::to_json_map(document_scores[0].iter());
+    insta::assert_snapshot!(serde_json::to_string_pretty(&scores).unwrap(), @r###"
+    {
+      "words": {
+        "order": 0,
+        "matchingWords": 2,
+        "maxMatchingWords": 2,
+        "score": 1.0
+      },
+      "score:log1p(popularity) * 0.3 + rating": {
+        "order": 1,
+        "value": 4.0
+      }
+    }
+    "###);
+}
+
+#[test]
+fn test_function_score_fields_must_be_faceted() {
+    let index = create_index();
+
+    let error = index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Score(S("rating + id"))]);
+        })
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::UserError(UserError::CriterionError(CriterionError::NotFacetedField { ref field, .. }))
+            if field == "id"
+    ));
+    insta::assert_snapshot!(error, @"`score:rating + id` ranking rule is invalid: the `id` attribute must be filterable or sortable.");
+
+    // the fields of an existing ranking rule can't stop being faceted
+    let error = index.update_settings(|s| s.reset_sortable_fields()).unwrap_err();
+    insta::assert_snapshot!(error, @"`score:log1p(popularity) * 0.3 + rating` ranking rule is invalid: the `popularity` attribute must be filterable or sortable.");
+}
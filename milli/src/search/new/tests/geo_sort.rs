@@ -307,3 +307,74 @@ fn geo_sort_without_any_geo_faceted_documents() {
     insta::assert_snapshot!(format!("{ids:?}"), @"[0, 2, 3]");
     insta::assert_snapshot!(format!("{scores:#?}"));
 }
+
+#[test]
+fn geo_sort_multiple_points() {
+    let index = create_index();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "_geo": [{ "lat": 10, "lng": 10 }, { "lat": 1, "lng": 0 }] },
+            { "id": 1, "_geo": { "lat": 2, "lng": 0 } },
+            { "id": 2, "_geo": [{ "lat": 3, "lng": 0 }, { "lat": -20, "lng": 0 }] },
+            { "id": 3, "_geo": [{ "lat": 0, "lng": 0.5 }] },
+            { "id": 4 },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&rtxn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+
+    // the documents are sorted by their closest point
+    s.sort_criteria(vec![AscDesc::Asc(Member::Geo([0., 0.]))]);
+    let (ids, scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[3, 0, 1, 2, 4]");
+    // and the score details tell which point matched
+    let geo_values: Vec<_> = scores
+        .iter()
+        .map(|scores| {
+            scores.iter().find_map(|score| match score {
+                ScoreDetails::GeoSort(geo_sort) => Some(geo_sort.value),
+                _ => None,
+            })
+        })
+        .collect();
+    insta::assert_snapshot!(format!("{geo_values:?}"), @"[Some(Some([0.0, 0.5])), Some(Some([1.0, 0.0])), Some(Some([2.0, 0.0])), Some(Some([3.0, 0.0])), Some(None)]");
+
+    s.sort_criteria(vec![AscDesc::Desc(Member::Geo([0., 0.]))]);
+    let (ids, _scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[2, 1, 0, 3, 4]");
+}
+
+#[test]
+fn geo_sort_shapes() {
+    let index = create_index();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "_geo": { "lat": 0, "lng": 0 } },
+            { "id": 1, "_geo": { "type": "LineString", "coordinates": [[2, -1], [2, 1]] } },
+            { "id": 2, "_geo": { "lat": 0, "lng": 3 } },
+            { "id": 3, "_geo": { "type": "Polygon", "coordinates": [[[4, -1], [5, -1], [5, 1], [4, 1], [4, -1]]] } },
+            { "id": 4, "_geo": { "type": "Point", "coordinates": [6, 0] } },
+            { "id": 5, "_geo": { "type": "Polygon", "coordinates": [[[-10, -1], [-9, -1], [-9, 1], [-10, 1], [-10, -1]]] } },
+            { "id": 6, "_geo": { "lat": 0, "lng": -7 } },
+            { "id": 7 },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&rtxn, &index);
+
+    // the shapes are sorted by their nearest point, whatever the size of the buffers
+    s.sort_criteria(vec![AscDesc::Asc(Member::Geo([0., 0.]))]);
+    let (ids, _scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[0, 1, 2, 3, 4, 6, 5, 7]");
+
+    s.sort_criteria(vec![AscDesc::Desc(Member::Geo([0., 0.]))]);
+    let (ids, _scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[5, 6, 4, 3, 2, 1, 0, 7]");
+}
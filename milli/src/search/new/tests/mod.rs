@@ -1,19 +1,27 @@
 pub mod attribute_fid;
 pub mod attribute_position;
+pub mod boolean_query;
+pub mod boost;
 pub mod cutoff;
+pub mod decay;
 pub mod distinct;
 pub mod exactness;
+pub mod function_score;
 pub mod geo_sort;
 pub mod integration;
 #[cfg(feature = "all-tokenizations")]
 #[cfg(not(feature = "chinese-pinyin"))]
 pub mod language;
 pub mod ngram_split_words;
+pub mod phonetic;
 pub mod proximity;
 pub mod proximity_typo;
 pub mod sort;
 pub mod stop_words;
+pub mod synonyms;
+pub mod term_rarity;
 pub mod typo;
+pub mod typo_budget;
 pub mod typo_proximity;
 pub mod words_tms;
 
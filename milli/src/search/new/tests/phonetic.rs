@@ -0,0 +1,93 @@
+/*!
+This module tests the phonetic matching of the words of the phonetic attributes:
+
+1. the words of the phonetic attributes sounding like a query word are matched
+only once the setting is enabled
+2. the documents matching them are ranked after the exact and typo matches
+3. the words too short to tolerate typos still match the words sounding alike
+4. the words sounding alike are only matched in the phonetic attributes
+5. they are not matched when the typo tolerance is disabled, nor for the exact words
+*/
+
+use std::collections::BTreeSet;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["name".to_owned(), "nickname".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Typo]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "rupert" },
+            { "id": 1, "name": "robert" },
+            { "id": 2, "name": "robbert" },
+            { "id": 3, "name": "sean" },
+            { "id": 4, "name": "shaun" },
+            { "id": 5, "nickname": "shaun" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query(query);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    collect_field_values(index, &txn, "id", &documents_ids)
+}
+
+#[test]
+fn test_phonetic_attributes() {
+    let index = create_index();
+
+    let ids = search(&index, "robert");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2"]"###);
+
+    index
+        .update_settings(|s| {
+            s.set_phonetic_fields(BTreeSet::from_iter(["name".to_owned()]));
+        })
+        .unwrap();
+
+    // rupert sounds like robert but is two typos away, it comes after the typo matches
+    let ids = search(&index, "robert");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2", "0"]"###);
+
+    // sean is too short to tolerate typos but still sounds like shaun,
+    // which is not matched in the nickname attribute
+    let ids = search(&index, "sean");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3", "4"]"###);
+
+    index.update_settings(|s| s.set_exact_words(BTreeSet::from_iter(["sean".to_owned()]))).unwrap();
+    let ids = search(&index, "sean");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3"]"###);
+
+    index
+        .update_settings(|s| {
+            s.reset_exact_words();
+            s.set_autorize_typos(false);
+        })
+        .unwrap();
+    let ids = search(&index, "sean");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3"]"###);
+    index.update_settings(|s| s.reset_authorize_typos()).unwrap();
+
+    index.update_settings(|s| s.reset_phonetic_fields()).unwrap();
+
+    let ids = search(&index, "robert");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2"]"###);
+}
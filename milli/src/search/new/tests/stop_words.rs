@@ -7,13 +7,14 @@ This module tests the following properties about stop words:
 - If a query consists only of stop words, a placeholder query is used instead
 - A prefix word is never ignored, even if the prefix is a stop word
 - Phrases consisting only of stop words are ignored
+- Stop words given at search time are ignored in the query, but not in the documents
 */
 
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 use crate::index::tests::TempIndex;
-use crate::{Search, SearchResult, TermsMatchingStrategy};
+use crate::{Error, Search, SearchResult, TermsMatchingStrategy, UserError, MAX_WORD_LENGTH};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -459,3 +460,41 @@ fn test_stop_words_in_phrase() {
     // As a result the scores are empty lists
     insta::assert_snapshot!(format!("{document_scores:#?}"));
 }
+
+#[test]
+fn test_search_time_stop_words() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("captain room");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+
+    // `captain` is ignored in the query, but it is still indexed
+    let mut s = Search::new(&txn, &index);
+    s.query("captain room");
+    s.stop_words(BTreeSet::from_iter(["captain".to_owned()]));
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("captain");
+    s.stop_words(BTreeSet::from_iter(["captain".to_owned(), "marvel".to_owned()]));
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // the last word of the query is a prefix, so it is never ignored
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("captain");
+    s.stop_words(BTreeSet::from_iter(["a".repeat(MAX_WORD_LENGTH + 1)]));
+    let error = s.execute().unwrap_err();
+    assert!(
+        matches!(error, Error::UserError(UserError::SearchStopWordTooLong { .. })),
+        "{error:?}"
+    );
+}
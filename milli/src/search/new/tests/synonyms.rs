@@ -0,0 +1,217 @@
+/*!
+This module tests the synonyms given at search time:
+
+1. they are used on top of the synonyms of the index
+2. they are normalized like the synonyms of the index
+3. they only apply to the search they were given to
+4. their number is limited
+
+And the synonym rules of the index:
+
+5. a comma-separated list of words are all synonyms of each other
+6. `word => synonym` only gives synonyms to the words on the left
+7. `<placeholder>` is a synonym of all the values of the entry
+8. the keys of the synonyms are never read as rules
+9. documents matching the original word are ranked before the ones matching a synonym rule
+*/
+
+use std::collections::BTreeMap;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, Error, Search, SearchResult, TermsMatchingStrategy, UserError};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_synonyms(BTreeMap::from_iter([(
+                "sneakers".to_owned(),
+                vec!["trainers".to_owned()],
+            )]));
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "running shoes" },
+            { "id": 1, "text": "running trainers" },
+            { "id": 2, "text": "running sneakers" },
+            { "id": 3, "text": "climbing boots" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(
+    index: &TempIndex,
+    query: &str,
+    synonyms: Option<BTreeMap<String, Vec<String>>>,
+) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query(query);
+    if let Some(synonyms) = synonyms {
+        s.synonyms(synonyms);
+    }
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    collect_field_values(index, &txn, "id", &documents_ids)
+}
+
+#[test]
+fn test_search_time_synonyms() {
+    let index = create_index();
+
+    let ids = search(&index, "sneakers running", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2"]"###);
+
+    let synonyms = BTreeMap::from_iter([("Sneakers".to_owned(), vec!["SHOES".to_owned()])]);
+    let ids = search(&index, "sneakers running", Some(synonyms));
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2"]"###);
+
+    let synonyms = BTreeMap::from_iter([(
+        "footwear".to_owned(),
+        vec!["shoes".to_owned(), "climbing boots".to_owned()],
+    )]);
+    let ids = search(&index, "footwear", Some(synonyms));
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "3"]"###);
+
+    // the synonyms of a search don't leak into the next ones
+    let ids = search(&index, "footwear", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[]");
+}
+
+#[test]
+fn test_too_many_search_time_synonyms() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("sneakers");
+    s.synonyms((0..=100).map(|i| (format!("word{i}"), vec!["shoes".to_owned()])).collect());
+    let error = s.execute().unwrap_err();
+    assert!(
+        matches!(
+            error,
+            Error::UserError(UserError::TooManySearchSynonyms { count: 101, limit: 100 })
+        ),
+        "{error:?}"
+    );
+
+    let mut s = Search::new(&txn, &index);
+    s.query("sneakers");
+    s.synonyms(BTreeMap::from_iter([(
+        "sneakers".to_owned(),
+        (0..=50).map(|i| format!("word{i}")).collect(),
+    )]));
+    let error = s.execute().unwrap_err();
+    assert!(
+        matches!(
+            error,
+            Error::UserError(UserError::TooManySearchSynonymsForWord { count: 51, limit: 50, .. })
+        ),
+        "{error:?}"
+    );
+
+    let mut s = Search::new(&txn, &index);
+    s.query("sneakers");
+    s.synonyms(BTreeMap::from_iter([("sneakers".to_owned(), vec!["a".repeat(513)])]));
+    let error = s.execute().unwrap_err();
+    assert!(
+        matches!(error, Error::UserError(UserError::SearchSynonymTooLong { limit: 512, .. })),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn test_synonym_rules() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_synonym_rules(BTreeMap::from_iter([
+                ("shoes, boots".to_owned(), vec![]),
+                ("climbing => running".to_owned(), vec![]),
+                ("<number>".to_owned(), vec!["nine".to_owned(), "ten".to_owned()]),
+            ]));
+        })
+        .unwrap();
+    index.add_documents(documents!([{ "id": 4, "text": "size <number> trail shoes" }])).unwrap();
+
+    let ids = search(&index, "boots", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "3", "4"]"###);
+    let ids = search(&index, "shoes", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "3", "4"]"###);
+
+    let ids = search(&index, "climbing", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2", "3"]"###);
+    // the rule only goes one way
+    let ids = search(&index, "running", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2"]"###);
+
+    let ids = search(&index, "ten", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4"]"###);
+
+    // the synonyms given at search time are not rules
+    let synonyms = BTreeMap::from_iter([("sneakers => shoes".to_owned(), vec![])]);
+    let ids = search(&index, "sneakers", Some(synonyms));
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2"]"###);
+}
+
+#[test]
+fn test_synonyms_are_not_rules() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_synonyms(BTreeMap::from_iter([
+                ("shoes, boots".to_owned(), vec!["footwear".to_owned()]),
+                ("<number>".to_owned(), vec!["ten".to_owned()]),
+            ]));
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 4, "text": "size <number> trail shoes" },
+            { "id": 5, "text": "footwear" },
+        ]))
+        .unwrap();
+
+    let ids = search(&index, "boots", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3"]"###);
+    let ids = search(&index, "shoes boots", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["5"]"###);
+    let ids = search(&index, "ten", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[]");
+    let ids = search(&index, "number", None);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4"]"###);
+}
+
+#[test]
+fn test_original_word_ranked_before_synonyms() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Typo]);
+            s.set_synonym_rules(BTreeMap::from_iter([(
+                "sneakers".to_owned(),
+                vec!["shoes".to_owned()],
+            )]));
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    // the synonyms of the index cost nothing, unlike the ones of the synonym rules
+    let mut s = Search::new(&txn, &index);
+    s.query("sneakers");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2", "0"]"###);
+}
@@ -0,0 +1,205 @@
+/*!
+This module tests the `termRarity` ranking rule:
+
+1. the documents matching the rarest terms of the query are returned first,
+even when they match fewer terms than the others
+2. the rank of each bucket is reported in the score details
+3. the `words` ranking rule has no effect after `termRarity`, and `termRarity` can't be placed after it
+4. only the terms the matching strategy can remove are ignored, the rule has no effect
+when the `all` term matching strategy is used
+*/
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::search::new::tests::collect_field_values;
+use crate::{
+    Criterion, CriterionError, Error, Search, SearchResult, TermsMatchingStrategy, UserError,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::TermRarity]);
+        })
+        .unwrap();
+
+    // `common` is in every document, `medium` in three of them and `rare` in only one
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "common" },
+            { "id": 1, "text": "common medium" },
+            { "id": 2, "text": "common medium" },
+            { "id": 3, "text": "common medium" },
+            { "id": 4, "text": "common rare" },
+            { "id": 5, "text": "common" },
+            { "id": 6, "text": "common" },
+            { "id": 7, "text": "common" },
+            { "id": 8, "text": "common" },
+            { "id": 9, "text": "common" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn term_rarity_scores(document_scores: &[Vec<ScoreDetails>]) -> Vec<f64> {
+    document_scores
+        .iter()
+        .map(|scores| {
+            scores
+                .iter()
+                .find_map(|score| match score {
+                    ScoreDetails::TermRarity(rank) => Some(rank.local_score()),
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_term_rarity() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("common medium rare");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4", "1", "2", "3", "0", "5", "6", "7", "8", "9"]"###);
+
+    // with the `words` ranking rule, the documents matching the most terms come first
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("common medium rare");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2", "3", "0", "4", "5", "6", "7", "8", "9"]"###);
+}
+
+#[test]
+fn test_term_rarity_score_details() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.query("common medium rare");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4", "1", "2", "3", "0", "5", "6", "7", "8", "9"]"###);
+
+    let scores = term_rarity_scores(&document_scores);
+    // the documents of the same bucket share the same score
+    assert_eq!(scores[1], scores[3]);
+    assert_eq!(scores[4], scores[9]);
+    // and the buckets are returned by decreasing score
+    assert!(scores[0] > scores[1], "{scores:?}");
+    assert!(scores[1] > scores[4], "{scores:?}");
+
+    let details = ScoreDetails::to_json_map(document_scores[0].iter());
+    assert_eq!(details["termRarity"]["order"], 0);
+    assert_eq!(details["termRarity"]["score"], scores[0]);
+}
+
+#[test]
+fn test_words_after_term_rarity() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::TermRarity, Criterion::Words]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.query("common medium rare");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4", "1", "2", "3", "0", "5", "6", "7", "8", "9"]"###);
+    assert!(document_scores.iter().flatten().all(|score| !matches!(score, ScoreDetails::Words(_))));
+}
+
+#[test]
+fn test_term_rarity_after_words() {
+    let index = create_index();
+
+    for criteria in [
+        vec![Criterion::Words, Criterion::TermRarity],
+        vec![Criterion::Typo, Criterion::TermRarity, Criterion::Words],
+    ] {
+        let error = index.update_settings(|s| s.set_criteria(criteria.clone())).unwrap_err();
+        assert!(
+            matches!(
+                error,
+                Error::UserError(UserError::CriterionError(
+                    CriterionError::TermRarityAfterWords { .. }
+                ))
+            ),
+            "{criteria:?}: {error}"
+        );
+    }
+
+    let error = index
+        .update_settings(|s| s.set_criteria(vec![Criterion::Proximity, Criterion::TermRarity]))
+        .unwrap_err();
+    insta::assert_snapshot!(error, @"`termRarity` ranking rule is invalid: it replaces `words` and must be placed before the `proximity` ranking rule.");
+}
+
+#[test]
+fn test_term_rarity_matching_strategy() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    // the first term is never removed by the `last` strategy, the documents without it don't match
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("medium rare common");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2", "3"]"###);
+
+    // the rarest term is never removed by the `frequency` strategy
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Frequency);
+    s.query("common medium rare");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["4"]"###);
+}
+
+#[test]
+fn test_term_rarity_matching_strategy_all() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.query("common medium");
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "2", "3"]"###);
+    assert!(document_scores.iter().all(|scores| scores.is_empty()));
+}
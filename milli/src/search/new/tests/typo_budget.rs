@@ -0,0 +1,141 @@
+/*!
+This module tests the typo budgets of the index:
+
+1. the documents matching a word with more typos than allowed in an attribute
+are not returned, unless the word is found in another attribute allowing them
+2. the number of typos of a single word cannot exceed the budget of the query
+3. the documents matching the words of the query with more typos in total
+than allowed in a single query are not returned
+4. the budgets of the attributes are at most 2 typos, for searchable attributes only
+*/
+
+use std::collections::BTreeMap;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::tests::collect_field_values;
+use crate::{Error, Search, SearchResult, TermsMatchingStrategy, UserError};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["sku".to_owned(), "brand".to_owned(), "title".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "sku": "summertime" },
+            { "id": 1, "sku": "sumertime" },
+            { "id": 2, "brand": "sumertime" },
+            { "id": 3, "brand": "sumertme" },
+            { "id": 4, "title": "sumertme" },
+            { "id": 5, "title": "sumertime wintrtime" },
+            { "id": 6, "title": "sumertime wintertime" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query(query);
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    collect_field_values(index, &txn, "id", &documents_ids)
+}
+
+#[test]
+fn test_max_typos_per_attribute() {
+    let index = create_index();
+
+    let ids = search(&index, "summertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2", "3", "4", "5", "6"]"###);
+
+    index
+        .update_settings(|s| {
+            s.set_max_typos_per_attribute(BTreeMap::from_iter([
+                ("sku".to_owned(), 0),
+                ("brand".to_owned(), 1),
+            ]));
+        })
+        .unwrap();
+
+    let ids = search(&index, "summertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "2", "4", "5", "6"]"###);
+
+    // the budget of the attribute also applies to the terms restricted to it
+    let ids = search(&index, "brand:summertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["2"]"###);
+}
+
+#[test]
+fn test_max_typos_per_query() {
+    let index = create_index();
+
+    let ids = search(&index, "summertime wintertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["5", "6"]"###);
+
+    index
+        .update_settings(|s| {
+            s.set_max_typos_per_query(1);
+        })
+        .unwrap();
+
+    let ids = search(&index, "summertime wintertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["6"]"###);
+
+    let ids = search(&index, "summertime");
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["0", "1", "2", "5", "6"]"###);
+}
+
+#[test]
+fn test_invalid_max_typos_per_attribute() {
+    let index = create_index();
+
+    let error = index
+        .update_settings(|s| {
+            s.set_max_typos_per_attribute(BTreeMap::from_iter([("sku".to_owned(), 3)]));
+        })
+        .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            Error::UserError(UserError::InvalidMaxTyposPerAttribute { max_typos: 3, .. })
+        ),
+        "{error:?}"
+    );
+
+    let error = index
+        .update_settings(|s| {
+            s.set_max_typos_per_attribute(BTreeMap::from_iter([("description".to_owned(), 1)]));
+        })
+        .unwrap_err();
+    assert!(
+        matches!(error, Error::UserError(UserError::InvalidMaxTyposAttribute { .. })),
+        "{error:?}"
+    );
+
+    index
+        .update_settings(|s| {
+            s.set_max_typos_per_attribute(BTreeMap::from_iter([("sku".to_owned(), 1)]));
+        })
+        .unwrap();
+
+    // the attribute can no longer be made unsearchable
+    let error = index
+        .update_settings(|s| {
+            s.set_searchable_fields(vec!["brand".to_owned(), "title".to_owned()]);
+        })
+        .unwrap_err();
+    assert!(
+        matches!(error, Error::UserError(UserError::InvalidMaxTyposAttribute { .. })),
+        "{error:?}"
+    );
+}
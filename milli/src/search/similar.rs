@@ -135,6 +135,7 @@ impl<'a> Similar<'a> {
         Ok(SearchResult {
             matching_words: Default::default(),
             candidates,
+            disjunctive_candidates: Vec::new(),
             documents_ids,
             document_scores,
             degraded: false,
@@ -229,6 +229,11 @@ pub fn snap_word_fid_docids(index: &Index) -> String {
         &format!("{word:<16} {fid:<3} {}", display_bitmap(&b))
     })
 }
+pub fn snap_phonetic_word_docids(index: &Index) -> String {
+    make_db_snap_from_iter!(index, phonetic_word_docids, |(s, b)| {
+        &format!("{s:<16} {}", display_bitmap(&b))
+    })
+}
 pub fn snap_field_id_word_count_docids(index: &Index) -> String {
     make_db_snap_from_iter!(index, field_id_word_count_docids, |((field_id, word_count), b)| {
         &format!("{field_id:<3} {word_count:<6} {}", display_bitmap(&b))
@@ -262,6 +267,11 @@ pub fn snap_facet_id_is_empty_docids(index: &Index) -> String {
         &format!("{facet_id:<3} {}", display_bitmap(&docids))
     })
 }
+pub fn snap_facet_id_bool_docids(index: &Index) -> String {
+    make_db_snap_from_iter!(index, facet_id_bool_docids, |((facet_id, boolean), docids)| {
+        &format!("{facet_id:<3} {boolean:<5} {}", display_bitmap(&docids))
+    })
+}
 pub fn snap_facet_id_string_docids(index: &Index) -> String {
     make_db_snap_from_iter!(index, facet_id_string_docids, |(
         FacetGroupKey { field_id, level, left_bound },
@@ -452,6 +462,9 @@ macro_rules! full_snap_of_db {
     ($index:ident, word_fid_docids) => {{
         $crate::snapshot_tests::snap_word_fid_docids(&$index)
     }};
+    ($index:ident, phonetic_word_docids) => {{
+        $crate::snapshot_tests::snap_phonetic_word_docids(&$index)
+    }};
     ($index:ident, field_id_word_count_docids) => {{
         $crate::snapshot_tests::snap_field_id_word_count_docids(&$index)
     }};
@@ -476,6 +489,9 @@ macro_rules! full_snap_of_db {
     ($index:ident, facet_id_is_empty_docids) => {{
         $crate::snapshot_tests::snap_facet_id_is_empty_docids(&$index)
     }};
+    ($index:ident, facet_id_bool_docids) => {{
+        $crate::snapshot_tests::snap_facet_id_bool_docids(&$index)
+    }};
     ($index:ident, documents_ids) => {{
         $crate::snapshot_tests::snap_documents_ids(&$index)
     }};
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+/// The operator separating the words of a one-way rule from their synonyms.
+const ONE_WAY_OPERATOR: &str = "=>";
+
+/// Expands the synonym rules written by the user into a list of words along with their synonyms.
+///
+/// Unlike the keys of the synonyms, which are always read as written, the key of each entry
+/// of the synonym rules is a rule that can be:
+/// - a word or phrase, whose synonyms are the values of the entry: `"ipod": ["music player"]`;
+/// - a comma-separated list of equivalent words or phrases, which are all synonyms of each other
+///   and of the values of the entry: `"ipod, music player": []`;
+/// - a one-way rule, giving the words on the left of `=>` the words on its right and the values
+///   of the entry as synonyms: `"ipod => ipod, music player": []`;
+/// - a placeholder between angle brackets, which becomes a synonym of each value of the entry:
+///   `"<streetnumber>": ["1", "2"]`. Searching for any of the values then returns the documents
+///   containing the placeholder.
+///
+/// The words and synonyms are returned as written, they still have to be normalized.
+pub fn expand_synonym_rules(synonyms: &BTreeMap<String, Vec<String>>) -> Vec<(&str, Vec<&str>)> {
+    let mut expanded = Vec::new();
+    for (rule, values) in synonyms {
+        let values = values.iter().map(String::as_str);
+        if let Some((words, synonyms)) = rule.split_once(ONE_WAY_OPERATOR) {
+            let synonyms: Vec<_> = split_list(synonyms).chain(values).collect();
+            expanded.extend(split_list(words).map(|word| (word, synonyms.clone())));
+        } else if let Some(placeholder) = rule.strip_prefix('<').and_then(|r| r.strip_suffix('>')) {
+            expanded.extend(values.map(|value| (value, vec![placeholder])));
+        } else if rule.contains(',') {
+            let equivalents: Vec<_> = split_list(rule).chain(values).collect();
+            for word in &equivalents {
+                let synonyms = equivalents.iter().filter(|synonym| *synonym != word).copied();
+                expanded.push((*word, synonyms.collect()));
+            }
+        } else {
+            expanded.push((rule.as_str(), values.collect()));
+        }
+    }
+    expanded
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::btreemap;
+
+    use super::*;
+
+    #[test]
+    fn expand_rules() {
+        let synonyms = btreemap! {
+            S("sneakers") => vec![S("trainers")],
+            S("ipod, music player") => vec![S("mp3 player")],
+            S("tv, television => telly, tube") => vec![S("box")],
+            S("<streetnumber>") => vec![S("1"), S("2")],
+        };
+        assert_eq!(
+            expand_synonym_rules(&synonyms),
+            vec![
+                ("1", vec!["streetnumber"]),
+                ("2", vec!["streetnumber"]),
+                ("ipod", vec!["music player", "mp3 player"]),
+                ("music player", vec!["ipod", "mp3 player"]),
+                ("mp3 player", vec!["ipod", "music player"]),
+                ("sneakers", vec!["trainers"]),
+                ("tv", vec!["telly", "tube", "box"]),
+                ("television", vec!["telly", "tube", "box"]),
+            ]
+        );
+    }
+}
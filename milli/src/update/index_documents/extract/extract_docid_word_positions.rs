@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::convert::TryInto;
 use std::fs::File;
 use std::io::BufReader;
@@ -8,24 +9,29 @@ use obkv::{KvReader, KvWriterU16};
 use roaring::RoaringBitmap;
 use serde_json::Value;
 
-use super::helpers::{create_sorter, keep_latest_obkv, sorter_into_reader, GrenadParameters};
+use super::helpers::{
+    create_sorter, keep_latest_obkv, merge_deladd_cbo_roaring_bitmaps, sorter_into_reader,
+    GrenadParameters,
+};
 use crate::error::{InternalError, SerializationError};
-use crate::update::del_add::{del_add_from_two_obkvs, DelAdd, KvReaderDelAdd};
+use crate::phonetic::soundex;
+use crate::update::del_add::{del_add_from_two_obkvs, DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
+use crate::update::MergeFn;
 use crate::{FieldId, Result, MAX_POSITION_PER_ATTRIBUTE, MAX_WORD_LENGTH};
 
 /// Extracts the word and positions where this word appear and
 /// prefixes it by the document id.
 ///
-/// Returns the generated internal documents ids and a grenad reader
-/// with the list of extracted words from the given chunk of documents.
+/// Returns a grenad reader with the list of extracted words from the given chunk of documents,
+/// and a grenad reader with the phonetic codes of the words of the phonetic attributes.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
     settings_diff: &InnerIndexSettingsDiff,
     max_positions_per_attributes: Option<u32>,
-) -> Result<grenad::Reader<BufReader<File>>> {
+) -> Result<(grenad::Reader<BufReader<File>>, grenad::Reader<BufReader<File>>)> {
     let max_positions_per_attributes = max_positions_per_attributes
         .map_or(MAX_POSITION_PER_ATTRIBUTE, |max| max.min(MAX_POSITION_PER_ATTRIBUTE));
     let max_memory = indexer.max_memory_by_thread();
@@ -39,7 +45,15 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
         indexer.max_nb_chunks,
-        max_memory,
+        max_memory.map(|m| m / 2),
+    );
+    let mut phonetic_word_docids_sorter = create_sorter(
+        grenad::SortAlgorithm::Unstable,
+        merge_deladd_cbo_roaring_bitmaps,
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        indexer.max_nb_chunks,
+        max_memory.map(|m| m / 2),
     );
 
     // initialize buffers.
@@ -138,10 +152,56 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
             key_buffer.extend_from_slice(&field_id.to_be_bytes());
             docid_word_positions_sorter.insert(&key_buffer, value)?;
         }
+
+        phonetic_words_into_sorter(
+            document_id,
+            &del_buffers.phonetic_words,
+            &add_buffers.phonetic_words,
+            &mut phonetic_word_docids_sorter,
+        )?;
     }
 
-    // the returned sorter is serialized as: key: (DocId, FieldId), value: KV<DelAdd, KV<u16, String>>.
-    sorter_into_reader(docid_word_positions_sorter, indexer)
+    // the first returned sorter is serialized as: key: (DocId, FieldId), value: KV<DelAdd, KV<u16, String>>.
+    // the second one as: key: (PhoneticCode, Word), value: KV<DelAdd, CboRoaringBitmap>.
+    Ok((
+        sorter_into_reader(docid_word_positions_sorter, indexer)?,
+        sorter_into_reader(phonetic_word_docids_sorter, indexer)?,
+    ))
+}
+
+/// Writes the deleted and added phonetic words of a document into the sorter.
+fn phonetic_words_into_sorter(
+    document_id: u32,
+    del_words: &BTreeSet<String>,
+    add_words: &BTreeSet<String>,
+    sorter: &mut grenad::Sorter<MergeFn>,
+) -> Result<()> {
+    use itertools::merge_join_by;
+    use itertools::EitherOrBoth::{Both, Left, Right};
+
+    let mut buffer = Vec::new();
+    for eob in merge_join_by(del_words.iter(), add_words.iter(), |d, a| d.cmp(a)) {
+        buffer.clear();
+        let mut value_writer = KvWriterDelAdd::new(&mut buffer);
+        let word = match eob {
+            Left(word) => {
+                value_writer.insert(DelAdd::Deletion, document_id.to_ne_bytes())?;
+                word
+            }
+            Right(word) => {
+                value_writer.insert(DelAdd::Addition, document_id.to_ne_bytes())?;
+                word
+            }
+            Both(word, _) => {
+                value_writer.insert(DelAdd::Deletion, document_id.to_ne_bytes())?;
+                value_writer.insert(DelAdd::Addition, document_id.to_ne_bytes())?;
+                word
+            }
+        };
+        sorter.insert(word.as_bytes(), value_writer.into_inner()?)?;
+    }
+
+    Ok(())
 }
 
 /// Check if any searchable fields of a document changed.
@@ -197,6 +257,7 @@ fn tokens_from_document<'a>(
     buffers: &'a mut Buffers,
 ) -> Result<&'a [u8]> {
     buffers.obkv_buffer.clear();
+    buffers.phonetic_words.clear();
     let mut document_writer = KvWriterU16::new(&mut buffers.obkv_buffer);
     for (field_id, field_bytes) in obkv.iter() {
         // if field is searchable.
@@ -227,6 +288,12 @@ fn tokens_from_document<'a>(
                                 .try_into()
                                 .map_err(|_| SerializationError::InvalidNumberSerialization)?;
                             writer.insert(position, token.as_bytes())?;
+
+                            if settings.phonetic_fields_ids.contains(&field_id) {
+                                if let Some(code) = soundex(token) {
+                                    buffers.phonetic_words.insert(code + token);
+                                }
+                            }
                         }
                     }
 
@@ -315,4 +382,6 @@ struct Buffers {
     obkv_buffer: Vec<u8>,
     // buffer used to store the value data containing an obkv of tokens with their positions.
     obkv_positions_buffer: Vec<u8>,
+    // the phonetic codes followed by their words of the phonetic fields of the document.
+    phonetic_words: BTreeSet<String>,
 }
@@ -7,20 +7,22 @@ use std::mem::size_of;
 use std::result::Result as StdResult;
 
 use bytemuck::bytes_of;
+use filter_parser::parse_date;
 use grenad::Sorter;
 use heed::BytesEncode;
 use itertools::{merge_join_by, EitherOrBoth, Itertools};
 use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
-use serde_json::{from_slice, Value};
+use serde_json::{from_slice, Map, Value};
 use FilterableValues::{Empty, Null, Values};
 
 use super::helpers::{create_sorter, keep_first, sorter_into_reader, GrenadParameters};
 use crate::error::InternalError;
 use crate::facet::value_encoding::f64_into_bytes;
+use crate::heed_codec::facet::{FieldIdBoolCodec, NestedFacetValue, NestedFacetValueCodec};
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::{create_writer, writer_into_reader};
-use crate::update::settings::InnerIndexSettingsDiff;
+use crate::update::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
 use crate::{CboRoaringBitmapCodec, DocumentId, Error, FieldId, Result, MAX_FACET_VALUE_LENGTH};
 
 /// The length of the elements that are always in the buffer when inserting new values.
@@ -33,6 +35,8 @@ pub struct ExtractedFacetValues {
     pub fid_facet_is_null_docids_chunk: grenad::Reader<BufReader<File>>,
     pub fid_facet_is_empty_docids_chunk: grenad::Reader<BufReader<File>>,
     pub fid_facet_exists_docids_chunk: grenad::Reader<BufReader<File>>,
+    pub fid_facet_bool_docids_chunk: grenad::Reader<BufReader<File>>,
+    pub fid_docid_nested_element_ids_chunk: grenad::Reader<BufReader<File>>,
 }
 
 /// Extracts the facet values of each faceted field of each document.
@@ -54,7 +58,7 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
         indexer.max_nb_chunks,
-        max_memory.map(|m| m / 2),
+        max_memory.map(|m| m / 3),
     );
 
     let mut fid_docid_facet_strings_sorter = create_sorter(
@@ -63,24 +67,49 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
         indexer.max_nb_chunks,
-        max_memory.map(|m| m / 2),
+        max_memory.map(|m| m / 3),
+    );
+
+    let mut fid_docid_nested_element_ids_sorter = create_sorter(
+        grenad::SortAlgorithm::Stable,
+        keep_first,
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        indexer.max_nb_chunks,
+        max_memory.map(|m| m / 3),
     );
 
     // The tuples represents the Del and Add side for a bitmap
     let mut facet_exists_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
     let mut facet_is_null_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
     let mut facet_is_empty_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
+    let mut facet_bool_docids = BTreeMap::<(FieldId, bool), (RoaringBitmap, RoaringBitmap)>::new();
 
     // We create two buffers for mutable ref issues with closures.
     let mut numbers_key_buffer = Vec::new();
     let mut strings_key_buffer = Vec::new();
+    let mut nested_key_buffer = Vec::new();
 
     let old_faceted_fids: BTreeSet<_> =
         settings_diff.old.faceted_fields_ids.iter().copied().collect();
     let new_faceted_fids: BTreeSet<_> =
         settings_diff.new.faceted_fields_ids.iter().copied().collect();
-
-    if !settings_diff.settings_update_only || old_faceted_fids != new_faceted_fids {
+    let modified_date_fids = settings_diff.modified_date_fields_ids();
+    let old_nested_fids: BTreeSet<_> =
+        settings_diff.old.nested_fields_ids.iter().copied().collect();
+    let new_nested_fids: BTreeSet<_> =
+        settings_diff.new.nested_fields_ids.iter().copied().collect();
+    // the booleans were stored as strings before having their own database
+    let old_booleans_as_strings = settings_diff.old.facets_version < 1;
+    let modified_facets_version =
+        settings_diff.old.facets_version != settings_diff.new.facets_version;
+
+    if !settings_diff.settings_update_only
+        || old_faceted_fids != new_faceted_fids
+        || !modified_date_fids.is_empty()
+        || old_nested_fids != new_nested_fids
+        || modified_facets_version
+    {
         let mut cursor = obkv_documents.into_cursor()?;
         while let Some((docid_bytes, value)) = cursor.move_on_next()? {
             let obkv = obkv::KvReader::new(value);
@@ -113,7 +142,10 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                     }
                     EitherOrBoth::Both(&field_id, _) => {
                         // during settings update, recompute the changing settings only.
-                        if settings_diff.settings_update_only {
+                        if settings_diff.settings_update_only
+                            && !modified_date_fids.contains(&field_id)
+                            && !modified_facets_version
+                        {
                             continue;
                         }
 
@@ -163,10 +195,26 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                         .new
                         .geo_fields_ids
                         .map_or(false, |(lat, lng)| field_id == lat || field_id == lng);
-                    let del_filterable_values =
-                        del_value.map(|value| extract_facet_values(&value, del_geo_support));
-                    let add_filterable_values =
-                        add_value.map(|value| extract_facet_values(&value, add_geo_support));
+                    let del_date_support = settings_diff.old.date_fields_ids.contains(&field_id);
+                    let add_date_support = settings_diff.new.date_fields_ids.contains(&field_id);
+                    let del_filterable_values = del_value.map(|value| {
+                        let values = extract_facet_values(&value, del_geo_support);
+                        let values =
+                            if del_date_support { with_date_timestamps(values) } else { values };
+                        if old_booleans_as_strings {
+                            with_booleans_as_strings(values)
+                        } else {
+                            values
+                        }
+                    });
+                    let add_filterable_values = add_value.map(|value| {
+                        let values = extract_facet_values(&value, add_geo_support);
+                        if add_date_support {
+                            with_date_timestamps(values)
+                        } else {
+                            values
+                        }
+                    });
 
                     // Those closures are just here to simplify things a bit.
                     let mut insert_numbers_diff = |del_numbers, add_numbers| {
@@ -185,6 +233,15 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                             add_strings,
                         )
                     };
+                    let mut insert_booleans_diff = |del_booleans, add_booleans| {
+                        insert_booleans_diff(
+                            &mut facet_bool_docids,
+                            field_id,
+                            document,
+                            del_booleans,
+                            add_booleans,
+                        )
+                    };
 
                     match (del_filterable_values, add_filterable_values) {
                         (None, None) => (),
@@ -195,9 +252,10 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                             Empty => {
                                 del_is_empty.insert(document);
                             }
-                            Values { numbers, strings } => {
+                            Values { numbers, strings, booleans } => {
                                 insert_numbers_diff(numbers, vec![])?;
                                 insert_strings_diff(strings, vec![])?;
+                                insert_booleans_diff(booleans, vec![]);
                             }
                         },
                         (None, Some(add_filterable_values)) => match add_filterable_values {
@@ -207,9 +265,10 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                             Empty => {
                                 add_is_empty.insert(document);
                             }
-                            Values { numbers, strings } => {
+                            Values { numbers, strings, booleans } => {
                                 insert_numbers_diff(vec![], numbers)?;
                                 insert_strings_diff(vec![], strings)?;
+                                insert_booleans_diff(vec![], booleans);
                             }
                         },
                         (Some(del_filterable_values), Some(add_filterable_values)) => {
@@ -223,38 +282,86 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                                     del_is_empty.insert(document);
                                     add_is_null.insert(document);
                                 }
-                                (Null, Values { numbers, strings }) => {
+                                (Null, Values { numbers, strings, booleans }) => {
                                     insert_numbers_diff(vec![], numbers)?;
                                     insert_strings_diff(vec![], strings)?;
+                                    insert_booleans_diff(vec![], booleans);
                                     del_is_null.insert(document);
                                 }
-                                (Empty, Values { numbers, strings }) => {
+                                (Empty, Values { numbers, strings, booleans }) => {
                                     insert_numbers_diff(vec![], numbers)?;
                                     insert_strings_diff(vec![], strings)?;
+                                    insert_booleans_diff(vec![], booleans);
                                     del_is_empty.insert(document);
                                 }
-                                (Values { numbers, strings }, Null) => {
+                                (Values { numbers, strings, booleans }, Null) => {
                                     add_is_null.insert(document);
                                     insert_numbers_diff(numbers, vec![])?;
                                     insert_strings_diff(strings, vec![])?;
+                                    insert_booleans_diff(booleans, vec![]);
                                 }
-                                (Values { numbers, strings }, Empty) => {
+                                (Values { numbers, strings, booleans }, Empty) => {
                                     add_is_empty.insert(document);
                                     insert_numbers_diff(numbers, vec![])?;
                                     insert_strings_diff(strings, vec![])?;
+                                    insert_booleans_diff(booleans, vec![]);
                                 }
                                 (
-                                    Values { numbers: del_numbers, strings: del_strings },
-                                    Values { numbers: add_numbers, strings: add_strings },
+                                    Values {
+                                        numbers: del_numbers,
+                                        strings: del_strings,
+                                        booleans: del_booleans,
+                                    },
+                                    Values {
+                                        numbers: add_numbers,
+                                        strings: add_strings,
+                                        booleans: add_booleans,
+                                    },
                                 ) => {
                                     insert_numbers_diff(del_numbers, add_numbers)?;
                                     insert_strings_diff(del_strings, add_strings)?;
+                                    insert_booleans_diff(del_booleans, add_booleans);
                                 }
                             }
                         }
                     }
                 }
             }
+
+            // give ids to the objects of the nested fields to correlate their values
+            let document: [u8; 4] = docid_bytes[..4].try_into().ok().unwrap();
+            let document = DocumentId::from_be_bytes(document);
+            for eob in merge_join_by(old_nested_fids.iter(), new_nested_fids.iter(), |old, new| {
+                old.cmp(new)
+            }) {
+                let del_element_ids = match eob {
+                    EitherOrBoth::Left(&field_id) | EitherOrBoth::Both(&field_id, _) => {
+                        extract_nested_element_ids(
+                            |field_id| get_document_json_value(field_id, DelAdd::Deletion),
+                            field_id,
+                            &settings_diff.old,
+                        )?
+                    }
+                    EitherOrBoth::Right(_) => BTreeMap::new(),
+                };
+                let add_element_ids = match eob {
+                    EitherOrBoth::Right(&field_id) | EitherOrBoth::Both(_, &field_id) => {
+                        extract_nested_element_ids(
+                            |field_id| get_document_json_value(field_id, DelAdd::Addition),
+                            field_id,
+                            &settings_diff.new,
+                        )?
+                    }
+                    EitherOrBoth::Left(_) => BTreeMap::new(),
+                };
+                insert_nested_element_ids_diff(
+                    &mut fid_docid_nested_element_ids_sorter,
+                    &mut nested_key_buffer,
+                    document,
+                    del_element_ids,
+                    add_element_ids,
+                )?;
+            }
         }
     }
 
@@ -292,12 +399,29 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     }
     let facet_is_empty_docids_reader = writer_into_reader(facet_is_empty_docids_writer)?;
 
+    let mut facet_bool_docids_writer = create_writer(
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        tempfile::tempfile()?,
+    );
+    for (key, (del_bitmap, add_bitmap)) in facet_bool_docids.into_iter() {
+        deladd_obkv_cbo_roaring_bitmaps(&mut buffer, &del_bitmap, &add_bitmap)?;
+        let key = FieldIdBoolCodec::bytes_encode(&key).map_err(heed::Error::Encoding)?;
+        facet_bool_docids_writer.insert(key, &buffer)?;
+    }
+    let facet_bool_docids_reader = writer_into_reader(facet_bool_docids_writer)?;
+
     Ok(ExtractedFacetValues {
         fid_docid_facet_numbers_chunk: sorter_into_reader(fid_docid_facet_numbers_sorter, indexer)?,
         fid_docid_facet_strings_chunk: sorter_into_reader(fid_docid_facet_strings_sorter, indexer)?,
         fid_facet_is_null_docids_chunk: facet_is_null_docids_reader,
         fid_facet_is_empty_docids_chunk: facet_is_empty_docids_reader,
         fid_facet_exists_docids_chunk: facet_exists_docids_reader,
+        fid_facet_bool_docids_chunk: facet_bool_docids_reader,
+        fid_docid_nested_element_ids_chunk: sorter_into_reader(
+            fid_docid_nested_element_ids_sorter,
+            indexer,
+        )?,
     })
 }
 
@@ -316,6 +440,27 @@ fn deladd_obkv_cbo_roaring_bitmaps(
     obkv.finish()
 }
 
+/// Inserts the document in the Del or Add side of the booleans that differ between both sides.
+fn insert_booleans_diff(
+    facet_bool_docids: &mut BTreeMap<(FieldId, bool), (RoaringBitmap, RoaringBitmap)>,
+    field_id: FieldId,
+    document: DocumentId,
+    del_booleans: Vec<bool>,
+    add_booleans: Vec<bool>,
+) {
+    for boolean in [false, true] {
+        match (del_booleans.contains(&boolean), add_booleans.contains(&boolean)) {
+            (true, false) => {
+                facet_bool_docids.entry((field_id, boolean)).or_default().0.insert(document);
+            }
+            (false, true) => {
+                facet_bool_docids.entry((field_id, boolean)).or_default().1.insert(document);
+            }
+            (true, true) | (false, false) => (),
+        }
+    }
+}
+
 /// Truncates a string to the biggest valid LMDB key size.
 fn truncate_str(s: &str) -> &str {
     let index = s
@@ -506,14 +651,195 @@ where
     Ok(())
 }
 
+/// Computes the diff between both Del and Add element ids and
+/// only inserts the values whose element ids differ in the sorter.
+fn insert_nested_element_ids_diff<MF>(
+    fid_docid_nested_element_ids_sorter: &mut Sorter<MF>,
+    key_buffer: &mut Vec<u8>,
+    document: DocumentId,
+    del_element_ids: BTreeMap<(FieldId, Vec<u8>), RoaringBitmap>,
+    add_element_ids: BTreeMap<(FieldId, Vec<u8>), RoaringBitmap>,
+) -> Result<()>
+where
+    MF: for<'a> Fn(&[u8], &[Cow<'a, [u8]>]) -> StdResult<Cow<'a, [u8]>, Error>,
+{
+    let merged_element_ids_iter =
+        merge_join_by(del_element_ids, add_element_ids, |(del, _), (add, _)| del.cmp(add));
+
+    for eob in merged_element_ids_iter {
+        let ((field_id, value), del, add) = match eob {
+            EitherOrBoth::Both((key, del), (_, add)) if del != add => (key, Some(del), Some(add)),
+            EitherOrBoth::Both(_, _) => continue, // no need to touch anything
+            EitherOrBoth::Left((key, del)) => (key, Some(del), None),
+            EitherOrBoth::Right((key, add)) => (key, None, Some(add)),
+        };
+
+        // Note: this encoding is consistent with FieldDocIdFacetCodec
+        key_buffer.clear();
+        key_buffer.extend_from_slice(&field_id.to_be_bytes());
+        key_buffer.extend_from_slice(&document.to_be_bytes());
+        key_buffer.extend_from_slice(&value);
+
+        let mut obkv = KvWriterDelAdd::memory();
+        if let Some(del) = del {
+            obkv.insert(DelAdd::Deletion, CboRoaringBitmapCodec::bytes_encode(&del).unwrap())?;
+        }
+        if let Some(add) = add {
+            obkv.insert(DelAdd::Addition, CboRoaringBitmapCodec::bytes_encode(&add).unwrap())?;
+        }
+        let bytes = obkv.into_inner()?;
+        fid_docid_nested_element_ids_sorter.insert(&key_buffer, bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Extracts the facet values of the fields of each object of a nested field, along with the
+/// indexes of the objects containing them, the element ids. The objects themselves are stored
+/// under the field id of the nested field.
+///
+/// Returns the field ids and the encoded [`NestedFacetValue`]s with their element ids.
+fn extract_nested_element_ids(
+    document_json_value: impl Fn(FieldId) -> StdResult<Option<Value>, InternalError>,
+    nested_field_id: FieldId,
+    settings: &InnerIndexSettings,
+) -> Result<BTreeMap<(FieldId, Vec<u8>), RoaringBitmap>> {
+    fn values_at_path<'v>(value: &'v Value, path: &str, output: &mut Vec<&'v Value>) {
+        match value {
+            _ if path.is_empty() => output.push(value),
+            Value::Object(object) => {
+                for (key, value) in object {
+                    if key == path {
+                        output.push(value);
+                    } else if let Some(rest) =
+                        path.strip_prefix(key.as_str()).and_then(|rest| rest.strip_prefix('.'))
+                    {
+                        values_at_path(value, rest, output);
+                    }
+                }
+            }
+            Value::Array(values) => {
+                values.iter().for_each(|value| values_at_path(value, path, output))
+            }
+            _ => (),
+        }
+    }
+
+    fn objects<'v>(value: &'v Value, output: &mut Vec<&'v Map<String, Value>>) {
+        match value {
+            Value::Object(object) => output.push(object),
+            Value::Array(values) => values.iter().for_each(|value| objects(value, output)),
+            _ => (),
+        }
+    }
+
+    let mut element_ids = BTreeMap::<_, RoaringBitmap>::new();
+    let Some(nested_field) = settings.fields_ids_map.name(nested_field_id) else {
+        return Ok(element_ids);
+    };
+
+    // The flattened value of a field nested in an array of objects is only the value of its first
+    // object, so the objects are read from the outermost field of the document containing them.
+    let prefixes = nested_field
+        .match_indices('.')
+        .map(|(index, _)| &nested_field[..index])
+        .chain(std::iter::once(nested_field));
+    let mut outermost = None;
+    for prefix in prefixes {
+        let Some(field_id) = settings.fields_ids_map.id(prefix) else { continue };
+        if let Some(value) = document_json_value(field_id)? {
+            let path = nested_field[prefix.len()..].strip_prefix('.').unwrap_or_default();
+            outermost = Some((value, path));
+            break;
+        }
+    }
+    let Some((value, path)) = outermost else { return Ok(element_ids) };
+    let mut values = Vec::new();
+    values_at_path(&value, path, &mut values);
+    // the fields of the deeper nested fields are correlated with their own objects
+    let deeper_nested_fields: Vec<_> = settings
+        .nested_fields_ids
+        .iter()
+        .filter_map(|&field_id| settings.fields_ids_map.name(field_id))
+        .filter(|name| *name != nested_field && crate::is_faceted_by(name, nested_field))
+        .collect();
+    let mut insert = |field_id: FieldId, value: NestedFacetValue, element_id: u32| {
+        if let Ok(bytes) = NestedFacetValueCodec::bytes_encode(&value) {
+            element_ids.entry((field_id, bytes.into_owned())).or_default().insert(element_id);
+        }
+    };
+
+    let mut nested_objects = Vec::new();
+    values.into_iter().for_each(|value| objects(value, &mut nested_objects));
+    for (element_id, object) in (0..).zip(nested_objects) {
+        insert(nested_field_id, NestedFacetValue::Object, element_id);
+
+        for (key, value) in flatten_serde_json::flatten(object) {
+            let name = format!("{nested_field}.{key}");
+            let Some(field_id) = settings.fields_ids_map.id(&name) else { continue };
+            if !settings.faceted_fields_ids.contains(&field_id)
+                || deeper_nested_fields.iter().any(|deeper| crate::is_faceted_by(&name, deeper))
+            {
+                continue;
+            }
+
+            let values = extract_facet_values(&value, false);
+            let values = if settings.date_fields_ids.contains(&field_id) {
+                with_date_timestamps(values)
+            } else {
+                values
+            };
+            match values {
+                Null => insert(field_id, NestedFacetValue::Null, element_id),
+                Empty => insert(field_id, NestedFacetValue::Empty, element_id),
+                Values { numbers, strings, booleans } => {
+                    for number in numbers {
+                        insert(field_id, NestedFacetValue::Number(number), element_id);
+                    }
+                    for (normalized, _original) in &strings {
+                        let normalized = truncate_str(normalized);
+                        if !normalized.is_empty() {
+                            insert(field_id, NestedFacetValue::String(normalized), element_id);
+                        }
+                    }
+                    for boolean in booleans {
+                        insert(field_id, NestedFacetValue::Bool(boolean), element_id);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(element_ids)
+}
+
 /// Represent what a document field contains.
 enum FilterableValues {
     /// Corresponds to the JSON `null` value.
     Null,
     /// Corresponds to either, an empty string `""`, an empty array `[]`, or an empty object `{}`.
     Empty,
-    /// Represents all the numbers and strings values found in this document field.
-    Values { numbers: Vec<f64>, strings: Vec<(String, String)> },
+    /// Represents all the numbers, strings and booleans values found in this document field.
+    Values { numbers: Vec<f64>, strings: Vec<(String, String)>, booleans: Vec<bool> },
+}
+
+/// Adds the Unix timestamps of the dates of a date field to its numbers so that they can be
+/// filtered and sorted chronologically, the original strings are kept for the facet distribution.
+fn with_date_timestamps(mut values: FilterableValues) -> FilterableValues {
+    if let Values { numbers, strings, .. } = &mut values {
+        let timestamps = strings.iter().filter_map(|(_, original)| parse_date(original));
+        numbers.extend(timestamps);
+    }
+    values
+}
+
+/// Moves the booleans to the strings, the way they were stored before having their own database.
+fn with_booleans_as_strings(mut values: FilterableValues) -> FilterableValues {
+    if let Values { strings, booleans, .. } = &mut values {
+        let booleans = booleans.drain(..).map(|boolean| (boolean.to_string(), boolean.to_string()));
+        strings.extend(booleans);
+    }
+    values
 }
 
 /// Extracts the facet values of a JSON field.
@@ -523,11 +849,12 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         can_recurse: bool,
         output_numbers: &mut Vec<f64>,
         output_strings: &mut Vec<(String, String)>,
+        output_booleans: &mut Vec<bool>,
         geo_field: bool,
     ) {
         match value {
             Value::Null => (),
-            Value::Bool(b) => output_strings.push((b.to_string(), b.to_string())),
+            Value::Bool(b) => output_booleans.push(*b),
             Value::Number(number) => {
                 if let Some(float) = number.as_f64() {
                     output_numbers.push(float);
@@ -556,6 +883,7 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                             false,
                             output_numbers,
                             output_strings,
+                            output_booleans,
                             geo_field,
                         );
                     }
@@ -573,8 +901,16 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         otherwise => {
             let mut numbers = Vec::new();
             let mut strings = Vec::new();
-            inner_extract_facet_values(otherwise, true, &mut numbers, &mut strings, geo_field);
-            FilterableValues::Values { numbers, strings }
+            let mut booleans = Vec::new();
+            inner_extract_facet_values(
+                otherwise,
+                true,
+                &mut numbers,
+                &mut strings,
+                &mut booleans,
+                geo_field,
+            );
+            FilterableValues::Values { numbers, strings, booleans }
         }
     }
 }
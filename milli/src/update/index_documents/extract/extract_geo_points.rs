@@ -40,23 +40,19 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
         };
 
         // extract old version
-        let del_lat_lng =
-            extract_lat_lng(&obkv, &settings_diff.old, DelAdd::Deletion, document_id)?;
+        let del_lat_lngs =
+            extract_lat_lngs(&obkv, &settings_diff.old, DelAdd::Deletion, document_id)?;
         // extract new version
-        let add_lat_lng =
-            extract_lat_lng(&obkv, &settings_diff.new, DelAdd::Addition, document_id)?;
+        let add_lat_lngs =
+            extract_lat_lngs(&obkv, &settings_diff.new, DelAdd::Addition, document_id)?;
 
-        if del_lat_lng != add_lat_lng {
+        if del_lat_lngs != add_lat_lngs {
             let mut obkv = KvWriterDelAdd::memory();
-            if let Some([lat, lng]) = del_lat_lng {
-                #[allow(clippy::drop_non_drop)]
-                let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
-                obkv.insert(DelAdd::Deletion, bytes)?;
+            if !del_lat_lngs.is_empty() {
+                obkv.insert(DelAdd::Deletion, lat_lngs_to_bytes(&del_lat_lngs))?;
             }
-            if let Some([lat, lng]) = add_lat_lng {
-                #[allow(clippy::drop_non_drop)]
-                let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
-                obkv.insert(DelAdd::Addition, bytes)?;
+            if !add_lat_lngs.is_empty() {
+                obkv.insert(DelAdd::Addition, lat_lngs_to_bytes(&add_lat_lngs))?;
             }
             let bytes = obkv.into_inner()?;
             writer.insert(docid_bytes, bytes)?;
@@ -66,38 +62,84 @@ pub fn extract_geo_points<R: io::Read + io::Seek>(
     writer_into_reader(writer)
 }
 
-/// Extract the finite floats lat and lng from two bytes slices.
-fn extract_lat_lng(
+/// Concatenates the latitude and longitude of every point.
+fn lat_lngs_to_bytes(lat_lngs: &[[f64; 2]]) -> Vec<u8> {
+    lat_lngs
+        .iter()
+        .flat_map(|[lat, lng]| {
+            #[allow(clippy::drop_non_drop)]
+            let bytes: [u8; 16] = concat_arrays![lat.to_ne_bytes(), lng.to_ne_bytes()];
+            bytes
+        })
+        .collect()
+}
+
+/// Extract the finite floats lat and lng of all the points of the document.
+///
+/// The `_geo` field can either be a single point, in which case we read it from the
+/// `_geo.lat` and `_geo.lng` fields, or an array of points that we read from the `_geo` field
+/// as flattening it would lose the association between the latitudes and longitudes.
+fn extract_lat_lngs(
     document: &obkv::KvReader<'_, FieldId>,
     settings: &InnerIndexSettings,
     deladd: DelAdd,
     document_id: impl Fn() -> Value,
-) -> Result<Option<[f64; 2]>> {
+) -> Result<Vec<[f64; 2]>> {
     match settings.geo_fields_ids {
         Some((lat_fid, lng_fid)) => {
-            let lat = document.get(lat_fid).map(KvReaderDelAdd::new).and_then(|r| r.get(deladd));
-            let lng = document.get(lng_fid).map(KvReaderDelAdd::new).and_then(|r| r.get(deladd));
-            let (lat, lng) = match (lat, lng) {
-                (Some(lat), Some(lng)) => (lat, lng),
-                (Some(_), None) => {
-                    return Err(GeoError::MissingLatitude { document_id: document_id() }.into())
-                }
-                (None, Some(_)) => {
-                    return Err(GeoError::MissingLongitude { document_id: document_id() }.into())
+            let geo = settings
+                .fields_ids_map
+                .id("_geo")
+                .and_then(|fid| document.get(fid))
+                .map(KvReaderDelAdd::new)
+                .and_then(|r| r.get(deladd));
+            if let Some(geo) = geo {
+                if let Value::Array(points) =
+                    serde_json::from_slice(geo).map_err(InternalError::SerdeJson)?
+                {
+                    return points
+                        .into_iter()
+                        .filter_map(|mut point| {
+                            let lat = point.get_mut("lat").map(Value::take);
+                            let lng = point.get_mut("lng").map(Value::take);
+                            extract_lat_lng(lat, lng, &document_id).transpose()
+                        })
+                        .collect();
                 }
-                (None, None) => return Ok(None),
-            };
-            let lat = extract_finite_float_from_value(
-                serde_json::from_slice(lat).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lat| GeoError::BadLatitude { document_id: document_id(), value: lat })?;
+            }
 
-            let lng = extract_finite_float_from_value(
-                serde_json::from_slice(lng).map_err(InternalError::SerdeJson)?,
-            )
-            .map_err(|lng| GeoError::BadLongitude { document_id: document_id(), value: lng })?;
-            Ok(Some([lat, lng]))
+            let lat = document.get(lat_fid).map(KvReaderDelAdd::new).and_then(|r| r.get(deladd));
+            let lng = document.get(lng_fid).map(KvReaderDelAdd::new).and_then(|r| r.get(deladd));
+            let lat =
+                lat.map(serde_json::from_slice).transpose().map_err(InternalError::SerdeJson)?;
+            let lng =
+                lng.map(serde_json::from_slice).transpose().map_err(InternalError::SerdeJson)?;
+            Ok(extract_lat_lng(lat, lng, &document_id)?.into_iter().collect())
         }
-        None => Ok(None),
+        None => Ok(Vec::new()),
     }
 }
+
+/// Extract the finite floats lat and lng from two values.
+fn extract_lat_lng(
+    lat: Option<Value>,
+    lng: Option<Value>,
+    document_id: impl Fn() -> Value,
+) -> Result<Option<[f64; 2]>> {
+    let (lat, lng) = match (lat, lng) {
+        (Some(lat), Some(lng)) => (lat, lng),
+        (Some(_), None) => {
+            return Err(GeoError::MissingLatitude { document_id: document_id() }.into())
+        }
+        (None, Some(_)) => {
+            return Err(GeoError::MissingLongitude { document_id: document_id() }.into())
+        }
+        (None, None) => return Ok(None),
+    };
+    let lat = extract_finite_float_from_value(lat)
+        .map_err(|lat| GeoError::BadLatitude { document_id: document_id(), value: lat })?;
+
+    let lng = extract_finite_float_from_value(lng)
+        .map_err(|lng| GeoError::BadLongitude { document_id: document_id(), value: lng })?;
+    Ok(Some([lat, lng]))
+}
@@ -0,0 +1,74 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+
+use serde_json::Value;
+
+use super::helpers::{create_writer, writer_into_reader, GrenadParameters};
+use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
+use crate::update::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
+use crate::{FieldId, GeoShape, InternalError, Result};
+
+/// Extracts the GeoJSON geometries contained in each document under the `_geo` field.
+///
+/// Returns the generated grenad reader containing the docid as key associated to the
+/// JSON geometry, the geometries have already been validated when enriching the documents.
+#[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
+pub fn extract_geo_shapes<R: io::Read + io::Seek>(
+    obkv_documents: grenad::Reader<R>,
+    indexer: GrenadParameters,
+    settings_diff: &InnerIndexSettingsDiff,
+) -> Result<grenad::Reader<BufReader<File>>> {
+    let mut writer = create_writer(
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        tempfile::tempfile()?,
+    );
+
+    let mut cursor = obkv_documents.into_cursor()?;
+    while let Some((docid_bytes, value)) = cursor.move_on_next()? {
+        let obkv = obkv::KvReader::new(value);
+
+        // extract old version
+        let del_shape = extract_geojson(&obkv, &settings_diff.old, DelAdd::Deletion)?;
+        // extract new version
+        let add_shape = extract_geojson(&obkv, &settings_diff.new, DelAdd::Addition)?;
+
+        if del_shape != add_shape {
+            let mut obkv = KvWriterDelAdd::memory();
+            if let Some(shape) = del_shape {
+                obkv.insert(DelAdd::Deletion, shape)?;
+            }
+            if let Some(shape) = add_shape {
+                obkv.insert(DelAdd::Addition, shape)?;
+            }
+            let bytes = obkv.into_inner()?;
+            writer.insert(docid_bytes, bytes)?;
+        }
+    }
+
+    writer_into_reader(writer)
+}
+
+/// Returns the `_geo` field of the document if `_geo` is faceted and it is a GeoJSON geometry.
+fn extract_geojson<'a>(
+    document: &obkv::KvReader<'a, FieldId>,
+    settings: &InnerIndexSettings,
+    deladd: DelAdd,
+) -> Result<Option<&'a [u8]>> {
+    if settings.geo_fields_ids.is_none() {
+        return Ok(None);
+    }
+    let geo = settings
+        .fields_ids_map
+        .id("_geo")
+        .and_then(|fid| document.get(fid))
+        .map(KvReaderDelAdd::new)
+        .and_then(|r| r.get(deladd));
+    match geo {
+        Some(bytes) => {
+            let value: Value = serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)?;
+            Ok(GeoShape::is_geojson(&value).then_some(bytes))
+        }
+        None => Ok(None),
+    }
+}
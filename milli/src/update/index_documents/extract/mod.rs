@@ -4,6 +4,7 @@ mod extract_facet_string_docids;
 mod extract_fid_docid_facet_values;
 mod extract_fid_word_count_docids;
 mod extract_geo_points;
+mod extract_geo_shapes;
 mod extract_vector_points;
 mod extract_word_docids;
 mod extract_word_pair_proximity_docids;
@@ -22,6 +23,7 @@ use self::extract_facet_string_docids::extract_facet_string_docids;
 use self::extract_fid_docid_facet_values::{extract_fid_docid_facet_values, ExtractedFacetValues};
 use self::extract_fid_word_count_docids::extract_fid_word_count_docids;
 use self::extract_geo_points::extract_geo_points;
+use self::extract_geo_shapes::extract_geo_shapes;
 use self::extract_vector_points::{
     extract_embeddings, extract_vector_points, ExtractedVectorPoints,
 };
@@ -310,9 +312,11 @@ fn send_original_documents_data(
 /// Extract chunked data and send it into lmdb_writer_sx sender:
 /// - documents_ids
 /// - docid_word_positions
+/// - phonetic_word_docids
 /// - docid_fid_facet_numbers
 /// - docid_fid_facet_strings
 /// - docid_fid_facet_exists
+/// - fid_docid_nested_element_ids
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 fn send_and_extract_flattened_documents_data(
@@ -330,6 +334,17 @@ fn send_and_extract_flattened_documents_data(
         flattened_documents_chunk.and_then(|c| unsafe { as_cloneable_grenad(&c) })?;
 
     if settings_diff.run_geo_indexing() {
+        let documents_chunk_cloned = flattened_documents_chunk.clone();
+        let lmdb_writer_sx_cloned = lmdb_writer_sx.clone();
+        let settings_diff_cloned = settings_diff.clone();
+        rayon::spawn(move || {
+            let result = extract_geo_shapes(documents_chunk_cloned, indexer, &settings_diff_cloned);
+            let _ = match result {
+                Ok(geo_shapes) => lmdb_writer_sx_cloned.send(Ok(TypedChunk::GeoShapes(geo_shapes))),
+                Err(error) => lmdb_writer_sx_cloned.send(Err(error)),
+            };
+        });
+
         let documents_chunk_cloned = flattened_documents_chunk.clone();
         let lmdb_writer_sx_cloned = lmdb_writer_sx.clone();
         let settings_diff = settings_diff.clone();
@@ -346,12 +361,16 @@ fn send_and_extract_flattened_documents_data(
     let (docid_word_positions_chunk, fid_docid_facet_values_chunks): (Result<_>, Result<_>) =
         rayon::join(
             || {
-                let docid_word_positions_chunk = extract_docid_word_positions(
-                    flattened_documents_chunk.clone(),
-                    indexer,
-                    &settings_diff,
-                    max_positions_per_attributes,
-                )?;
+                let (docid_word_positions_chunk, phonetic_word_docids_chunk) =
+                    extract_docid_word_positions(
+                        flattened_documents_chunk.clone(),
+                        indexer,
+                        &settings_diff,
+                        max_positions_per_attributes,
+                    )?;
+
+                let _ = lmdb_writer_sx
+                    .send(Ok(TypedChunk::PhoneticWordDocids(phonetic_word_docids_chunk)));
 
                 // send docid_word_positions_chunk to DB writer
                 let docid_word_positions_chunk =
@@ -366,6 +385,8 @@ fn send_and_extract_flattened_documents_data(
                     fid_facet_is_null_docids_chunk,
                     fid_facet_is_empty_docids_chunk,
                     fid_facet_exists_docids_chunk,
+                    fid_facet_bool_docids_chunk,
+                    fid_docid_nested_element_ids_chunk,
                 } = extract_fid_docid_facet_values(
                     flattened_documents_chunk.clone(),
                     indexer,
@@ -398,6 +419,13 @@ fn send_and_extract_flattened_documents_data(
                 let _ = lmdb_writer_sx
                     .send(Ok(TypedChunk::FieldIdFacetExistsDocids(fid_facet_exists_docids_chunk)));
 
+                let _ = lmdb_writer_sx
+                    .send(Ok(TypedChunk::FieldIdFacetBoolDocids(fid_facet_bool_docids_chunk)));
+
+                let _ = lmdb_writer_sx.send(Ok(TypedChunk::FieldIdDocidNestedElementIds(
+                    fid_docid_nested_element_ids_chunk,
+                )));
+
                 Ok((fid_docid_facet_numbers_chunk, fid_docid_facet_strings_chunk))
             },
         );
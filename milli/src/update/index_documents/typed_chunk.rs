@@ -29,8 +29,8 @@ use crate::update::index_documents::helpers::{
 use crate::update::settings::InnerIndexSettingsDiff;
 use crate::vector::ArroyWrapper;
 use crate::{
-    lat_lng_to_xyz, CboRoaringBitmapCodec, DocumentId, FieldId, GeoPoint, Index, InternalError,
-    Result, SerializationError, U8StrStrCodec,
+    lat_lng_to_xyz, CboRoaringBitmapCodec, DocumentId, FieldId, GeoPoint, GeoShape,
+    GeoShapeEnvelope, Index, InternalError, Result, SerializationError, U8StrStrCodec,
 };
 
 /// This struct accumulates and group the TypedChunks
@@ -70,6 +70,7 @@ impl ChunkAccumulator {
 pub(crate) enum TypedChunk {
     FieldIdDocidFacetStrings(grenad::Reader<CursorClonableMmap>),
     FieldIdDocidFacetNumbers(grenad::Reader<CursorClonableMmap>),
+    FieldIdDocidNestedElementIds(grenad::Reader<BufReader<File>>),
     Documents(grenad::Reader<CursorClonableMmap>),
     FieldIdWordCountDocids(grenad::Reader<BufReader<File>>),
     WordDocids {
@@ -78,13 +79,16 @@ pub(crate) enum TypedChunk {
         word_fid_docids_reader: grenad::Reader<BufReader<File>>,
     },
     WordPositionDocids(grenad::Reader<BufReader<File>>),
+    PhoneticWordDocids(grenad::Reader<BufReader<File>>),
     WordPairProximityDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetStringDocids((grenad::Reader<BufReader<File>>, grenad::Reader<BufReader<File>>)),
     FieldIdFacetNumberDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetExistsDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetIsNullDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetIsEmptyDocids(grenad::Reader<BufReader<File>>),
+    FieldIdFacetBoolDocids(grenad::Reader<BufReader<File>>),
     GeoPoints(grenad::Reader<BufReader<File>>),
+    GeoShapes(grenad::Reader<BufReader<File>>),
     VectorPoints {
         remove_vectors: grenad::Reader<BufReader<File>>,
         embeddings: Option<grenad::Reader<BufReader<File>>>,
@@ -102,17 +106,21 @@ impl TypedChunk {
         match (self, other) {
             (FieldIdDocidFacetStrings(_), FieldIdDocidFacetStrings(_))
             | (FieldIdDocidFacetNumbers(_), FieldIdDocidFacetNumbers(_))
+            | (FieldIdDocidNestedElementIds(_), FieldIdDocidNestedElementIds(_))
             | (Documents(_), Documents(_))
             | (FieldIdWordCountDocids(_), FieldIdWordCountDocids(_))
             | (WordDocids { .. }, WordDocids { .. })
             | (WordPositionDocids(_), WordPositionDocids(_))
+            | (PhoneticWordDocids(_), PhoneticWordDocids(_))
             | (WordPairProximityDocids(_), WordPairProximityDocids(_))
             | (FieldIdFacetStringDocids(_), FieldIdFacetStringDocids(_))
             | (FieldIdFacetNumberDocids(_), FieldIdFacetNumberDocids(_))
             | (FieldIdFacetExistsDocids(_), FieldIdFacetExistsDocids(_))
             | (FieldIdFacetIsNullDocids(_), FieldIdFacetIsNullDocids(_))
             | (FieldIdFacetIsEmptyDocids(_), FieldIdFacetIsEmptyDocids(_))
-            | (GeoPoints(_), GeoPoints(_)) => true,
+            | (FieldIdFacetBoolDocids(_), FieldIdFacetBoolDocids(_))
+            | (GeoPoints(_), GeoPoints(_))
+            | (GeoShapes(_), GeoShapes(_)) => true,
             (
                 VectorPoints { embedder_name: left, expected_dimension: left_dim, .. },
                 VectorPoints { embedder_name: right, expected_dimension: right_dim, .. },
@@ -478,6 +486,53 @@ pub(crate) fn write_typed_chunk_into_index(
             )?;
             is_merged_database = true;
         }
+        TypedChunk::PhoneticWordDocids(_) => {
+            let span = tracing::trace_span!(target: "indexing::write_db", "phonetic_word_docids");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn);
+            for typed_chunk in typed_chunks {
+                let TypedChunk::PhoneticWordDocids(chunk) = typed_chunk else {
+                    unreachable!();
+                };
+
+                builder.push(chunk.into_cursor()?);
+            }
+            let merger = builder.build();
+
+            write_entries_into_database(
+                merger,
+                &index.phonetic_word_docids,
+                wtxn,
+                deladd_serialize_add_side,
+                merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap,
+            )?;
+            is_merged_database = true;
+        }
+        TypedChunk::FieldIdFacetBoolDocids(_) => {
+            let span =
+                tracing::trace_span!(target: "indexing::write_db", "field_id_facet_bool_docids");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn);
+            for typed_chunk in typed_chunks {
+                let TypedChunk::FieldIdFacetBoolDocids(chunk) = typed_chunk else {
+                    unreachable!();
+                };
+
+                builder.push(chunk.into_cursor()?);
+            }
+            let merger = builder.build();
+
+            write_entries_into_database(
+                merger,
+                &index.facet_id_bool_docids,
+                wtxn,
+                deladd_serialize_add_side,
+                merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap,
+            )?;
+            is_merged_database = true;
+        }
         TypedChunk::WordPairProximityDocids(_) => {
             let span =
                 tracing::trace_span!(target: "indexing::write_db", "word_pair_proximity_docids");
@@ -579,6 +634,36 @@ pub(crate) fn write_typed_chunk_into_index(
                 }
             }
         }
+        TypedChunk::FieldIdDocidNestedElementIds(_) => {
+            let span = tracing::trace_span!(target: "indexing::write_db", "field_id_docid_nested_element_ids");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(keep_first as MergeFn);
+            for typed_chunk in typed_chunks {
+                let TypedChunk::FieldIdDocidNestedElementIds(chunk) = typed_chunk else {
+                    unreachable!();
+                };
+
+                builder.push(chunk.into_cursor()?);
+            }
+            let merger = builder.build();
+
+            let index_fid_docid_nested_element_ids =
+                index.field_id_docid_nested_element_ids.remap_types::<Bytes, Bytes>();
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, value)) = iter.next()? {
+                let reader = KvReaderDelAdd::new(value);
+                if valid_lmdb_key(key) {
+                    match (reader.get(DelAdd::Deletion), reader.get(DelAdd::Addition)) {
+                        (None, None) => {}
+                        (_, Some(new)) => index_fid_docid_nested_element_ids.put(wtxn, key, new)?,
+                        (Some(_), None) => {
+                            index_fid_docid_nested_element_ids.delete(wtxn, key)?;
+                        }
+                    }
+                }
+            }
+        }
         TypedChunk::GeoPoints(_) => {
             let span = tracing::trace_span!(target: "indexing::write_db", "geo_points");
             let _entered = span.enter();
@@ -602,20 +687,58 @@ pub(crate) fn write_typed_chunk_into_index(
                 let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
 
                 let deladd_obkv = KvReaderDelAdd::new(value);
+                // a document can be located at multiple points
                 if let Some(value) = deladd_obkv.get(DelAdd::Deletion) {
-                    let geopoint = extract_geo_point(value, docid);
-                    rtree.remove(&geopoint);
+                    for value in value.chunks_exact(16) {
+                        let geopoint = extract_geo_point(value, docid);
+                        rtree.remove(&geopoint);
+                    }
                     geo_faceted_docids.remove(docid);
                 }
                 if let Some(value) = deladd_obkv.get(DelAdd::Addition) {
-                    let geopoint = extract_geo_point(value, docid);
-                    rtree.insert(geopoint);
+                    for value in value.chunks_exact(16) {
+                        let geopoint = extract_geo_point(value, docid);
+                        rtree.insert(geopoint);
+                    }
                     geo_faceted_docids.insert(docid);
                 }
             }
             index.put_geo_rtree(wtxn, &rtree)?;
             index.put_geo_faceted_documents_ids(wtxn, &geo_faceted_docids)?;
         }
+        TypedChunk::GeoShapes(_) => {
+            let span = tracing::trace_span!(target: "indexing::write_db", "geo_shapes");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(keep_first as MergeFn);
+            for typed_chunk in typed_chunks {
+                let TypedChunk::GeoShapes(chunk) = typed_chunk else {
+                    unreachable!();
+                };
+
+                builder.push(chunk.into_cursor()?);
+            }
+            let merger = builder.build();
+
+            let mut rtree = index.geo_shapes_rtree(wtxn)?.unwrap_or_default();
+
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, value)) = iter.next()? {
+                // convert the key back to a u32 (4 bytes)
+                let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
+
+                let deladd_obkv = KvReaderDelAdd::new(value);
+                if let Some(value) = deladd_obkv.get(DelAdd::Deletion) {
+                    let envelope = extract_geo_shape_envelope(value, docid)?;
+                    rtree.remove(&envelope);
+                }
+                if let Some(value) = deladd_obkv.get(DelAdd::Addition) {
+                    let envelope = extract_geo_shape_envelope(value, docid)?;
+                    rtree.insert(envelope);
+                }
+            }
+            index.put_geo_shapes_rtree(wtxn, &rtree)?;
+        }
         TypedChunk::VectorPoints { .. } => {
             let span = tracing::trace_span!(target: "indexing::write_db", "vector_points");
             let _entered = span.enter();
@@ -797,6 +920,14 @@ fn extract_geo_point(value: &[u8], docid: DocumentId) -> GeoPoint {
     GeoPoint::new(xyz_point, (docid, point))
 }
 
+/// Parses the GeoJSON geometry back and computes its envelope.
+fn extract_geo_shape_envelope(value: &[u8], docid: DocumentId) -> Result<GeoShapeEnvelope> {
+    let value = serde_json::from_slice(value).map_err(InternalError::SerdeJson)?;
+    // the geometry has been validated when enriching the documents
+    let shape = GeoShape::from_geojson(&value).unwrap();
+    Ok(shape.to_envelope(docid))
+}
+
 fn merge_word_docids_reader_into_fst(
     merger: Merger<CursorClonableMmap, MergeFn>,
 ) -> Result<fst::Set<Vec<u8>>> {
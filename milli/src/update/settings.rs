@@ -18,10 +18,12 @@ use crate::criterion::Criterion;
 use crate::error::UserError;
 use crate::index::{
     IndexEmbeddingConfig, DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
+    FACETS_VERSION,
 };
 use crate::order_by_map::OrderByMap;
 use crate::prompt::default_max_bytes;
 use crate::proximity::ProximityPrecision;
+use crate::synonym_rules::expand_synonym_rules;
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
 use crate::vector::parsed_vectors::RESERVED_VECTORS_FIELD_NAME;
@@ -30,7 +32,10 @@ use crate::vector::settings::{
     WriteBackToDocuments,
 };
 use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
-use crate::{FieldId, FieldsIdsMap, Index, LocalizedAttributesRule, LocalizedFieldIds, Result};
+use crate::{
+    FieldId, FieldsIdsMap, Filter, Index, LocalizedAttributesRule, LocalizedFieldIds,
+    MerchandisingRule, Result, Weight,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Setting<T> {
@@ -145,9 +150,13 @@ pub struct Settings<'a, 't, 'i> {
     indexer_config: &'a IndexerConfig,
 
     searchable_fields: Setting<Vec<String>>,
+    /// The weights of the searchable fields, they are weighted by their order when `None`.
+    searchable_fields_weights: Option<BTreeMap<String, Weight>>,
     displayed_fields: Setting<Vec<String>>,
     filterable_fields: Setting<HashSet<String>>,
     sortable_fields: Setting<HashSet<String>>,
+    nested_fields: Setting<BTreeSet<String>>,
+    date_fields: Setting<BTreeSet<String>>,
     criteria: Setting<Vec<Criterion>>,
     stop_words: Setting<BTreeSet<String>>,
     non_separator_tokens: Setting<BTreeSet<String>>,
@@ -155,6 +164,7 @@ pub struct Settings<'a, 't, 'i> {
     dictionary: Setting<BTreeSet<String>>,
     distinct_field: Setting<String>,
     synonyms: Setting<BTreeMap<String, Vec<String>>>,
+    synonym_rules: Setting<BTreeMap<String, Vec<String>>>,
     primary_key: Setting<String>,
     authorize_typos: Setting<bool>,
     min_word_len_two_typos: Setting<u8>,
@@ -162,6 +172,9 @@ pub struct Settings<'a, 't, 'i> {
     exact_words: Setting<BTreeSet<String>>,
     /// Attributes on which typo tolerance is disabled.
     exact_attributes: Setting<HashSet<String>>,
+    max_typos_per_attribute: Setting<BTreeMap<String, u8>>,
+    max_typos_per_query: Setting<u8>,
+    phonetic_fields: Setting<BTreeSet<String>>,
     max_values_per_facet: Setting<usize>,
     sort_facet_values_by: Setting<OrderByMap>,
     pagination_max_total_hits: Setting<usize>,
@@ -169,6 +182,8 @@ pub struct Settings<'a, 't, 'i> {
     embedder_settings: Setting<BTreeMap<String, Setting<EmbeddingSettings>>>,
     search_cutoff: Setting<u64>,
     localized_attributes_rules: Setting<Vec<LocalizedAttributesRule>>,
+    merchandising_rules: Setting<Vec<MerchandisingRule>>,
+    upgrade_facets: bool,
 }
 
 impl<'a, 't, 'i> Settings<'a, 't, 'i> {
@@ -181,9 +196,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             wtxn,
             index,
             searchable_fields: Setting::NotSet,
+            searchable_fields_weights: None,
             displayed_fields: Setting::NotSet,
             filterable_fields: Setting::NotSet,
             sortable_fields: Setting::NotSet,
+            nested_fields: Setting::NotSet,
+            date_fields: Setting::NotSet,
             criteria: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
@@ -191,12 +209,16 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             dictionary: Setting::NotSet,
             distinct_field: Setting::NotSet,
             synonyms: Setting::NotSet,
+            synonym_rules: Setting::NotSet,
             primary_key: Setting::NotSet,
             authorize_typos: Setting::NotSet,
             exact_words: Setting::NotSet,
             min_word_len_two_typos: Setting::NotSet,
             min_word_len_one_typo: Setting::NotSet,
             exact_attributes: Setting::NotSet,
+            max_typos_per_attribute: Setting::NotSet,
+            max_typos_per_query: Setting::NotSet,
+            phonetic_fields: Setting::NotSet,
             max_values_per_facet: Setting::NotSet,
             sort_facet_values_by: Setting::NotSet,
             pagination_max_total_hits: Setting::NotSet,
@@ -204,16 +226,32 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             embedder_settings: Setting::NotSet,
             search_cutoff: Setting::NotSet,
             localized_attributes_rules: Setting::NotSet,
+            merchandising_rules: Setting::NotSet,
+            upgrade_facets: false,
             indexer_config,
         }
     }
 
     pub fn reset_searchable_fields(&mut self) {
         self.searchable_fields = Setting::Reset;
+        self.searchable_fields_weights = None;
     }
 
     pub fn set_searchable_fields(&mut self, names: Vec<String>) {
         self.searchable_fields = Setting::Set(names);
+        self.searchable_fields_weights = None;
+    }
+
+    /// Sets the searchable fields along with their weights, the higher the weight,
+    /// the more important the field. Fields with the same weight are equally important.
+    pub fn set_weighted_searchable_fields(&mut self, fields: Vec<(String, Weight)>) {
+        let mut weights = BTreeMap::new();
+        for (name, weight) in &fields {
+            // fields are deduplicated, only the first occurrence is taken into account
+            weights.entry(name.clone()).or_insert(*weight);
+        }
+        self.searchable_fields = Setting::Set(fields.into_iter().map(|(name, _)| name).collect());
+        self.searchable_fields_weights = Some(weights);
     }
 
     pub fn reset_displayed_fields(&mut self) {
@@ -240,6 +278,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.sortable_fields = Setting::Reset;
     }
 
+    pub fn set_nested_fields(&mut self, names: BTreeSet<String>) {
+        self.nested_fields = Setting::Set(names);
+    }
+
+    pub fn reset_nested_fields(&mut self) {
+        self.nested_fields = Setting::Reset;
+    }
+
+    pub fn set_date_fields(&mut self, names: BTreeSet<String>) {
+        self.date_fields = Setting::Set(names);
+    }
+
+    pub fn reset_date_fields(&mut self) {
+        self.date_fields = Setting::Reset;
+    }
+
     pub fn reset_criteria(&mut self) {
         self.criteria = Setting::Reset;
     }
@@ -306,6 +360,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.synonyms = if synonyms.is_empty() { Setting::Reset } else { Setting::Set(synonyms) }
     }
 
+    pub fn reset_synonym_rules(&mut self) {
+        self.synonym_rules = Setting::Reset;
+    }
+
+    pub fn set_synonym_rules(&mut self, rules: BTreeMap<String, Vec<String>>) {
+        self.synonym_rules = if rules.is_empty() { Setting::Reset } else { Setting::Set(rules) }
+    }
+
     pub fn reset_primary_key(&mut self) {
         self.primary_key = Setting::Reset;
     }
@@ -354,6 +416,30 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.exact_attributes = Setting::Reset;
     }
 
+    pub fn set_max_typos_per_attribute(&mut self, max_typos: BTreeMap<String, u8>) {
+        self.max_typos_per_attribute = Setting::Set(max_typos);
+    }
+
+    pub fn reset_max_typos_per_attribute(&mut self) {
+        self.max_typos_per_attribute = Setting::Reset;
+    }
+
+    pub fn set_max_typos_per_query(&mut self, val: u8) {
+        self.max_typos_per_query = Setting::Set(val);
+    }
+
+    pub fn reset_max_typos_per_query(&mut self) {
+        self.max_typos_per_query = Setting::Reset;
+    }
+
+    pub fn set_phonetic_fields(&mut self, names: BTreeSet<String>) {
+        self.phonetic_fields = Setting::Set(names);
+    }
+
+    pub fn reset_phonetic_fields(&mut self) {
+        self.phonetic_fields = Setting::Reset;
+    }
+
     pub fn set_max_values_per_facet(&mut self, value: usize) {
         self.max_values_per_facet = Setting::Set(value);
     }
@@ -410,6 +496,19 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.localized_attributes_rules = Setting::Reset;
     }
 
+    pub fn set_merchandising_rules(&mut self, rules: Vec<MerchandisingRule>) {
+        self.merchandising_rules = Setting::Set(rules);
+    }
+
+    pub fn reset_merchandising_rules(&mut self) {
+        self.merchandising_rules = Setting::Reset;
+    }
+
+    /// Extracts the facets again if they are stored with an older layout, see [`FACETS_VERSION`].
+    pub fn upgrade_facets(&mut self) {
+        self.upgrade_facets = true;
+    }
+
     #[tracing::instrument(
         level = "trace"
         skip(self, progress_callback, should_abort, settings_diff),
@@ -492,14 +591,25 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             Setting::Set(ref fields) => {
                 // Check to see if the searchable fields changed before doing anything else
                 let old_fields = self.index.searchable_fields(self.wtxn)?;
+                let old_weights = self.index.user_defined_searchable_fields_weights(self.wtxn)?;
                 let did_change = {
                     let new_fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
-                    new_fields != old_fields
+                    new_fields != old_fields || self.searchable_fields_weights != old_weights
                 };
                 if !did_change {
                     return Ok(false);
                 }
 
+                // The weights must be written first as they are used to generate the fields weights map
+                match &self.searchable_fields_weights {
+                    Some(weights) => {
+                        self.index.put_user_defined_searchable_fields_weights(self.wtxn, weights)?
+                    }
+                    None => {
+                        self.index.delete_user_defined_searchable_fields_weights(self.wtxn)?;
+                    }
+                }
+
                 // Since we're updating the settings we can only add new fields at the end of the field id map
                 let mut fields_ids_map = self.index.fields_ids_map(self.wtxn)?;
                 // fields are deduplicated, only the first occurrence is taken into account
@@ -579,6 +689,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_rules == Setting::NotSet {
+            self.synonym_rules = Setting::Set(self.index.user_defined_synonym_rules(self.wtxn)?);
+        }
 
         Ok(changes)
     }
@@ -604,6 +717,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_rules == Setting::NotSet {
+            self.synonym_rules = Setting::Set(self.index.user_defined_synonym_rules(self.wtxn)?);
+        }
 
         Ok(changes)
     }
@@ -629,6 +745,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         if changes && self.synonyms == Setting::NotSet {
             self.synonyms = Setting::Set(self.index.user_defined_synonyms(self.wtxn)?);
         }
+        if changes && self.synonym_rules == Setting::NotSet {
+            self.synonym_rules = Setting::Set(self.index.user_defined_synonym_rules(self.wtxn)?);
+        }
 
         Ok(changes)
     }
@@ -636,65 +755,10 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     fn update_synonyms(&mut self) -> Result<bool> {
         match self.synonyms {
             Setting::Set(ref user_synonyms) => {
-                fn normalize(tokenizer: &Tokenizer<'_>, text: &str) -> Vec<String> {
-                    tokenizer
-                        .tokenize(text)
-                        .filter_map(|token| {
-                            if token.is_word() && !token.lemma().is_empty() {
-                                Some(token.lemma().to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                }
-
-                let mut builder = TokenizerBuilder::new();
-                let stop_words = self.index.stop_words(self.wtxn)?;
-                if let Some(ref stop_words) = stop_words {
-                    builder.stop_words(stop_words);
-                }
-
-                let separators = self.index.allowed_separators(self.wtxn)?;
-                let separators: Option<Vec<_>> =
-                    separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                if let Some(ref separators) = separators {
-                    builder.separators(separators);
-                }
-
-                let dictionary = self.index.dictionary(self.wtxn)?;
-                let dictionary: Option<Vec<_>> =
-                    dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                if let Some(ref dictionary) = dictionary {
-                    builder.words_dict(dictionary);
-                }
-
-                let tokenizer = builder.build();
-
-                let mut new_synonyms = HashMap::new();
-                for (word, synonyms) in user_synonyms {
-                    // Normalize both the word and associated synonyms.
-                    let normalized_word = normalize(&tokenizer, word);
-                    let normalized_synonyms: Vec<_> = synonyms
-                        .iter()
-                        .map(|synonym| normalize(&tokenizer, synonym))
-                        .filter(|synonym| !synonym.is_empty())
-                        .collect();
-
-                    // Store the normalized synonyms under the normalized word,
-                    // merging the possible duplicate words.
-                    if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
-                        let entry = new_synonyms.entry(normalized_word).or_insert_with(Vec::new);
-                        entry.extend(normalized_synonyms.into_iter());
-                    }
-                }
-
-                // Make sure that we don't have duplicate synonyms.
-                new_synonyms.iter_mut().for_each(|(_, synonyms)| {
-                    synonyms.sort_unstable();
-                    synonyms.dedup();
-                });
-
+                let synonyms = user_synonyms
+                    .iter()
+                    .map(|(word, synonyms)| (word.as_str(), synonyms.iter().map(String::as_str)));
+                let new_synonyms = normalize_synonyms(self.index, self.wtxn, synonyms)?;
                 let old_synonyms = self.index.synonyms(self.wtxn)?;
 
                 if new_synonyms != old_synonyms {
@@ -709,6 +773,25 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         }
     }
 
+    fn update_synonym_rules(&mut self) -> Result<bool> {
+        match self.synonym_rules {
+            Setting::Set(ref user_rules) => {
+                let synonyms = expand_synonym_rules(user_rules);
+                let new_synonyms = normalize_synonyms(self.index, self.wtxn, synonyms)?;
+                let old_synonyms = self.index.synonym_rules(self.wtxn)?;
+
+                if new_synonyms != old_synonyms {
+                    self.index.put_synonym_rules(self.wtxn, &new_synonyms, user_rules)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_synonym_rules(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
     fn update_exact_attributes(&mut self) -> Result<bool> {
         match self.exact_attributes {
             Setting::Set(ref attrs) => {
@@ -728,6 +811,26 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         }
     }
 
+    fn update_max_typos(&mut self) -> Result<()> {
+        match &self.max_typos_per_attribute {
+            Setting::Set(max_typos) => {
+                self.index.put_max_typos_per_attribute(self.wtxn, max_typos)?;
+            }
+            Setting::Reset => {
+                self.index.delete_max_typos_per_attribute(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        match self.max_typos_per_query {
+            Setting::Set(val) => self.index.put_max_typos_per_query(self.wtxn, val)?,
+            Setting::Reset => {
+                self.index.delete_max_typos_per_query(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
     fn update_filterable(&mut self) -> Result<()> {
         match self.filterable_fields {
             Setting::Set(ref fields) => {
@@ -762,6 +865,45 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    fn update_nested(&mut self) -> Result<()> {
+        match self.nested_fields {
+            Setting::Set(ref fields) => {
+                self.index.put_nested_fields(self.wtxn, fields)?;
+            }
+            Setting::Reset => {
+                self.index.delete_nested_fields(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_phonetic(&mut self) -> Result<()> {
+        match self.phonetic_fields {
+            Setting::Set(ref fields) => {
+                self.index.put_phonetic_fields(self.wtxn, fields)?;
+            }
+            Setting::Reset => {
+                self.index.delete_phonetic_fields(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_date(&mut self) -> Result<()> {
+        match self.date_fields {
+            Setting::Set(ref fields) => {
+                self.index.put_date_fields(self.wtxn, fields)?;
+            }
+            Setting::Reset => {
+                self.index.delete_date_fields(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
     fn update_criteria(&mut self) -> Result<()> {
         match &self.criteria {
             Setting::Set(criteria) => {
@@ -775,6 +917,93 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    /// Checks that the typo budgets of the attributes are valid numbers of typos
+    /// for searchable attributes.
+    fn validate_max_typos_per_attribute(&mut self) -> Result<()> {
+        if matches!(self.max_typos_per_attribute, Setting::NotSet)
+            && matches!(self.searchable_fields, Setting::NotSet)
+        {
+            return Ok(());
+        }
+
+        let max_typos_per_attribute = self.index.max_typos_per_attribute(self.wtxn)?;
+        let searchable_fields = self.index.user_defined_searchable_fields(self.wtxn)?;
+        for (attribute, &max_typos) in &max_typos_per_attribute {
+            if max_typos > 2 {
+                let attribute = attribute.clone();
+                return Err(UserError::InvalidMaxTyposPerAttribute { attribute, max_typos }.into());
+            }
+            if let Some(searchable_fields) = &searchable_fields {
+                if !crate::is_faceted(attribute, searchable_fields) {
+                    return Err(UserError::InvalidMaxTyposAttribute {
+                        attribute: attribute.clone(),
+                        valid_fields: searchable_fields.iter().map(|f| f.to_string()).collect(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the filters of the merchandising rules are about filterable attributes.
+    fn validate_merchandising_rules(&mut self) -> Result<()> {
+        if matches!(self.merchandising_rules, Setting::NotSet)
+            && matches!(self.filterable_fields, Setting::NotSet)
+        {
+            return Ok(());
+        }
+
+        let filterable_fields = self.index.filterable_fields(self.wtxn)?;
+        for rule in self.index.merchandising_rules(self.wtxn)? {
+            let filters = [
+                ("condition.filter", &rule.condition.filter),
+                ("actions.filter", &rule.actions.filter),
+                ("actions.boost", &rule.actions.boost),
+            ];
+            for (name, filter) in filters {
+                let Some(filter) = filter.as_deref().map(Filter::from_str).transpose()?.flatten()
+                else {
+                    continue;
+                };
+                let not_filterable = filter
+                    .fields()
+                    .into_iter()
+                    .find(|field| !crate::is_faceted(field, &filterable_fields));
+                if let Some(attribute) = not_filterable {
+                    return Err(UserError::InvalidMerchandisingRuleFilter {
+                        filter: name,
+                        attribute: attribute.to_string(),
+                        valid_fields: filterable_fields.iter().cloned().collect(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the ranking rules against each other and against the faceted fields,
+    /// see [`crate::criterion::validate_criteria`].
+    fn validate_criteria(&mut self) -> Result<()> {
+        if matches!(self.criteria, Setting::NotSet)
+            && matches!(self.filterable_fields, Setting::NotSet)
+            && matches!(self.sortable_fields, Setting::NotSet)
+        {
+            return Ok(());
+        }
+
+        let criteria = self.index.criteria(self.wtxn)?;
+        let filterable_fields = self.index.filterable_fields(self.wtxn)?;
+        // the sorted and decayed fields of the ranking rules are faceted by the index
+        let faceted_fields = self.index.user_defined_faceted_fields(self.wtxn)?;
+        crate::criterion::validate_criteria(&criteria, &filterable_fields, &faceted_fields)?;
+
+        Ok(())
+    }
+
     fn update_primary_key(&mut self) -> Result<()> {
         match self.primary_key {
             Setting::Set(ref primary_key) => {
@@ -1162,6 +1391,27 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    fn update_facets_version(&mut self) -> Result<()> {
+        // the new version makes the facets be extracted again by the settings diff
+        if self.upgrade_facets && self.index.facets_version(self.wtxn)? != FACETS_VERSION {
+            self.index.put_facets_version(self.wtxn)?;
+        }
+        Ok(())
+    }
+
+    fn update_merchandising_rules(&mut self) -> Result<()> {
+        match &self.merchandising_rules {
+            Setting::Set(rules) => {
+                self.index.put_merchandising_rules(self.wtxn, rules)?;
+            }
+            Setting::Reset => {
+                self.index.delete_merchandising_rules(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
     pub fn execute<FP, FA>(mut self, progress_callback: FP, should_abort: FA) -> Result<()>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
@@ -1179,23 +1429,38 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_authorize_typos()?;
         self.update_min_typo_word_len()?;
         self.update_exact_words()?;
+        // the typo budgets are enforced at search time
+        self.update_max_typos()?;
         self.update_max_values_per_facet()?;
         self.update_sort_facet_values_by()?;
         self.update_pagination_max_total_hits()?;
         self.update_search_cutoff()?;
+        // the merchandising rules are applied at search time
+        self.update_merchandising_rules()?;
 
         // could trigger re-indexing
         self.update_filterable()?;
         self.update_sortable()?;
+        self.update_nested()?;
+        self.update_date()?;
         self.update_stop_words()?;
         self.update_non_separator_tokens()?;
         self.update_separator_tokens()?;
         self.update_dictionary()?;
         self.update_synonyms()?;
+        self.update_synonym_rules()?;
         self.update_searchable()?;
         self.update_exact_attributes()?;
+        self.update_phonetic()?;
         self.update_proximity_precision()?;
         self.update_localized_attributes_rules()?;
+        // the fields of the ranking rules must be faceted with the new settings
+        self.validate_criteria()?;
+        // the attributes of the typo budgets must be searchable with the new settings
+        self.validate_max_typos_per_attribute()?;
+        // the attributes of the rules filters must be filterable with the new settings
+        self.validate_merchandising_rules()?;
+        self.update_facets_version()?;
 
         let embedding_config_updates = self.update_embedding_configs()?;
 
@@ -1240,6 +1505,8 @@ pub struct InnerIndexSettingsDiff {
     pub(crate) cache_user_defined_searchables: bool,
     // Cache the check to see if the exact_attributes are different.
     pub(crate) cache_exact_attributes: bool,
+    // Cache the check to see if the phonetic attributes are different.
+    pub(crate) cache_phonetic_attributes: bool,
 }
 
 impl InnerIndexSettingsDiff {
@@ -1280,6 +1547,9 @@ impl InnerIndexSettingsDiff {
 
         let cache_exact_attributes = old_settings.exact_attributes != new_settings.exact_attributes;
 
+        let cache_phonetic_attributes =
+            old_settings.phonetic_fields_ids != new_settings.phonetic_fields_ids;
+
         let cache_user_defined_searchables = old_settings.user_defined_searchable_fields
             != new_settings.user_defined_searchable_fields;
 
@@ -1326,6 +1596,7 @@ impl InnerIndexSettingsDiff {
             cache_reindex_searchable_without_user_defined,
             cache_user_defined_searchables,
             cache_exact_attributes,
+            cache_phonetic_attributes,
         }
     }
 
@@ -1336,6 +1607,7 @@ impl InnerIndexSettingsDiff {
     pub fn reindex_searchable(&self) -> bool {
         self.cache_reindex_searchable_without_user_defined
             || self.cache_exact_attributes
+            || self.cache_phonetic_attributes
             || self.cache_user_defined_searchables
     }
 
@@ -1348,7 +1620,10 @@ impl InnerIndexSettingsDiff {
     }
 
     pub fn reindex_searchable_id(&self, id: FieldId) -> Option<DelAddOperation> {
-        if self.cache_reindex_searchable_without_user_defined || self.cache_exact_attributes {
+        if self.cache_reindex_searchable_without_user_defined
+            || self.cache_exact_attributes
+            || self.cache_phonetic_attributes
+        {
             Some(DelAddOperation::DeletionAndAddition)
         } else if let Some(only_additional_fields) = &self.only_additional_fields {
             let additional_field = self.new.fields_ids_map.name(id).unwrap();
@@ -1385,6 +1660,9 @@ impl InnerIndexSettingsDiff {
 
         (existing_fields - old_faceted_fields) != (existing_fields - new_faceted_fields)
             || self.old.localized_faceted_fields_ids != self.new.localized_faceted_fields_ids
+            || self.old.date_fields_ids != self.new.date_fields_ids
+            || self.old.nested_fields_ids != self.new.nested_fields_ids
+            || self.old.facets_version != self.new.facets_version
     }
 
     pub fn reindex_vectors(&self) -> bool {
@@ -1401,7 +1679,32 @@ impl InnerIndexSettingsDiff {
     }
 
     pub fn modified_faceted_fields(&self) -> HashSet<String> {
-        &self.old.user_defined_faceted_fields ^ &self.new.user_defined_faceted_fields
+        let mut modified_fields =
+            &self.old.user_defined_faceted_fields ^ &self.new.user_defined_faceted_fields;
+        // the values of the date fields must be extracted again to add or remove their timestamps
+        // and the objects of the nested fields to add or remove their correlation ids
+        for id in
+            self.modified_date_fields_ids().into_iter().chain(self.modified_nested_fields_ids())
+        {
+            if let Some(name) = self.new.fields_ids_map.name(id) {
+                modified_fields.insert(name.to_string());
+            }
+        }
+        // all the facets are extracted again when the layout of their databases changed
+        if self.old.facets_version != self.new.facets_version {
+            modified_fields.extend(self.new.user_defined_faceted_fields.iter().cloned());
+        }
+        modified_fields
+    }
+
+    /// Returns the fields that became or are no longer date fields.
+    pub fn modified_date_fields_ids(&self) -> HashSet<FieldId> {
+        &self.old.date_fields_ids ^ &self.new.date_fields_ids
+    }
+
+    /// Returns the fields that became or are no longer nested fields.
+    pub fn modified_nested_fields_ids(&self) -> HashSet<FieldId> {
+        &self.old.nested_fields_ids ^ &self.new.nested_fields_ids
     }
 }
 
@@ -1416,14 +1719,18 @@ pub(crate) struct InnerIndexSettings {
     pub faceted_fields_ids: HashSet<FieldId>,
     pub searchable_fields_ids: Vec<FieldId>,
     pub exact_attributes: HashSet<FieldId>,
+    pub phonetic_fields_ids: HashSet<FieldId>,
     pub proximity_precision: ProximityPrecision,
     pub embedding_configs: EmbeddingConfigs,
     pub existing_fields: HashSet<String>,
     pub geo_fields_ids: Option<(FieldId, FieldId)>,
+    pub date_fields_ids: HashSet<FieldId>,
+    pub nested_fields_ids: HashSet<FieldId>,
     pub non_searchable_fields_ids: Vec<FieldId>,
     pub non_faceted_fields_ids: Vec<FieldId>,
     pub localized_searchable_fields_ids: LocalizedFieldIds,
     pub localized_faceted_fields_ids: LocalizedFieldIds,
+    pub facets_version: u32,
 }
 
 impl InnerIndexSettings {
@@ -1440,6 +1747,7 @@ impl InnerIndexSettings {
         let mut searchable_fields_ids = index.searchable_fields_ids(rtxn)?;
         let mut faceted_fields_ids = index.faceted_fields_ids(rtxn)?;
         let exact_attributes = index.exact_attributes_ids(rtxn)?;
+        let phonetic_fields_ids = index.phonetic_fields_ids(rtxn)?;
         let proximity_precision = index.proximity_precision(rtxn)?.unwrap_or_default();
         let embedding_configs = embedders(index.embedding_configs(rtxn)?)?;
         let existing_fields: HashSet<_> = index
@@ -1465,7 +1773,10 @@ impl InnerIndexSettings {
             }
             None => None,
         };
+        let date_fields_ids = index.date_fields_ids(rtxn)?;
+        let nested_fields_ids = index.nested_fields_ids(rtxn)?;
         let localized_attributes_rules = index.localized_attributes_rules(rtxn)?;
+        let facets_version = index.facets_version(rtxn)?;
         let localized_searchable_fields_ids = LocalizedFieldIds::new(
             &localized_attributes_rules,
             &fields_ids_map,
@@ -1491,14 +1802,18 @@ impl InnerIndexSettings {
             faceted_fields_ids,
             searchable_fields_ids,
             exact_attributes,
+            phonetic_fields_ids,
             proximity_precision,
             embedding_configs,
             existing_fields,
             geo_fields_ids,
+            date_fields_ids,
+            nested_fields_ids,
             non_searchable_fields_ids: vectors_fids.clone(),
             non_faceted_fields_ids: vectors_fids.clone(),
             localized_searchable_fields_ids,
             localized_faceted_fields_ids,
+            facets_version,
         })
     }
 
@@ -1514,6 +1829,16 @@ impl InnerIndexSettings {
         index.put_faceted_fields(wtxn, &new_facets)?;
 
         self.faceted_fields_ids = index.faceted_fields_ids(wtxn)?;
+        let date_fields = index.date_fields(wtxn)?;
+        self.date_fields_ids = self
+            .fields_ids_map
+            .iter()
+            .filter(|(_fid, field)| crate::is_faceted(field, &date_fields))
+            .map(|(fid, _field)| fid)
+            .collect();
+        let nested_fields = index.nested_fields(wtxn)?;
+        self.nested_fields_ids =
+            nested_fields.iter().filter_map(|name| self.fields_ids_map.id(name)).collect();
         let localized_attributes_rules = index.localized_attributes_rules(wtxn)?;
         self.localized_faceted_fields_ids = LocalizedFieldIds::new(
             &localized_attributes_rules,
@@ -1544,6 +1869,13 @@ impl InnerIndexSettings {
             )?;
         }
         self.searchable_fields_ids = index.searchable_fields_ids(wtxn)?;
+        let phonetic_fields = index.phonetic_fields(wtxn)?;
+        self.phonetic_fields_ids = self
+            .fields_ids_map
+            .iter()
+            .filter(|(_fid, field)| crate::is_faceted(field, &phonetic_fields))
+            .map(|(fid, _field)| fid)
+            .collect();
         let localized_attributes_rules = index.localized_attributes_rules(wtxn)?;
         self.localized_searchable_fields_ids = LocalizedFieldIds::new(
             &localized_attributes_rules,
@@ -1555,6 +1887,78 @@ impl InnerIndexSettings {
     }
 }
 
+/// Normalizes the words and their synonyms with the tokenizer settings of the index,
+/// merging the duplicate words.
+fn normalize_synonyms<'s, S>(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    synonyms: impl IntoIterator<Item = (&'s str, S)>,
+) -> Result<HashMap<Vec<String>, Vec<Vec<String>>>>
+where
+    S: IntoIterator<Item = &'s str>,
+{
+    fn normalize(tokenizer: &Tokenizer<'_>, text: &str) -> Vec<String> {
+        tokenizer
+            .tokenize(text)
+            .filter_map(|token| {
+                if token.is_word() && !token.lemma().is_empty() {
+                    Some(token.lemma().to_string())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    }
+
+    let mut builder = TokenizerBuilder::new();
+    let stop_words = index.stop_words(rtxn)?;
+    if let Some(ref stop_words) = stop_words {
+        builder.stop_words(stop_words);
+    }
+
+    let separators = index.allowed_separators(rtxn)?;
+    let separators: Option<Vec<_>> =
+        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref separators) = separators {
+        builder.separators(separators);
+    }
+
+    let dictionary = index.dictionary(rtxn)?;
+    let dictionary: Option<Vec<_>> =
+        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref dictionary) = dictionary {
+        builder.words_dict(dictionary);
+    }
+
+    let tokenizer = builder.build();
+
+    let mut new_synonyms = HashMap::new();
+    for (word, synonyms) in synonyms {
+        // Normalize both the word and associated synonyms.
+        let normalized_word = normalize(&tokenizer, word);
+        let normalized_synonyms: Vec<_> = synonyms
+            .into_iter()
+            .map(|synonym| normalize(&tokenizer, synonym))
+            .filter(|synonym| !synonym.is_empty())
+            .collect();
+
+        // Store the normalized synonyms under the normalized word,
+        // merging the possible duplicate words.
+        if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
+            let entry = new_synonyms.entry(normalized_word).or_insert_with(Vec::new);
+            entry.extend(normalized_synonyms.into_iter());
+        }
+    }
+
+    // Make sure that we don't have duplicate synonyms.
+    new_synonyms.iter_mut().for_each(|(_, synonyms)| {
+        synonyms.sort_unstable();
+        synonyms.dedup();
+    });
+
+    Ok(new_synonyms)
+}
+
 fn embedders(embedding_configs: Vec<IndexEmbeddingConfig>) -> Result<EmbeddingConfigs> {
     let res: Result<_> = embedding_configs
         .into_iter()
@@ -2694,9 +3098,12 @@ mod tests {
                     index: _,
                     indexer_config: _,
                     searchable_fields,
+                    searchable_fields_weights,
                     displayed_fields,
                     filterable_fields,
                     sortable_fields,
+                    nested_fields,
+                    date_fields,
                     criteria,
                     stop_words,
                     non_separator_tokens,
@@ -2704,12 +3111,16 @@ mod tests {
                     dictionary,
                     distinct_field,
                     synonyms,
+                    synonym_rules,
                     primary_key,
                     authorize_typos,
                     min_word_len_two_typos,
                     min_word_len_one_typo,
                     exact_words,
                     exact_attributes,
+                    max_typos_per_attribute,
+                    max_typos_per_query,
+                    phonetic_fields,
                     max_values_per_facet,
                     sort_facet_values_by,
                     pagination_max_total_hits,
@@ -2717,11 +3128,15 @@ mod tests {
                     embedder_settings,
                     search_cutoff,
                     localized_attributes_rules,
+                    merchandising_rules,
                 } = settings;
                 assert!(matches!(searchable_fields, Setting::NotSet));
+                assert!(searchable_fields_weights.is_none());
                 assert!(matches!(displayed_fields, Setting::NotSet));
                 assert!(matches!(filterable_fields, Setting::NotSet));
                 assert!(matches!(sortable_fields, Setting::NotSet));
+                assert!(matches!(nested_fields, Setting::NotSet));
+                assert!(matches!(date_fields, Setting::NotSet));
                 assert!(matches!(criteria, Setting::NotSet));
                 assert!(matches!(stop_words, Setting::NotSet));
                 assert!(matches!(non_separator_tokens, Setting::NotSet));
@@ -2729,12 +3144,16 @@ mod tests {
                 assert!(matches!(dictionary, Setting::NotSet));
                 assert!(matches!(distinct_field, Setting::NotSet));
                 assert!(matches!(synonyms, Setting::NotSet));
+                assert!(matches!(synonym_rules, Setting::NotSet));
                 assert!(matches!(primary_key, Setting::NotSet));
                 assert!(matches!(authorize_typos, Setting::NotSet));
                 assert!(matches!(min_word_len_two_typos, Setting::NotSet));
                 assert!(matches!(min_word_len_one_typo, Setting::NotSet));
                 assert!(matches!(exact_words, Setting::NotSet));
                 assert!(matches!(exact_attributes, Setting::NotSet));
+                assert!(matches!(max_typos_per_attribute, Setting::NotSet));
+                assert!(matches!(max_typos_per_query, Setting::NotSet));
+                assert!(matches!(phonetic_fields, Setting::NotSet));
                 assert!(matches!(max_values_per_facet, Setting::NotSet));
                 assert!(matches!(sort_facet_values_by, Setting::NotSet));
                 assert!(matches!(pagination_max_total_hits, Setting::NotSet));
@@ -2742,6 +3161,7 @@ mod tests {
                 assert!(matches!(embedder_settings, Setting::NotSet));
                 assert!(matches!(search_cutoff, Setting::NotSet));
                 assert!(matches!(localized_attributes_rules, Setting::NotSet));
+                assert!(matches!(merchandising_rules, Setting::NotSet));
             })
             .unwrap();
     }
@@ -149,9 +149,13 @@ pub fn expected_order(
                     new_groups
                         .extend(group.linear_group_by_key(|d| d.asc_desc_rank).map(Vec::from));
                 }
-                Criterion::Asc(_) | Criterion::Desc(_) | Criterion::Sort => {
-                    new_groups.push(group.clone())
-                }
+                Criterion::Asc(_)
+                | Criterion::Desc(_)
+                | Criterion::TermRarity
+                | Criterion::Score(_)
+                | Criterion::Decay(_)
+                | Criterion::Boost(_)
+                | Criterion::Sort => new_groups.push(group.clone()),
             }
         }
         groups = std::mem::take(&mut new_groups);